@@ -0,0 +1,86 @@
+use std::{
+    fs::{create_dir, create_dir_all, read_dir, remove_dir_all},
+    io::ErrorKind,
+    path::PathBuf,
+};
+
+const SNAPSHOT_PREFIX: &str = "snapshot-";
+
+/// Directory with snapshots of unattended analyses, every snapshot is directory named by its
+/// timestamp, so snapshots sorted by name are sorted from the oldest
+#[derive(Clone, Debug)]
+pub struct Artifacts {
+    directory: PathBuf,
+}
+
+impl Artifacts {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    /// Returns directories of snapshots from the oldest
+    pub fn snapshots(&self) -> Result<Vec<PathBuf>, String> {
+        if !self.directory.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut snapshots = vec![];
+        for entry in read_dir(&self.directory)
+            .map_err(|e| format!("failed to read {:?}: {}", self.directory, e))?
+        {
+            let path = entry
+                .map_err(|e| format!("failed to read {:?}: {}", self.directory, e))?
+                .path();
+            let is_snapshot = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with(SNAPSHOT_PREFIX));
+            if is_snapshot && path.is_dir() {
+                snapshots.push(path);
+            }
+        }
+
+        snapshots.sort();
+        Ok(snapshots)
+    }
+
+    /// Returns directory of the newest snapshot
+    pub fn latest(&self) -> Result<Option<PathBuf>, String> {
+        Ok(self.snapshots()?.pop())
+    }
+
+    /// Creates directory of snapshot with `timestamp` (seconds since epoch), snapshots created in
+    /// the same second get suffix with their order (`-0001`), existing snapshot is never reused
+    pub fn create_snapshot(&self, timestamp: u64) -> Result<PathBuf, String> {
+        create_dir_all(&self.directory)
+            .map_err(|e| format!("failed to create {:?}: {}", self.directory, e))?;
+
+        let name = format!("{}{:020}", SNAPSHOT_PREFIX, timestamp);
+        for order in 0..10000 {
+            let path = match order {
+                0 => self.directory.join(&name),
+                _ => self.directory.join(format!("{}-{:04}", name, order)),
+            };
+            match create_dir(&path) {
+                Ok(()) => return Ok(path),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(format!("failed to create {:?}: {}", path, e)),
+            }
+        }
+
+        Err(format!("too many snapshots with timestamp {}", timestamp))
+    }
+
+    /// Removes all but the newest `keep` snapshots, returns removed snapshots
+    pub fn rotate(&self, keep: usize) -> Result<Vec<PathBuf>, String> {
+        let mut snapshots = self.snapshots()?;
+        let removed: Vec<PathBuf> = snapshots
+            .drain(..snapshots.len().saturating_sub(keep))
+            .collect();
+
+        for path in &removed {
+            remove_dir_all(path).map_err(|e| format!("failed to remove {:?}: {}", path, e))?;
+        }
+
+        Ok(removed)
+    }
+}
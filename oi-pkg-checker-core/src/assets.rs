@@ -1,3 +1,11 @@
 pub mod assets_types;
 pub mod catalogs_c;
+pub mod depot;
+pub mod history;
+pub mod installed_image;
+pub mod make_cache;
+pub mod manifest_dependencies;
 pub mod open_indiana_oi_userland_git;
+pub mod pkg_repository;
+pub mod rewrite_rules;
+pub mod snapshot;
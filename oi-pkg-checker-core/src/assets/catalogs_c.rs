@@ -1,16 +1,54 @@
-use std::{env, fs::File, io::Read, path::PathBuf, process::exit};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::{read_to_string, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+    process::exit,
+};
 
 use fmri::{FMRI, FMRIList, Publisher, Version};
 use log::{debug, error};
+use serde::{
+    de::{DeserializeSeed, IgnoredAny, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
 use serde_json::Value;
 
-use crate::{ComponentPackagesList, Problems, problems::Problem::RenamedPackageInComponent};
+use crate::{
+    ComponentPackagesList, DependencyTypes, Problems,
+    problems::Problem::{
+        DuplicateDependency, InvalidFmri, ObsoleteWithDependencies, RenamedPackageInComponent,
+        TimestampAnomaly,
+    },
+};
 use crate::packages::{
     component::Component, components::Components, depend_types::DependTypes,
     dependencies::Dependencies, dependency::Dependency, package::Package,
     package_versions::PackageVersions,
+    version_parts::{current_timestamp, is_plausible_timestamp, VersionParts},
 };
 
+/// Catalog loaded from origin of publisher, see [`crate::PublisherConfig`]
+#[derive(PartialEq, Serialize, Deserialize, Clone, Debug)]
+pub struct CatalogOrigin {
+    pub catalog: PathBuf,
+    pub publisher: String,
+    pub origin: String,
+    /// newest version of every package of publisher in catalog, key is package name
+    pub packages: BTreeMap<String, FMRI>,
+}
+
+/// Time of the last update of loaded catalog, see [`crate::Components::check_catalog_staleness`]
+#[derive(PartialEq, Serialize, Deserialize, Clone, Debug)]
+pub struct CatalogTimestamp {
+    pub catalog: PathBuf,
+    pub publishers: Vec<String>,
+    /// `last-modified` from catalog.attrs next to catalog or the newest timestamp of package
+    /// version in catalog (`20220126T070330Z`)
+    pub last_modified: String,
+}
+
 #[derive(Debug)]
 enum Attribute {
     Fmri(FMRI),
@@ -37,8 +75,8 @@ enum Name {
 }
 
 impl Attributes {
-    /// returns only fmri and type attribute
-    fn parse_attributes(attributes_string: String) -> Self {
+    /// returns only fmri and type attribute, or invalid fmri as error
+    fn parse_attributes(attributes_string: String) -> Result<Self, String> {
         let mut attributes = Self(vec![]);
 
         for attribute_value in attributes_string.split_whitespace() {
@@ -49,11 +87,13 @@ impl Attributes {
                 .split_once('=')
                 .expect("bad attribute value");
             let att = match attribute {
-                "fmri" => Attribute::Fmri(FMRI::parse_raw(value).unwrap()),
+                "fmri" => Attribute::Fmri(FMRI::parse_raw(value).map_err(|_| value.to_owned())?),
                 "type" => Attribute::DType(value.to_owned()),
                 "name" => Attribute::Name(value.to_owned()),
                 "value" => Attribute::Value(value.to_owned()),
-                "predicate" => Attribute::Predicate(FMRI::parse_raw(value).unwrap()),
+                "predicate" => {
+                    Attribute::Predicate(FMRI::parse_raw(value).map_err(|_| value.to_owned())?)
+                }
                 _ => {
                     debug!("Unknown attribute found: {} value: {}", attribute, value);
                     Attribute::Other
@@ -63,7 +103,7 @@ impl Attributes {
             attributes.0.push(att)
         }
 
-        attributes
+        Ok(attributes)
     }
 
     fn get_type_from_attributes(&self) -> &String {
@@ -118,17 +158,17 @@ impl Attributes {
 
 /// Returns only depend actions
 /// Parses "depend fmri=pkg:/system/library@0.5.11-2017.0.0.16778 type=require" into [`DependTypes`]
-fn parse_depend(depend: String) -> DependTypes {
+pub(crate) fn parse_depend(depend: String) -> Result<DependTypes, String> {
     if !depend.starts_with("depend") {
         // action is not depend
         panic!("bad function calling")
     }
 
-    let attributes = Attributes::parse_attributes(depend.trim_start_matches("depend").to_owned());
+    let attributes = Attributes::parse_attributes(depend.trim_start_matches("depend").to_owned())?;
 
     let d_type = attributes.get_type_from_attributes();
 
-    return match d_type.as_str() {
+    Ok(match d_type.as_str() {
         "require" => DependTypes::Require(attributes.get_fmri_from_attributes()),
         "optional" => DependTypes::Optional(attributes.get_fmri_from_attributes()),
         "incorporate" => DependTypes::Incorporate(attributes.get_fmri_from_attributes()),
@@ -150,22 +190,22 @@ fn parse_depend(depend: String) -> DependTypes {
         ),
         "group" => DependTypes::Group(attributes.get_fmri_from_attributes()),
         _ => panic!("unknown depend type: {}", d_type),
-    };
+    })
 }
 
-fn parse_set(set: String) -> Name {
+fn parse_set(set: String) -> Result<Name, String> {
     if !set.starts_with("set") {
         // action is not set
         panic!("bad function calling")
     }
 
-    let attributes = Attributes::parse_attributes(set.trim_start_matches("set").to_owned());
+    let attributes = Attributes::parse_attributes(set.trim_start_matches("set").to_owned())?;
 
     match attributes.get_name_from_attributes() {
         None => panic!("\"name\" is not in attributes"),
         Some(name) => {
             if name == "pkg.obsolete" && attributes.get_value_from_attributes() == "true" {
-                return Name::Obsolete;
+                return Ok(Name::Obsolete);
             }
         }
     }
@@ -174,166 +214,396 @@ fn parse_set(set: String) -> Name {
         None => panic!("\"name\" is not in attributes"),
         Some(name) => {
             if name == "pkg.renamed" && attributes.get_value_from_attributes() == "true" {
-                return Name::Renamed;
+                return Ok(Name::Renamed);
             }
         }
     }
 
-    Name::Other
+    Ok(Name::Other)
 }
 
-fn parse_action(action: String) -> Results {
+/// Returns invalid fmri as error
+fn parse_action(action: String) -> Result<Results, String> {
     if action.starts_with("depend") {
-        return Results::Dependency(Box::new(parse_depend(action.clone())));
+        return Ok(Results::Dependency(Box::new(parse_depend(action.clone())?)));
     }
 
     if action.starts_with("set") {
-        return match parse_set(action.clone()) {
+        return Ok(match parse_set(action.clone())? {
             Name::Obsolete => Results::Obsolete,
             Name::Renamed => Results::Renamed,
             Name::Other => Results::Other,
-        };
+        });
     }
 
     panic!("other unknown action: {}", &action.clone())
 }
 
-pub fn open_json_file(mut source_path: PathBuf) -> Value {
-    if !source_path.is_absolute() {
-        if let Ok(mut current_dir) = env::current_dir() {
-            current_dir.push(source_path);
-            source_path = current_dir;
-        } else {
-            error!("can not get current dir of {:?}", source_path);
-            exit(1);
-        }
-    }
+/// Returns `last-modified` (without fraction of second) from catalog.attrs in directory of
+/// catalog
+fn last_modified(catalog: &Path) -> Option<String> {
+    let attrs = catalog.parent()?.join("catalog.attrs");
+    let attrs: Value = serde_json::from_str(&read_to_string(attrs).ok()?).ok()?;
+    let last_modified = attrs.get("last-modified")?.as_str()?;
+
+    Some(match last_modified.split_once('.') {
+        Some((last_modified, _)) => format!("{}Z", last_modified),
+        None => last_modified.to_owned(),
+    })
+}
 
-    // open file
-    let mut file = File::open(source_path.clone())
-        .unwrap_or_else(|_| panic!("failed to open file {:?}", source_path));
+/// Checks that timestamp of [`FMRI`] is plausible and that versions with newer timestamps are
+/// not older than already loaded versions
+fn check_timestamp(problems: &mut Problems, fmri: &FMRI, loaded_versions: &[FMRI], now: &str) {
+    let timestamp_of = |fmri: &FMRI| VersionParts::from_fmri(fmri).and_then(|v| v.timestamp);
+
+    let Some(timestamp) = timestamp_of(fmri) else {
+        return;
+    };
+
+    if !is_plausible_timestamp(&timestamp, now) {
+        problems.add_problem(TimestampAnomaly(fmri.clone(), None));
+        return;
+    }
 
-    // get content
-    let mut contains = String::new();
-    file.read_to_string(&mut contains)
-        .expect("failed to read file");
-
-    // parse json and return
-    match serde_json::from_str::<Value>(&contains) {
-        Ok(json) => json,
-        Err(err) => {
-            error!(
-                "fatal invalid JSON found in {:?}, error: {}",
-                source_path, err
-            );
-            exit(1);
+    for loaded in loaded_versions {
+        if let Some(loaded_timestamp) = timestamp_of(loaded) {
+            if timestamp > loaded_timestamp && fmri.get_version_ref() < loaded.get_version_ref() {
+                problems.add_problem(TimestampAnomaly(fmri.clone(), Some(loaded.clone())));
+            } else if timestamp < loaded_timestamp
+                && fmri.get_version_ref() > loaded.get_version_ref()
+            {
+                problems.add_problem(TimestampAnomaly(loaded.clone(), Some(fmri.clone())));
+            }
         }
     }
 }
 
+/// Version of package in catalog with its actions
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CatalogVersion {
+    version: String,
+    #[serde(default)]
+    actions: Vec<String>,
+}
+
+/// State of loading of catalog, packages are loaded one by one while catalog is parsed, see
+/// [`load_catalog_c`]
+struct CatalogLoader<'a> {
+    components: &'a mut Components,
+    problems: &'a mut Problems,
+    package_names_in_pkg5_list: &'a ComponentPackagesList,
+    catalog_origin: Option<CatalogOrigin>,
+    catalog_timestamp: CatalogTimestamp,
+    now: String,
+}
+
+/// Loads packages of catalog (catalog.dependency.C) into components, catalog is parsed as stream
+/// (only versions of one package are in memory at once)
 pub fn load_catalog_c(
     components: &mut Components,
     source_path: PathBuf,
     problems: &mut Problems,
     package_names_in_pkg5_list: &ComponentPackagesList,
 ) {
-    // open json file
-    let json_value = open_json_file(source_path);
-
-    // for every publisher(String) nad packages(Object) in json
-    for (publisher, packages) in json_value.as_object().expect("expected object") {
-        // skip _SIGNATURE
-        if publisher == "_SIGNATURE" {
-            continue;
+    let catalog_origin =
+        components
+            .get_origin_of_catalog(&source_path)
+            .map(|(publisher, origin)| CatalogOrigin {
+                catalog: source_path.clone(),
+                publisher: publisher.clone(),
+                origin: origin.clone(),
+                packages: BTreeMap::new(),
+            });
+
+    let file = File::open(&source_path)
+        .unwrap_or_else(|_| panic!("failed to open file {:?}", source_path));
+
+    let mut loader = CatalogLoader {
+        components,
+        problems,
+        package_names_in_pkg5_list,
+        catalog_origin,
+        catalog_timestamp: CatalogTimestamp {
+            last_modified: last_modified(&source_path).unwrap_or_default(),
+            catalog: source_path.clone(),
+            publishers: vec![],
+        },
+        now: current_timestamp(),
+    };
+
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(file));
+    if let Err(err) = (&mut loader)
+        .deserialize(&mut deserializer)
+        .and_then(|_| deserializer.end())
+    {
+        error!(
+            "fatal invalid JSON found in {:?}, error: {}",
+            source_path, err
+        );
+        exit(1);
+    }
+
+    let CatalogLoader {
+        components,
+        catalog_origin,
+        catalog_timestamp,
+        ..
+    } = loader;
+    if let Some(catalog_origin) = catalog_origin {
+        components.add_catalog_origin(catalog_origin);
+    }
+    if !catalog_timestamp.last_modified.is_empty() {
+        components.add_catalog_timestamp(catalog_timestamp);
+    }
+
+    // remove empty components and package versions
+    components.remove_empty_package_versions();
+    components.remove_empty_components();
+}
+
+impl<'de> DeserializeSeed<'de> for &mut CatalogLoader<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+/// Visits publishers (`{"publisher": {"package": [versions]}}`) of catalog
+impl<'de> Visitor<'de> for &mut CatalogLoader<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("object of publishers")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(publisher) = map.next_key::<String>()? {
+            // skip _SIGNATURE
+            if publisher == "_SIGNATURE" {
+                map.next_value::<IgnoredAny>()?;
+                continue;
+            }
+
+            // aliases are replaced by name of publisher
+            let publisher = self.components.get_publisher_name(&publisher);
+            self.catalog_timestamp.publishers.push(publisher.clone());
+
+            map.next_value_seed(PublisherPackages {
+                loader: self,
+                publisher: &publisher,
+            })?;
         }
 
-        // for package_name(String), package_versions(Object) in packages
-        for (package_name, package_versions) in packages.as_object().expect("expected object") {
-            // create fmri of package
-            let mut fmri = FMRI::parse_raw(package_name).unwrap();
-            fmri.change_publisher(Publisher::new(publisher.clone()).unwrap());
-
-            // create package_versions with above fmri
-            let mut versions = PackageVersions::new(fmri.clone());
-
-            // for package_version(Object) in package_versions(Array)
-            for package_version in package_versions.as_array().expect("expected array") {
-                // Create dependencies
-                let mut dependencies = Dependencies::new();
-                let mut obsolete = false;
-                let mut renamed = false;
-
-                // for key(String)[actions|version] and value(array|String) in package_version
-                for (key, value) in package_version.as_object().expect("expected object") {
-                    if key == "actions" {
-                        // for action(String) in actions(array)
-                        for action in value.as_array().expect("array") {
-                            // parse action into dependency
-                            match parse_action(action.as_str().expect("str").to_owned()) {
-                                Results::Dependency(d_type) => {
-                                    dependencies.add(Dependency::new(&d_type));
-                                }
-                                Results::Obsolete => obsolete = true,
-                                Results::Renamed => renamed = true,
-                                Results::Other => {}
-                            }
-                        }
-                    } else if key == "version" {
-                        // get version of current package_version
-                        // it is changing on every package_version (will be used in *)
-                        fmri.change_version(
-                            Version::new(value.clone().as_str().expect("str").to_string()).unwrap(),
-                        );
-                    } else {
-                        panic!("unknown key: {}", key)
+        Ok(())
+    }
+}
+
+/// Packages of publisher in catalog
+struct PublisherPackages<'a, 'b> {
+    loader: &'a mut CatalogLoader<'b>,
+    publisher: &'a String,
+}
+
+impl<'de> DeserializeSeed<'de> for PublisherPackages<'_, '_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for PublisherPackages<'_, '_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("object of packages")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(package_name) = map.next_key::<String>()? {
+            let package_versions: Vec<CatalogVersion> = map.next_value()?;
+            self.loader
+                .load_package(self.publisher, &package_name, package_versions);
+        }
+
+        Ok(())
+    }
+}
+
+impl CatalogLoader<'_> {
+    /// Loads versions of package of publisher into components
+    fn load_package(
+        &mut self,
+        publisher: &str,
+        package_name: &str,
+        package_versions: Vec<CatalogVersion>,
+    ) {
+        let components = &mut *self.components;
+        let problems = &mut *self.problems;
+        let package_names_in_pkg5_list = self.package_names_in_pkg5_list;
+        let catalog_timestamp = &mut self.catalog_timestamp;
+        let now = &self.now;
+
+        // create fmri of package
+        let mut fmri = match FMRI::parse_raw(package_name) {
+            Ok(fmri) => fmri,
+            Err(_) => {
+                problems.add_problem(InvalidFmri(
+                    package_name.to_owned(),
+                    format!("catalog (publisher {})", publisher),
+                ));
+                return;
+            }
+        };
+        components.rewrite_fmri(&mut fmri);
+        match Publisher::new(publisher.to_owned()) {
+            Ok(publisher) => fmri.change_publisher(publisher),
+            Err(_) => {
+                problems.add_problem(InvalidFmri(
+                    format!("pkg://{}/{}", publisher, package_name),
+                    format!("catalog (publisher {})", publisher),
+                ));
+                return;
+            }
+        }
+
+        // create package_versions with above fmri
+        let mut versions = PackageVersions::new(fmri.clone());
+
+        // all loaded versions of package, used for timestamp validation
+        let mut loaded_versions: Vec<FMRI> = vec![];
+
+        // for package_version(Object) in package_versions(Array)
+        for package_version in package_versions {
+            let version = match Version::new(package_version.version.clone()) {
+                Ok(version) => version,
+                Err(_) => {
+                    problems.add_problem(InvalidFmri(
+                        format!("{}@{}", package_name, package_version.version),
+                        format!("catalog (publisher {})", publisher),
+                    ));
+                    continue;
+                }
+            };
+
+            // Create dependencies
+            let mut dependencies = Dependencies::new();
+            let mut obsolete = false;
+            let mut renamed = false;
+
+            // parse actions into dependencies
+            for action in package_version.actions {
+                match parse_action(action) {
+                    Ok(Results::Dependency(mut d_type)) => {
+                        components.rewrite_depend_type(&mut d_type);
+                        dependencies.add(Dependency::new(&d_type));
                     }
+                    Ok(Results::Obsolete) => obsolete = true,
+                    Ok(Results::Renamed) => renamed = true,
+                    Ok(Results::Other) => {}
+                    Err(raw_fmri) => problems.add_problem(InvalidFmri(
+                        raw_fmri,
+                        format!("catalog (actions of {})", package_name),
+                    )),
+                }
+            }
+
+            // version of current package_version, it is changing on every package_version
+            // (will be used in *)
+            fmri.change_version(version);
+
+            if let Some(timestamp) =
+                VersionParts::from_fmri(&fmri).and_then(|version| version.timestamp)
+            {
+                if catalog_timestamp.last_modified < timestamp {
+                    catalog_timestamp.last_modified = timestamp;
                 }
+            }
+
+            if components.is_strict_timestamps() {
+                check_timestamp(problems, &fmri, &loaded_versions, now);
+                loaded_versions.push(fmri.clone());
+            }
+
+            // create package with fmri with version of current package_version (changed in *)
+            let mut package = Package::new(fmri.clone(), obsolete, renamed);
+
+            for duplicate in dependencies.get_duplicates() {
+                problems.add_problem(DuplicateDependency(
+                    duplicate.get_ref().clone(),
+                    DependencyTypes::Runtime,
+                    Some(fmri.clone()),
+                    package_names_in_pkg5_list
+                        .get_component_name_of_package(&fmri)
+                        .cloned()
+                        .unwrap_or_default(),
+                ));
+            }
 
-                // create package with fmri with version of current package_version (changed in *)
-                let mut package = Package::new(fmri.clone(), obsolete, renamed);
+            if obsolete && !dependencies.get_ref().is_empty() {
+                problems.add_problem(ObsoleteWithDependencies(
+                    fmri.clone(),
+                    dependencies
+                        .get_ref()
+                        .iter()
+                        .map(|dependency| dependency.get_ref().clone())
+                        .collect(),
+                ));
+            }
 
-                // add dependencies into package
-                package.add_runtime_dependencies(dependencies);
+            // add dependencies into package
+            package.add_runtime_dependencies(dependencies);
 
-                // add package into package_versions
-                match versions.add_package(package.clone()) {
-                    None => {}
-                    Some(_) => {
-                        // add obsolete
-                        components.add_obsoleted(package.clone().fmri());
+            if !package.is_obsolete() && !package.is_renamed() {
+                components.add_published(fmri.clone());
+            }
 
-                        // TODO: RenamedPackageInComponent is already being collected in get_component_packages_of_package_versions (remove this?)
-                        if package.is_obsolete() {
-                            for component_packages in package_names_in_pkg5_list.get() {
-                                for package_in_pkg5 in
-                                    component_packages.packages_in_component.get_ref()
+            // add package into package_versions
+            match versions.add_package(package.clone()) {
+                None => {}
+                Some(_) => {
+                    // add obsolete
+                    components.add_obsoleted(package.clone().fmri());
+
+                    // TODO: RenamedPackageInComponent is already being collected in get_component_packages_of_package_versions (remove this?)
+                    if package.is_obsolete() {
+                        for component_packages in package_names_in_pkg5_list.get() {
+                            for package_in_pkg5 in
+                                component_packages.packages_in_component.get_ref()
+                            {
+                                if package.fmri_ref().get_package_name_as_ref_string()
+                                    == package_in_pkg5.get_package_name_as_ref_string()
                                 {
-                                    if package.fmri_ref().get_package_name_as_ref_string()
-                                        == package_in_pkg5.get_package_name_as_ref_string()
-                                    {
-                                        problems.add_problem(RenamedPackageInComponent(
-                                            package.clone().fmri(),
-                                            component_packages.component_name.clone(),
-                                        ));
-                                    }
+                                    problems.add_problem(RenamedPackageInComponent(
+                                        package.clone().fmri(),
+                                        component_packages.component_name.clone(),
+                                    ));
                                 }
                             }
-                        } else {
-                            panic!("function .add_package() can return Some(_) only when obsolete package is entered")
                         }
+                    } else {
+                        panic!("function .add_package() can return Some(_) only when obsolete package is entered")
                     }
                 }
             }
+        }
 
-            // create new component with only one package_versions
-            let mut component = Component::new("".to_owned());
-            component.add(versions);
-
-            // add component into components
-            components.add(component);
+        if let Some(catalog_origin) = &mut self.catalog_origin {
+            if catalog_origin.publisher == publisher && fmri.has_version() {
+                catalog_origin
+                    .packages
+                    .insert(fmri.get_package_name_as_ref_string().clone(), fmri.clone());
+            }
         }
+
+        // create new component with only one package_versions
+        let mut component = Component::new("".to_owned());
+        component.add(versions);
+
+        // add component into components
+        components.add(component);
     }
-    // remove empty components and package versions
-    components.remove_empty_package_versions();
-    components.remove_empty_components();
 }
@@ -0,0 +1,371 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env::var,
+    fs::{create_dir_all, read, read_to_string, remove_file, rename},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Mutex,
+    thread,
+};
+
+use log::{debug, info, warn};
+use serde_json::Value;
+use sha1_smol::Sha1;
+
+use crate::problems::{
+    Problem,
+    Problem::{NonExistingRequired, RequiredFromOtherDepot},
+    Problems,
+};
+
+/// Number of retries of download after transient error (e.g. timeout or 5xx response)
+const RETRIES: &str = "3";
+/// Timeout of connection to depot in seconds
+const CONNECT_TIMEOUT: &str = "30";
+/// Timeout of whole download in seconds
+const MAX_TIME: &str = "600";
+
+/// Number of packages looked up in depots in parallel
+const LOOKUP_JOBS: usize = 8;
+
+/// Checks that `curl`, which downloads catalogs and looks up packages in depots, can be run
+pub fn check_curl() -> Result<(), String> {
+    match Command::new("curl").arg("--version").output() {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(format!(
+            "curl is needed to download from depots, but it failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+        Err(e) => Err(format!(
+            "curl is needed to download from depots, but it can't be run: {}",
+            e
+        )),
+    }
+}
+
+/// Downloads catalog (catalog.attrs and all catalog parts it lists, e.g. catalog.base.C and
+/// catalog.dependency.C) of publisher from pkg(5) depot `url` (e.g.
+/// `https://pkg.openindiana.org/hipster`) into `directory` and returns path to
+/// catalog.dependency.C
+///
+/// SHA-1 of every part (computed as pkg(5) signs it) must match its signature in catalog.attrs,
+/// so damaged parts and parts from different catalog updates are not used.
+///
+/// With `cache`, files already in `directory` are requested only if they changed on depot (by
+/// ETag and Last-Modified) and parts whose signature matches catalog.attrs are not downloaded
+/// again.
+pub fn fetch_catalog(url: &str, directory: &Path, cache: bool) -> Result<PathBuf, String> {
+    create_dir_all(directory).map_err(|e| format!("failed to create {:?}: {}", directory, e))?;
+    let catalog_url = format!("{}/catalog/1", url.trim_end_matches('/'));
+
+    let attrs_path = directory.join("catalog.attrs");
+    download(
+        &format!("{}/catalog.attrs", catalog_url),
+        &attrs_path,
+        cache,
+    )?;
+    let attrs = load_json(&attrs_path)?;
+
+    let Some(parts) = attrs.get("parts").and_then(Value::as_object) else {
+        return Err(format!("catalog.attrs of {} doesn't list parts", url));
+    };
+
+    let mut changed_parts = vec![];
+    for (name, part_attrs) in parts {
+        if !name.starts_with("catalog.") || name.contains('/') {
+            return Err(format!("invalid catalog part {} of {}", name, url));
+        }
+
+        let part_path = directory.join(name);
+        let cached = cache
+            && read(&part_path)
+                .map_err(|e| e.to_string())
+                .and_then(|part| verify_signature(name, part_attrs, &part))
+                .is_ok();
+        if cached {
+            debug!("{:?} is up to date", part_path);
+            continue;
+        }
+
+        changed_parts.push((name, part_attrs, part_path));
+    }
+
+    // parts (e.g. catalog.base.C, catalog.dependency.C and catalog.summary.C) are downloaded
+    // concurrently
+    thread::scope(|scope| {
+        let downloads: Vec<_> = changed_parts
+            .iter()
+            .map(|(name, _, part_path)| {
+                let part_url = format!("{}/{}", catalog_url, name);
+                scope.spawn(move || download(&part_url, part_path, cache))
+            })
+            .collect();
+
+        downloads
+            .into_iter()
+            .try_for_each(|download| download.join().expect("download thread panicked"))
+    })?;
+
+    for (name, part_attrs, part_path) in &changed_parts {
+        let part = read(part_path).map_err(|e| format!("failed to read {:?}: {}", part_path, e))?;
+        verify_signature(name, part_attrs, &part)?;
+    }
+
+    if !parts.contains_key("catalog.dependency.C") {
+        return Err(format!("catalog of {} has no catalog.dependency.C", url));
+    }
+
+    info!("fetched catalog of {} into {:?}", url, directory);
+    Ok(directory.join("catalog.dependency.C"))
+}
+
+/// Fetches catalogs of more depots concurrently, see [`fetch_catalog`], every depot is given by
+/// URLs of its mirrors tried in order and directory, results are in order of `depots`
+pub fn fetch_catalogs(
+    depots: &[(Vec<String>, PathBuf)],
+    cache: bool,
+) -> Vec<Result<PathBuf, String>> {
+    thread::scope(|scope| {
+        let fetches: Vec<_> = depots
+            .iter()
+            .map(|(urls, directory)| {
+                scope.spawn(move || fetch_catalog_from_mirrors(urls, directory, cache))
+            })
+            .collect();
+
+        fetches
+            .into_iter()
+            .map(|fetch| fetch.join().expect("fetch thread panicked"))
+            .collect()
+    })
+}
+
+/// Fetches catalog from the first of `urls` that succeeds, returns error of the last one
+fn fetch_catalog_from_mirrors(
+    urls: &[String],
+    directory: &Path,
+    cache: bool,
+) -> Result<PathBuf, String> {
+    let mut result = Err("depot has no URL".to_owned());
+    for url in urls {
+        result = fetch_catalog(url, directory, cache);
+        match &result {
+            Ok(_) => break,
+            Err(e) => warn!("{}", e),
+        }
+    }
+    result
+}
+
+/// Returns proxy for `url` from `var` (e.g. environment variable), `http_proxy` for http and
+/// `https_proxy` for https URLs, lowercase names are preferred to uppercase names
+pub(crate) fn proxy_for(url: &str, var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let (scheme, _) = url.split_once("://")?;
+    let name = match scheme {
+        "http" => "http_proxy",
+        "https" => "https_proxy",
+        _ => return None,
+    };
+
+    var(name)
+        .or_else(|| var(&name.to_uppercase()))
+        .filter(|proxy| !proxy.is_empty())
+}
+
+/// Looks up packages required by [`NonExistingRequired`] problems in depots at `urls` (e.g.
+/// depots of other consolidations) and replaces problems of packages published in some of them
+/// with [`RequiredFromOtherDepot`]
+pub fn lookup_non_existing_required(problems: &mut Problems, urls: &[String]) {
+    let package_names: BTreeSet<String> = problems
+        .get_ref()
+        .iter()
+        .filter_map(|problem| match problem {
+            NonExistingRequired(depend_type, _, _, _) => depend_type.get_content_ref().ok(),
+            _ => None,
+        })
+        .map(|fmri| fmri.get_package_name_as_ref_string().clone())
+        .collect();
+
+    let queue = Mutex::new(package_names.into_iter());
+    let found = Mutex::new(BTreeMap::new());
+    thread::scope(|scope| {
+        for _ in 0..LOOKUP_JOBS {
+            scope.spawn(|| loop {
+                let Some(package_name) = queue.lock().unwrap().next() else {
+                    break;
+                };
+                let url = urls.iter().find(|url| {
+                    depot_has_package(url, &package_name)
+                        .map_err(|e| warn!("{}", e))
+                        .unwrap_or(false)
+                });
+                if let Some(url) = url {
+                    found.lock().unwrap().insert(package_name, url.clone());
+                }
+            });
+        }
+    });
+
+    downgrade_non_existing_required(problems, &found.into_inner().unwrap());
+}
+
+/// Replaces [`NonExistingRequired`] problems of packages in `found` (depot URL by package name)
+/// with [`RequiredFromOtherDepot`]
+pub(crate) fn downgrade_non_existing_required(
+    problems: &mut Problems,
+    found: &BTreeMap<String, String>,
+) {
+    let depot_of = |problem: &Problem| match problem {
+        NonExistingRequired(depend_type, _, _, _) => depend_type
+            .get_content_ref()
+            .ok()
+            .and_then(|fmri| found.get(fmri.get_package_name_as_ref_string())),
+        _ => None,
+    };
+
+    let downgraded: Vec<Problem> = problems
+        .get_ref()
+        .iter()
+        .filter_map(|problem| match (problem, depot_of(problem)) {
+            (
+                NonExistingRequired(depend_type, dependency_type, required_by, component_name),
+                Some(url),
+            ) => Some(RequiredFromOtherDepot(
+                depend_type.clone(),
+                dependency_type.clone(),
+                required_by.clone(),
+                component_name.clone(),
+                url.clone(),
+            )),
+            _ => None,
+        })
+        .collect();
+
+    problems.retain(|problem| depot_of(problem).is_none());
+    for problem in downgraded {
+        problems.add_problem(problem);
+    }
+}
+
+/// Returns true if depot at `url` has package `package_name` (info operation of pkg(5) depot
+/// finds its newest version)
+fn depot_has_package(url: &str, package_name: &str) -> Result<bool, String> {
+    let info_url = format!("{}/info/0/{}", url.trim_end_matches('/'), package_name);
+    debug!("looking up {}", info_url);
+
+    let mut command = Command::new("curl");
+    command
+        .args(["-sS", "-o", "/dev/null", "-w", "%{http_code}"])
+        .args(["--retry", RETRIES, "--connect-timeout", CONNECT_TIMEOUT])
+        .args(["--max-time", MAX_TIME]);
+    if let Some(proxy) = proxy_for(url, |name| var(name).ok()) {
+        command.arg("--proxy").arg(proxy);
+    }
+
+    let output = command
+        .arg(&info_url)
+        .output()
+        .map_err(|e| format!("failed to run curl: {}", e))?;
+
+    match String::from_utf8_lossy(&output.stdout).as_ref() {
+        "200" => Ok(true),
+        "404" => Ok(false),
+        code => Err(format!(
+            "failed to look up {}: {} {}",
+            info_url,
+            code,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+/// Checks that SHA-1 of content of catalog part `name` (see [`part_digest`]) matches
+/// `signature-sha-1` from its attributes in catalog.attrs
+pub(crate) fn verify_signature(name: &str, part_attrs: &Value, part: &[u8]) -> Result<(), String> {
+    let expected = part_attrs.get("signature-sha-1").and_then(Value::as_str);
+
+    match (expected, part_digest(part)) {
+        (None, _) => Err(format!("catalog.attrs has no signature of {}", name)),
+        (Some(_), None) => Err(format!("{} is not signed", name)),
+        (Some(expected), Some(digest)) if expected != digest => Err(format!(
+            "SHA-1 of {} is {}, but catalog.attrs expects {}",
+            name, digest, expected
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Returns SHA-1 of catalog part as pkg(5) computes it: part is written as `{...}\n` and hashed,
+/// then `,"_SIGNATURE":{...}` is inserted in front of the final `}\n`, so the hash is computed
+/// of content before the last `_SIGNATURE` member followed by `}\n`
+pub(crate) fn part_digest(part: &[u8]) -> Option<String> {
+    const SIGNATURE: &[u8] = b",\"_SIGNATURE\":";
+    let end = part
+        .windows(SIGNATURE.len())
+        .rposition(|window| window == SIGNATURE)?;
+
+    let mut sha1 = Sha1::new();
+    sha1.update(&part[..end]);
+    sha1.update(b"}\n");
+    Some(sha1.digest().to_string())
+}
+
+/// Downloads `url` into `path`, with `cache` existing `path` is replaced only if server doesn't
+/// respond with 304 Not Modified to request with its ETag (saved in `<path>.etag`) and
+/// modification time (set from Last-Modified)
+fn download(url: &str, path: &Path, cache: bool) -> Result<(), String> {
+    debug!("downloading {} into {:?}", url, path);
+    let downloaded = PathBuf::from(format!("{}.download", path.to_string_lossy()));
+    let etag = PathBuf::from(format!("{}.etag", path.to_string_lossy()));
+    let downloaded_etag = PathBuf::from(format!("{}.etag", downloaded.to_string_lossy()));
+
+    let mut command = Command::new("curl");
+    command
+        .args(["-fsSLR", "-w", "%{http_code}"])
+        .args(["--retry", RETRIES, "--connect-timeout", CONNECT_TIMEOUT])
+        .args(["--max-time", MAX_TIME, "-o"])
+        .arg(&downloaded)
+        .arg("--etag-save")
+        .arg(&downloaded_etag);
+    // curl ignores HTTP_PROXY (uppercase)
+    if let Some(proxy) = proxy_for(url, |name| var(name).ok()) {
+        command.arg("--proxy").arg(proxy);
+    }
+    if cache && path.exists() {
+        command.arg("-z").arg(path);
+        if etag.exists() {
+            command.arg("--etag-compare").arg(&etag);
+        }
+    }
+
+    let output = command
+        .arg(url)
+        .output()
+        .map_err(|e| format!("failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "failed to download {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    // curl doesn't create output if file is not modified (for file:// URLs too)
+    if String::from_utf8_lossy(&output.stdout) == "304" || !downloaded.exists() {
+        debug!("{} is not modified", url);
+        let _ = remove_file(&downloaded);
+        let _ = remove_file(&downloaded_etag);
+        return Ok(());
+    }
+
+    rename(&downloaded, path).map_err(|e| format!("failed to write {:?}: {}", path, e))?;
+    if rename(&downloaded_etag, &etag).is_err() {
+        let _ = remove_file(&etag);
+    }
+    Ok(())
+}
+
+fn load_json(path: &Path) -> Result<Value, String> {
+    let content = read_to_string(path).map_err(|e| format!("failed to read {:?}: {}", path, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("invalid json {:?}: {}", path, e))
+}
@@ -0,0 +1,131 @@
+use std::{fs::read_to_string, path::Path};
+
+use fmri::FMRI;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::{
+        catalogs_c::parse_depend, manifest_dependencies::depend_actions,
+        open_indiana_oi_userland_git::manifest_paths,
+    },
+    packages::actions::{parse_actions, Action},
+    problems::{Problem::InvalidFmri, Problems},
+    DependTypes,
+};
+
+/// Package obsoleted (no targets) or renamed (to targets) in history of oi-userland
+#[derive(PartialEq, Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryRecord {
+    /// package with version of obsoletion or rename
+    pub fmri: FMRI,
+    pub renamed_to: Vec<FMRI>,
+}
+
+impl HistoryRecord {
+    pub fn is_renamed(&self) -> bool {
+        !self.renamed_to.is_empty()
+    }
+}
+
+/// Loads history of obsoleted and renamed packages from `meta-packages/history` in components of
+/// oi-userland: file `history` (`fmri [renamed-to ...] [noincorporate]` per line) and
+/// obsoletion or rename stubs (*.p5m)
+pub fn load_history(components_path: &Path, problems: &mut Problems) -> Vec<HistoryRecord> {
+    let directory = components_path.join("meta-packages/history");
+    let mut history = vec![];
+
+    let file = directory.join("history");
+    if let Ok(content) = read_to_string(&file) {
+        history.append(&mut parse_history(&content, &file, problems));
+    }
+
+    for stub in manifest_paths(&directory) {
+        let Ok(content) = read_to_string(&stub) else {
+            continue;
+        };
+        match parse_history_stub(&content) {
+            Ok(Some(record)) => history.push(record),
+            Ok(None) => {}
+            Err(raw_fmri) => {
+                problems.add_problem(InvalidFmri(raw_fmri, stub.to_string_lossy().to_string()))
+            }
+        }
+    }
+
+    history
+}
+
+/// Parses lines of history file, lines with invalid FMRIs are reported and skipped
+pub(crate) fn parse_history(
+    content: &str,
+    path: &Path,
+    problems: &mut Problems,
+) -> Vec<HistoryRecord> {
+    let mut history = vec![];
+
+    'lines: for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut fmris = vec![];
+        for token in line
+            .split_whitespace()
+            .filter(|token| *token != "noincorporate")
+        {
+            match FMRI::parse_raw(token) {
+                Ok(fmri) => fmris.push(fmri),
+                Err(_) => {
+                    problems.add_problem(InvalidFmri(
+                        token.to_owned(),
+                        path.to_string_lossy().to_string(),
+                    ));
+                    continue 'lines;
+                }
+            }
+        }
+
+        if fmris.is_empty() {
+            continue;
+        }
+        let fmri = fmris.remove(0);
+        history.push(HistoryRecord {
+            fmri,
+            renamed_to: fmris,
+        });
+    }
+
+    history
+}
+
+/// Parses obsoletion or rename stub (manifest with `pkg.obsolete` or `pkg.renamed`, rename
+/// targets are its require dependencies), returns [`None`] for other manifests and invalid FMRI
+/// as error
+pub(crate) fn parse_history_stub(content: &str) -> Result<Option<HistoryRecord>, String> {
+    let (mut fmri, mut obsolete, mut renamed) = (None, false, false);
+
+    for action in parse_actions(content) {
+        let Action::Set(set) = action else {
+            continue;
+        };
+        let value = set.values.first().cloned().unwrap_or_default();
+        match set.name.as_str() {
+            "pkg.fmri" => fmri = Some(FMRI::parse_raw(&value).map_err(|_| value)?),
+            "pkg.obsolete" => obsolete = value == "true",
+            "pkg.renamed" => renamed = value == "true",
+            _ => {}
+        }
+    }
+
+    let Some(fmri) = fmri.filter(|_| obsolete || renamed) else {
+        return Ok(None);
+    };
+
+    let mut renamed_to = vec![];
+    if renamed {
+        for action in depend_actions(content) {
+            if let Ok(DependTypes::Require(target)) = parse_depend(action) {
+                renamed_to.push(target);
+            }
+        }
+    }
+
+    Ok(Some(HistoryRecord { fmri, renamed_to }))
+}
@@ -0,0 +1,93 @@
+use std::{fs::read_to_string, path::Path};
+
+use fmri::{Publisher, Version, FMRI};
+use serde_json::Value;
+
+/// Loads installed packages of image from output of `pkg list -H` (or `pkg list -Hv`) saved in
+/// file or from image metadata directory (`/var/pkg`, installed packages are in
+/// `state/installed/catalog.base.C`)
+pub fn load_installed_packages(path: &Path) -> Result<Vec<FMRI>, String> {
+    if path.is_dir() {
+        let catalog = path.join("state/installed/catalog.base.C");
+        let content =
+            read_to_string(&catalog).map_err(|e| format!("failed to read {:?}: {}", catalog, e))?;
+        return parse_installed_catalog(&content)
+            .map_err(|e| format!("invalid catalog {:?}: {}", catalog, e));
+    }
+
+    let content = read_to_string(path).map_err(|e| format!("failed to read {:?}: {}", path, e))?;
+    parse_pkg_list(&content)
+}
+
+/// Parses lines of `pkg list -H` (`name (publisher) version IFO`, publisher is printed only if it
+/// is not the preferred one) or `pkg list -Hv` (`fmri IFO`)
+pub(crate) fn parse_pkg_list(content: &str) -> Result<Vec<FMRI>, String> {
+    let mut installed = vec![];
+
+    for line in content.lines() {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let invalid = || format!("invalid line of pkg list: {}", line);
+
+        let fmri = match columns.as_slice() {
+            [] => continue,
+            [fmri, _] if fmri.starts_with("pkg:/") => {
+                FMRI::parse_raw(fmri).map_err(|_| invalid())?
+            }
+            [name, publisher, version, _]
+                if publisher.starts_with('(') && publisher.ends_with(')') =>
+            {
+                let mut fmri = package_fmri(name, version).ok_or_else(invalid)?;
+                fmri.change_publisher(
+                    Publisher::new(publisher.trim_matches(['(', ')']).to_owned())
+                        .map_err(|_| invalid())?,
+                );
+                fmri
+            }
+            [name, version, _] => package_fmri(name, version).ok_or_else(invalid)?,
+            _ => return Err(invalid()),
+        };
+
+        installed.push(fmri);
+    }
+
+    Ok(installed)
+}
+
+/// Parses catalog.base.C of installed packages (publisher, package name and versions)
+pub(crate) fn parse_installed_catalog(content: &str) -> Result<Vec<FMRI>, String> {
+    let catalog: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let Some(publishers) = catalog.as_object() else {
+        return Err("expected object".to_owned());
+    };
+
+    let mut installed = vec![];
+    for (publisher, packages) in publishers {
+        if publisher.starts_with('_') {
+            continue;
+        }
+
+        for (name, versions) in packages.as_object().into_iter().flatten() {
+            for version in versions.as_array().into_iter().flatten() {
+                let Some(version) = version.get("version").and_then(Value::as_str) else {
+                    return Err(format!("{} has no version", name));
+                };
+
+                let mut fmri = package_fmri(name, version)
+                    .ok_or_else(|| format!("invalid package {}@{}", name, version))?;
+                fmri.change_publisher(
+                    Publisher::new(publisher.clone())
+                        .map_err(|_| format!("invalid publisher {}", publisher))?,
+                );
+                installed.push(fmri);
+            }
+        }
+    }
+
+    Ok(installed)
+}
+
+fn package_fmri(name: &str, version: &str) -> Option<FMRI> {
+    let mut fmri = FMRI::parse_raw(name).ok()?;
+    fmri.change_version(Version::new(version.to_owned()).ok()?);
+    Some(fmri)
+}
@@ -0,0 +1,185 @@
+use std::{
+    collections::BTreeMap,
+    fs::read,
+    path::{Path, PathBuf},
+};
+
+use bincode::{deserialize, serialize};
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    git::{changed_files, head_commit},
+    state_file::{read_locked, write_atomic},
+};
+
+/// Outputs of gmake cached between runs, outputs are valid for components that didn't change
+/// since `commit` of oi-userland and whose Makefile with included files has the same hash
+#[derive(PartialEq, Serialize, Deserialize, Default, Clone, Debug)]
+pub struct MakeCache {
+    commit: Option<String>,
+    /// output of make target by component path and make target
+    outputs: BTreeMap<PathBuf, BTreeMap<String, String>>,
+    /// hash of Makefile and included files by component path, see [`makefile_hash`]
+    hashes: BTreeMap<PathBuf, u64>,
+}
+
+impl MakeCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads cache from file, returns empty cache if file doesn't exist or is invalid
+    pub fn load<P: AsRef<Path> + ?Sized>(path: &P) -> Self {
+        if !path.as_ref().exists() {
+            return Self::new();
+        }
+
+        read_locked(path)
+            .and_then(|data| deserialize(&data).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| {
+                debug!("ignoring make cache {:?}: {}", path.as_ref(), e);
+                Self::new()
+            })
+    }
+
+    /// Writes cache atomically under lock, see [`write_atomic`]
+    pub fn save<P: AsRef<Path> + ?Sized>(&self, path: &P) -> Result<(), String> {
+        write_atomic(path, &serialize(self).map_err(|e| e.to_string())?)
+    }
+
+    /// Drops outputs of components changed since cached commit (`git diff --name-only`, including
+    /// uncommitted changes) of git `repository` and outputs of components with changed hash of
+    /// Makefile, only hashes are checked if diff is unknown
+    pub fn invalidate(&mut self, repository: &Path) {
+        let head = head_commit(repository).map_err(|e| debug!("{}", e)).ok();
+
+        let changed_files = match (&self.commit, &head) {
+            (Some(commit), Some(_)) => changed_files(repository, commit)
+                .map_err(|e| debug!("{}", e))
+                .ok(),
+            _ => None,
+        };
+
+        match changed_files {
+            Some(changed_files) => {
+                let changed_files: Vec<&str> = changed_files.iter().map(String::as_str).collect();
+                self.invalidate_changed(repository, &changed_files)
+            }
+            None => debug!(
+                "changes of {:?} are unknown, checking only hashes",
+                repository
+            ),
+        }
+
+        self.commit = head;
+        self.invalidate_changed_hashes();
+    }
+
+    /// Drops outputs of components whose Makefile or files it includes changed (or whose hash is
+    /// unknown)
+    pub fn invalidate_changed_hashes(&mut self) {
+        let hashes = &self.hashes;
+        self.outputs.retain(|component_path, _| {
+            let valid = hashes.get(component_path) == Some(&makefile_hash(component_path));
+            if !valid {
+                debug!(
+                    "Makefile of {:?} changed, dropping make cache",
+                    component_path
+                );
+            }
+            valid
+        });
+        self.hashes
+            .retain(|component_path, _| self.outputs.contains_key(component_path));
+    }
+
+    /// Drops outputs of components with changed files (relative to `repository`), change outside
+    /// of components (e.g. in make-rules) drops all outputs
+    pub fn invalidate_changed(&mut self, repository: &Path, changed_files: &[&str]) {
+        for changed_file in changed_files {
+            if !changed_file.starts_with("components/") {
+                debug!("{} changed, dropping make cache", changed_file);
+                self.outputs.clear();
+                self.hashes.clear();
+                return;
+            }
+
+            let changed_file = repository.join(changed_file);
+            self.outputs
+                .retain(|component_path, _| !changed_file.starts_with(component_path));
+            self.hashes
+                .retain(|component_path, _| !changed_file.starts_with(component_path));
+        }
+    }
+
+    pub fn get(&self, component_path: &Path, target: &str) -> Option<&String> {
+        self.outputs.get(component_path)?.get(target)
+    }
+
+    pub fn insert(&mut self, component_path: PathBuf, target: String, output: String) {
+        self.hashes
+            .entry(component_path.clone())
+            .or_insert_with(|| makefile_hash(&component_path));
+        self.outputs
+            .entry(component_path)
+            .or_default()
+            .insert(target, output);
+    }
+}
+
+/// Returns FNV-1a hash of Makefile of component and files it includes (recursively),
+/// `$(WS_MAKE_RULES)` and `$(WS_TOP)` in included paths are resolved, other includes with macros
+/// are skipped
+pub fn makefile_hash(component_path: &Path) -> u64 {
+    let workspace = component_path
+        .ancestors()
+        .find(|directory| directory.join("make-rules").is_dir());
+
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut visited: Vec<PathBuf> = vec![];
+    let mut queue = vec![component_path.join("Makefile")];
+
+    while let Some(path) = queue.pop() {
+        if visited.contains(&path) {
+            continue;
+        }
+        visited.push(path.clone());
+
+        let content = read(&path).unwrap_or_default();
+        for byte in path
+            .to_string_lossy()
+            .bytes()
+            .chain(content.iter().copied())
+        {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+
+        let directory = path.parent().unwrap_or(component_path).to_path_buf();
+        for line in String::from_utf8_lossy(&content).lines() {
+            let Some(included) = ["include ", "-include ", "sinclude "]
+                .iter()
+                .find_map(|directive| line.trim_start().strip_prefix(directive))
+            else {
+                continue;
+            };
+
+            for included in included.split_whitespace() {
+                let resolved = match (workspace, included) {
+                    (Some(workspace), _) if included.starts_with("$(WS_MAKE_RULES)/") => workspace
+                        .join("make-rules")
+                        .join(&included["$(WS_MAKE_RULES)/".len()..]),
+                    (Some(workspace), _) if included.starts_with("$(WS_TOP)/") => {
+                        workspace.join(&included["$(WS_TOP)/".len()..])
+                    }
+                    _ if included.contains('$') => continue,
+                    _ => directory.join(included),
+                };
+                queue.push(resolved);
+            }
+        }
+    }
+
+    hash
+}
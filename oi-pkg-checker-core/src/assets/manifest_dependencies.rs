@@ -0,0 +1,164 @@
+use std::{
+    fs::{read_dir, read_to_string},
+    path::{Path, PathBuf},
+};
+
+use fmri::FMRI;
+use log::debug;
+
+use crate::{
+    assets::{
+        catalogs_c::parse_depend,
+        open_indiana_oi_userland_git::{load_makefile_variable, manifest_paths, parse_manifest},
+    },
+    packages::{
+        actions::{parse_actions, Action},
+        dependencies::Dependencies,
+        dependency::Dependency,
+    },
+    problems::{Problem::InvalidFmri, Problems},
+    ComponentPackagesList,
+};
+
+/// Depend types that can be loaded into [`crate::DependTypes`]
+const DEPEND_TYPES: [&str; 6] = [
+    "require",
+    "optional",
+    "incorporate",
+    "require-any",
+    "conditional",
+    "group",
+];
+
+/// Returns packages of pkg5 manifests (*.p5m) of components with runtime dependencies from their
+/// depend actions
+///
+/// Output of manifest in build directory of component is preferred to manifest itself,
+/// `build/manifest-$(MACH)-<name>.depend.res` (resolved by pkgdepend) before
+/// `build/manifest-$(MACH)-<name>.mogrified` (after pkgmogrify transforms). Depend actions with
+/// unresolved macros or dependencies (`__TBD`) are skipped.
+pub fn load_manifest_dependencies(
+    component_packages_list: &ComponentPackagesList,
+    problems: &mut Problems,
+) -> Vec<(FMRI, Dependencies)> {
+    let mut packages = vec![];
+
+    for component_packages in component_packages_list.get() {
+        let component_path = &component_packages.path_to_component;
+        let component_fmri =
+            load_makefile_variable(&component_path.join("Makefile"), "COMPONENT_FMRI");
+
+        for manifest in manifest_paths(component_path) {
+            let Ok(content) = read_to_string(&manifest) else {
+                continue;
+            };
+
+            let Some(fmri) = parse_manifest(&content, component_fmri.as_deref())
+                .0
+                .and_then(|package_name| FMRI::parse_raw(&package_name).ok())
+            else {
+                continue;
+            };
+
+            let (source, content) = match build_output(&manifest)
+                .and_then(|path| read_to_string(&path).ok().map(|content| (path, content)))
+            {
+                Some(output) => output,
+                None => (manifest, content),
+            };
+            debug!("loading dependencies of {} from {:?}", fmri, source);
+
+            let mut dependencies = Dependencies::new();
+            for action in depend_actions(&content) {
+                match parse_depend(action) {
+                    Ok(depend_type) => dependencies.add(Dependency::new(&depend_type)),
+                    Err(raw_fmri) => problems
+                        .add_problem(InvalidFmri(raw_fmri, source.to_string_lossy().to_string())),
+                }
+            }
+
+            packages.push((fmri, dependencies));
+        }
+    }
+
+    packages
+}
+
+/// Returns packages of pkg5 manifests (*.p5m) of components with their actions (except depend
+/// actions), output of manifest in build directory is preferred like in
+/// [`load_manifest_dependencies`]
+pub fn load_manifest_actions(
+    component_packages_list: &ComponentPackagesList,
+) -> Vec<(FMRI, Vec<Action>)> {
+    let mut packages = vec![];
+
+    for component_packages in component_packages_list.get() {
+        let component_path = &component_packages.path_to_component;
+        let component_fmri =
+            load_makefile_variable(&component_path.join("Makefile"), "COMPONENT_FMRI");
+
+        for manifest in manifest_paths(component_path) {
+            let Ok(content) = read_to_string(&manifest) else {
+                continue;
+            };
+
+            let Some(fmri) = parse_manifest(&content, component_fmri.as_deref())
+                .0
+                .and_then(|package_name| FMRI::parse_raw(&package_name).ok())
+            else {
+                continue;
+            };
+
+            let content = build_output(&manifest)
+                .and_then(|path| read_to_string(path).ok())
+                .unwrap_or(content);
+
+            packages.push((fmri, parse_actions(&content)));
+        }
+    }
+
+    packages
+}
+
+/// Returns depend actions of manifest that can be parsed
+pub(crate) fn depend_actions(content: &str) -> Vec<String> {
+    content
+        .replace("\\\n", " ")
+        .lines()
+        .map(|action| action.split_whitespace().collect::<Vec<&str>>())
+        .filter(|tokens| {
+            tokens.first() == Some(&"depend")
+                && tokens[1..].iter().all(|token| token.contains('='))
+                && tokens.iter().any(|token| {
+                    token
+                        .strip_prefix("type=")
+                        .is_some_and(|depend_type| DEPEND_TYPES.contains(&depend_type))
+                })
+                && !tokens
+                    .iter()
+                    .any(|token| token.contains("$(") || token.contains("__TBD"))
+        })
+        .map(|tokens| tokens.join(" "))
+        .collect()
+}
+
+/// Returns path of the most processed output of manifest in build directory of component
+fn build_output(manifest: &Path) -> Option<PathBuf> {
+    let name = manifest.file_stem()?.to_string_lossy().to_string();
+    let build_directory = manifest.parent()?.join("build");
+
+    [".depend.res", ".mogrified"].iter().find_map(|extension| {
+        let mut outputs: Vec<PathBuf> = read_dir(&build_directory)
+            .ok()?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|file_name| file_name.to_str()?.strip_prefix("manifest-"))
+                    .and_then(|file_name| file_name.split_once('-'))
+                    .is_some_and(|(_, file_name)| file_name == format!("{}{}", name, extension))
+            })
+            .collect();
+        outputs.sort();
+        outputs.into_iter().next()
+    })
+}
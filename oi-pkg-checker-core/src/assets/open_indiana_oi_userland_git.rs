@@ -1,22 +1,37 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::{read_dir, read_to_string},
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, Output},
+    sync::Mutex,
+    thread,
 };
 
 use fmri::{FMRI, fmri_list::FMRIList};
+use log::{debug, warn};
+use regex::Regex;
+use serde_json::Value;
 
 use crate::{
-    assets::catalogs_c::open_json_file,
+    assets::make_cache::MakeCache,
+    config::{Classifications, ComponentDiscovery, EncumberedLicenses},
+    namespace_filter::NamespaceFilter,
     Components,
     Dependencies, DependencyTypes, DependencyTypes::{Build, SystemBuild, SystemTest, Test},
+    MakefileMetadata,
     PackageVersions,
+    packages::actions::Action,
     problems::{
         Problem::{
-            MissingComponentForPackage, ObsoletedPackageInComponent, PackageInMultipleComponents,
-            RenamedPackageInComponent, UnRunnableMakeCommand,
+            DuplicateComponentName, DuplicateDependency, DuplicatePkg5Entry, EncumberedPlacement,
+            FileConflict, ForeignPackageInPkg5, InvalidClassification, InvalidFmri, InvalidPkg5Key,
+            MalformedPkg5, MediatorConflict, MissingComponentForPackage, MissingComponentLicense,
+            MissingLicenseAction, ObsoleteComponent, ObsoletedPackageBuilt,
+            ObsoletedPackageInComponent, PackageInMultipleComponents, PackageMissingInPkg5,
+            PackageVersionsInMultipleComponents, Pkg5NameMismatch, RenamedPackageInComponent,
+            StalePkg5Entry, UnRunnableMakeCommand,
         },
-        Problems,
+        AlternativeState, MakeFailure, Problems,
     },
 };
 use crate::problems::Problem::NonExistingPackageInPkg5;
@@ -32,7 +47,7 @@ pub struct ComponentPackages {
 }
 
 impl ComponentPackagesList {
-    pub fn new(oi_userland_components: &Path) -> Self {
+    pub fn new(oi_userland_components: &Path, problems: &mut Problems) -> Self {
         let components_path = oi_userland_components.to_string_lossy();
 
         let _output = Command::new("sh")
@@ -52,35 +67,123 @@ impl ComponentPackagesList {
             .output()
             .expect("failed to run command");
 
-        let mut component_packages_list: Self = Self(vec![]);
+        let component_directories: Vec<String> = String::from_utf8(output.stdout)
+            .unwrap()
+            .split('\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| line.split_whitespace().last().unwrap().to_owned())
+            .collect();
+
+        Self::from_component_directories(oi_userland_components, component_directories, problems)
+    }
+
+    /// Finds components with gmake (components.mk) or by scanning directories and merges
+    /// components of overlays into them, see [`ComponentDiscovery`]
+    pub fn discover(
+        oi_userland_components: &Path,
+        discovery: &ComponentDiscovery,
+        problems: &mut Problems,
+    ) -> Self {
+        let mut component_packages_list = match discovery.scan {
+            true => Self::scan(oi_userland_components, &discovery.ignore, problems),
+            false => Self::new(oi_userland_components, problems),
+        };
+
+        for overlay in &discovery.overlays {
+            component_packages_list.overlay(Self::scan(overlay, &discovery.ignore, problems));
+        }
+
+        component_packages_list
+    }
+
+    /// Merges components of `overlay`, they replace components with the same name
+    pub fn overlay(&mut self, overlay: Self) {
+        for component_packages in overlay.0 {
+            match self
+                .0
+                .iter_mut()
+                .find(|upstream| upstream.component_name == component_packages.component_name)
+            {
+                Some(upstream) => {
+                    debug!(
+                        "component {} is shadowed by {:?}",
+                        upstream.component_name, component_packages.path_to_component
+                    );
+                    *upstream = component_packages
+                }
+                None => self.0.push(component_packages),
+            }
+        }
+    }
+
+    /// Finds components without gmake (checkout is not changed), component is directory with
+    /// Makefile and pkg5 in `oi_userland_components` or in its subdirectory (e.g.
+    /// `python/requests`), directories matching `ignore` patterns (see [`ComponentDiscovery`])
+    /// are skipped
+    pub fn scan(oi_userland_components: &Path, ignore: &[String], problems: &mut Problems) -> Self {
+        let ignore = NamespaceFilter::new(vec![], ignore.to_vec());
+        let is_component = |directory: &Path, name: &str| {
+            directory.join("Makefile").exists()
+                && directory.join("pkg5").exists()
+                && ignore.allows(name)
+        };
+        let mut component_directories = vec![];
 
-        for line in String::from_utf8(output.stdout).unwrap().split('\n') {
-            if line.is_empty() {
+        for directory in sorted_directories(oi_userland_components) {
+            let Some(name) = directory
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+            else {
                 continue;
+            };
+
+            if directory.join("Makefile").exists() {
+                if is_component(&directory, &name) {
+                    component_directories.push(name);
+                }
+                continue;
+            }
+
+            for subdirectory in sorted_directories(&directory) {
+                if let Some(subname) = subdirectory.file_name() {
+                    let subname = format!("{}/{}", name, subname.to_string_lossy());
+                    if is_component(&subdirectory, &subname) {
+                        component_directories.push(subname);
+                    }
+                }
             }
+        }
+
+        Self::from_component_directories(oi_userland_components, component_directories, problems)
+    }
+
+    /// Loads pkg5 of components in directories relative to `oi_userland_components`
+    fn from_component_directories(
+        oi_userland_components: &Path,
+        component_directories: Vec<String>,
+        problems: &mut Problems,
+    ) -> Self {
+        let components_path = oi_userland_components.to_string_lossy();
+        let mut component_packages_list: Self = Self(vec![]);
 
-            let component_name = line.split_whitespace().last().unwrap().to_owned();
+        for component_directory in component_directories {
+            let component_name = component_directory
+                .trim_start_matches("./")
+                .trim_end_matches('/')
+                .to_owned();
 
             let path_to_component =
-                PathBuf::from(format!("{}/{}", components_path, component_name));
-
-            let mut packages_in_component = FMRIList::new();
-            for fmri in open_json_file(
-                PathBuf::from(format!(
-                    "{}/pkg5",
-                    path_to_component.clone().to_string_lossy()
-                )), // pkg5 location
-            )
-            .as_object()
-            .expect("expect object")
-            .get("fmris")
-            .expect("expect fmris")
-            .as_array()
-            .expect("expect array")
-            {
-                packages_in_component
-                    .add(FMRI::parse_raw(fmri.as_str().expect("expect string")).unwrap())
-            }
+                PathBuf::from(format!("{}/{}", components_path, component_directory));
+
+            // pkg5 location
+            let pkg5_path = PathBuf::from(format!(
+                "{}/pkg5",
+                path_to_component.clone().to_string_lossy()
+            ));
+
+            let Some(packages_in_component) = load_packages_in_pkg5(&pkg5_path, problems) else {
+                continue;
+            };
 
             component_packages_list.0.push(ComponentPackages {
                 component_name,
@@ -96,6 +199,29 @@ impl ComponentPackagesList {
         &self.0
     }
 
+    /// Returns name of component that has package with name of `fmri` in pkg5
+    pub fn get_component_name_of_package(&self, fmri: &FMRI) -> Option<&String> {
+        self.0
+            .iter()
+            .find(|component_packages| {
+                component_packages
+                    .packages_in_component
+                    .get_ref()
+                    .iter()
+                    .any(|package| package.package_name_eq(fmri))
+            })
+            .map(|component_packages| &component_packages.component_name)
+    }
+
+    /// Rewrites packages in components with [rewrite rules][crate::Components::set_rewrite_rules]
+    pub fn rewrite(&mut self, components: &mut Components) {
+        for component_packages in &mut self.0 {
+            for fmri in component_packages.packages_in_component.get_ref_mut() {
+                components.rewrite_fmri(fmri)
+            }
+        }
+    }
+
     pub fn get_component_packages_of_package_versions(
         &self,
         problems: &mut Problems,
@@ -132,73 +258,646 @@ impl ComponentPackagesList {
         None
     }
 
+    /// Returns dependencies of type printed by gmake in component directory, output of gmake is
+    /// taken from `make_cache` or from `prefetched` outputs if possible
     fn get_dependencies_of_component(
         &self,
         problems: &mut Problems,
+        make_cache: &mut MakeCache,
         component_path: PathBuf,
         dependencies_type: &DependencyTypes,
+        native_makefiles: bool,
+        prefetched: &HashMap<PathBuf, Output>,
     ) -> Result<FMRIList, ()> {
-        let mut make_command: String = "gmake ".to_owned();
+        let (make_command, target) = make_command(dependencies_type);
 
-        #[cfg(target_os = "linux")]
-        make_command.push_str("GSED=/usr/bin/sed ");
+        let native_output =
+            native_dependencies(&component_path, dependencies_type, native_makefiles);
 
-        make_command.push_str(match dependencies_type {
-            Build => "print-value-REQUIRED_PACKAGES",
-            Test => "print-value-TEST_REQUIRED_PACKAGES",
-            SystemBuild => "print-value-USERLAND_REQUIRED_PACKAGES",
-            SystemTest => "print-value-USERLAND_TEST_REQUIRED_PACKAGES",
-            _ => panic!(),
-        });
+        let binding = match (native_output, make_cache.get(&component_path, target)) {
+            (Some(output), _) => output,
+            (None, Some(output)) => output.clone(),
+            (None, None) => {
+                let command = prefetched
+                    .get(&component_path)
+                    .cloned()
+                    .unwrap_or_else(|| run_make(&component_path, &make_command));
 
-        let command = Command::new("sh")
-            .arg("-c")
-            .arg(format!(
-                "cd {} && {}",
-                component_path.to_string_lossy(),
-                make_command
-            ))
-            .output()
-            .expect("failed to run command");
+                if !command.status.success() {
+                    let stderr = String::from_utf8_lossy(&command.stderr);
+                    problems.add_problem(UnRunnableMakeCommand(
+                        make_command.to_owned(),
+                        component_path,
+                        MakeFailure::classify(&stderr, command.status.code()),
+                        stderr_snippet(&stderr),
+                    ));
 
-        if command.status.code().unwrap() != 0 {
-            problems.add_problem(UnRunnableMakeCommand(
-                make_command.to_owned(),
-                component_path,
-            ));
+                    return Err(());
+                }
+
+                let output = String::from_utf8(command.stdout).unwrap();
+                make_cache.insert(component_path.clone(), target.to_owned(), output.clone());
+                output
+            }
+        };
 
-            return Err(());
+        let mut fmri_list: Vec<FMRI> = vec![];
+        for raw_fmri in binding.split_whitespace() {
+            match FMRI::parse_raw(raw_fmri) {
+                Ok(fmri) => fmri_list.push(fmri),
+                Err(_) => problems.add_problem(InvalidFmri(
+                    raw_fmri.to_owned(),
+                    format!("{} in {:?}", make_command, component_path),
+                )),
+            }
         }
 
-        let binding = String::from_utf8(command.stdout).unwrap();
+        Ok(FMRIList::from(fmri_list))
+    }
 
-        let fmri_list: Vec<FMRI> = binding
-            .split_whitespace()
-            .map(|fmri| FMRI::parse_raw(fmri).unwrap())
-            .collect();
+    /// Returns BUILD_STYLE by component name, components without BUILD_STYLE in Makefile are
+    /// skipped
+    pub fn get_build_styles(&self) -> HashMap<String, String> {
+        self.get()
+            .iter()
+            .filter_map(|component_packages| {
+                load_makefile_variable(
+                    &component_packages.path_to_component.join("Makefile"),
+                    "BUILD_STYLE",
+                )
+                .map(|build_style| (component_packages.component_name.clone(), build_style))
+            })
+            .collect()
+    }
 
-        Ok(FMRIList::from(fmri_list))
+    /// finds component names listed more times (e.g. `library/a` and `./library/a/`), packages of
+    /// such components are merged into one component
+    pub fn duplicate_component_names(&self, problems: &mut Problems) {
+        let mut map: BTreeMap<&String, Vec<PathBuf>> = BTreeMap::new();
+
+        for component_packages in self.get() {
+            map.entry(&component_packages.component_name)
+                .or_default()
+                .push(component_packages.path_to_component.clone())
+        }
+
+        for (component_name, paths) in map {
+            if paths.len() > 1 {
+                problems.add_problem(DuplicateComponentName(component_name.clone(), paths));
+            }
+        }
+    }
+
+    /// finds pkg5 files with `name` other than COMPONENT_NAME in Makefile (or name of component
+    /// directory if COMPONENT_NAME is missing or contains macros)
+    pub fn pkg5_names(&self, problems: &mut Problems) {
+        for component_packages in self.get() {
+            let component_path = &component_packages.path_to_component;
+            let Some(pkg5_name) = load_pkg5_name(&component_path.join("pkg5")) else {
+                continue;
+            };
+
+            let makefile = component_path.join("Makefile");
+            let expected = load_makefile_variable(&makefile, "COMPONENT_NAME")
+                .filter(|component_name| !component_name.contains("$("))
+                .unwrap_or_else(|| {
+                    let component_name = &component_packages.component_name;
+                    component_name
+                        .rsplit('/')
+                        .next()
+                        .unwrap_or(component_name)
+                        .to_owned()
+                });
+
+            if pkg5_name != expected {
+                problems.add_problem(Pkg5NameMismatch(
+                    component_packages.component_name.clone(),
+                    pkg5_name,
+                    expected,
+                ));
+            }
+        }
     }
 
-    /// finds same package in multiple components
+    /// finds same package in multiple components, package listed at different versions (e.g.
+    /// during rename transition) is reported as [`PackageVersionsInMultipleComponents`]
     pub fn same_packages_in_components(&self, problems: &mut Problems) {
-        let mut map: HashMap<&FMRI, Vec<&String>> = HashMap::new();
+        let mut map: BTreeMap<&String, Vec<(&FMRI, &String)>> = BTreeMap::new();
 
         for component_packages in self.get() {
             for fmri in component_packages.packages_in_component.get_ref() {
-                map.entry(fmri)
+                map.entry(fmri.get_package_name_as_ref_string())
                     .or_default()
-                    .push(&component_packages.component_name)
+                    .push((fmri, &component_packages.component_name))
             }
         }
 
-        for (fmri, components) in map {
-            if components.len() > 1 {
+        for listed in map.into_values() {
+            if listed.len() < 2 {
+                continue;
+            }
+
+            let (fmri, _) = listed[0];
+            if listed.iter().all(|(other, _)| other == &fmri) {
                 problems.add_problem(PackageInMultipleComponents(
                     fmri.clone(),
-                    components.into_iter().cloned().collect::<Vec<String>>(),
+                    listed
+                        .into_iter()
+                        .map(|(_, component_name)| component_name.clone())
+                        .collect(),
+                ));
+            } else {
+                let mut fmri = fmri.clone();
+                fmri.remove_version();
+                problems.add_problem(PackageVersionsInMultipleComponents(
+                    fmri,
+                    listed
+                        .into_iter()
+                        .map(|(fmri, component_name)| {
+                            let version = fmri
+                                .get_version_as_string()
+                                .map(|version| version.trim_start_matches('@').to_owned());
+                            (component_name.clone(), version)
+                        })
+                        .collect(),
+                ));
+            }
+        }
+    }
+
+    /// finds packages listed in pkg5 of more components, that are in pkg5 of component which
+    /// doesn't have their manifest (builder is known only if just one component has manifest)
+    pub fn foreign_packages_in_pkg5(&self, problems: &mut Problems) {
+        let manifests = self.load_manifests(problems);
+
+        let mut listed: BTreeMap<&String, Vec<(&FMRI, &ComponentPackages)>> = BTreeMap::new();
+        for component_packages in self.get() {
+            for fmri in component_packages.packages_in_component.get_ref() {
+                listed
+                    .entry(fmri.get_package_name_as_ref_string())
+                    .or_default()
+                    .push((fmri, component_packages));
+            }
+        }
+
+        for (package_name, listed) in listed {
+            if listed.len() < 2 {
+                continue;
+            }
+
+            let builders: BTreeSet<&String> = manifests
+                .iter()
+                .filter(|(fmri, _, _)| fmri.get_package_name_as_ref_string() == package_name)
+                .map(|(_, component_name, _)| *component_name)
+                .collect();
+
+            if builders.len() != 1 {
+                continue;
+            }
+
+            let Some(builder) = self
+                .get()
+                .iter()
+                .find(|component_packages| builders.contains(&component_packages.component_name))
+            else {
+                continue;
+            };
+
+            for (fmri, component_packages) in listed {
+                if component_packages.component_name != builder.component_name {
+                    problems.add_problem(ForeignPackageInPkg5(
+                        fmri.clone(),
+                        component_packages.component_name.clone(),
+                        builder.component_name.clone(),
+                        component_packages.path_to_component.clone(),
+                        builder.path_to_component.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Returns packages with their component and paths they deliver loaded from pkg5 manifests
+    /// (*.p5m) of components
+    fn load_manifests(&self, problems: &mut Problems) -> Vec<Manifest<'_>> {
+        let mut manifests_paths = vec![];
+
+        for component_packages in self.get() {
+            let component_path = &component_packages.path_to_component;
+            let component_fmri =
+                load_makefile_variable(&component_path.join("Makefile"), "COMPONENT_FMRI");
+
+            for manifest in manifest_paths(component_path) {
+                let Ok(content) = read_to_string(&manifest) else {
+                    continue;
+                };
+
+                let (Some(package_name), paths) =
+                    parse_manifest(&content, component_fmri.as_deref())
+                else {
+                    continue;
+                };
+
+                match FMRI::parse_raw(&package_name) {
+                    Ok(fmri) => {
+                        manifests_paths.push((fmri, &component_packages.component_name, paths))
+                    }
+                    Err(_) => problems.add_problem(InvalidFmri(
+                        package_name,
+                        manifest.to_string_lossy().to_string(),
+                    )),
+                }
+            }
+        }
+
+        manifests_paths
+    }
+
+    /// finds components with only obsoleted packages in pkg5, their
+    /// [`ObsoletedPackageInComponent`] problems are replaced by one [`ObsoleteComponent`]
+    pub fn obsolete_components(&self, problems: &mut Problems, components: &Components) {
+        for component_packages in self.get() {
+            let packages = component_packages.packages_in_component.get_ref();
+            if packages.is_empty()
+                || !packages
+                    .iter()
+                    .all(|fmri| components.is_fmri_obsoleted(fmri))
+            {
+                continue;
+            }
+
+            problems.retain(|problem| {
+                !matches!(
+                    problem,
+                    ObsoletedPackageInComponent(_, component_name)
+                        if *component_name == component_packages.component_name
+                )
+            });
+            problems.add_problem(ObsoleteComponent(
+                component_packages.component_name.clone(),
+                packages.clone(),
+            ));
+        }
+    }
+
+    /// compares packages in pkg5 of every component with packages of its pkg5 manifests (*.p5m),
+    /// components with manifests whose package name can't be resolved are skipped
+    pub fn pkg5_consistency(&self, problems: &mut Problems, components: &Components) {
+        let manifests = self.load_manifests(problems);
+
+        for component_packages in self.get() {
+            let in_manifests: Vec<&FMRI> = manifests
+                .iter()
+                .filter(|(_, component_name, _)| {
+                    **component_name == component_packages.component_name
+                })
+                .map(|(fmri, _, _)| fmri)
+                .collect();
+
+            if in_manifests.is_empty()
+                || in_manifests.len() != manifest_paths(&component_packages.path_to_component).len()
+            {
+                continue;
+            }
+
+            let in_pkg5 = component_packages.packages_in_component.get_ref();
+
+            for fmri in &in_manifests {
+                if !in_pkg5.iter().any(|listed| listed.package_name_eq(fmri))
+                    && components.check_if_fmri_exists_as_package(fmri)
+                {
+                    problems.add_problem(PackageMissingInPkg5(
+                        (*fmri).clone(),
+                        component_packages.component_name.clone(),
+                    ))
+                }
+            }
+
+            for fmri in in_pkg5 {
+                // obsoleted and renamed packages are delivered by history file, non-existing
+                // packages are reported by non_existing_packages_in_pkg5
+                if !in_manifests
+                    .iter()
+                    .any(|manifest| manifest.package_name_eq(fmri))
+                    && matches!(
+                        components.get_alternative_state(fmri),
+                        AlternativeState::Viable | AlternativeState::Stale
+                    )
+                {
+                    problems.add_problem(StalePkg5Entry(
+                        fmri.clone(),
+                        component_packages.component_name.clone(),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// finds obsoleted packages that still have manifest in component, packages listed in pkg5
+    /// are reported as [`ObsoletedPackageInComponent`]
+    pub fn obsoleted_packages_built(&self, problems: &mut Problems, components: &Components) {
+        let manifests = self.load_manifests(problems);
+
+        for component_packages in self.get() {
+            let in_manifests: Vec<&FMRI> = manifests
+                .iter()
+                .filter(|(_, component_name, _)| {
+                    **component_name == component_packages.component_name
+                })
+                .map(|(fmri, _, _)| fmri)
+                .collect();
+
+            let obsoleted: Vec<&FMRI> = in_manifests
+                .iter()
+                .filter(|fmri| components.get_alternative_state(fmri) == AlternativeState::Obsolete)
+                .copied()
+                .collect();
+            let only_obsoleted = !obsoleted.is_empty() && obsoleted.len() == in_manifests.len();
+
+            for fmri in obsoleted {
+                if !component_packages.packages_in_component.contains(fmri) {
+                    problems.add_problem(ObsoletedPackageBuilt(
+                        fmri.clone(),
+                        component_packages.component_name.clone(),
+                        only_obsoleted,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// checks `info.classification` of published non-obsoleted packages in pkg5 manifests (*.p5m)
+    /// of components, values with macros are skipped
+    pub fn classifications(
+        &self,
+        problems: &mut Problems,
+        components: &Components,
+        classifications: &Classifications,
+    ) {
+        for component_packages in self.get() {
+            let component_path = &component_packages.path_to_component;
+            let component_fmri =
+                load_makefile_variable(&component_path.join("Makefile"), "COMPONENT_FMRI");
+
+            for manifest in manifest_paths(component_path) {
+                let Ok(content) = read_to_string(&manifest) else {
+                    continue;
+                };
+
+                let Some(fmri) = parse_manifest(&content, component_fmri.as_deref())
+                    .0
+                    .and_then(|package_name| FMRI::parse_raw(&package_name).ok())
+                else {
+                    continue;
+                };
+
+                if !matches!(
+                    components.get_alternative_state(&fmri),
+                    AlternativeState::Viable | AlternativeState::Stale
+                ) {
+                    continue;
+                }
+
+                let values = parse_classifications(&content);
+                if values.is_empty() {
+                    problems.add_problem(InvalidClassification(
+                        fmri,
+                        component_packages.component_name.clone(),
+                        None,
+                        "missing".to_owned(),
+                    ));
+                    continue;
+                }
+
+                for value in values {
+                    if value.contains("$(") {
+                        continue;
+                    }
+                    if let Some(reason) = classifications.check(&value) {
+                        problems.add_problem(InvalidClassification(
+                            fmri.clone(),
+                            component_packages.component_name.clone(),
+                            Some(value),
+                            reason,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// finds components without license declaration in Makefile and published non-obsoleted
+    /// packages whose pkg5 manifests (*.p5m) deliver files without license action
+    pub fn licenses(&self, problems: &mut Problems, components: &Components) {
+        for component_packages in self.get() {
+            let component_path = &component_packages.path_to_component;
+            let makefile = component_path.join("Makefile");
+
+            if makefile.exists()
+                && load_makefile_variable(&makefile, "COMPONENT_LICENSE").is_none()
+                && load_makefile_variable(&makefile, "COMPONENT_LICENSE_FILE").is_none()
+            {
+                problems.add_problem(MissingComponentLicense(
+                    component_packages.component_name.clone(),
+                    makefile.clone(),
+                ));
+            }
+
+            let component_fmri = load_makefile_variable(&makefile, "COMPONENT_FMRI");
+
+            for manifest in manifest_paths(component_path) {
+                let Ok(content) = read_to_string(&manifest) else {
+                    continue;
+                };
+
+                let Some(fmri) = parse_manifest(&content, component_fmri.as_deref())
+                    .0
+                    .and_then(|package_name| FMRI::parse_raw(&package_name).ok())
+                else {
+                    continue;
+                };
+
+                if has_action(&content, "file")
+                    && !has_action(&content, "license")
+                    && matches!(
+                        components.get_alternative_state(&fmri),
+                        AlternativeState::Viable | AlternativeState::Stale
+                    )
+                {
+                    problems.add_problem(MissingLicenseAction(
+                        fmri,
+                        component_packages.component_name.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// suggests moving components with encumbered license (COMPONENT_LICENSE or license action
+    /// of its packages) to directory of encumbered components and moving components without it
+    /// out of there, license values are split by `,`, `;` and `/`
+    pub fn encumbered_placement(
+        &self,
+        problems: &mut Problems,
+        components: &Components,
+        encumbered_licenses: &EncumberedLicenses,
+    ) {
+        if encumbered_licenses.licenses.is_empty() {
+            return;
+        }
+
+        let is_encumbered = |license: &str| {
+            license.split([',', ';', '/']).any(|identifier| {
+                encumbered_licenses
+                    .licenses
+                    .iter()
+                    .any(|encumbered| encumbered.eq_ignore_ascii_case(identifier.trim()))
+            })
+        };
+
+        for component_packages in self.get() {
+            let component_path = &component_packages.path_to_component;
+
+            let mut licenses = vec![];
+            if let Some(license) =
+                load_makefile_variable(&component_path.join("Makefile"), "COMPONENT_LICENSE")
+            {
+                licenses.push((format!("COMPONENT_LICENSE {}", license), license));
+            }
+            for fmri in component_packages.packages_in_component.get_ref() {
+                let Some(package_versions) = components.get_package_versions_from_fmri(fmri) else {
+                    continue;
+                };
+                for action in package_versions.get_actions_ref() {
+                    if let Action::License(license) = action {
+                        licenses.push((
+                            format!(
+                                "license {} of {}",
+                                license.license,
+                                fmri.get_package_name_as_ref_string()
+                            ),
+                            license.license.clone(),
+                        ));
+                    }
+                }
+            }
+            if licenses.is_empty() {
+                continue;
+            }
+
+            let in_encumbered = component_path
+                .iter()
+                .any(|directory| *directory == *encumbered_licenses.directory);
+            let evidence: Vec<String> = licenses
+                .iter()
+                .filter(|(_, license)| is_encumbered(license) != in_encumbered)
+                .map(|(evidence, _)| evidence.clone())
+                .collect();
+
+            // component in encumbered directory is misplaced only if none of its licenses is
+            // encumbered
+            if (!in_encumbered && !evidence.is_empty())
+                || (in_encumbered && evidence.len() == licenses.len())
+            {
+                problems.add_problem(EncumberedPlacement(
+                    component_packages.component_name.clone(),
+                    !in_encumbered,
+                    evidence,
+                ));
+            }
+        }
+    }
+
+    /// Returns names of packages that deliver some path in pkg5 manifests of components
+    pub fn get_packages_with_files(&self, problems: &mut Problems) -> BTreeSet<String> {
+        self.load_manifests(problems)
+            .into_iter()
+            .filter(|(_, _, paths)| !paths.is_empty())
+            .map(|(fmri, _, _)| fmri.get_package_name_as_ref_string().clone())
+            .collect()
+    }
+
+    /// finds paths delivered by more packages in pkg5 manifests (*.p5m) of components and
+    /// mediated paths whose packages disagree on mediation
+    pub fn file_conflicts(&self, problems: &mut Problems) {
+        let mut map: BTreeMap<String, Vec<DeliveredBy>> = BTreeMap::new();
+
+        for (fmri, component_name, paths) in self.load_manifests(problems) {
+            for (path, mediation) in paths {
+                let packages = map.entry(path).or_default();
+                if !packages
+                    .iter()
+                    .any(|(added, _, _)| added.package_name_eq(&fmri))
+                {
+                    packages.push((fmri.clone(), component_name, mediation));
+                }
+            }
+        }
+
+        for (path, packages) in map {
+            let (mediated, unmediated): (Vec<_>, Vec<_>) = packages
+                .iter()
+                .partition(|(_, _, mediation)| mediation.is_some());
+
+            if unmediated.len() > 1 {
+                problems.add_problem(FileConflict(
+                    path.clone(),
+                    unmediated.iter().map(|(fmri, _, _)| fmri.clone()).collect(),
+                    component_names(&unmediated),
                 ));
             }
+
+            let mut mediator_conflict = |packages: &[&DeliveredBy], reason| {
+                problems.add_problem(MediatorConflict(
+                    path.clone(),
+                    packages.iter().map(|(fmri, _, _)| fmri.clone()).collect(),
+                    component_names(packages),
+                    reason,
+                ))
+            };
+
+            let mediations: Vec<&Mediation> = mediated
+                .iter()
+                .filter_map(|(_, _, mediation)| mediation.as_ref())
+                .collect();
+
+            if !mediated.is_empty() && !unmediated.is_empty() {
+                let all: Vec<_> = packages.iter().collect();
+                mediator_conflict(&all, "path is mediated only in some packages".to_owned());
+            } else if mediations
+                .iter()
+                .any(|mediation| mediation.mediator != mediations[0].mediator)
+            {
+                mediator_conflict(&mediated, "packages use different mediators".to_owned());
+            } else if mediations.iter().enumerate().any(|(i, mediation)| {
+                mediations[i + 1..].iter().any(|other| {
+                    other.version == mediation.version
+                        && other.implementation == mediation.implementation
+                        && other.priority == mediation.priority
+                })
+            }) {
+                mediator_conflict(
+                    &mediated,
+                    "packages have the same mediator-version, mediator-implementation and \
+                    mediator-priority"
+                        .to_owned(),
+                );
+            }
+
+            for package in &mediated {
+                if let Some(Mediation {
+                    priority: Some(priority),
+                    ..
+                }) = &package.2
+                {
+                    if priority != "vendor" && priority != "site" {
+                        mediator_conflict(
+                            &[package],
+                            format!("mediator-priority {} is not vendor or site", priority),
+                        );
+                    }
+                }
+            }
         }
     }
 
@@ -222,25 +921,467 @@ impl ComponentPackagesList {
     }
 }
 
+/// Key of pkg5 with checker of its value
+type Pkg5Key = (&'static str, fn(&Value) -> bool);
+
+/// Raw fmris of pkg5 with invalid keys and reasons
+type Pkg5 = (Vec<String>, Vec<(String, String)>);
+
+/// Keys of pkg5, `fmris` and `name` are required
+const PKG5_KEYS: [Pkg5Key; 4] = [
+    ("dependencies", is_string_array),
+    ("fmris", is_string_array),
+    ("name", Value::is_string),
+    ("obsolete", is_string_array),
+];
+
+fn is_string_array(value: &Value) -> bool {
+    value
+        .as_array()
+        .is_some_and(|array| array.iter().all(Value::is_string))
+}
+
+/// Returns raw fmris from pkg5 file with invalid keys (missing required keys, keys with invalid
+/// values and unknown keys) and reasons, or reason why the file is malformed
+fn load_pkg5(path: &Path) -> Result<Pkg5, String> {
+    let content = read_to_string(path).map_err(|e| format!("can't read file: {}", e))?;
+
+    let json: Value = serde_json::from_str(&content).map_err(|e| format!("invalid JSON: {}", e))?;
+    let pkg5 = json.as_object().ok_or("expected object")?;
+
+    let raw_fmris = pkg5
+        .get("fmris")
+        .ok_or("missing \"fmris\"")?
+        .as_array()
+        .ok_or("\"fmris\" is not array")?
+        .iter()
+        .map(|fmri| {
+            fmri.as_str()
+                .map(|fmri| fmri.to_owned())
+                .ok_or_else(|| format!("fmri {} is not string", fmri))
+        })
+        .collect::<Result<Vec<String>, String>>()?;
+
+    let mut invalid_keys = vec![];
+    for (key, is_valid) in PKG5_KEYS {
+        match pkg5.get(key) {
+            None if key == "name" => invalid_keys.push((key.to_owned(), "is missing".to_owned())),
+            Some(value) if !is_valid(value) => {
+                invalid_keys.push((key.to_owned(), format!("has invalid value {}", value)))
+            }
+            _ => {}
+        }
+    }
+    for key in pkg5.keys() {
+        if !PKG5_KEYS.iter().any(|(known, _)| known == key) {
+            invalid_keys.push((key.clone(), "is unknown".to_owned()));
+        }
+    }
+
+    Ok((raw_fmris, invalid_keys))
+}
+
+/// Returns value of `name` in pkg5, [`None`] if pkg5 can't be loaded or has no name
+fn load_pkg5_name(path: &Path) -> Option<String> {
+    let json: Value = serde_json::from_str(&read_to_string(path).ok()?).ok()?;
+    json.get("name")?.as_str().map(|name| name.to_owned())
+}
+
+/// Returns packages listed in pkg5, packages listed more times are added only once
+pub(crate) fn load_packages_in_pkg5(pkg5_path: &Path, problems: &mut Problems) -> Option<FMRIList> {
+    let (raw_fmris, invalid_keys) = match load_pkg5(pkg5_path) {
+        Ok(pkg5) => pkg5,
+        Err(reason) => {
+            problems.add_problem(MalformedPkg5(pkg5_path.to_path_buf(), reason));
+            return None;
+        }
+    };
+
+    for (key, reason) in invalid_keys {
+        problems.add_problem(InvalidPkg5Key(pkg5_path.to_path_buf(), key, reason));
+    }
+
+    let mut packages_in_component = FMRIList::new();
+    for raw_fmri in raw_fmris {
+        match FMRI::parse_raw(&raw_fmri) {
+            Ok(fmri) if packages_in_component.contains(&fmri) => {
+                problems.add_problem(DuplicatePkg5Entry(fmri, pkg5_path.to_path_buf()))
+            }
+            Ok(fmri) => packages_in_component.add(fmri),
+            Err(_) => problems.add_problem(InvalidFmri(
+                raw_fmri,
+                pkg5_path.to_string_lossy().to_string(),
+            )),
+        }
+    }
+
+    Some(packages_in_component)
+}
+
+impl From<Vec<ComponentPackages>> for ComponentPackagesList {
+    fn from(component_packages: Vec<ComponentPackages>) -> Self {
+        Self(component_packages)
+    }
+}
+
+/// Returns sorted paths of pkg5 manifests (*.p5m) in component directory
+pub(crate) fn manifest_paths(component_path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = read_dir(component_path) else {
+        return vec![];
+    };
+    let mut manifests: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "p5m"))
+        .collect();
+    manifests.sort();
+    manifests
+}
+
+/// Returns value of variable `name` assigned in Makefile of component
+pub(crate) fn load_makefile_variable(path: &Path, name: &str) -> Option<String> {
+    makefile_variable(&read_to_string(path).ok()?, name)
+}
+
+/// Returns COMPONENT_NAME, COMPONENT_VERSION, COMPONENT_PROJECT_URL, TPNO and BUILD_STYLE
+/// assigned in Makefile of component
+pub(crate) fn load_makefile_metadata(path: &Path) -> MakefileMetadata {
+    let makefile = read_to_string(path).unwrap_or_default();
+    let variable = |name| {
+        makefile_variable(&makefile, name).map(|value| expand_makefile_variables(&makefile, value))
+    };
+
+    MakefileMetadata {
+        name: variable("COMPONENT_NAME"),
+        version: variable("COMPONENT_VERSION"),
+        project_url: variable("COMPONENT_PROJECT_URL"),
+        tpno: variable("TPNO"),
+        build_style: variable("BUILD_STYLE"),
+    }
+}
+
+/// Replaces references to variables assigned in the same Makefile (e.g. `$(COMPONENT_NAME)` in
+/// COMPONENT_PROJECT_URL), other references are kept
+fn expand_makefile_variables(makefile: &str, mut value: String) -> String {
+    let reference = Regex::new(r"\$\(([A-Za-z0-9_]+)\)").expect("invalid regex");
+
+    // nested references are expanded up to depth 5
+    for _ in 0..5 {
+        let expanded = reference
+            .replace_all(&value, |captures: &regex::Captures| {
+                makefile_variable(makefile, &captures[1]).unwrap_or_else(|| captures[0].to_owned())
+            })
+            .to_string();
+        if expanded == value {
+            break;
+        }
+        value = expanded;
+    }
+
+    value
+}
+
+/// Returns value of variable `name` assigned in content of Makefile
+fn makefile_variable(makefile: &str, name: &str) -> Option<String> {
+    makefile.lines().find_map(|line| {
+        let value = line.strip_prefix(name)?.trim_start();
+        let value = value
+            .strip_prefix(":=")
+            .or_else(|| value.strip_prefix("?="))
+            .or_else(|| value.strip_prefix('='))?
+            .trim();
+
+        (!value.is_empty()).then(|| value.to_owned())
+    })
+}
+
+/// Returns packages assigned to variable `name` (e.g. `REQUIRED_PACKAGES`) in Makefile of
+/// component without running gmake
+///
+/// Only plain assignments (`=`, `:=`, `?=` and `+=`) outside of conditionals are supported,
+/// [`None`] is returned if variable is used in other way or its value contains macros. Included
+/// files are not read.
+pub(crate) fn native_makefile_list(makefile: &str, name: &str) -> Option<Vec<String>> {
+    let variable =
+        Regex::new(&format!(r"(^|[^A-Za-z0-9_]){}($|[^A-Za-z0-9_])", name)).expect("invalid regex");
+    let mut packages: Option<Vec<String>> = None;
+    let mut conditionals = 0;
+
+    for line in makefile.replace("\\\n", " ").lines() {
+        let line = line.trim();
+        let directive = line.split_whitespace().next().unwrap_or_default();
+
+        match directive {
+            "ifeq" | "ifneq" | "ifdef" | "ifndef" => conditionals += 1,
+            "endif" => conditionals -= 1,
+            _ => {}
+        }
+
+        if line.starts_with('#') || !variable.is_match(line) {
+            continue;
+        }
+
+        let value = line.strip_prefix(name)?.trim_start();
+        let (operator, value) = ["+=", ":=", "?=", "="]
+            .into_iter()
+            .find_map(|operator| Some((operator, value.strip_prefix(operator)?)))?;
+
+        if conditionals > 0 || value.contains('$') {
+            return None;
+        }
+
+        let values = value.split_whitespace().map(|value| value.to_owned());
+        match (operator, &mut packages) {
+            ("+=", Some(packages)) => packages.extend(values),
+            ("?=", Some(_)) => {}
+            _ => packages = Some(values.collect()),
+        }
+    }
+
+    Some(packages.unwrap_or_default())
+}
+
+/// Mediation of link (mediator, mediator-version, mediator-implementation and mediator-priority
+/// attributes)
+#[derive(PartialEq, Clone, Debug)]
+pub(crate) struct Mediation {
+    pub mediator: String,
+    pub version: Option<String>,
+    pub implementation: Option<String>,
+    pub priority: Option<String>,
+}
+
+/// Returns package name and paths delivered by file, link and hardlink actions of pkg5 manifest
+/// with their mediation, `$(COMPONENT_FMRI)` in package name is replaced by `component_fmri`,
+/// package name with other macros is [`None`]
+pub(crate) fn parse_manifest(
+    content: &str,
+    component_fmri: Option<&str>,
+) -> (Option<String>, Vec<(String, Option<Mediation>)>) {
+    let mut package_name = None;
+    let mut paths = vec![];
+
+    let attribute = |tokens: &[&str], name: &str| {
+        tokens.iter().find_map(|token| {
+            token
+                .strip_prefix(name)
+                .and_then(|value| value.strip_prefix('='))
+                .map(|value| value.trim_matches('"').to_owned())
+        })
+    };
+
+    for action in content.replace("\\\n", " ").lines() {
+        let tokens: Vec<&str> = action.split_whitespace().collect();
+
+        match tokens.first() {
+            Some(&"set") if tokens.contains(&"name=pkg.fmri") => {
+                package_name = attribute(&tokens, "value").map(|value| {
+                    let value = value.trim_start_matches("pkg:/");
+                    let name = value.split('@').next().unwrap_or_default();
+                    match component_fmri {
+                        Some(component_fmri) => name.replace("$(COMPONENT_FMRI)", component_fmri),
+                        None => name.to_owned(),
+                    }
+                });
+            }
+            Some(&"file") | Some(&"link") | Some(&"hardlink") => {
+                if let Some(path) = attribute(&tokens, "path") {
+                    let mediation = attribute(&tokens, "mediator").map(|mediator| Mediation {
+                        mediator,
+                        version: attribute(&tokens, "mediator-version"),
+                        implementation: attribute(&tokens, "mediator-implementation"),
+                        priority: attribute(&tokens, "mediator-priority"),
+                    });
+                    paths.push((path.trim_start_matches('/').to_owned(), mediation));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (package_name.filter(|name| !name.contains("$(")), paths)
+}
+
+/// Returns true if pkg5 manifest has action named `action_name` (e.g. `license`)
+fn has_action(content: &str, action_name: &str) -> bool {
+    content
+        .lines()
+        .any(|line| line.split_whitespace().next() == Some(action_name))
+}
+
+/// Returns values of `info.classification` set in pkg5 manifest, quoted values can contain spaces
+/// (e.g. `"org.opensolaris.category.2008:Desktop (GNOME)/Sessions"`)
+pub(crate) fn parse_classifications(content: &str) -> Vec<String> {
+    let mut classifications = vec![];
+
+    for action in content.replace("\\\n", " ").lines() {
+        let mut tokens = vec![];
+        let mut token = String::new();
+        let mut quoted = false;
+        for c in action.chars() {
+            match c {
+                '"' => quoted = !quoted,
+                c if c.is_whitespace() && !quoted => {
+                    if !token.is_empty() {
+                        tokens.push(std::mem::take(&mut token));
+                    }
+                }
+                c => token.push(c),
+            }
+        }
+        if !token.is_empty() {
+            tokens.push(token);
+        }
+
+        if tokens.first().map(String::as_str) == Some("set")
+            && tokens
+                .iter()
+                .any(|token| token == "name=info.classification")
+        {
+            classifications.extend(
+                tokens
+                    .iter()
+                    .filter_map(|token| token.strip_prefix("value="))
+                    .map(|value| value.to_owned()),
+            );
+        }
+    }
+
+    classifications
+}
+
+/// Package with name of its component and paths it delivers with their mediation
+type Manifest<'a> = (FMRI, &'a String, Vec<(String, Option<Mediation>)>);
+
+/// Package with name of its component and mediation that deliver some path
+type DeliveredBy<'a> = (FMRI, &'a String, Option<Mediation>);
+
+/// Returns names of components of packages without duplicates
+fn component_names(packages: &[&DeliveredBy]) -> Vec<String> {
+    let mut component_names: Vec<String> = vec![];
+    for (_, component_name, _) in packages {
+        if !component_names.contains(component_name) {
+            component_names.push((*component_name).clone());
+        }
+    }
+    component_names
+}
+
+/// Loads dependencies of type from components into packages, only components with path in
+/// `changed` are loaded if it is set
 pub fn load_dependencies(
     components: &mut Components,
     problems: &mut Problems,
     component_packages_list: &ComponentPackagesList,
     dependencies_type: &DependencyTypes,
+    changed: Option<&[PathBuf]>,
 ) {
-    for component in components.get_ref_mut() {
-        for packet_versions in component.get_versions_ref_mut() {
-            if let Some(component_path) = component_packages_list
-                .get_component_packages_of_package_versions(problems, packet_versions)
-                .map(|component_packages| component_packages.path_to_component)
-            {
-                if let Ok(fmri_list) = component_packages_list.get_dependencies_of_component(
+    let rewrite_rules = components.get_rewrite_rules_ref().clone();
+    let mut make_cache = components.take_make_cache();
+    let native_makefiles = components.is_native_makefiles();
+    let without_make = components.is_without_make();
+    let mut rewrites = vec![];
+
+    let component_packages_of_versions: Vec<Vec<Option<ComponentPackages>>> = components
+        .get_ref()
+        .iter()
+        .map(|component| {
+            component
+                .get_versions_ref()
+                .iter()
+                .map(|package_versions| {
+                    component_packages_list
+                        .get_component_packages_of_package_versions(problems, package_versions)
+                        .filter(|component_packages| {
+                            changed.is_none_or(|changed| {
+                                changed.contains(&component_packages.path_to_component)
+                            })
+                        })
+                })
+                .collect()
+        })
+        .collect();
+
+    let (make_command, target) = make_command(dependencies_type);
+    let mut component_paths: Vec<PathBuf> = vec![];
+    for component_packages in component_packages_of_versions.iter().flatten().flatten() {
+        let component_path = &component_packages.path_to_component;
+        if !component_paths.contains(component_path)
+            && make_cache.get(component_path, target).is_none()
+            && native_dependencies(component_path, dependencies_type, native_makefiles).is_none()
+        {
+            component_paths.push(component_path.clone());
+        }
+    }
+    if without_make {
+        for component_path in &component_paths {
+            match dependencies_type {
+                Build | Test => problems.add_problem(UnRunnableMakeCommand(
+                    make_command.clone(),
+                    component_path.clone(),
+                    MakeFailure::NeedsGmake,
+                    "Makefile can't be parsed without gmake".to_owned(),
+                )),
+                _ => debug!(
+                    "skipping {} of {:?}, Makefile needs gmake",
+                    target, component_path
+                ),
+            }
+        }
+        if !component_paths.is_empty() {
+            warn!(
+                "{} of {} components skipped, their Makefiles need gmake",
+                target,
+                component_paths.len()
+            );
+        }
+    }
+    let prefetched = match without_make {
+        true => HashMap::new(),
+        false => run_make_parallel(
+            component_paths.clone(),
+            &make_command,
+            components.get_jobs(),
+        ),
+    };
+
+    for (component, component_packages_of_versions) in components
+        .get_ref_mut()
+        .iter_mut()
+        .zip(component_packages_of_versions)
+    {
+        for (packet_versions, component_packages) in component
+            .get_versions_ref_mut()
+            .iter_mut()
+            .zip(component_packages_of_versions)
+        {
+            if let Some(component_packages) = component_packages {
+                if without_make && component_paths.contains(&component_packages.path_to_component) {
+                    continue;
+                }
+
+                if let Ok(mut fmri_list) = component_packages_list.get_dependencies_of_component(
                     problems,
-                    component_path,
+                    &mut make_cache,
+                    component_packages.path_to_component,
                     dependencies_type,
+                    native_makefiles,
+                    &prefetched,
                 ) {
+                    for fmri in fmri_list.get_ref_mut() {
+                        rewrites.extend(rewrite_rules.rewrite(fmri));
+                    }
+
                     let deps = Dependencies::new_from_fmri_list(fmri_list);
 
+                    for duplicate in deps.get_duplicates() {
+                        problems.add_problem(DuplicateDependency(
+                            duplicate.get_ref().clone(),
+                            dependencies_type.clone(),
+                            None,
+                            component_packages.component_name.clone(),
+                        ));
+                    }
+
                     for package in packet_versions.get_packages_ref_mut() {
                         match dependencies_type {
                             Build => package.add_build_dependencies(deps.clone()),
@@ -254,6 +1395,115 @@ pub fn load_dependencies(
             }
         }
     }
+
+    components.set_make_cache(make_cache);
+
+    for rewrite in rewrites {
+        components.add_rewrite(rewrite)
+    }
+}
+
+/// Returns gmake command printing dependencies of type and its target
+fn make_command(dependencies_type: &DependencyTypes) -> (String, &'static str) {
+    let target = match dependencies_type {
+        Build => "print-value-REQUIRED_PACKAGES",
+        Test => "print-value-TEST_REQUIRED_PACKAGES",
+        SystemBuild => "print-value-USERLAND_REQUIRED_PACKAGES",
+        SystemTest => "print-value-USERLAND_TEST_REQUIRED_PACKAGES",
+        _ => panic!(),
+    };
+
+    let mut make_command: String = "gmake ".to_owned();
+
+    #[cfg(target_os = "linux")]
+    make_command.push_str("GSED=/usr/bin/sed ");
+
+    make_command.push_str(target);
+
+    (make_command, target)
+}
+
+/// Returns dependencies of type parsed from Makefile of component (see [`native_makefile_list`])
+/// if `native_makefiles` is enabled
+fn native_dependencies(
+    component_path: &Path,
+    dependencies_type: &DependencyTypes,
+    native_makefiles: bool,
+) -> Option<String> {
+    let (_, target) = make_command(dependencies_type);
+
+    match (native_makefiles, dependencies_type) {
+        (true, Build | Test) => read_to_string(component_path.join("Makefile"))
+            .ok()
+            .and_then(|makefile| {
+                native_makefile_list(&makefile, target.trim_start_matches("print-value-"))
+            })
+            .map(|packages| packages.join(" ")),
+        _ => None,
+    }
+}
+
+/// Returns sorted subdirectories of directory (except hidden ones), missing directory has none
+fn sorted_directories(directory: &Path) -> Vec<PathBuf> {
+    let mut directories: Vec<PathBuf> = read_dir(directory)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_dir()
+                && !path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    directories.sort();
+    directories
+}
+
+fn run_make(component_path: &Path, make_command: &str) -> Output {
+    Command::new("sh")
+        .arg("-c")
+        .arg(format!(
+            "cd {} && {}",
+            component_path.to_string_lossy(),
+            make_command
+        ))
+        .output()
+        .expect("failed to run command")
+}
+
+/// Runs make command in component directories on `jobs` threads, outputs are returned by
+/// component path, so they don't depend on order in which commands finished
+pub(crate) fn run_make_parallel(
+    component_paths: Vec<PathBuf>,
+    make_command: &str,
+    jobs: usize,
+) -> HashMap<PathBuf, Output> {
+    let queue = Mutex::new(component_paths.into_iter());
+    let outputs = Mutex::new(HashMap::new());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            scope.spawn(|| loop {
+                let Some(component_path) = queue.lock().unwrap().next() else {
+                    break;
+                };
+                let output = run_make(&component_path, make_command);
+                outputs.lock().unwrap().insert(component_path, output);
+            });
+        }
+    });
+
+    outputs.into_inner().unwrap()
+}
+
+/// Returns the last lines of gmake stderr, that usually contain the error
+fn stderr_snippet(stderr: &str) -> String {
+    let lines: Vec<&str> = stderr
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    lines[lines.len().saturating_sub(5)..].join("\n")
 }
 
 pub fn component_list(
@@ -265,16 +1515,34 @@ pub fn component_list(
 
     for component in components.get_ref() {
         for package_version in component.get_versions_ref() {
-            new_components.add_package_to_component_with_name(
-                package_version,
+            // packages of external publishers are not built by components
+            let component_name = if components.is_external(package_version.fmri_ref()) {
+                None
+            } else {
                 component_packages_list
                     .get_component_packages_of_package_versions(problems, package_version)
                     .map(|component_packages| component_packages.component_name)
-                    .unwrap_or_else(|| "".to_owned()),
+            };
+
+            new_components.add_package_to_component_with_name(
+                package_version,
+                component_name.unwrap_or_else(|| "".to_owned()),
             )
         }
     }
 
     new_components.name_unnamed_components();
+    for component in new_components.get_ref_mut() {
+        let name = component.get_name_ref();
+        if let Some(component_packages) = component_packages_list
+            .get()
+            .iter()
+            .find(|component_packages| &component_packages.component_name == name)
+        {
+            component.set_makefile_metadata(load_makefile_metadata(
+                &component_packages.path_to_component.join("Makefile"),
+            ));
+        }
+    }
     components.change(new_components.get());
 }
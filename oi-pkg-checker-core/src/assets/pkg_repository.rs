@@ -0,0 +1,186 @@
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    fs::{create_dir_all, read_dir, read_to_string, remove_dir_all, remove_file, write},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use fmri::FMRI;
+use log::{debug, info};
+use serde_json::{json, Map, Value};
+
+/// Package version loaded from manifest in repository with actions that catalog.dependency.C
+/// contains
+type RepositoryPackage = (FMRI, Vec<String>);
+
+/// Creates catalog.dependency.C in `directory` from package manifests of on-disk pkg(5)
+/// repository (created by `pkgrepo create`, with pkg5.repository and publisher directories) and
+/// returns its path
+///
+/// Catalogs of repository are not used, so packages published with `pkgsend --no-catalog` are
+/// loaded too.
+pub fn repository_catalog(repository: &Path, directory: &Path) -> Result<PathBuf, String> {
+    if !repository.join("pkg5.repository").exists() {
+        return Err(format!("{:?} is not pkg(5) repository", repository));
+    }
+
+    write_catalog(repository, directory)
+}
+
+/// Creates catalog.dependency.C in `directory` from package manifests of pkg(5) archive (.p5p
+/// created by `pkgrecv -a`) and returns its path, archive is extracted with tar (without options
+/// specific to GNU tar, so tar of illumos works too) and everything except manifests is removed
+pub fn archive_catalog(archive: &Path, directory: &Path) -> Result<PathBuf, String> {
+    let extracted = directory.join("archive");
+    if extracted.exists() {
+        remove_dir_all(&extracted)
+            .map_err(|e| format!("failed to remove {:?}: {}", extracted, e))?;
+    }
+    create_dir_all(&extracted).map_err(|e| format!("failed to create {:?}: {}", extracted, e))?;
+
+    let output = Command::new("tar")
+        .arg("-xf")
+        .arg(archive)
+        .arg("-C")
+        .arg(&extracted)
+        .output()
+        .map_err(|e| format!("failed to run tar: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{:?} is not pkg(5) archive: {}",
+            archive,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    remove_payload(&extracted)?;
+
+    write_catalog(&extracted, directory)
+}
+
+/// Removes everything except manifests (`publisher/*/pkg`) from extracted archive
+fn remove_payload(extracted: &Path) -> Result<(), String> {
+    let remove = |path: &Path| {
+        match path.is_dir() {
+            true => remove_dir_all(path),
+            false => remove_file(path),
+        }
+        .map_err(|e| format!("failed to remove {:?}: {}", path, e))
+    };
+
+    for path in sorted_entries(extracted)? {
+        if !path.ends_with("publisher") {
+            remove(&path)?;
+        }
+    }
+
+    for publisher_path in sorted_entries(&extracted.join("publisher"))? {
+        for path in sorted_entries(&publisher_path)? {
+            if !path.ends_with("pkg") {
+                remove(&path)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates catalog.dependency.C in `directory` from manifests in publisher directories of
+/// repository or extracted archive
+fn write_catalog(repository: &Path, directory: &Path) -> Result<PathBuf, String> {
+    let mut catalog = Map::new();
+
+    for publisher_path in sorted_entries(&repository.join("publisher"))? {
+        let Some(publisher) = publisher_path.file_name() else {
+            continue;
+        };
+
+        let mut packages: BTreeMap<String, Vec<RepositoryPackage>> = BTreeMap::new();
+        for package_path in sorted_entries(&publisher_path.join("pkg"))? {
+            for manifest_path in sorted_entries(&package_path)? {
+                let content = read_to_string(&manifest_path)
+                    .map_err(|e| format!("failed to read {:?}: {}", manifest_path, e))?;
+                let Some((fmri, actions)) = parse_repository_manifest(&content) else {
+                    debug!("skipping {:?} without pkg.fmri", manifest_path);
+                    continue;
+                };
+
+                packages
+                    .entry(fmri.get_package_name_as_ref_string().clone())
+                    .or_default()
+                    .push((fmri, actions));
+            }
+        }
+
+        let mut publisher_catalog = Map::new();
+        for (package_name, mut versions) in packages {
+            versions.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            publisher_catalog.insert(
+                package_name,
+                versions
+                    .into_iter()
+                    .map(|(fmri, actions)| {
+                        let version = fmri.get_version_as_string().unwrap_or_default();
+                        json!({
+                            "version": version.trim_start_matches('@'),
+                            "actions": actions,
+                        })
+                    })
+                    .collect(),
+            );
+        }
+
+        catalog.insert(
+            publisher.to_string_lossy().to_string(),
+            Value::Object(publisher_catalog),
+        );
+    }
+
+    create_dir_all(directory).map_err(|e| format!("failed to create {:?}: {}", directory, e))?;
+    let catalog_path = directory.join("catalog.dependency.C");
+    write(&catalog_path, Value::Object(catalog).to_string())
+        .map_err(|e| format!("failed to write {:?}: {}", catalog_path, e))?;
+
+    info!(
+        "loaded packages of {:?} into {:?}",
+        repository, catalog_path
+    );
+    Ok(catalog_path)
+}
+
+/// Returns versioned [`FMRI`] of package manifest from repository with its depend actions and
+/// pkg.obsolete and pkg.renamed attributes, [`None`] if manifest doesn't set valid pkg.fmri
+pub(crate) fn parse_repository_manifest(content: &str) -> Option<RepositoryPackage> {
+    let mut fmri = None;
+    let mut actions = vec![];
+
+    for action in content.replace("\\\n", " ").lines() {
+        let action = action.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+        if let Some(value) = action.strip_prefix("set name=pkg.fmri value=") {
+            fmri = FMRI::parse_raw(value).ok();
+        } else if action.starts_with("depend ")
+            || action.starts_with("set name=pkg.obsolete ")
+            || action.starts_with("set name=pkg.renamed ")
+        {
+            actions.push(action);
+        }
+    }
+
+    Some((fmri?, actions))
+}
+
+/// Returns sorted paths in directory, missing directory has no paths
+fn sorted_entries(directory: &Path) -> Result<Vec<PathBuf>, String> {
+    if !directory.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut paths: Vec<PathBuf> = read_dir(directory)
+        .map_err(|e| format!("failed to read {:?}: {}", directory, e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
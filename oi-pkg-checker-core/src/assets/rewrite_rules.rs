@@ -0,0 +1,100 @@
+use std::{fs::read_to_string, path::PathBuf};
+
+use fmri::FMRI;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Rewrite rule as it is written in rules file
+#[derive(Deserialize)]
+struct RawRewriteRule {
+    pattern: String,
+    replacement: String,
+}
+
+/// Regex based rule, that rewrites package name of [`FMRI`]
+#[derive(Clone, Debug)]
+pub struct RewriteRule {
+    pattern: Regex,
+    replacement: String,
+}
+
+/// Rewrite rules applied to every [`FMRI`] during loading, see [`crate::Components::rewrite_fmri`]
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct RewriteRules(Vec<RewriteRule>);
+
+/// Record of applied rewrite (provenance)
+#[derive(PartialEq, Serialize, Deserialize, Clone, Debug)]
+pub struct Rewrite {
+    pub original: String,
+    pub rewritten: String,
+}
+
+impl RewriteRule {
+    pub fn new(pattern: &str, replacement: &str) -> Result<Self, String> {
+        Ok(Self {
+            pattern: Regex::new(pattern)
+                .map_err(|e| format!("invalid rewrite pattern '{}': {}", pattern, e))?,
+            replacement: replacement.to_owned(),
+        })
+    }
+}
+
+impl PartialEq for RewriteRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern.as_str() == other.pattern.as_str() && self.replacement == other.replacement
+    }
+}
+
+impl RewriteRules {
+    pub fn new() -> Self {
+        Self(vec![])
+    }
+
+    /// Loads rules from JSON file, e.g. `[{"pattern": "^experimental/", "replacement": ""}]`
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        let content = read_to_string(&path)
+            .map_err(|e| format!("failed to read rewrite rules {:?}: {}", path, e))?;
+        let raw_rules: Vec<RawRewriteRule> = serde_json::from_str(&content)
+            .map_err(|e| format!("invalid rewrite rules {:?}: {}", path, e))?;
+
+        let mut rules = Self::new();
+        for raw_rule in raw_rules {
+            rules.add(RewriteRule::new(&raw_rule.pattern, &raw_rule.replacement)?);
+        }
+
+        Ok(rules)
+    }
+
+    pub fn add(&mut self, rule: RewriteRule) {
+        self.0.push(rule)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Applies all rules (in order) to package name of [`FMRI`], returns [`Rewrite`] if the name
+    /// was changed
+    pub fn rewrite(&self, fmri: &mut FMRI) -> Option<Rewrite> {
+        let original = fmri.get_package_name_as_ref_string().clone();
+
+        let mut rewritten = original.clone();
+        for rule in &self.0 {
+            rewritten = rule
+                .pattern
+                .replace_all(&rewritten, rule.replacement.as_str())
+                .into_owned();
+        }
+
+        if rewritten == original || rewritten.is_empty() {
+            return None;
+        }
+
+        *fmri.get_package_name_as_ref_mut_string() = rewritten.clone();
+
+        Some(Rewrite {
+            original,
+            rewritten,
+        })
+    }
+}
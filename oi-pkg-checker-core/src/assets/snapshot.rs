@@ -0,0 +1,57 @@
+use std::{
+    fs::{create_dir_all, read_dir, remove_dir_all},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use log::info;
+
+/// Extracts snapshot of oi-userland (e.g. `.tar.gz` of release tag) into `directory` and returns
+/// path to its components directory, snapshot can contain oi-userland directly or in one
+/// top-level directory (e.g. `oi-userland-2024.04/components`)
+pub fn extract_snapshot(tarball: &Path, directory: &Path) -> Result<PathBuf, String> {
+    if directory.exists() {
+        remove_dir_all(directory)
+            .map_err(|e| format!("failed to remove {:?}: {}", directory, e))?;
+    }
+    create_dir_all(directory).map_err(|e| format!("failed to create {:?}: {}", directory, e))?;
+
+    let output = Command::new("tar")
+        .arg("-xf")
+        .arg(tarball)
+        .arg("-C")
+        .arg(directory)
+        .output()
+        .map_err(|e| format!("failed to run tar: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "failed to extract {:?}: {}",
+            tarball,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let components = snapshot_components(directory)
+        .ok_or_else(|| format!("{:?} is not snapshot of oi-userland", tarball))?;
+
+    info!("extracted {:?} into {:?}", tarball, directory);
+    Ok(components)
+}
+
+/// Returns components directory of extracted snapshot
+fn snapshot_components(directory: &Path) -> Option<PathBuf> {
+    if directory.join("components").is_dir() {
+        return Some(directory.join("components"));
+    }
+
+    let top_level: Vec<PathBuf> = read_dir(directory)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+
+    match top_level.as_slice() {
+        [top_level] if top_level.join("components").is_dir() => Some(top_level.join("components")),
+        _ => None,
+    }
+}
@@ -0,0 +1,211 @@
+/// Check that produces problems, see [`crate::ProblemKind::check_id`]
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Check {
+    pub id: &'static str,
+    pub description: &'static str,
+}
+
+/// Registry of all checks
+pub const CHECKS: &[Check] = &[
+    Check {
+        id: "input-validity",
+        description: "fmris and pkg5 files can be parsed",
+    },
+    Check {
+        id: "component-packages",
+        description: "every package is in pkg5 of one component and renamed or obsoleted \
+            packages are not",
+    },
+    Check {
+        id: "make-commands",
+        description: "gmake can print dependencies of component",
+    },
+    Check {
+        id: "timestamps",
+        description: "timestamps of package versions are plausible (--strict-timestamps)",
+    },
+    Check {
+        id: "dependency-validity",
+        description: "required packages exist and are not obsoleted",
+    },
+    Check {
+        id: "useless-components",
+        description: "components deliver at least one non-obsoleted package",
+    },
+    Check {
+        id: "renamed-needs-renamed",
+        description: "renamed packages are not renamed to renamed packages",
+    },
+    Check {
+        id: "version-constraints",
+        description: "requires don't pin more than incorporation",
+    },
+    Check {
+        id: "required-versions",
+        description: "required versions of packages are published",
+    },
+    Check {
+        id: "require-any-alternatives",
+        description: "require-any dependencies have more viable alternatives",
+    },
+    Check {
+        id: "category-policies",
+        description: "components follow policy of their category",
+    },
+    Check {
+        id: "package-names",
+        description: "package names follow naming conventions",
+    },
+    Check {
+        id: "build-cycles",
+        description: "components don't have cycles in build dependencies",
+    },
+    Check {
+        id: "dependency-kinds",
+        description: "dependencies have the right type",
+    },
+    Check {
+        id: "self-dependencies",
+        description: "packages and components don't depend on themselves",
+    },
+    Check {
+        id: "duplicate-dependencies",
+        description: "dependencies are not declared more times",
+    },
+    Check {
+        id: "rename-chains",
+        description:
+            "renamed packages are renamed to existing non-obsoleted packages without loops",
+    },
+    Check {
+        id: "history-collisions",
+        description: "new packages don't reuse names obsoleted or renamed in history",
+    },
+    Check {
+        id: "obsolete-dependencies",
+        description: "obsoleted package versions don't have dependencies",
+    },
+    Check {
+        id: "versioned-modules",
+        description: "interpreter modules have variants with interpreter version suffix",
+    },
+    Check {
+        id: "incorporation-constraints",
+        description: "published versions satisfy incorporate dependencies",
+    },
+    Check {
+        id: "incorporated-versions",
+        description: "incorporated versions are published and not obsoleted",
+    },
+    Check {
+        id: "incorporation-cycles",
+        description: "incorporations don't incorporate each other",
+    },
+    Check {
+        id: "dependency-bloat",
+        description: "components don't have much more build dependencies than components with \
+            the same BUILD_STYLE",
+    },
+    Check {
+        id: "unversioned-requires",
+        description: "require dependencies have version (unversioned_requires.enabled)",
+    },
+    Check {
+        id: "catalog-staleness",
+        description: "catalogs are recent and catalogs of publishers were updated at similar time",
+    },
+    Check {
+        id: "mirror-skew",
+        description: "origins of publisher have the same packages",
+    },
+    Check {
+        id: "publisher-version-skew",
+        description: "publishers of package have the same newest version",
+    },
+    Check {
+        id: "divergent-dependencies",
+        description: "the same package version has the same dependencies in all publishers",
+    },
+    Check {
+        id: "cross-publisher-dependencies",
+        description: "packages of the primary publisher need only packages it publishes",
+    },
+    Check {
+        id: "external-dependencies",
+        description: "packages don't need packages published only by external publishers",
+    },
+    Check {
+        id: "stale-required-packages",
+        description:
+            "REQUIRED_PACKAGES are referenced by runtime dependencies of component packages",
+    },
+    Check {
+        id: "only-stub-dependents",
+        description: "packages are required not only by obsoleted or renamed packages",
+    },
+    Check {
+        id: "orphan-packages",
+        description: "packages are reachable from root packages by runtime dependencies \
+            (orphan_packages.enabled)",
+    },
+    Check {
+        id: "duplicate-component-names",
+        description: "component names are listed only once in components.mk",
+    },
+    Check {
+        id: "same-packages-in-components",
+        description: "package is in pkg5 of only one component",
+    },
+    Check {
+        id: "foreign-packages-in-pkg5",
+        description: "packages are in pkg5 only of component with their manifest",
+    },
+    Check {
+        id: "file-conflicts",
+        description: "path is delivered by only one package (mediated links are allowed)",
+    },
+    Check {
+        id: "mediator-conflicts",
+        description: "packages delivering the same mediated path agree on mediation",
+    },
+    Check {
+        id: "obsolete-components",
+        description: "components don't list or build obsoleted packages",
+    },
+    Check {
+        id: "non-existing-packages-in-pkg5",
+        description: "packages in pkg5 exist",
+    },
+    Check {
+        id: "pkg5-consistency",
+        description: "pkg5 lists exactly the published packages with manifest in component",
+    },
+    Check {
+        id: "pkg5-names",
+        description: "name in pkg5 is COMPONENT_NAME of component",
+    },
+    Check {
+        id: "classifications",
+        description: "published packages have info.classification from approved scheme",
+    },
+    Check {
+        id: "licenses",
+        description: "components declare license and packages delivering files have license \
+            action",
+    },
+    Check {
+        id: "encumbered-placement",
+        description: "components with encumbered licenses are in encumbered repository and \
+            other components are not",
+    },
+    Check {
+        id: "installed-image",
+        description: "installed packages are published, not obsoleted or renamed and not held \
+            back by incorporations (check-image)",
+    },
+];
+
+/// Returns check from [`CHECKS`] with `id`
+pub fn get_check(id: &str) -> Option<&'static Check> {
+    CHECKS.iter().find(|check| check.id == id)
+}
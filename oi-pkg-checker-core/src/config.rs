@@ -0,0 +1,585 @@
+use std::{collections::HashMap, fs::read_to_string, path::PathBuf, sync::OnceLock};
+
+use fmri::FMRI;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::checks::get_check;
+
+/// Configuration of checks loaded from JSON file, missing keys use default values
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct Config {
+    pub category_policies: Vec<CategoryPolicy>,
+    /// owner (maintainer) of component by component name
+    pub component_owners: HashMap<String, String>,
+    pub naming: NamingConventions,
+    pub classifications: Classifications,
+    pub reclassification: Reclassification,
+    pub unversioned_requires: UnversionedRequires,
+    pub orphan_packages: OrphanPackages,
+    pub catalog_staleness: CatalogStaleness,
+    pub component_discovery: ComponentDiscovery,
+    pub encumbered_licenses: EncumberedLicenses,
+    pub publishers: Vec<PublisherConfig>,
+    /// names of publishers ordered by priority, package published by more of them is loaded
+    /// only from publisher with the highest priority, see
+    /// [`crate::Components::apply_publisher_priority`]
+    pub publisher_priority: Vec<String>,
+    /// URLs of depots (e.g. of other consolidations) where packages required by components, but
+    /// missing in loaded catalogs are looked up, see [`crate::lookup_non_existing_required`]
+    pub depot_lookup: Vec<String>,
+    /// runtime dependencies of published packages are loaded from pkg5 manifests of components
+    /// (uncommitted changes), see [`crate::load_manifest_dependencies`]
+    pub manifest_dependencies: bool,
+    /// REQUIRED_PACKAGES and TEST_REQUIRED_PACKAGES are parsed from Makefiles of components,
+    /// gmake is run only for Makefiles with conditionals or macros in them
+    pub native_makefiles: bool,
+    /// ids of [checks][crate::checks::CHECKS] whose problems are not reported
+    pub disabled_checks: Vec<String>,
+}
+
+/// Packages used to suggest other type of dependency, names are matched as prefixes
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Reclassification {
+    /// tools needed only during build, runtime dependencies on them should be build dependencies
+    pub build_tools: Vec<String>,
+    /// packages delivering shared libraries, build dependencies on them should be runtime
+    /// dependencies too
+    pub shared_libraries: Vec<String>,
+    /// packages excluded from `shared_libraries` (e.g. language modules)
+    pub not_shared_libraries: Vec<String>,
+}
+
+/// Publisher with other names used in catalogs (aliases) and origins (mirrors)
+#[derive(Deserialize, PartialEq, Default, Clone, Debug)]
+#[serde(default)]
+pub struct PublisherConfig {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub origins: Vec<Origin>,
+    /// packages of primary publisher must not need packages published only by other publishers
+    pub primary: bool,
+    /// packages of external publisher (e.g. osnet, jds or sfe) are not built by oi-userland
+    /// components
+    pub external: bool,
+}
+
+/// Origin of publisher and catalog (catalog.dependency.C) downloaded from it
+#[derive(Deserialize, PartialEq, Default, Clone, Debug)]
+#[serde(default)]
+pub struct Origin {
+    pub url: String,
+    /// catalog is fetched from depot at `url` if it is empty, see
+    /// [`crate::fetch_catalog`]
+    pub catalog: PathBuf,
+    /// URLs of depots mirroring `url` (e.g. internal mirror), catalog is fetched from the first
+    /// available of them before trying `url`
+    pub mirrors: Vec<String>,
+}
+
+/// Opt-in check of require dependencies without version, see
+/// [`crate::Components::check_unversioned_requires`]
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct UnversionedRequires {
+    pub enabled: bool,
+    /// regexes of names of packages that can have unversioned require dependencies
+    pub allowed_packages: Vec<String>,
+    /// metapackages can have unversioned require dependencies
+    pub allow_metapackages: bool,
+    /// compiled `allowed_packages`, see [`UnversionedRequires::regexes`]
+    #[serde(skip)]
+    pub(crate) regexes: OnceLock<Vec<Regex>>,
+}
+
+/// Opt-in check of packages not reachable from roots by runtime dependencies, see
+/// [`crate::Components::check_orphan_packages`]
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct OrphanPackages {
+    pub enabled: bool,
+    /// regexes of names of root packages
+    pub roots: Vec<String>,
+    /// metapackages are roots
+    pub metapackages: bool,
+    /// packages with incorporate dependencies are roots
+    pub incorporations: bool,
+    /// compiled `roots`, see [`OrphanPackages::regexes`]
+    #[serde(skip)]
+    pub(crate) regexes: OnceLock<Vec<Regex>>,
+}
+
+/// Check of age of loaded catalogs, see [`crate::Components::check_catalog_staleness`]
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct CatalogStaleness {
+    /// catalog last modified more days ago is stale (0 disables the check)
+    pub max_age_days: u64,
+    /// catalog last modified more days before the newest catalog of other publishers is stale
+    /// (0 disables the check)
+    pub max_skew_days: u64,
+}
+
+/// Discovery of components in oi-userland, see [`crate::ComponentPackagesList::discover`]
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct ComponentDiscovery {
+    /// components are found by scanning directories instead of generating components.mk with
+    /// gmake
+    pub scan: bool,
+    /// component directories not scanned, directory (e.g. `python/foo`) or prefix ending with
+    /// `*` (e.g. `python/*`) like COMPONENTS_IGNORE
+    pub ignore: Vec<String>,
+    /// additional component trees (e.g. downstream forks) scanned after oi-userland, their
+    /// components shadow components with the same name, later overlays shadow earlier ones
+    pub overlays: Vec<PathBuf>,
+}
+
+/// Licenses of components that belong to the encumbered repository, see
+/// [`crate::ComponentPackagesList::encumbered_placement`]
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct EncumberedLicenses {
+    /// license identifiers (case insensitive) of encumbered components (empty disables the check)
+    pub licenses: Vec<String>,
+    /// name of directory with encumbered components (e.g. `components/encumbered`)
+    pub directory: String,
+}
+
+/// Conventions for package names, see [`crate::Components::check_package_names`]
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct NamingConventions {
+    /// first segment of package name must be one of these categories (empty means any category)
+    pub allowed_categories: Vec<String>,
+    /// package name must not contain uppercase letters
+    pub lowercase: bool,
+    /// package name must not start with these prefixes (e.g. legacy `SUNW` names)
+    pub forbidden_prefixes: Vec<String>,
+    /// regex that branch of package version (e.g. `2024.0.0.1`) must match (empty means any
+    /// branch)
+    pub branch_pattern: String,
+    /// packages with name starting with `prefix` must match `pattern`
+    pub patterns: Vec<NamePattern>,
+    /// prefixes of interpreter modules that must have variants with interpreter version suffix
+    /// (e.g. `library/python/foo-311`)
+    pub versioned_module_prefixes: Vec<String>,
+    /// compiled `patterns` and `branch_pattern`, they must not be changed after the first check,
+    /// see [`NamingConventions::regexes`]
+    #[serde(skip)]
+    pub(crate) regexes: OnceLock<(Vec<Regex>, Option<Regex>)>,
+}
+
+/// Approved values of `info.classification`, see
+/// [`crate::assets::open_indiana_oi_userland_git::ComponentPackagesList::classifications`]
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Classifications {
+    /// scheme before `:` in value (e.g. `org.opensolaris.category.2008`)
+    pub scheme: String,
+    /// allowed sections (e.g. `System`) or sections with subsection (e.g. `System/Shells`)
+    pub sections: Vec<String>,
+}
+
+/// Regex that names of packages starting with `prefix` must match
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct NamePattern {
+    pub prefix: String,
+    pub pattern: String,
+}
+
+/// Policy for components in category (components with name starting with `category`, e.g.
+/// `meta-packages/`)
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct CategoryPolicy {
+    pub category: String,
+    /// components in category must not need packages from components in these categories
+    pub forbidden_dependency_categories: Vec<String>,
+    /// packages in category must start with one of these prefixes (empty means any package)
+    pub allowed_package_prefixes: Vec<String>,
+    /// packages in category must be metapackages, see [`crate::Components::is_metapackage`]
+    pub metapackages_only: bool,
+}
+
+impl Config {
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        let content = read_to_string(&path)
+            .map_err(|e| format!("failed to read config {:?}: {}", path, e))?;
+        let config: Self =
+            serde_json::from_str(&content).map_err(|e| format!("invalid config: {}", e))?;
+
+        config.naming.regexes()?;
+
+        config.unversioned_requires.regexes()?;
+        config.orphan_packages.regexes()?;
+
+        for check_id in &config.disabled_checks {
+            if get_check(check_id).is_none() {
+                return Err(format!("unknown check: {}", check_id));
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Returns true if catalogs are fetched from depots or packages are looked up in depots, both
+    /// need `curl`, see [`crate::check_curl`]
+    pub fn uses_depots(&self) -> bool {
+        !self.depot_lookup.is_empty()
+            || self
+                .publishers
+                .iter()
+                .flat_map(|publisher| &publisher.origins)
+                .any(|origin| origin.catalog.as_os_str().is_empty() && !origin.url.is_empty())
+    }
+}
+
+impl Default for NamingConventions {
+    /// Conventions of OpenIndiana
+    fn default() -> Self {
+        Self {
+            allowed_categories: [
+                "application", "archiver", "audio", "benchmark", "codec", "communication",
+                "compress", "consolidation", "crypto", "data", "database", "desktop", "developer",
+                "diagnostic", "documentation", "driver", "editor", "entire", "file", "gnome",
+                "image", "incorporation", "install", "library", "locale", "mail", "media",
+                "metapackages", "network", "package", "print", "release", "runtime", "security",
+                "service", "shell", "storage", "system", "terminal", "text", "web", "x11",
+            ]
+            .map(|category| category.to_owned())
+            .into(),
+            lowercase: true,
+            forbidden_prefixes: vec!["SUNW".to_owned()],
+            branch_pattern: r"^[0-9]{4}\.[0-9]+\.[0-9]+\.[0-9]+$".to_owned(),
+            patterns: vec![
+                NamePattern {
+                    prefix: "library/python/".to_owned(),
+                    pattern: r"^library/python/([a-z0-9][a-z0-9_+-]*[a-z_+-]|[a-z0-9][a-z0-9._+-]*-[0-9]{2,3})$".to_owned(),
+                },
+                NamePattern {
+                    prefix: "library/perl-5/".to_owned(),
+                    pattern: r"^library/perl-5/([a-z0-9][a-z0-9_+-]*[a-z_+-]|[a-z0-9][a-z0-9._+-]*-[0-9]{3})$".to_owned(),
+                },
+            ],
+            versioned_module_prefixes: vec![
+                "library/python/".to_owned(),
+                "library/perl-5/".to_owned(),
+            ],
+            regexes: OnceLock::new(),
+        }
+    }
+}
+
+impl Default for Classifications {
+    /// Classification scheme of pkg(7)
+    fn default() -> Self {
+        Self {
+            scheme: "org.opensolaris.category.2008".to_owned(),
+            sections: [
+                "Applications",
+                "Desktop (GNOME)",
+                "Development",
+                "Drivers",
+                "Meta Packages",
+                "System",
+                "Web Services",
+            ]
+            .map(|section| section.to_owned())
+            .into(),
+        }
+    }
+}
+
+impl Classifications {
+    /// Returns reason why classification `value` is not approved
+    pub fn check(&self, value: &str) -> Option<String> {
+        let Some((scheme, path)) = value.split_once(':') else {
+            return Some("value has no scheme".to_owned());
+        };
+
+        if scheme != self.scheme {
+            return Some(format!("scheme is not {}", self.scheme));
+        }
+
+        match path.split_once('/') {
+            Some((_, subsection)) if !subsection.is_empty() => {}
+            _ => return Some("value has no subsection".to_owned()),
+        }
+
+        if !self.sections.is_empty()
+            && !self
+                .sections
+                .iter()
+                .any(|section| path == section || path.starts_with(&format!("{}/", section)))
+        {
+            return Some("section is not allowed".to_owned());
+        }
+
+        None
+    }
+}
+
+impl Default for Reclassification {
+    fn default() -> Self {
+        let owned = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+
+        Self {
+            build_tools: owned(&[
+                "developer/build/autoconf",
+                "developer/build/automake",
+                "developer/build/cmake",
+                "developer/build/gnu-make",
+                "developer/build/libtool",
+                "developer/build/meson",
+                "developer/build/ninja",
+                "developer/build/pkg-config",
+                "developer/gcc",
+                "developer/clang",
+                "developer/lexer/flex",
+                "developer/parser/bison",
+            ]),
+            shared_libraries: owned(&["library/"]),
+            not_shared_libraries: owned(&[
+                "library/golang/",
+                "library/java/",
+                "library/perl-5/",
+                "library/python/",
+                "library/ruby/",
+            ]),
+        }
+    }
+}
+
+impl Reclassification {
+    pub fn is_build_tool(&self, package_name: &str) -> bool {
+        self.build_tools
+            .iter()
+            .any(|prefix| package_name.starts_with(prefix))
+    }
+
+    pub fn is_shared_library(&self, package_name: &str) -> bool {
+        self.shared_libraries
+            .iter()
+            .any(|prefix| package_name.starts_with(prefix))
+            && !self
+                .not_shared_libraries
+                .iter()
+                .any(|prefix| package_name.starts_with(prefix))
+    }
+}
+
+impl NamingConventions {
+    /// Returns [`None`] if any branch is allowed
+    pub fn branch_regex(&self) -> Result<Option<Regex>, String> {
+        if self.branch_pattern.is_empty() {
+            return Ok(None);
+        }
+
+        Regex::new(&self.branch_pattern)
+            .map(Some)
+            .map_err(|e| format!("invalid branch pattern: {}", e))
+    }
+
+    /// Returns compiled `patterns` (in the same order) and `branch_pattern`, they are compiled
+    /// only once, by the first call (in [`Config::load`])
+    pub fn regexes(&self) -> Result<&(Vec<Regex>, Option<Regex>), String> {
+        if let Some(regexes) = self.regexes.get() {
+            return Ok(regexes);
+        }
+
+        let pattern_regexes = self
+            .patterns
+            .iter()
+            .map(NamePattern::regex)
+            .collect::<Result<Vec<Regex>, String>>()?;
+        let branch_regex = self.branch_regex()?;
+
+        Ok(self.regexes.get_or_init(|| (pattern_regexes, branch_regex)))
+    }
+
+    /// Returns reasons why package name or branch of its version violates conventions
+    pub fn check(&self, fmri: &FMRI) -> Vec<String> {
+        let package_name = fmri.get_package_name_as_ref_string();
+        let mut violations = vec![];
+
+        let category = package_name.split('/').next().unwrap_or_default();
+        if !self.allowed_categories.is_empty()
+            && !self.allowed_categories.iter().any(|c| c == category)
+        {
+            violations.push("top-level category is not allowed".to_owned());
+        }
+
+        if self.lowercase && package_name.chars().any(|c| c.is_uppercase()) {
+            violations.push("name contains uppercase letters".to_owned());
+        }
+
+        if let Some(prefix) = self
+            .forbidden_prefixes
+            .iter()
+            .find(|prefix| package_name.starts_with(prefix.as_str()))
+        {
+            violations.push(format!("name starts with forbidden prefix {}", prefix));
+        }
+
+        let (pattern_regexes, branch_regex) = self.regexes().expect("invalid pattern");
+
+        for (name_pattern, regex) in self.patterns.iter().zip(pattern_regexes) {
+            if package_name.starts_with(&name_pattern.prefix) && !regex.is_match(package_name) {
+                violations.push(format!("name doesn't match {}", name_pattern.pattern));
+            }
+        }
+
+        let branch = fmri.get_version_as_string().and_then(|version| {
+            let version = version.split(':').next().unwrap_or_default().to_owned();
+            version.split_once('-').map(|(_, branch)| branch.to_owned())
+        });
+        if let (Some(branch), Some(regex)) = (branch, branch_regex) {
+            if !regex.is_match(&branch) {
+                violations.push(format!(
+                    "branch {} doesn't match {}",
+                    branch, self.branch_pattern
+                ));
+            }
+        }
+
+        violations
+    }
+
+    /// Returns prefix, name without version suffix and version suffix (e.g. `311`) of
+    /// interpreter module, [`None`] if package is not interpreter module
+    pub fn split_module_version<'a>(
+        &'a self,
+        package_name: &'a str,
+    ) -> Option<(&'a str, &'a str, Option<&'a str>)> {
+        let prefix = self
+            .versioned_module_prefixes
+            .iter()
+            .find(|prefix| package_name.starts_with(prefix.as_str()))?;
+
+        match package_name.rsplit_once('-') {
+            Some((name, suffix))
+                if name.len() > prefix.len()
+                    && (2..=3).contains(&suffix.len())
+                    && suffix.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                Some((prefix, name, Some(suffix)))
+            }
+            _ => Some((prefix, package_name, None)),
+        }
+    }
+}
+
+impl PublisherConfig {
+    pub fn is_named(&self, publisher: &str) -> bool {
+        self.name == publisher || self.aliases.iter().any(|alias| alias == publisher)
+    }
+}
+
+impl Default for UnversionedRequires {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allowed_packages: vec!["^metapackages/".to_owned()],
+            allow_metapackages: true,
+            regexes: OnceLock::new(),
+        }
+    }
+}
+
+impl UnversionedRequires {
+    /// Returns compiled `allowed_packages` (in the same order), they are compiled only once, by
+    /// the first call (in [`Config::load`])
+    pub fn regexes(&self) -> Result<&Vec<Regex>, String> {
+        if let Some(regexes) = self.regexes.get() {
+            return Ok(regexes);
+        }
+
+        let regexes = compile_patterns(&self.allowed_packages)?;
+        Ok(self.regexes.get_or_init(|| regexes))
+    }
+}
+
+impl Default for CatalogStaleness {
+    fn default() -> Self {
+        Self {
+            max_age_days: 30,
+            max_skew_days: 7,
+        }
+    }
+}
+
+impl Default for EncumberedLicenses {
+    fn default() -> Self {
+        Self {
+            licenses: vec![],
+            directory: "encumbered".to_owned(),
+        }
+    }
+}
+
+impl Default for OrphanPackages {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            roots: ["^metapackages/", "^incorporation/", "^group/", "^entire$"]
+                .map(|pattern| pattern.to_owned())
+                .into(),
+            metapackages: true,
+            incorporations: true,
+            regexes: OnceLock::new(),
+        }
+    }
+}
+
+impl OrphanPackages {
+    /// Returns compiled `roots` (in the same order), they are compiled only once, by the first
+    /// call (in [`Config::load`])
+    pub fn regexes(&self) -> Result<&Vec<Regex>, String> {
+        if let Some(regexes) = self.regexes.get() {
+            return Ok(regexes);
+        }
+
+        let regexes = compile_patterns(&self.roots)?;
+        Ok(self.regexes.get_or_init(|| regexes))
+    }
+}
+
+/// Compiles regexes, error names the invalid pattern
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern).map_err(|e| format!("invalid pattern {}: {}", pattern, e))
+        })
+        .collect()
+}
+
+impl NamePattern {
+    pub fn regex(&self) -> Result<Regex, String> {
+        Regex::new(&self.pattern).map_err(|e| format!("invalid pattern for {}: {}", self.prefix, e))
+    }
+}
+
+impl CategoryPolicy {
+    pub fn contains_component(&self, component_name: &str) -> bool {
+        component_name.starts_with(&self.category)
+    }
+
+    pub fn is_dependency_category_forbidden(&self, component_name: &str) -> bool {
+        self.forbidden_dependency_categories
+            .iter()
+            .any(|category| component_name.starts_with(category))
+    }
+
+    pub fn is_package_allowed(&self, package_name: &str) -> bool {
+        self.allowed_package_prefixes.is_empty()
+            || self
+                .allowed_package_prefixes
+                .iter()
+                .any(|prefix| package_name.starts_with(prefix))
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+
+use fmri::FMRI;
+
+use serde::Serialize;
+
+use crate::packages::{
+    components::Components, depend_types::DependTypes, dependency_type::DependencyTypes,
+    package::Package, package_versions::PackageVersions,
+};
+
+/// Edge of dependency path
+#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub struct DependencyEdge {
+    pub from: String,
+    pub to: String,
+    pub dependency_type: DependencyTypes,
+    /// name of depend action (require, optional, ...)
+    pub depend_type: String,
+}
+
+/// Dependency paths between two packages
+#[derive(Debug)]
+pub struct DependencyPaths {
+    pub source: String,
+    pub target: String,
+    pub paths: Vec<Vec<DependencyEdge>>,
+    /// state of packages in paths (obsolete, renamed)
+    states: HashMap<String, (bool, bool)>,
+}
+
+impl DependencyPaths {
+    /// Finds all dependency paths (without repeated packages) from `source` to `target` with at
+    /// most `max_depth` edges
+    pub fn new(components: &Components, source: &FMRI, target: &FMRI, max_depth: usize) -> Self {
+        let mut packages: HashMap<&String, &PackageVersions> = HashMap::new();
+        let namespace_filter = components.get_namespace_filter_ref();
+        for component in components.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if !namespace_filter
+                    .allows(package_versions.fmri_ref().get_package_name_as_ref_string())
+                {
+                    continue;
+                }
+
+                packages.insert(
+                    package_versions.fmri_ref().get_package_name_as_ref_string(),
+                    package_versions,
+                );
+            }
+        }
+
+        let mut dependency_paths = Self {
+            source: source.get_package_name_as_ref_string().clone(),
+            target: target.get_package_name_as_ref_string().clone(),
+            paths: vec![],
+            states: HashMap::new(),
+        };
+
+        let mut path = vec![];
+        dependency_paths.find(
+            &packages,
+            &dependency_paths.source.clone(),
+            &mut path,
+            max_depth,
+        );
+
+        for path in &dependency_paths.paths {
+            for edge in path {
+                for name in [&edge.from, &edge.to] {
+                    let state = match packages.get(name) {
+                        Some(package_versions) => (
+                            package_versions.is_obsolete(),
+                            package_versions.is_renamed(),
+                        ),
+                        None => (
+                            components
+                                .get_obsoleted_ref()
+                                .get_ref()
+                                .iter()
+                                .any(|obsoleted| {
+                                    obsoleted.get_package_name_as_ref_string() == name
+                                }),
+                            false,
+                        ),
+                    };
+                    dependency_paths.states.insert(name.clone(), state);
+                }
+            }
+        }
+
+        dependency_paths
+    }
+
+    fn find(
+        &mut self,
+        packages: &HashMap<&String, &PackageVersions>,
+        current: &String,
+        path: &mut Vec<DependencyEdge>,
+        max_depth: usize,
+    ) {
+        if current == &self.target && !path.is_empty() {
+            self.paths.push(path.clone());
+            return;
+        }
+
+        if path.len() >= max_depth {
+            return;
+        }
+
+        let Some(package) = packages
+            .get(current)
+            .and_then(|package_versions| package_versions.get_packages_ref().last())
+        else {
+            return;
+        };
+
+        for edge in package_edges(current, package) {
+            if edge.to == self.source
+                || path.iter().any(|added| added.to == edge.to)
+                || (edge.to != self.target && !packages.contains_key(&edge.to))
+            {
+                continue;
+            }
+
+            let to = edge.to.clone();
+            path.push(edge);
+            self.find(packages, &to, path, max_depth);
+            path.pop();
+        }
+    }
+
+    /// Returns paths as Mermaid flowchart
+    pub fn to_mermaid(&self) -> String {
+        // names of nodes in order of appearance and their ids
+        let mut names: Vec<&String> = vec![];
+        let mut ids: HashMap<&String, usize> = HashMap::new();
+        let mut edges: Vec<String> = vec![];
+
+        for path in &self.paths {
+            for edge in path {
+                for name in [&edge.from, &edge.to] {
+                    if !ids.contains_key(name) {
+                        ids.insert(name, names.len());
+                        names.push(name);
+                    }
+                }
+
+                let line = format!(
+                    "    n{} -->|{} {}| n{}",
+                    ids[&edge.from], edge.dependency_type, edge.depend_type, ids[&edge.to]
+                );
+
+                if !edges.contains(&line) {
+                    edges.push(line);
+                }
+            }
+        }
+
+        let mut mermaid = "graph LR\n".to_owned();
+
+        for (id, name) in names.iter().enumerate() {
+            let class = match self.states.get(*name) {
+                Some((true, _)) => ":::obsolete",
+                Some((_, true)) => ":::renamed",
+                _ => "",
+            };
+            mermaid.push_str(&format!("    n{}[\"{}\"]{}\n", id, name, class));
+        }
+
+        for edge in edges {
+            mermaid.push_str(&edge);
+            mermaid.push('\n');
+        }
+
+        mermaid.push_str("    classDef obsolete fill:#f99,stroke:#900\n");
+        mermaid.push_str("    classDef renamed fill:#ff9,stroke:#990\n");
+
+        mermaid
+    }
+
+    /// Returns paths as standalone HTML page with Mermaid flowchart
+    pub fn to_html(&self) -> String {
+        format!(
+            "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Dependency paths from {} to {}</title>
+<script src=\"https://cdn.jsdelivr.net/npm/mermaid/dist/mermaid.min.js\"></script>
+</head>
+<body>
+<h1>Dependency paths from {} to {} ({} paths)</h1>
+<pre class=\"mermaid\">
+{}</pre>
+<script>mermaid.initialize({{ startOnLoad: true }});</script>
+</body>
+</html>
+",
+            self.source,
+            self.target,
+            self.source,
+            self.target,
+            self.paths.len(),
+            self.to_mermaid()
+        )
+    }
+}
+
+/// Returns edges from package named `from` to packages it depends on (predicate of conditional
+/// dependency is not a dependency)
+pub(crate) fn package_edges(from: &str, package: &Package) -> Vec<DependencyEdge> {
+    let mut edges = vec![];
+
+    for (dependencies, dependency_type) in [
+        (package.get_runtime_dependencies(), DependencyTypes::Runtime),
+        (package.get_build_dependencies(), DependencyTypes::Build),
+        (package.get_test_dependencies(), DependencyTypes::Test),
+        (
+            package.get_system_build_dependencies(),
+            DependencyTypes::SystemBuild,
+        ),
+        (
+            package.get_system_test_dependencies(),
+            DependencyTypes::SystemTest,
+        ),
+    ] {
+        for dependency in dependencies {
+            let depend_type = match dependency.get_ref() {
+                DependTypes::Require(_) => "require",
+                DependTypes::Optional(_) => "optional",
+                DependTypes::Incorporate(_) => "incorporate",
+                DependTypes::RequireAny(_) => "require-any",
+                DependTypes::Conditional(_, _) => "conditional",
+                DependTypes::Group(_) => "group",
+                _ => continue,
+            };
+
+            let fmris = match dependency.get_ref() {
+                DependTypes::Conditional(fmri, _) => vec![fmri],
+                depend_type => depend_type.get_fmris_ref(),
+            };
+
+            for fmri in fmris {
+                edges.push(DependencyEdge {
+                    from: from.to_owned(),
+                    to: fmri.get_package_name_as_ref_string().clone(),
+                    dependency_type: dependency_type.clone(),
+                    depend_type: depend_type.to_owned(),
+                });
+            }
+        }
+    }
+
+    edges
+}
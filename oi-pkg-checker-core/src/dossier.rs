@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+
+use fmri::FMRI;
+
+use crate::{
+    packages::{
+        components::{Components, Dependents},
+        dependency::Dependency,
+        dependency_type::DependencyTypes,
+    },
+    problems::{Problem, Problems},
+    ComponentPackagesList,
+};
+
+/// Complete report about one package
+#[derive(Debug)]
+pub struct Dossier {
+    pub fmri: FMRI,
+    /// loaded versions of package (only the newest one is kept)
+    pub versions: Vec<FMRI>,
+    /// obsoleted versions of package (history)
+    pub obsoleted_versions: Vec<FMRI>,
+    pub obsolete: bool,
+    pub renamed: bool,
+    /// see [`Components::is_metapackage`]
+    pub metapackage: bool,
+    pub component_name: Option<String>,
+    pub component_path: Option<PathBuf>,
+    /// dependencies of package by dependency type
+    pub dependencies: Vec<(DependencyTypes, Vec<Dependency>)>,
+    /// packages that need this package
+    pub dependents: Option<Dependents>,
+    /// problems in which package or its component occur
+    pub problems: Vec<Problem>,
+}
+
+impl Dossier {
+    /// Returns [`None`] if package doesn't exist and was never obsoleted
+    pub fn new(
+        components: &Components,
+        problems: &Problems,
+        component_packages_list: &ComponentPackagesList,
+        fmri: &FMRI,
+        full_incorporate: bool,
+    ) -> Option<Self> {
+        let obsoleted_versions: Vec<FMRI> = components
+            .get_obsoleted_ref()
+            .get_ref()
+            .iter()
+            .filter(|obsoleted| obsoleted.package_name_eq(fmri))
+            .cloned()
+            .collect();
+
+        let package_versions = components.get_package_versions_from_fmri(fmri);
+
+        if package_versions.is_none() && obsoleted_versions.is_empty() {
+            return None;
+        }
+
+        let mut dossier = Self {
+            fmri: fmri.clone(),
+            versions: vec![],
+            obsoleted_versions,
+            obsolete: false,
+            renamed: false,
+            metapackage: false,
+            component_name: None,
+            component_path: None,
+            dependencies: vec![],
+            dependents: components.get_dependencies_with_fmri(fmri, full_incorporate),
+            problems: vec![],
+        };
+
+        if let Some(package_versions) = &package_versions {
+            dossier.obsolete = package_versions.is_obsolete();
+            dossier.renamed = package_versions.is_renamed();
+            dossier.metapackage = components.is_metapackage(package_versions);
+
+            for package in package_versions.get_packages_ref() {
+                dossier.versions.push(package.fmri_ref().clone());
+            }
+
+            if let Some(package) = package_versions.get_packages_ref().last() {
+                dossier.dependencies = vec![
+                    (
+                        DependencyTypes::Runtime,
+                        package.get_runtime_dependencies().clone(),
+                    ),
+                    (
+                        DependencyTypes::Build,
+                        package.get_build_dependencies().clone(),
+                    ),
+                    (
+                        DependencyTypes::Test,
+                        package.get_test_dependencies().clone(),
+                    ),
+                    (
+                        DependencyTypes::SystemBuild,
+                        package.get_system_build_dependencies().clone(),
+                    ),
+                    (
+                        DependencyTypes::SystemTest,
+                        package.get_system_test_dependencies().clone(),
+                    ),
+                ];
+            }
+
+            if let Some(component_packages) = component_packages_list
+                .get_component_packages_of_package_versions(&mut Problems::new(), package_versions)
+            {
+                dossier.component_name = Some(component_packages.component_name);
+                dossier.component_path = Some(component_packages.path_to_component);
+            }
+        }
+
+        if dossier.component_name.is_none() {
+            dossier.component_name = components.get_component_name_by_package(fmri).cloned();
+        }
+
+        dossier.problems = problems.filter_by_package(fmri).cloned().collect();
+        if let Some(component_name) = &dossier.component_name {
+            for problem in problems.filter_by_component(component_name) {
+                if !dossier.problems.contains(problem) {
+                    dossier.problems.push(problem.clone())
+                }
+            }
+        }
+
+        Some(dossier)
+    }
+}
@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::{
+    packages::{components::Components, dependency_type::DependencyTypes},
+    problems::{Problem, ProblemKind, Problems},
+    ComponentPackagesList,
+};
+
+/// Kind of change in file
+#[derive(Serialize, PartialEq, Clone, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    /// remove `old_value`
+    Remove,
+    /// add `new_value`
+    Add,
+}
+
+/// One change that fixes a problem, checker only describes it, applying is up to the caller
+///
+/// Values are package names, operations with `variable` change list of packages in that Makefile
+/// variable, so they don't depend on how the assignment is written (`=` or `+=`, spacing,
+/// continuation lines).
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct FixOperation {
+    /// fixed problem, [`None`] for renamed REQUIRED_PACKAGES, which are not reported as problem
+    pub problem: Option<ProblemKind>,
+    pub file: PathBuf,
+    pub variable: Option<String>,
+    pub operation: Operation,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+/// Returns fix operations for problems that can be fixed automatically:
+/// packages that should not be in pkg5 and renamed packages in REQUIRED_PACKAGES (replaced by
+/// removal of renamed package and addition of its rename target)
+pub fn fix_plan(
+    problems: &Problems,
+    components: &Components,
+    component_packages_list: &ComponentPackagesList,
+) -> Vec<FixOperation> {
+    let component_path = |component_name: &String| -> Option<PathBuf> {
+        component_packages_list
+            .get()
+            .iter()
+            .find(|component_packages| &component_packages.component_name == component_name)
+            .map(|component_packages| component_packages.path_to_component.clone())
+    };
+
+    let mut fix_plan = vec![];
+
+    for problem in problems.get_ref() {
+        match problem {
+            Problem::RenamedPackageInComponent(fmri, component_name)
+            | Problem::ObsoletedPackageInComponent(fmri, component_name)
+            | Problem::NonExistingPackageInPkg5(fmri, component_name) => {
+                if let Some(path) = component_path(component_name) {
+                    fix_plan.push(FixOperation {
+                        problem: Some(problem.kind()),
+                        file: path.join("pkg5"),
+                        variable: None,
+                        operation: Operation::Remove,
+                        old_value: Some(fmri.get_package_name_as_ref_string().clone()),
+                        new_value: None,
+                    })
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (fmri, dependency_type, component_name) in components.get_renamed_required_by_components() {
+        let variable = match dependency_type {
+            DependencyTypes::Build => "REQUIRED_PACKAGES",
+            DependencyTypes::Test => "TEST_REQUIRED_PACKAGES",
+            _ => continue,
+        };
+
+        if let (Some(path), Some(target)) = (
+            component_path(&component_name),
+            components.get_rename_target(&fmri),
+        ) {
+            fix_plan.push(FixOperation {
+                problem: None,
+                file: path.join("Makefile"),
+                variable: Some(variable.to_owned()),
+                operation: Operation::Remove,
+                old_value: Some(fmri.get_package_name_as_ref_string().clone()),
+                new_value: None,
+            });
+            fix_plan.push(FixOperation {
+                problem: None,
+                file: path.join("Makefile"),
+                variable: Some(variable.to_owned()),
+                operation: Operation::Add,
+                old_value: None,
+                new_value: Some(target.get_package_name_as_ref_string().clone()),
+            });
+        }
+    }
+
+    fix_plan
+}
@@ -0,0 +1,139 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use fmri::FMRI;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{
+    dependency_paths::{package_edges, DependencyEdge},
+    packages::components::Components,
+    problems::{AlternativeState, Problems},
+};
+
+/// Package in [`ForceGraph`]
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct GraphNode {
+    pub id: String,
+    pub component: Option<String>,
+    pub obsolete: bool,
+    pub renamed: bool,
+    /// number of problems in which package occurs
+    pub problems: usize,
+}
+
+/// Dependency graph of the newest package versions for force-directed visualizers
+///
+/// Packages that are only required (e.g. obsoleted packages) are nodes too, so every edge connects
+/// existing nodes. Packages not passing [namespace filter][Components::set_namespace_filter] are
+/// left out.
+#[derive(PartialEq, Clone, Debug)]
+pub struct ForceGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<DependencyEdge>,
+}
+
+impl ForceGraph {
+    pub fn new(components: &Components, problems: &Problems) -> Self {
+        let namespace_filter = components.get_namespace_filter_ref();
+
+        let mut nodes: BTreeMap<String, GraphNode> = BTreeMap::new();
+        let mut edges: BTreeSet<DependencyEdge> = BTreeSet::new();
+
+        let node =
+            |fmri: &FMRI, component: Option<String>, obsolete: bool, renamed: bool| GraphNode {
+                id: fmri.get_package_name_as_ref_string().clone(),
+                component,
+                obsolete,
+                renamed,
+                problems: problems.filter_by_package(fmri).count(),
+            };
+
+        for component in components.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                let fmri = package_versions.fmri_ref();
+                let name = fmri.get_package_name_as_ref_string();
+                if !namespace_filter.allows(name) {
+                    continue;
+                }
+
+                nodes.insert(
+                    name.clone(),
+                    node(
+                        fmri,
+                        Some(component.get_name_ref().clone()).filter(|name| !name.is_empty()),
+                        package_versions.is_obsolete(),
+                        package_versions.is_renamed(),
+                    ),
+                );
+
+                if let Some(package) = package_versions.get_packages_ref().last() {
+                    edges.extend(
+                        package_edges(name, package)
+                            .into_iter()
+                            .filter(|edge| namespace_filter.allows(&edge.to)),
+                    );
+                }
+            }
+        }
+
+        for edge in &edges {
+            if !nodes.contains_key(&edge.to) {
+                let Ok(fmri) = FMRI::parse_raw(&edge.to) else {
+                    continue;
+                };
+                let obsolete =
+                    components.get_alternative_state(&fmri) == AlternativeState::Obsolete;
+                nodes.insert(edge.to.clone(), node(&fmri, None, obsolete, false));
+            }
+        }
+
+        Self {
+            nodes: nodes.into_values().collect(),
+            edges: edges.into_iter().collect(),
+        }
+    }
+
+    /// Returns graph as `{"nodes": [...], "links": [...]}` for d3-force
+    pub fn to_d3(&self) -> Value {
+        json!({
+            "nodes": self.nodes,
+            "links": self
+                .edges
+                .iter()
+                .map(|edge| json!({
+                    "source": edge.from,
+                    "target": edge.to,
+                    "dependency_type": edge.dependency_type,
+                    "depend_type": edge.depend_type,
+                }))
+                .collect::<Vec<Value>>(),
+        })
+    }
+
+    /// Returns graph as `{"elements": {"nodes": [...], "edges": [...]}}` for Cytoscape.js
+    pub fn to_cytoscape(&self) -> Value {
+        json!({
+            "elements": {
+                "nodes": self
+                    .nodes
+                    .iter()
+                    .map(|node| json!({ "data": node }))
+                    .collect::<Vec<Value>>(),
+                "edges": self
+                    .edges
+                    .iter()
+                    .enumerate()
+                    .map(|(index, edge)| json!({
+                        "data": {
+                            "id": format!("e{}", index),
+                            "source": edge.from,
+                            "target": edge.to,
+                            "dependency_type": edge.dependency_type,
+                            "depend_type": edge.depend_type,
+                        }
+                    }))
+                    .collect::<Vec<Value>>(),
+            }
+        })
+    }
+}
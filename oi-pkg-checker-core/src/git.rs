@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use git2::Repository;
+
+/// Opens git repository containing `path`
+fn discover(path: &Path) -> Result<Repository, String> {
+    Repository::discover(path).map_err(|e| format!("{:?} is not in git repository: {}", path, e))
+}
+
+/// Returns id of checked-out commit (HEAD) of git repository containing `path`
+pub fn head_commit(path: &Path) -> Result<String, String> {
+    let repository = discover(path)?;
+    let commit = repository
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| format!("failed to resolve HEAD of {:?}: {}", path, e))?;
+
+    Ok(commit.id().to_string())
+}
+
+/// Returns files (relative to top of repository) changed in `range` of git repository containing
+/// `path`, `range` is `<from>..<to>` or one commit compared with working tree including
+/// uncommitted changes (same as `git diff --name-only <range>`)
+pub fn changed_files(path: &Path, range: &str) -> Result<Vec<String>, String> {
+    let repository = discover(path)?;
+    let error = |e: git2::Error| format!("failed to get changes of {} in {:?}: {}", range, path, e);
+
+    let revspec = repository.revparse(range).map_err(error)?;
+    let Some(from) = revspec.from() else {
+        return Err(format!("invalid range {}", range));
+    };
+    let from = from.peel_to_tree().map_err(error)?;
+
+    let diff = match revspec.to() {
+        Some(to) => {
+            let to = to.peel_to_tree().map_err(error)?;
+            repository.diff_tree_to_tree(Some(&from), Some(&to), None)
+        }
+        None => repository.diff_tree_to_workdir_with_index(Some(&from), None),
+    }
+    .map_err(error)?;
+
+    let mut changed_files: Vec<String> = vec![];
+    for delta in diff.deltas() {
+        for file in [delta.old_file(), delta.new_file()] {
+            if let Some(file) = file.path().map(|file| file.to_string_lossy().to_string()) {
+                if !changed_files.contains(&file) {
+                    changed_files.push(file);
+                }
+            }
+        }
+    }
+
+    Ok(changed_files)
+}
@@ -0,0 +1,79 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Returns strongly connected components of directed graph given by `edges` (Tarjan's algorithm)
+///
+/// Nodes in every strongly connected component are sorted, components are sorted by their first
+/// node. Nodes that are only targets of edges are part of graph too.
+pub fn strongly_connected_components<N: Ord + Clone>(
+    edges: &BTreeMap<N, BTreeSet<N>>,
+) -> Vec<Vec<N>> {
+    struct State<'a, N> {
+        edges: &'a BTreeMap<N, BTreeSet<N>>,
+        index: BTreeMap<&'a N, usize>,
+        low_link: BTreeMap<&'a N, usize>,
+        stack: Vec<&'a N>,
+        on_stack: BTreeSet<&'a N>,
+        components: Vec<Vec<N>>,
+    }
+
+    fn visit<'a, N: Ord + Clone>(state: &mut State<'a, N>, node: &'a N) {
+        let index = state.index.len();
+        state.index.insert(node, index);
+        state.low_link.insert(node, index);
+        state.stack.push(node);
+        state.on_stack.insert(node);
+
+        if let Some(targets) = state.edges.get(node) {
+            for target in targets {
+                if !state.index.contains_key(target) {
+                    visit(state, target);
+                    let low_link = state.low_link[target].min(state.low_link[node]);
+                    state.low_link.insert(node, low_link);
+                } else if state.on_stack.contains(target) {
+                    let low_link = state.index[target].min(state.low_link[node]);
+                    state.low_link.insert(node, low_link);
+                }
+            }
+        }
+
+        if state.low_link[node] == state.index[node] {
+            let mut component = vec![];
+            while let Some(member) = state.stack.pop() {
+                state.on_stack.remove(member);
+                component.push(member.clone());
+                if member == node {
+                    break;
+                }
+            }
+            component.sort();
+            state.components.push(component);
+        }
+    }
+
+    let mut state = State {
+        edges,
+        index: BTreeMap::new(),
+        low_link: BTreeMap::new(),
+        stack: vec![],
+        on_stack: BTreeSet::new(),
+        components: vec![],
+    };
+
+    for node in edges.keys().chain(edges.values().flatten()) {
+        if !state.index.contains_key(node) {
+            visit(&mut state, node);
+        }
+    }
+
+    state.components.sort();
+    state.components
+}
+
+/// Returns strongly connected components with more than one node, i.e. nodes that are in cycle
+/// (self loops are not cycles)
+pub fn cycles<N: Ord + Clone>(edges: &BTreeMap<N, BTreeSet<N>>) -> Vec<Vec<N>> {
+    strongly_connected_components(edges)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .collect()
+}
@@ -0,0 +1,136 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::{
+    dependency_paths::{package_edges, DependencyEdge},
+    packages::components::Components,
+};
+
+/// Change of dependency graph (packages and their dependencies) between two analyses
+#[derive(Serialize, PartialEq, Debug)]
+#[serde(tag = "change", rename_all = "kebab-case")]
+pub enum GraphChange {
+    NodeAdded {
+        node: String,
+    },
+    NodeRemoved {
+        node: String,
+    },
+    EdgeAdded(DependencyEdge),
+    EdgeRemoved(DependencyEdge),
+    FlagChanged {
+        node: String,
+        /// obsolete or renamed
+        flag: &'static str,
+        old: bool,
+        new: bool,
+    },
+}
+
+/// Packages passing namespace filter by name with their obsolete and renamed flags
+fn nodes(components: &Components) -> BTreeMap<&String, [(&'static str, bool); 2]> {
+    let mut nodes = BTreeMap::new();
+    for component in components.get_ref() {
+        for package_versions in component.get_versions_ref() {
+            if !components
+                .get_namespace_filter_ref()
+                .allows(package_versions.fmri_ref().get_package_name_as_ref_string())
+            {
+                continue;
+            }
+
+            nodes.insert(
+                package_versions.fmri_ref().get_package_name_as_ref_string(),
+                [
+                    ("obsolete", package_versions.is_obsolete()),
+                    ("renamed", package_versions.is_renamed()),
+                ],
+            );
+        }
+    }
+    nodes
+}
+
+/// Edges from the newest version of every package, both ends of edge pass namespace filter
+fn edges(components: &Components) -> BTreeSet<DependencyEdge> {
+    let namespace_filter = components.get_namespace_filter_ref();
+    let mut edges = BTreeSet::new();
+    for component in components.get_ref() {
+        for package_versions in component.get_versions_ref() {
+            if let Some(package) = package_versions.get_packages_ref().last() {
+                edges.extend(
+                    package_edges(
+                        package_versions.fmri_ref().get_package_name_as_ref_string(),
+                        package,
+                    )
+                    .into_iter()
+                    .filter(|edge| {
+                        namespace_filter.allows(&edge.from) && namespace_filter.allows(&edge.to)
+                    }),
+                );
+            }
+        }
+    }
+    edges
+}
+
+/// Returns changes that turn graph of `old` analysis into graph of `new` analysis
+///
+/// Removed edges come first and added edges last, so every edge in the stream connects
+/// existing nodes at the time it is applied.
+pub fn graph_delta(old: &Components, new: &Components) -> Vec<GraphChange> {
+    let (old_nodes, new_nodes) = (nodes(old), nodes(new));
+    let (old_edges, new_edges) = (edges(old), edges(new));
+
+    let mut changes: Vec<GraphChange> = old_edges
+        .difference(&new_edges)
+        .map(|edge| GraphChange::EdgeRemoved(edge.clone()))
+        .collect();
+
+    for node in old_nodes.keys() {
+        if !new_nodes.contains_key(node) {
+            changes.push(GraphChange::NodeRemoved {
+                node: (*node).clone(),
+            });
+        }
+    }
+
+    for (node, flags) in &new_nodes {
+        match old_nodes.get(node) {
+            None => changes.push(GraphChange::NodeAdded {
+                node: (*node).clone(),
+            }),
+            Some(old_flags) => {
+                for ((flag, old), (_, new)) in old_flags.iter().zip(flags) {
+                    if old != new {
+                        changes.push(GraphChange::FlagChanged {
+                            node: (*node).clone(),
+                            flag,
+                            old: *old,
+                            new: *new,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    changes.extend(
+        new_edges
+            .difference(&old_edges)
+            .map(|edge| GraphChange::EdgeAdded(edge.clone())),
+    );
+
+    changes
+}
+
+/// Returns changes as JSON lines (one JSON object per line)
+pub fn graph_delta_to_json_lines(changes: &[GraphChange]) -> String {
+    changes
+        .iter()
+        .map(|change| {
+            serde_json::to_string(change).expect("failed to serialize graph change") + "\n"
+        })
+        .collect()
+}
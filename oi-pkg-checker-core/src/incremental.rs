@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use log::debug;
+
+use crate::{
+    assets::open_indiana_oi_userland_git::ComponentPackagesList,
+    git::changed_files,
+    problems::{
+        Problem,
+        Problem::{
+            DuplicateDependency, InvalidFmri, ObsoleteWithDependencies, TimestampAnomaly,
+            UnRunnableMakeCommand,
+        },
+        Problems,
+    },
+};
+
+/// Returns components with files changed in git `range` (e.g. `origin/master..HEAD`) of
+/// oi-userland `repository`, [`None`] if files outside of components (e.g. make-rules) changed
+pub fn changed_components(
+    repository: &Path,
+    range: &str,
+    component_packages_list: &ComponentPackagesList,
+) -> Result<Option<ComponentPackagesList>, String> {
+    let changed_files = changed_files(repository, range)?;
+    let changed_files: Vec<&str> = changed_files.iter().map(String::as_str).collect();
+    if let Some(changed_file) = changed_files
+        .iter()
+        .find(|changed_file| !changed_file.starts_with("components/"))
+    {
+        debug!("{} changed, reloading all components", changed_file);
+        return Ok(None);
+    }
+
+    Ok(Some(changed_in(
+        repository,
+        &changed_files,
+        component_packages_list,
+    )))
+}
+
+/// Returns components with any of `changed_files` (relative to `repository`)
+pub(crate) fn changed_in(
+    repository: &Path,
+    changed_files: &[&str],
+    component_packages_list: &ComponentPackagesList,
+) -> ComponentPackagesList {
+    ComponentPackagesList::from(
+        component_packages_list
+            .get()
+            .iter()
+            .filter(|component_packages| {
+                changed_files.iter().any(|changed_file| {
+                    repository
+                        .join(changed_file)
+                        .starts_with(&component_packages.path_to_component)
+                })
+            })
+            .cloned()
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Adds problems of `previous` analysis that reload doesn't find again, problems found while
+/// loading catalogs and problems found by gmake in components that didn't change
+pub fn merge_problems(
+    problems: &mut Problems,
+    previous: &Problems,
+    changed: &ComponentPackagesList,
+) {
+    let is_changed = |problem: &Problem| -> bool {
+        changed.get().iter().any(|component_packages| {
+            let path = &component_packages.path_to_component;
+            match problem {
+                UnRunnableMakeCommand(_, component_path, _, _) => component_path == path,
+                InvalidFmri(_, location) => location.contains(&*path.to_string_lossy()),
+                _ => problem
+                    .get_component_names_ref()
+                    .contains(&&component_packages.component_name),
+            }
+        })
+    };
+
+    for problem in previous.get_ref() {
+        let from_loading = matches!(
+            problem,
+            TimestampAnomaly(..)
+                | InvalidFmri(..)
+                | DuplicateDependency(..)
+                | ObsoleteWithDependencies(..)
+                | UnRunnableMakeCommand(..)
+        );
+
+        if from_loading && !is_changed(problem) {
+            problems.add_problem(problem.clone());
+        }
+    }
+}
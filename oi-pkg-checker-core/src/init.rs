@@ -0,0 +1,35 @@
+use std::{fs::write, path::Path, process::Command};
+
+/// Configuration created by `init` command, publishers of OpenIndiana with all checks enabled
+pub const DEFAULT_CONFIG: &str = r#"{
+    "publishers": [
+        { "name": "openindiana.org", "primary": true },
+        { "name": "hipster-encumbered" }
+    ],
+    "disabled_checks": []
+}
+"#;
+
+/// Returns tools that can't be run (`<tool> --version` doesn't succeed)
+pub fn missing_tools<'a>(tools: &[&'a str]) -> Vec<&'a str> {
+    tools
+        .iter()
+        .filter(|tool| {
+            !Command::new(tool)
+                .arg("--version")
+                .output()
+                .is_ok_and(|output| output.status.success())
+        })
+        .copied()
+        .collect()
+}
+
+/// Writes [`DEFAULT_CONFIG`] into `path`, existing file is kept, returns whether file was created
+pub fn write_default_config(path: &Path) -> Result<bool, String> {
+    if path.exists() {
+        return Ok(false);
+    }
+
+    write(path, DEFAULT_CONFIG).map_err(|e| format!("failed to write {:?}: {}", path, e))?;
+    Ok(true)
+}
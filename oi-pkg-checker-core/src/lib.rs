@@ -1,15 +1,119 @@
-pub mod assets;
-pub mod packages;
-pub mod problems;
+//! Core of oi-pkg-checker, supported API is in [`prelude`]
+
+pub(crate) mod artifacts;
+pub(crate) mod assets;
+pub(crate) mod checks;
+pub(crate) mod config;
+pub(crate) mod dependency_paths;
+pub(crate) mod dossier;
+pub(crate) mod fix_plan;
+pub(crate) mod force_graph;
+pub(crate) mod git;
+pub(crate) mod graph;
+pub(crate) mod graph_delta;
+pub(crate) mod incremental;
+pub(crate) mod init;
+pub(crate) mod metadata;
+pub(crate) mod namespace_filter;
+pub(crate) mod packages;
+pub(crate) mod pinning;
+pub mod prelude;
+pub(crate) mod problems;
+pub(crate) mod session;
+pub(crate) mod state_file;
 #[cfg(test)]
 mod tests;
+pub(crate) mod transforms;
+
+// re-exports below are used by oi-pkg-checker binary, they are hidden and not covered by
+// stability of [`prelude`]
+#[doc(hidden)]
+pub use artifacts::Artifacts;
+
+#[doc(hidden)]
+pub use assets::{
+    assets_types::AssetTypes,
+    catalogs_c::{CatalogOrigin, CatalogTimestamp},
+    depot::{check_curl, fetch_catalog, fetch_catalogs, lookup_non_existing_required},
+    history::{load_history, HistoryRecord},
+    installed_image::load_installed_packages,
+    make_cache::MakeCache,
+    manifest_dependencies::{load_manifest_actions, load_manifest_dependencies},
+    open_indiana_oi_userland_git::{ComponentPackages, ComponentPackagesList},
+    pkg_repository::{archive_catalog, repository_catalog},
+    rewrite_rules::{Rewrite, RewriteRule, RewriteRules},
+    snapshot::extract_snapshot,
+};
+
+#[doc(hidden)]
+pub use checks::{get_check, Check, CHECKS};
+
+#[doc(hidden)]
+pub use config::{
+    CatalogStaleness, CategoryPolicy, Classifications, ComponentDiscovery, Config,
+    EncumberedLicenses, NamePattern, NamingConventions, Origin, OrphanPackages, PublisherConfig,
+    Reclassification, UnversionedRequires,
+};
+
+#[doc(hidden)]
+pub use dependency_paths::{DependencyEdge, DependencyPaths};
+
+#[doc(hidden)]
+pub use dossier::Dossier;
+
+#[doc(hidden)]
+pub use fix_plan::{fix_plan, FixOperation, Operation};
 
-pub use assets::{assets_types::AssetTypes, open_indiana_oi_userland_git::ComponentPackagesList};
+#[doc(hidden)]
+pub use force_graph::{ForceGraph, GraphNode};
 
+#[doc(hidden)]
+pub use git::{changed_files, head_commit};
+
+#[doc(hidden)]
+pub use graph_delta::{graph_delta, graph_delta_to_json_lines, GraphChange};
+
+#[doc(hidden)]
+pub use incremental::{changed_components, merge_problems};
+
+#[doc(hidden)]
+pub use init::{missing_tools, write_default_config, DEFAULT_CONFIG};
+
+#[doc(hidden)]
+pub use metadata::{component_metadata, component_metadata_to_csv, ComponentMetadata};
+
+#[doc(hidden)]
+pub use namespace_filter::NamespaceFilter;
+
+#[doc(hidden)]
 pub use packages::{
-    component::Component, components::Components, depend_types::DependTypes,
-    dependencies::Dependencies, dependency::Dependency, dependency_type::DependencyTypes,
-    package::Package, package_versions::PackageVersions,
+    actions::{
+        parse_actions, Action, Attributes, DirAction, DriverAction, FileAction, GroupAction,
+        LegacyAction, LicenseAction, LinkAction, SetAction, UserAction,
+    },
+    component::{Component, MakefileMetadata},
+    components::{Components, Dependents, DotOptions},
+    depend_types::DependTypes,
+    dependencies::Dependencies,
+    dependency::Dependency,
+    dependency_type::DependencyTypes,
+    package::Package,
+    package_versions::PackageVersions,
+    version_parts::VersionParts,
 };
 
-pub use problems::{report, Problems};
+#[doc(hidden)]
+pub use pinning::{load_pins, PinImpact};
+
+#[doc(hidden)]
+pub use problems::{
+    format_count, report, report_by_component, report_with_limit, AlternativeState,
+    DeduplicatedProblem, MakeFailure, Problem, ProblemKind, Problems, ProblemsDiff,
+    ProblemsSummary, Severity,
+};
+
+#[doc(hidden)]
+pub use session::{AnalysisSession, SessionEvent, SessionListener};
+
+#[doc(hidden)]
+pub use transforms::pkgmogrify_transforms;
@@ -0,0 +1,169 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use serde::Serialize;
+
+use crate::{
+    packages::{components::Components, dependency::Dependency},
+    problems::{Problem, Problems, Severity},
+    ComponentPackagesList,
+};
+
+/// Metadata of one component
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct ComponentMetadata {
+    pub name: String,
+    pub path: Option<PathBuf>,
+    /// COMPONENT_VERSION from Makefile
+    pub version: Option<String>,
+    /// packages delivered by component
+    pub packages: Vec<String>,
+    /// number of distinct dependencies of component by dependency type
+    pub runtime_dependencies: usize,
+    pub build_dependencies: usize,
+    pub test_dependencies: usize,
+    pub system_build_dependencies: usize,
+    pub system_test_dependencies: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub infos: usize,
+    pub owner: Option<String>,
+    /// 100 for component without problems, every error takes 10, warning 3 and info 1
+    pub health_score: u8,
+}
+
+/// Returns metadata of all named components, `owners` maps component name to its owner
+pub fn component_metadata(
+    components: &Components,
+    problems: &Problems,
+    component_packages_list: &ComponentPackagesList,
+    owners: &HashMap<String, String>,
+) -> Vec<ComponentMetadata> {
+    let mut metadata = vec![];
+
+    for component in components.get_ref() {
+        let name = component.get_name_ref();
+        if name.is_empty() {
+            continue;
+        }
+
+        let mut dependencies: [Vec<&Dependency>; 5] = Default::default();
+        let mut component_problems: Vec<&Problem> = problems.filter_by_component(name).collect();
+        let mut packages = vec![];
+
+        for package_versions in component.get_versions_ref() {
+            packages.push(
+                package_versions
+                    .fmri_ref()
+                    .get_package_name_as_ref_string()
+                    .clone(),
+            );
+
+            for problem in problems.filter_by_package(package_versions.fmri_ref()) {
+                if !component_problems.contains(&problem) {
+                    component_problems.push(problem)
+                }
+            }
+
+            let Some(package) = package_versions.get_packages_ref().last() else {
+                continue;
+            };
+
+            for (index, package_dependencies) in [
+                package.get_runtime_dependencies(),
+                package.get_build_dependencies(),
+                package.get_test_dependencies(),
+                package.get_system_build_dependencies(),
+                package.get_system_test_dependencies(),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                for dependency in package_dependencies {
+                    if !dependencies[index].contains(&dependency) {
+                        dependencies[index].push(dependency)
+                    }
+                }
+            }
+        }
+
+        let count = |severity: Severity| {
+            component_problems
+                .iter()
+                .filter(|problem| problem.severity() == severity)
+                .count()
+        };
+        let (errors, warnings, infos) = (
+            count(Severity::Error),
+            count(Severity::Warning),
+            count(Severity::Info),
+        );
+
+        metadata.push(ComponentMetadata {
+            name: name.clone(),
+            path: component_packages_list
+                .get()
+                .iter()
+                .find(|component_packages| &component_packages.component_name == name)
+                .map(|component_packages| component_packages.path_to_component.clone()),
+            version: component.get_version_ref().cloned(),
+            packages,
+            runtime_dependencies: dependencies[0].len(),
+            build_dependencies: dependencies[1].len(),
+            test_dependencies: dependencies[2].len(),
+            system_build_dependencies: dependencies[3].len(),
+            system_test_dependencies: dependencies[4].len(),
+            errors,
+            warnings,
+            infos,
+            owner: owners.get(name).cloned(),
+            health_score: 100_usize.saturating_sub(errors * 10 + warnings * 3 + infos) as u8,
+        })
+    }
+
+    metadata.sort_by(|a, b| a.name.cmp(&b.name));
+    metadata
+}
+
+/// Returns metadata as CSV with header, packages are separated by space
+pub fn component_metadata_to_csv(metadata: &[ComponentMetadata]) -> String {
+    let quote = |value: &str| -> String {
+        if value.contains([',', '"', '\n']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_owned()
+        }
+    };
+
+    let mut csv =
+        "name,path,version,packages,runtime_dependencies,build_dependencies,test_dependencies,\
+system_build_dependencies,system_test_dependencies,errors,warnings,infos,owner,health_score\n"
+            .to_owned();
+
+    for component in metadata {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            quote(&component.name),
+            quote(
+                &component
+                    .path
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            ),
+            quote(component.version.as_deref().unwrap_or_default()),
+            quote(&component.packages.join(" ")),
+            component.runtime_dependencies,
+            component.build_dependencies,
+            component.test_dependencies,
+            component.system_build_dependencies,
+            component.system_test_dependencies,
+            component.errors,
+            component.warnings,
+            component.infos,
+            quote(component.owner.as_deref().unwrap_or_default()),
+            component.health_score
+        ))
+    }
+
+    csv
+}
@@ -0,0 +1,32 @@
+/// Filter of packages in graph queries and exports by their names
+///
+/// Pattern is package name (e.g. `entire`) or prefix of package name ending with `*` (e.g.
+/// `consolidation/*`). Package passes filter if it matches some include pattern (or there is no
+/// include pattern) and doesn't match any exclude pattern.
+#[derive(PartialEq, Default, Clone, Debug)]
+pub struct NamespaceFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl NamespaceFilter {
+    pub fn new(include: Vec<String>, exclude: Vec<String>) -> Self {
+        Self { include, exclude }
+    }
+
+    /// Returns true if package name (with or without `pkg:/`) passes filter
+    pub fn allows(&self, package_name: &str) -> bool {
+        let package_name = package_name.trim_start_matches("pkg:/");
+
+        let matches = |pattern: &String| {
+            let pattern = pattern.trim_start_matches("pkg:/");
+            match pattern.strip_suffix('*') {
+                Some(prefix) => package_name.starts_with(prefix),
+                None => package_name == pattern,
+            }
+        };
+
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+}
@@ -1,3 +1,4 @@
+pub mod actions;
 pub mod component;
 pub mod components;
 mod cycles;
@@ -7,3 +8,4 @@ pub mod dependency;
 pub mod dependency_type;
 pub mod package;
 pub mod package_versions;
+pub mod version_parts;
@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Attributes of action that are not parsed into its fields, attribute can have more values
+pub type Attributes = BTreeMap<String, Vec<String>>;
+
+/// Represents action of package manifest (depend actions are [`crate::DependTypes`])
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Action {
+    File(FileAction),
+    Dir(DirAction),
+    Link(LinkAction),
+    Hardlink(LinkAction),
+    Driver(DriverAction),
+    User(UserAction),
+    Group(GroupAction),
+    Legacy(LegacyAction),
+    License(LicenseAction),
+    Set(SetAction),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FileAction {
+    pub path: String,
+    /// payload (hash in published manifest or path of source file in pkg5 manifest)
+    pub payload: Option<String>,
+    pub mode: Option<String>,
+    pub owner: Option<String>,
+    pub group: Option<String>,
+    pub attributes: Attributes,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DirAction {
+    pub path: String,
+    pub mode: Option<String>,
+    pub owner: Option<String>,
+    pub group: Option<String>,
+    pub attributes: Attributes,
+}
+
+/// Symbolic (link) or hard (hardlink) link
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LinkAction {
+    pub path: String,
+    pub target: String,
+    pub attributes: Attributes,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DriverAction {
+    pub name: String,
+    pub attributes: Attributes,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UserAction {
+    pub username: String,
+    pub uid: Option<String>,
+    pub group: Option<String>,
+    pub attributes: Attributes,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GroupAction {
+    pub groupname: String,
+    pub gid: Option<String>,
+    pub attributes: Attributes,
+}
+
+/// Package in legacy SVR4 packaging database
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LegacyAction {
+    pub pkg: String,
+    pub attributes: Attributes,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LicenseAction {
+    pub license: String,
+    /// payload (hash in published manifest or path of license file in pkg5 manifest)
+    pub payload: Option<String>,
+    pub attributes: Attributes,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SetAction {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+impl Action {
+    /// Returns path of file, dir, link and hardlink actions
+    pub fn path(&self) -> Option<&String> {
+        match self {
+            Action::File(FileAction { path, .. })
+            | Action::Dir(DirAction { path, .. })
+            | Action::Link(LinkAction { path, .. })
+            | Action::Hardlink(LinkAction { path, .. }) => Some(path),
+            _ => None,
+        }
+    }
+}
+
+/// Parses actions of manifest (published or pkg5 manifest), depend actions, unknown actions
+/// and actions without their key attribute are skipped
+pub fn parse_actions(content: &str) -> Vec<Action> {
+    let mut actions = vec![];
+
+    for line in content.replace("\\\n", " ").lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let tokens = tokenize(line);
+        let Some((action_type, tokens)) = tokens.split_first() else {
+            continue;
+        };
+
+        let mut payload = None;
+        let mut attributes = Attributes::new();
+        for token in tokens {
+            match token.split_once('=') {
+                Some((name, value)) => attributes
+                    .entry(name.to_owned())
+                    .or_default()
+                    .push(value.to_owned()),
+                None => payload = Some(token.clone()),
+            }
+        }
+
+        if let Some(action) = new_action(action_type, payload, attributes) {
+            actions.push(action);
+        }
+    }
+
+    actions
+}
+
+fn new_action(
+    action_type: &str,
+    payload: Option<String>,
+    mut attributes: Attributes,
+) -> Option<Action> {
+    let action = match action_type {
+        "file" => {
+            let path = take(&mut attributes, "path")?;
+            let (mode, owner, group) = (
+                take(&mut attributes, "mode"),
+                take(&mut attributes, "owner"),
+                take(&mut attributes, "group"),
+            );
+            Action::File(FileAction {
+                path,
+                payload,
+                mode,
+                owner,
+                group,
+                attributes,
+            })
+        }
+        "dir" => {
+            let path = take(&mut attributes, "path")?;
+            let (mode, owner, group) = (
+                take(&mut attributes, "mode"),
+                take(&mut attributes, "owner"),
+                take(&mut attributes, "group"),
+            );
+            Action::Dir(DirAction {
+                path,
+                mode,
+                owner,
+                group,
+                attributes,
+            })
+        }
+        "link" | "hardlink" => {
+            let (path, target) = (
+                take(&mut attributes, "path")?,
+                take(&mut attributes, "target")?,
+            );
+            let link = LinkAction {
+                path,
+                target,
+                attributes,
+            };
+            match action_type {
+                "link" => Action::Link(link),
+                _ => Action::Hardlink(link),
+            }
+        }
+        "driver" => {
+            let name = take(&mut attributes, "name")?;
+            Action::Driver(DriverAction { name, attributes })
+        }
+        "user" => {
+            let username = take(&mut attributes, "username")?;
+            let (uid, group) = (take(&mut attributes, "uid"), take(&mut attributes, "group"));
+            Action::User(UserAction {
+                username,
+                uid,
+                group,
+                attributes,
+            })
+        }
+        "group" => {
+            let groupname = take(&mut attributes, "groupname")?;
+            let gid = take(&mut attributes, "gid");
+            Action::Group(GroupAction {
+                groupname,
+                gid,
+                attributes,
+            })
+        }
+        "legacy" => {
+            let pkg = take(&mut attributes, "pkg")?;
+            Action::Legacy(LegacyAction { pkg, attributes })
+        }
+        "license" => {
+            let license = take(&mut attributes, "license")?;
+            Action::License(LicenseAction {
+                license,
+                payload,
+                attributes,
+            })
+        }
+        "set" => {
+            let name = take(&mut attributes, "name")?;
+            Action::Set(SetAction {
+                name,
+                values: attributes.remove("value").unwrap_or_default(),
+            })
+        }
+        _ => return None,
+    };
+
+    Some(action)
+}
+
+/// Removes attribute from attributes and returns its last value
+fn take(attributes: &mut Attributes, name: &str) -> Option<String> {
+    attributes.remove(name)?.pop()
+}
+
+/// Splits action into tokens separated by whitespace, quoted values (e.g.
+/// `value="a b"`) can contain whitespace and quotes are removed
+fn tokenize(action: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut token = String::new();
+    let mut quote = None;
+
+    for c in action.chars() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, c) if c.is_whitespace() => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            _ => token.push(c),
+        }
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    tokens
+}
@@ -9,6 +9,22 @@ use crate::packages::{
 pub struct Component {
     name: String,
     package_versions: Vec<PackageVersions>, // TODO: it should be Vec<&PackageVersions>
+    makefile_metadata: MakefileMetadata,
+}
+
+/// Variables assigned in Makefile of component, [`None`] if variable is not assigned
+#[derive(PartialEq, Serialize, Deserialize, Default, Clone, Debug)]
+pub struct MakefileMetadata {
+    /// COMPONENT_NAME
+    pub name: Option<String>,
+    /// COMPONENT_VERSION
+    pub version: Option<String>,
+    /// COMPONENT_PROJECT_URL
+    pub project_url: Option<String>,
+    /// TPNO
+    pub tpno: Option<String>,
+    /// BUILD_STYLE
+    pub build_style: Option<String>,
 }
 
 impl Component {
@@ -16,6 +32,7 @@ impl Component {
         Self {
             name,
             package_versions: vec![],
+            makefile_metadata: MakefileMetadata::default(),
         }
     }
 
@@ -138,4 +155,17 @@ impl Component {
     pub fn change_versions(&mut self, package_versions: Vec<PackageVersions>) {
         self.package_versions = package_versions
     }
+
+    pub fn set_makefile_metadata(&mut self, makefile_metadata: MakefileMetadata) {
+        self.makefile_metadata = makefile_metadata
+    }
+
+    pub fn get_makefile_metadata_ref(&self) -> &MakefileMetadata {
+        &self.makefile_metadata
+    }
+
+    /// Returns COMPONENT_VERSION from Makefile of component
+    pub fn get_version_ref(&self) -> Option<&String> {
+        self.makefile_metadata.version.as_ref()
+    }
 }
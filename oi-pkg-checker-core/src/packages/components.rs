@@ -1,34 +1,154 @@
 use std::{
     cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::{Display, Formatter},
-    fs::File,
-    io::{Read, Write},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use bincode::{deserialize, serialize};
 use fmri::{FMRI, fmri_list::FMRIList};
-use log::debug;
+use log::{debug, error};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     assets::{
         assets_types::AssetTypes,
-        catalogs_c::load_catalog_c,
+        catalogs_c::{load_catalog_c, CatalogOrigin, CatalogTimestamp},
+        history::HistoryRecord,
+        make_cache::MakeCache,
         open_indiana_oi_userland_git::{component_list, ComponentPackagesList, load_dependencies},
+        rewrite_rules::{Rewrite, RewriteRules},
+    },
+    config::{
+        CatalogStaleness, CategoryPolicy, NamingConventions, OrphanPackages, PublisherConfig,
+        Reclassification, UnversionedRequires,
     },
     DependTypes,
+    dependency_paths::package_edges,
+    graph::cycles,
+    namespace_filter::NamespaceFilter,
     packages::{
-        component::Component, dependency::Dependency, dependency_type::DependencyTypes,
+        actions::Action,
+        component::Component,
+        dependencies::Dependencies,
+        dependency::Dependency,
+        dependency_type::DependencyTypes,
+        package::Package,
         package_versions::PackageVersions,
+        version_parts::{current_timestamp, timestamp_days, VersionParts},
+    },
+    Problems,
+    problems::{
+        AlternativeState,
+        Problem::{
+            BrokenRenameChain, BuildDependencyCycle, CatalogAgeSkew, CrossPublisherDependency,
+            DependencyBloat, DivergentDependencies, ExternalDependency,
+            ForbiddenCategoryDependency, HeldBackInstalledPackage, HistoryNameCollision,
+            IncorporationCycle, IncorporationMismatch, MirrorSkew, OnlyStubDependents,
+            OrphanPackage, PackageNameViolation, PackageNotAllowedInCategory, PublisherVersionSkew,
+            ReclassifyDependency, RenameLoop, RenamedNeedsRenamed, RequireAnyAlternatives,
+            RequiredVersionNotPublished, SelfDependency, StaleCatalog, StaleRequiredPackage,
+            TighterThanIncorporation, UnavailableIncorporatedVersion, UnavailableInstalledPackage,
+            UnversionedModule, UnversionedRequire, UselessComponent,
+        },
     },
-    Problems, problems::Problem::{RenamedNeedsRenamed, UselessComponent},
+    state_file::{read_locked, write_atomic},
 };
 
+/// Dependency types loaded from components of oi-userland
+const COMPONENT_DEPENDENCY_TYPES: [DependencyTypes; 4] = [
+    DependencyTypes::Build,
+    DependencyTypes::Test,
+    DependencyTypes::SystemBuild,
+    DependencyTypes::SystemTest,
+];
+
+/// Packages that need some [`FMRI`], see [`Components::get_dependencies_with_fmri`]
+#[derive(Default, Debug)]
+pub struct Dependents {
+    /// fmri of package, dependency type, dependency and whether the package is renamed
+    pub dependencies: Vec<(FMRI, String, Dependency, bool)>,
+    /// incorporate dependencies aggregated into number of dependencies per incorporating package
+    pub incorporations: HashMap<FMRI, usize>,
+}
+
+impl Dependents {
+    /// Returns number of incorporate dependencies represented only by counters
+    pub fn aggregated_edges(&self) -> usize {
+        self.incorporations.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dependencies.is_empty() && self.incorporations.is_empty()
+    }
+}
+
+/// Options of Graphviz export, see [`Components::to_dot`]
+#[derive(Clone, Debug)]
+pub struct DotOptions {
+    /// runtime dependencies between packages (newest versions)
+    pub runtime: bool,
+    /// build and system build dependencies between components
+    pub build: bool,
+    /// obsolete packages are gray and renamed packages are orange
+    pub color: bool,
+    /// only nodes at most `depth` edges (in any direction) from package or component with this
+    /// name are exported
+    pub focus: Option<String>,
+    pub depth: usize,
+}
+
+impl Default for DotOptions {
+    fn default() -> Self {
+        Self {
+            runtime: true,
+            build: false,
+            color: true,
+            focus: None,
+            depth: 1,
+        }
+    }
+}
+
 #[derive(PartialEq, Serialize, Deserialize, Clone, Debug)]
 pub struct Components {
     components: Vec<Component>,
     obsolete: FMRIList,
+    /// all published versions of packages that are not obsoleted or renamed
+    published: FMRIList,
+    #[serde(skip)]
+    rewrite_rules: RewriteRules,
+    #[serde(skip)]
+    strict_timestamps: bool,
+    /// REQUIRED_PACKAGES are parsed from Makefiles, gmake is fallback
+    #[serde(skip)]
+    native_makefiles: bool,
+    /// gmake is not run, dependencies are loaded only from Makefiles and make cache
+    #[serde(skip)]
+    without_make: bool,
+    /// number of gmake commands run in parallel
+    #[serde(skip)]
+    jobs: usize,
+    /// outputs of gmake reused by next runs
+    #[serde(skip)]
+    make_cache: MakeCache,
+    /// applied rewrites (provenance)
+    rewrites: Vec<Rewrite>,
+    #[serde(skip)]
+    publishers: Vec<PublisherConfig>,
+    /// packages in graph queries and exports
+    #[serde(skip)]
+    namespace_filter: NamespaceFilter,
+    /// catalogs loaded from configured origins
+    catalog_origins: Vec<CatalogOrigin>,
+    /// times of the last update of loaded catalogs
+    catalog_timestamps: Vec<CatalogTimestamp>,
+    /// names of packages delivering paths in manifests, [`None`] if manifests were not loaded
+    packages_with_files: Option<BTreeSet<String>>,
+    /// checked-out commit of oi-userland that data correspond to
+    commit: Option<String>,
+    /// packages obsoleted and renamed in history of oi-userland
+    history: Vec<HistoryRecord>,
 }
 
 impl Components {
@@ -36,6 +156,21 @@ impl Components {
         Self {
             components: vec![],
             obsolete: FMRIList::new(),
+            published: FMRIList::new(),
+            rewrite_rules: RewriteRules::new(),
+            strict_timestamps: false,
+            native_makefiles: false,
+            without_make: false,
+            jobs: 1,
+            make_cache: MakeCache::new(),
+            rewrites: vec![],
+            publishers: vec![],
+            namespace_filter: NamespaceFilter::default(),
+            catalog_origins: vec![],
+            catalog_timestamps: vec![],
+            packages_with_files: None,
+            commit: None,
+            history: vec![],
         }
     }
 
@@ -53,48 +188,82 @@ impl Components {
             }
             AssetTypes::OpenIndianaOiUserlandGit => {
                 component_list(self, problems, component_packages_list);
-                load_dependencies(
-                    self,
-                    problems,
-                    component_packages_list,
-                    &DependencyTypes::Build,
-                );
-                load_dependencies(
-                    self,
-                    problems,
-                    component_packages_list,
-                    &DependencyTypes::Test,
-                );
-                load_dependencies(
-                    self,
-                    problems,
-                    component_packages_list,
-                    &DependencyTypes::SystemBuild,
-                );
-                load_dependencies(
-                    self,
-                    problems,
-                    component_packages_list,
-                    &DependencyTypes::SystemTest,
-                );
+                for dependencies_type in COMPONENT_DEPENDENCY_TYPES {
+                    load_dependencies(
+                        self,
+                        problems,
+                        component_packages_list,
+                        &dependencies_type,
+                        None,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Reloads dependencies of `changed` components (from [`ComponentPackagesList`] of
+    /// oi-userland) into previously loaded data, dependencies of other components are kept
+    pub fn reload_components(
+        &mut self,
+        problems: &mut Problems,
+        component_packages_list: &ComponentPackagesList,
+        changed: &ComponentPackagesList,
+    ) {
+        component_list(self, problems, component_packages_list);
+
+        let changed_paths: Vec<PathBuf> = changed
+            .get()
+            .iter()
+            .map(|component_packages| component_packages.path_to_component.clone())
+            .collect();
+
+        for component in &mut self.components {
+            if !changed.get().iter().any(|component_packages| {
+                &component_packages.component_name == component.get_name_ref()
+            }) {
+                continue;
+            }
+
+            for package_versions in component.get_versions_ref_mut() {
+                for package in package_versions.get_packages_ref_mut() {
+                    package.clear_component_dependencies();
+                }
             }
         }
+
+        for dependencies_type in COMPONENT_DEPENDENCY_TYPES {
+            load_dependencies(
+                self,
+                problems,
+                component_packages_list,
+                &dependencies_type,
+                Some(&changed_paths),
+            );
+        }
     }
 
+    /// Writes data atomically under lock, see [`write_atomic`]
     pub fn serialize<P: AsRef<Path> + ?Sized>(&self, path: &P) {
-        File::create(path)
-            .unwrap()
-            .write_all(&serialize(self).expect("failed to serialize data into binary"))
-            .expect("TODO: panic message");
+        self.try_serialize(path).expect("failed to write data");
+    }
+
+    /// Same as [`Components::serialize`], but returns error instead of panicking
+    pub fn try_serialize<P: AsRef<Path> + ?Sized>(&self, path: &P) -> Result<(), String> {
+        write_atomic(
+            path,
+            &serialize(self).map_err(|e| format!("failed to serialize data into binary: {}", e))?,
+        )
     }
 
     pub fn deserialize<P: AsRef<Path> + ?Sized>(path: &P) -> Self {
-        let data = &mut Vec::new();
-        File::open(path)
-            .expect("failed to open data file")
-            .read_to_end(data)
-            .expect("failed to read data");
-        deserialize(data).expect("failed to deserialize data from binary")
+        deserialize(&read_locked(path).expect("failed to read data"))
+            .expect("failed to deserialize data from binary")
+    }
+
+    /// Same as [`Components::deserialize`], but returns error instead of panicking
+    pub fn try_deserialize<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Self, String> {
+        deserialize(&read_locked(path)?)
+            .map_err(|e| format!("failed to deserialize data from binary: {}", e))
     }
 
     pub fn add_package_to_component_with_name(
@@ -237,19 +406,45 @@ impl Components {
         false
     }
 
+    /// Returns packages that need `fmri`
+    ///
+    /// Incorporations are the vast majority of dependents, so unless `full_incorporate` is set,
+    /// incorporate dependencies are only counted per incorporating package in returned
+    /// [`Dependents`]. This keeps the result small, dependencies stored in [`Components`] are not
+    /// affected.
     pub fn get_dependencies_with_fmri(
         &self,
         fmri: &FMRI,
-    ) -> Option<Vec<(FMRI, String, Dependency, bool)>> {
-        let mut list: Vec<(FMRI, String, Dependency, bool)> = Vec::new();
+        full_incorporate: bool,
+    ) -> Option<Dependents> {
+        let mut dependents = Dependents::default();
         for component in self.get_ref() {
             for package_version in component.get_versions_ref() {
+                if !self
+                    .namespace_filter
+                    .allows(package_version.fmri_ref().get_package_name_as_ref_string())
+                {
+                    continue;
+                }
+
                 for package in package_version.get_packages_ref() {
                     if !package.fmri_ref().package_name_eq(fmri) && !package.is_obsolete() {
                         if let Some(dependencies) = package.is_fmri_needed_as_dependency(self, fmri)
                         {
                             for (fmri, d_type, dependency) in dependencies {
-                                list.push((fmri, d_type, dependency, package.is_renamed()))
+                                if !full_incorporate {
+                                    if let DependTypes::Incorporate(_) = dependency.get_ref() {
+                                        *dependents.incorporations.entry(fmri).or_default() += 1;
+                                        continue;
+                                    }
+                                }
+
+                                dependents.dependencies.push((
+                                    fmri,
+                                    d_type,
+                                    dependency,
+                                    package.is_renamed(),
+                                ))
                             }
                         }
                     }
@@ -257,10 +452,10 @@ impl Components {
             }
         }
 
-        if list.is_empty() {
+        if dependents.is_empty() {
             return None;
         }
-        Some(list)
+        Some(dependents)
     }
 
     pub fn check_dependency_validity(&self, problems: &mut Problems) {
@@ -342,6 +537,63 @@ impl Components {
         }
     }
 
+    /// Keeps package published by more publishers from `priority` (ordered names or aliases of
+    /// publishers, the first one has the highest priority) only in publisher with the highest
+    /// priority, packages of other publishers are kept
+    pub fn apply_publisher_priority(&mut self, priority: &[String]) {
+        let priority: Vec<String> = priority
+            .iter()
+            .map(|publisher| self.get_publisher_name(publisher))
+            .collect();
+        let rank = |fmri: &FMRI| {
+            fmri.get_publisher_as_ref_string()
+                .and_then(|publisher| priority.iter().position(|name| name == publisher))
+        };
+
+        // the highest priority of publishers of package by package name
+        let mut highest: HashMap<String, usize> = HashMap::new();
+        for component in &self.components {
+            for package_versions in component.get_versions_ref() {
+                let fmri = package_versions.fmri_ref();
+                if let Some(rank) = rank(fmri) {
+                    highest
+                        .entry(fmri.get_package_name_as_ref_string().clone())
+                        .and_modify(|highest| *highest = rank.min(*highest))
+                        .or_insert(rank);
+                }
+            }
+        }
+
+        let is_overridden = |fmri: &FMRI| {
+            rank(fmri).is_some_and(|rank| {
+                highest
+                    .get(fmri.get_package_name_as_ref_string())
+                    .is_some_and(|highest| rank > *highest)
+            })
+        };
+
+        for component in &mut self.components {
+            component.get_versions_ref_mut().retain(|package_versions| {
+                let overridden = is_overridden(package_versions.fmri_ref());
+                if overridden {
+                    debug!(
+                        "{} is overridden by publisher with higher priority",
+                        package_versions.fmri_ref()
+                    );
+                }
+                !overridden
+            });
+        }
+        self.published
+            .get_ref_mut()
+            .retain(|fmri| !is_overridden(fmri));
+        self.obsolete
+            .get_ref_mut()
+            .retain(|fmri| !is_overridden(fmri));
+
+        self.remove_empty_components();
+    }
+
     pub fn is_there_newer_version(&self, fmri: &FMRI) -> Option<FMRI> {
         for component in self.get_ref() {
             for package_version in component.get_versions_ref() {
@@ -374,6 +626,7 @@ impl Components {
         false
     }
 
+    #[allow(clippy::collapsible_match)]
     pub fn check_if_renamed_needs_renamed(&self, problems: &mut Problems) {
         let mut find_needed_package_closure =
             |dependency: &Dependency, package_versions: &PackageVersions| match dependency
@@ -437,43 +690,2111 @@ impl Components {
         }
     }
 
-    pub fn get_package_versions_from_fmri(&self, fmri: &FMRI) -> Option<PackageVersions> {
+    /// Returns package that renamed package was renamed to (the first require dependency of
+    /// renamed package), packages that are not in catalogs are looked up in history
+    pub fn get_rename_target(&self, fmri: &FMRI) -> Option<FMRI> {
+        let Some(package_versions) = self.get_package_versions_from_fmri(fmri) else {
+            return self.get_history_record(fmri)?.renamed_to.first().cloned();
+        };
+
+        if !package_versions.is_renamed() {
+            return None;
+        }
+
+        for dependency in package_versions
+            .get_packages_ref()
+            .last()?
+            .get_runtime_dependencies()
+        {
+            if let DependTypes::Require(target) = dependency.get_ref() {
+                return Some(target.clone());
+            }
+        }
+
+        None
+    }
+
+    /// Returns rename chain of `fmri`: `fmri` followed by packages it is renamed to, the chain
+    /// stops at package that is not renamed or before package that is already in the chain
+    pub fn get_rename_chain(&self, fmri: &FMRI) -> Vec<FMRI> {
+        let mut chain = vec![fmri.clone()];
+
+        while let Some(target) = self.get_rename_target(chain.last().unwrap()) {
+            if chain.iter().any(|fmri| fmri.package_name_eq(&target)) {
+                break;
+            }
+            chain.push(target);
+        }
+
+        chain
+    }
+
+    /// Follows renames of `fmri` to the final target (for rename loop it is the last package
+    /// before the loop closes, see [`Components::get_rename_loop`])
+    pub fn resolve_rename(&self, fmri: &FMRI) -> FMRI {
+        self.get_rename_chain(fmri).pop().unwrap()
+    }
+
+    /// Returns packages in rename loop that rename chain of `fmri` ends in, the loop starts with
+    /// package with the lowest name
+    pub fn get_rename_loop(&self, fmri: &FMRI) -> Option<Vec<FMRI>> {
+        let chain = self.get_rename_chain(fmri);
+        let target = self.get_rename_target(chain.last().unwrap())?;
+        let start = chain
+            .iter()
+            .position(|fmri| fmri.package_name_eq(&target))?;
+
+        let mut rename_loop = chain[start..].to_vec();
+        let lowest = rename_loop
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, fmri)| fmri.get_package_name_as_ref_string())
+            .map(|(index, _)| index)
+            .unwrap();
+        rename_loop.rotate_left(lowest);
+
+        Some(rename_loop)
+    }
+
+    /// Finds renamed packages whose rename chain ends in obsolete or non-existent package or in
+    /// rename loop
+    pub fn check_rename_chains(&self, problems: &mut Problems) {
         for component in self.get_ref() {
             for package_versions in component.get_versions_ref() {
-                if package_versions.fmri_ref().package_name_eq(fmri) {
-                    return Some(package_versions.clone());
+                if !package_versions.is_renamed() {
+                    continue;
+                }
+
+                if let Some(rename_loop) = self.get_rename_loop(package_versions.fmri_ref()) {
+                    problems.add_problem(RenameLoop(rename_loop));
+                    continue;
+                }
+
+                let chain = self.get_rename_chain(package_versions.fmri_ref());
+                if chain.len() < 2 {
+                    continue;
+                }
+
+                let state = self.get_alternative_state(chain.last().unwrap());
+                if matches!(
+                    state,
+                    AlternativeState::Obsolete | AlternativeState::Missing
+                ) {
+                    problems.add_problem(BrokenRenameChain(chain, state));
                 }
             }
         }
+    }
 
-        None
+    /// Finds packages of components that are neither obsoleted nor renamed, but their name was
+    /// obsoleted or renamed in history
+    pub fn check_history_collisions(&self, problems: &mut Problems) {
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_obsolete() || package_versions.is_renamed() {
+                    continue;
+                }
+
+                if let Some(record) = self.get_history_record(package_versions.fmri_ref()) {
+                    problems.add_problem(HistoryNameCollision(
+                        package_versions.fmri_ref().clone(),
+                        record.fmri.clone(),
+                        component.get_name_ref().clone(),
+                    ));
+                }
+            }
+        }
     }
 
-    pub fn check_if_fmri_exists_as_package(&self, fmri: &FMRI) -> bool {
+    /// Returns renamed packages which components need as build or test dependency (they are in
+    /// REQUIRED_PACKAGES or TEST_REQUIRED_PACKAGES of Makefile), with dependency type and
+    /// component name
+    pub fn get_renamed_required_by_components(&self) -> Vec<(FMRI, DependencyTypes, String)> {
+        let mut renamed = vec![];
+
         for component in self.get_ref() {
             for package_versions in component.get_versions_ref() {
-                if package_versions.fmri_ref().package_name_eq(fmri) {
-                    return true;
+                if package_versions.is_renamed() || package_versions.is_obsolete() {
+                    continue;
+                }
+
+                for package in package_versions.get_packages_ref() {
+                    for (dependencies, dependency_type) in [
+                        (package.get_build_dependencies(), DependencyTypes::Build),
+                        (package.get_test_dependencies(), DependencyTypes::Test),
+                    ] {
+                        for dependency in dependencies {
+                            if let DependTypes::Require(fmri) = dependency.get_ref() {
+                                let mut fmri = fmri.clone();
+                                fmri.remove_version();
+                                let item = (
+                                    fmri,
+                                    dependency_type.clone(),
+                                    component.get_name_ref().clone(),
+                                );
+                                if !renamed.contains(&item)
+                                    && self
+                                        .get_package_versions_from_fmri(&item.0)
+                                        .is_some_and(|required| required.is_renamed())
+                                {
+                                    renamed.push(item);
+                                }
+                            }
+                        }
+                    }
                 }
             }
         }
-        false
+
+        renamed
     }
 
-    pub fn add(&mut self, component: Component) {
-        self.components.push(component)
+    /// Checks components against policies of their categories
+    pub fn check_category_policies(&self, problems: &mut Problems, policies: &[CategoryPolicy]) {
+        for component in self.get_ref() {
+            let component_name = component.get_name_ref();
+
+            for policy in policies {
+                if !policy.contains_component(component_name) {
+                    continue;
+                }
+
+                for package_versions in component.get_versions_ref() {
+                    if package_versions.is_renamed() || package_versions.is_obsolete() {
+                        continue;
+                    }
+
+                    if !policy.is_package_allowed(
+                        package_versions.fmri_ref().get_package_name_as_ref_string(),
+                    ) || (policy.metapackages_only && !self.is_metapackage(package_versions))
+                    {
+                        problems.add_problem(PackageNotAllowedInCategory(
+                            policy.category.clone(),
+                            component_name.clone(),
+                            package_versions.fmri_ref().clone(),
+                        ));
+                    }
+
+                    for package in package_versions.get_packages_ref() {
+                        for dependency in package
+                            .get_runtime_dependencies()
+                            .iter()
+                            .chain(package.get_build_dependencies())
+                            .chain(package.get_test_dependencies())
+                        {
+                            if let DependTypes::Incorporate(_) = dependency.get_ref() {
+                                continue;
+                            }
+
+                            for fmri in dependency.get_ref().get_fmris_ref() {
+                                if let Some(dependency_component) =
+                                    self.get_component_name_by_package(fmri)
+                                {
+                                    if policy.is_dependency_category_forbidden(dependency_component)
+                                    {
+                                        problems.add_problem(ForbiddenCategoryDependency(
+                                            policy.category.clone(),
+                                            component_name.clone(),
+                                            fmri.clone(),
+                                            dependency_component.clone(),
+                                        ));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
     }
 
-    pub fn get_obsoleted_ref(&self) -> &FMRIList {
-        &self.obsolete
+    /// Finds packages with names that violate naming conventions, obsolete and renamed packages
+    /// are skipped
+    pub fn check_package_names(&self, problems: &mut Problems, naming: &NamingConventions) {
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_renamed() || package_versions.is_obsolete() {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+
+                for reason in naming.check(package.fmri_ref()) {
+                    problems.add_problem(PackageNameViolation(
+                        package_versions.fmri_ref().clone(),
+                        component.get_name_ref().clone(),
+                        reason,
+                    ));
+                }
+            }
+        }
     }
 
-    pub fn add_obsoleted(&mut self, fmri: FMRI) {
-        self.obsolete.add(fmri)
+    /// Finds interpreter module packages (e.g. `library/python/foo`) without any variant with
+    /// interpreter version suffix (e.g. `library/python/foo-311`), suggests name with the newest
+    /// suffix used by other modules
+    pub fn check_versioned_modules(&self, problems: &mut Problems, naming: &NamingConventions) {
+        let mut versioned: BTreeSet<&str> = BTreeSet::new();
+        let mut newest_suffixes: HashMap<&str, &str> = HashMap::new();
+        let mut unversioned = vec![];
+
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_renamed() || package_versions.is_obsolete() {
+                    continue;
+                }
+
+                let package_name = package_versions.fmri_ref().get_package_name_as_ref_string();
+                match naming.split_module_version(package_name) {
+                    Some((prefix, name, Some(suffix))) => {
+                        versioned.insert(name);
+                        let newest = newest_suffixes.entry(prefix).or_insert(suffix);
+                        if suffix.parse::<u32>().unwrap() > newest.parse::<u32>().unwrap() {
+                            *newest = suffix;
+                        }
+                    }
+                    Some((prefix, name, None)) => {
+                        unversioned.push((package_versions, component.get_name_ref(), prefix, name))
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        for (package_versions, component_name, prefix, name) in unversioned {
+            if versioned.contains(name) {
+                continue;
+            }
+
+            problems.add_problem(UnversionedModule(
+                package_versions.fmri_ref().clone(),
+                component_name.clone(),
+                newest_suffixes
+                    .get(prefix)
+                    .map(|suffix| format!("{}-{}", name, suffix)),
+            ));
+        }
     }
 
-    pub fn is_fmri_obsoleted(&self, fmri: &FMRI) -> bool {
-        self.obsolete.contains(fmri)
+    /// Suggests reclassification of runtime dependencies on build tools and build dependencies
+    /// on shared libraries that no package of component needs at runtime
+    pub fn check_dependency_kinds(
+        &self,
+        problems: &mut Problems,
+        reclassification: &Reclassification,
+    ) {
+        for component in self.get_ref() {
+            let component_name = component.get_name_ref();
+            if component_name.is_empty() {
+                continue;
+            }
+
+            let mut delivered: Vec<&String> = vec![];
+            let mut runtime: Vec<&String> = vec![];
+            let mut build: Vec<&FMRI> = vec![];
+
+            for package_versions in component.get_versions_ref() {
+                delivered.push(package_versions.fmri_ref().get_package_name_as_ref_string());
+
+                if package_versions.is_renamed() || package_versions.is_obsolete() {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+
+                for dependency in package.get_runtime_dependencies() {
+                    if let DependTypes::Require(fmri) = dependency.get_ref() {
+                        if reclassification.is_build_tool(fmri.get_package_name_as_ref_string()) {
+                            problems.add_problem(ReclassifyDependency(
+                                fmri.clone(),
+                                DependencyTypes::Runtime,
+                                DependencyTypes::Build,
+                                component_name.clone(),
+                            ));
+                        }
+                    }
+
+                    for fmri in dependency.get_ref().get_fmris_ref() {
+                        runtime.push(fmri.get_package_name_as_ref_string());
+                    }
+                }
+
+                for dependency in package.get_build_dependencies() {
+                    build.extend(dependency.get_ref().get_fmris_ref());
+                }
+            }
+
+            for fmri in build {
+                let name = fmri.get_package_name_as_ref_string();
+                if reclassification.is_shared_library(name)
+                    && !runtime.contains(&name)
+                    && !delivered.contains(&name)
+                {
+                    problems.add_problem(ReclassifyDependency(
+                        fmri.clone(),
+                        DependencyTypes::Build,
+                        DependencyTypes::Runtime,
+                        component_name.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Reports non-obsoleted packages required only by obsoleted or renamed packages, they can
+    /// be removed once the stubs are purged (incorporate dependencies are not counted)
+    pub fn check_only_stub_dependents(&self, problems: &mut Problems) {
+        let mut dependents: HashMap<&String, Vec<(&FMRI, bool)>> = HashMap::new();
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+                let stub = package_versions.is_obsolete() || package_versions.is_renamed();
+
+                for dependency in package
+                    .get_runtime_dependencies()
+                    .iter()
+                    .chain(package.get_build_dependencies())
+                    .chain(package.get_test_dependencies())
+                    .chain(package.get_system_build_dependencies())
+                    .chain(package.get_system_test_dependencies())
+                {
+                    if let DependTypes::Incorporate(_) = dependency.get_ref() {
+                        continue;
+                    }
+
+                    for fmri in dependency.get_ref().get_fmris_ref() {
+                        if fmri.package_name_eq(package_versions.fmri_ref()) {
+                            continue;
+                        }
+
+                        let dependents = dependents
+                            .entry(fmri.get_package_name_as_ref_string())
+                            .or_default();
+                        if !dependents
+                            .iter()
+                            .any(|(added, _)| added.package_name_eq(package_versions.fmri_ref()))
+                        {
+                            dependents.push((package_versions.fmri_ref(), stub));
+                        }
+                    }
+                }
+            }
+        }
+
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_obsolete() || package_versions.is_renamed() {
+                    continue;
+                }
+
+                let Some(dependents) =
+                    dependents.get(package_versions.fmri_ref().get_package_name_as_ref_string())
+                else {
+                    continue;
+                };
+
+                if dependents.iter().all(|(_, stub)| *stub) {
+                    problems.add_problem(OnlyStubDependents(
+                        package_versions.fmri_ref().clone(),
+                        dependents.iter().map(|(fmri, _)| (*fmri).clone()).collect(),
+                        component.get_name_ref().clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Finds build dependencies of components (REQUIRED_PACKAGES) that are not in runtime
+    /// dependency closure of packages delivered by the component, build tools are skipped
+    pub fn check_stale_required_packages(
+        &self,
+        problems: &mut Problems,
+        reclassification: &Reclassification,
+    ) {
+        let mut packages: HashMap<&String, &PackageVersions> = HashMap::new();
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                packages.insert(
+                    package_versions.fmri_ref().get_package_name_as_ref_string(),
+                    package_versions,
+                );
+            }
+        }
+
+        for component in self.get_ref() {
+            let component_name = component.get_name_ref();
+            if component_name.is_empty() {
+                continue;
+            }
+
+            let mut delivered: Vec<&String> = vec![];
+            let mut build: Vec<&FMRI> = vec![];
+
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_renamed() || package_versions.is_obsolete() {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+
+                delivered.push(package_versions.fmri_ref().get_package_name_as_ref_string());
+                for dependency in package.get_build_dependencies() {
+                    build.extend(dependency.get_ref().get_fmris_ref());
+                }
+            }
+
+            let mut closure: BTreeSet<&String> = BTreeSet::new();
+            let mut stack = delivered.clone();
+            while let Some(package_name) = stack.pop() {
+                let Some(package) = packages
+                    .get(package_name)
+                    .and_then(|package_versions| package_versions.get_packages_ref().last())
+                else {
+                    continue;
+                };
+
+                for dependency in package.get_runtime_dependencies() {
+                    for fmri in dependency.get_ref().get_fmris_ref() {
+                        if closure.insert(fmri.get_package_name_as_ref_string()) {
+                            stack.push(fmri.get_package_name_as_ref_string());
+                        }
+                    }
+                }
+            }
+
+            for fmri in build {
+                let package_name = fmri.get_package_name_as_ref_string();
+                if closure.contains(package_name)
+                    || delivered.contains(&package_name)
+                    || reclassification.is_build_tool(package_name)
+                {
+                    continue;
+                }
+
+                problems.add_problem(StaleRequiredPackage(fmri.clone(), component_name.clone()));
+            }
+        }
+    }
+
+    /// Finds require dependencies of packages from the primary publisher (see
+    /// [`PublisherConfig::primary`]) on packages published only by other publishers
+    pub fn check_cross_publisher_dependencies(&self, problems: &mut Problems) {
+        let Some(primary) = self.publishers.iter().find(|publisher| publisher.primary) else {
+            return;
+        };
+
+        let is_primary = |fmri: &FMRI| {
+            fmri.get_publisher_as_ref_string()
+                .is_some_and(|publisher| primary.is_named(publisher))
+        };
+
+        // publishers of non-obsoleted packages by package name
+        let mut publishers: HashMap<&String, Vec<&FMRI>> = HashMap::new();
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if !package_versions.is_obsolete() {
+                    publishers
+                        .entry(package_versions.fmri_ref().get_package_name_as_ref_string())
+                        .or_default()
+                        .push(package_versions.fmri_ref());
+                }
+            }
+        }
+
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if !is_primary(package_versions.fmri_ref())
+                    || package_versions.is_obsolete()
+                    || package_versions.is_renamed()
+                {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+
+                for (dependencies, dependency_type) in [
+                    (package.get_runtime_dependencies(), DependencyTypes::Runtime),
+                    (package.get_build_dependencies(), DependencyTypes::Build),
+                ] {
+                    for dependency in dependencies {
+                        let (DependTypes::Require(required)
+                        | DependTypes::Conditional(required, _)) = dependency.get_ref()
+                        else {
+                            continue;
+                        };
+
+                        let Some(published_by) =
+                            publishers.get(required.get_package_name_as_ref_string())
+                        else {
+                            continue;
+                        };
+
+                        // dependencies on external publishers are reported by
+                        // check_external_dependencies
+                        if published_by.iter().any(|fmri| is_primary(fmri))
+                            || published_by.iter().all(|fmri| self.is_external(fmri))
+                        {
+                            continue;
+                        }
+
+                        problems.add_problem(CrossPublisherDependency(
+                            required.clone(),
+                            dependency_type.clone(),
+                            package.fmri_ref().clone(),
+                            published_by[0]
+                                .get_publisher_as_ref_string()
+                                .cloned()
+                                .unwrap_or_default(),
+                            component.get_name_ref().clone(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Finds dependencies of packages from non-external publishers on packages published only by
+    /// external publishers (see [`PublisherConfig::external`])
+    pub fn check_external_dependencies(&self, problems: &mut Problems) {
+        // publishers of non-obsoleted packages by package name
+        let mut publishers: HashMap<&String, Vec<&FMRI>> = HashMap::new();
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if !package_versions.is_obsolete() {
+                    publishers
+                        .entry(package_versions.fmri_ref().get_package_name_as_ref_string())
+                        .or_default()
+                        .push(package_versions.fmri_ref());
+                }
+            }
+        }
+
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if self.is_external(package_versions.fmri_ref())
+                    || package_versions.is_obsolete()
+                    || package_versions.is_renamed()
+                {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+
+                for (dependencies, dependency_type) in [
+                    (package.get_runtime_dependencies(), DependencyTypes::Runtime),
+                    (package.get_build_dependencies(), DependencyTypes::Build),
+                ] {
+                    for dependency in dependencies {
+                        for required in dependency.get_ref().get_fmris_ref() {
+                            let Some(published_by) =
+                                publishers.get(required.get_package_name_as_ref_string())
+                            else {
+                                continue;
+                            };
+
+                            if !published_by.iter().all(|fmri| self.is_external(fmri)) {
+                                continue;
+                            }
+
+                            problems.add_problem(ExternalDependency(
+                                required.clone(),
+                                dependency_type.clone(),
+                                package.fmri_ref().clone(),
+                                published_by[0]
+                                    .get_publisher_as_ref_string()
+                                    .cloned()
+                                    .unwrap_or_default(),
+                                component.get_name_ref().clone(),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Finds catalogs older than [`CatalogStaleness::max_age_days`] and catalogs older than the
+    /// newest catalog of other publishers by more than [`CatalogStaleness::max_skew_days`]
+    pub fn check_catalog_staleness(&self, problems: &mut Problems, staleness: &CatalogStaleness) {
+        let days =
+            |catalog_timestamp: &CatalogTimestamp| timestamp_days(&catalog_timestamp.last_modified);
+        let Some(today) = timestamp_days(&current_timestamp()) else {
+            return;
+        };
+
+        if staleness.max_age_days > 0 {
+            for catalog_timestamp in &self.catalog_timestamps {
+                let Some(age) = days(catalog_timestamp).map(|days| today.abs_diff(days)) else {
+                    continue;
+                };
+
+                if age > staleness.max_age_days {
+                    problems.add_problem(StaleCatalog(
+                        catalog_timestamp.catalog.clone(),
+                        catalog_timestamp.last_modified.clone(),
+                        age,
+                    ));
+                }
+            }
+        }
+
+        let Some(newest) = self
+            .catalog_timestamps
+            .iter()
+            .max_by(|a, b| a.last_modified.cmp(&b.last_modified))
+        else {
+            return;
+        };
+        let Some(newest_days) = days(newest) else {
+            return;
+        };
+
+        if staleness.max_skew_days > 0 {
+            for catalog_timestamp in &self.catalog_timestamps {
+                if catalog_timestamp.publishers == newest.publishers {
+                    continue;
+                }
+
+                let Some(skew) = days(catalog_timestamp).map(|days| newest_days.abs_diff(days))
+                else {
+                    continue;
+                };
+
+                if skew > staleness.max_skew_days {
+                    problems.add_problem(CatalogAgeSkew(
+                        catalog_timestamp.catalog.clone(),
+                        newest.catalog.clone(),
+                        skew,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Finds packages with different newest version in more publishers, reports every publisher
+    /// with older version against the publisher with the newest version
+    pub fn check_publisher_version_skew(&self, problems: &mut Problems) {
+        // the newest obsoleted versions by package name and publisher
+        let mut obsoleted: HashMap<(&String, Option<&String>), &FMRI> = HashMap::new();
+        for fmri in self.obsolete.get_ref() {
+            let newest = obsoleted
+                .entry((
+                    fmri.get_package_name_as_ref_string(),
+                    fmri.get_publisher_as_ref_string(),
+                ))
+                .or_insert(fmri);
+            if fmri.get_version_ref() > newest.get_version_ref() {
+                *newest = fmri;
+            }
+        }
+
+        // the newest version and its state by package name
+        let mut published: BTreeMap<&String, Vec<(&FMRI, AlternativeState, &String)>> =
+            BTreeMap::new();
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                let fmri = package_versions.fmri_ref();
+                let newest = if package_versions.is_obsolete() {
+                    obsoleted
+                        .get(&(
+                            fmri.get_package_name_as_ref_string(),
+                            fmri.get_publisher_as_ref_string(),
+                        ))
+                        .map(|fmri| (*fmri, AlternativeState::Obsolete))
+                } else {
+                    package_versions.get_packages_ref().last().map(|package| {
+                        (
+                            package.fmri_ref(),
+                            if package_versions.is_renamed() {
+                                AlternativeState::Renamed
+                            } else {
+                                AlternativeState::Viable
+                            },
+                        )
+                    })
+                };
+
+                if let Some((newest, state)) = newest {
+                    published
+                        .entry(fmri.get_package_name_as_ref_string())
+                        .or_default()
+                        .push((newest, state, component.get_name_ref()));
+                }
+            }
+        }
+
+        for versions in published.values() {
+            let Some((newer, newer_state, component_name)) = versions
+                .iter()
+                .max_by(|(a, _, _), (b, _, _)| a.get_version_ref().cmp(b.get_version_ref()))
+            else {
+                continue;
+            };
+
+            for (older, older_state, _) in versions {
+                if older.get_version_ref() < newer.get_version_ref() {
+                    problems.add_problem(PublisherVersionSkew(
+                        (*newer).clone(),
+                        *newer_state,
+                        (*older).clone(),
+                        *older_state,
+                        (*component_name).clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Finds the same version (timestamp is ignored) of package published by more publishers
+    /// with different runtime dependencies, e.g. rebuild published only to one publisher
+    pub fn check_divergent_dependencies(&self, problems: &mut Problems) {
+        let mut published: BTreeMap<(&String, String), Vec<(&Package, &String)>> = BTreeMap::new();
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_obsolete() || package_versions.is_renamed() {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+
+                let Some(version) = package.fmri_ref().get_version_as_string() else {
+                    continue;
+                };
+                let version = version.split(':').next().unwrap_or_default().to_owned();
+
+                published
+                    .entry((package.fmri_ref().get_package_name_as_ref_string(), version))
+                    .or_default()
+                    .push((package, component.get_name_ref()));
+            }
+        }
+
+        let dependencies_of = |package: &Package| -> Vec<DependTypes> {
+            package
+                .get_runtime_dependencies()
+                .iter()
+                .map(|dependency| dependency.get_ref().clone())
+                .collect()
+        };
+
+        for packages in published.values() {
+            let Some(((first, component_name), others)) = packages.split_first() else {
+                continue;
+            };
+            let first_dependencies = dependencies_of(first);
+
+            for (other, _) in others {
+                let other_dependencies = dependencies_of(other);
+                let only_in = |a: &Vec<DependTypes>, b: &Vec<DependTypes>| -> Vec<DependTypes> {
+                    a.iter()
+                        .filter(|depend_type| !b.contains(depend_type))
+                        .cloned()
+                        .collect()
+                };
+                let only_in_first = only_in(&first_dependencies, &other_dependencies);
+                let only_in_other = only_in(&other_dependencies, &first_dependencies);
+
+                if !only_in_first.is_empty() || !only_in_other.is_empty() {
+                    problems.add_problem(DivergentDependencies(
+                        first.fmri_ref().clone(),
+                        other.fmri_ref().clone(),
+                        only_in_first,
+                        only_in_other,
+                        (*component_name).clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Finds packages with different newest version (or missing) in origins of the same
+    /// publisher
+    pub fn check_mirror_skew(&self, problems: &mut Problems) {
+        let mut publishers: BTreeMap<&String, Vec<&CatalogOrigin>> = BTreeMap::new();
+        for catalog_origin in &self.catalog_origins {
+            publishers
+                .entry(&catalog_origin.publisher)
+                .or_default()
+                .push(catalog_origin);
+        }
+
+        for (publisher, catalog_origins) in publishers {
+            if catalog_origins.len() < 2 {
+                continue;
+            }
+
+            let packages: BTreeMap<&String, &FMRI> = catalog_origins
+                .iter()
+                .flat_map(|catalog_origin| catalog_origin.packages.iter())
+                .collect();
+
+            for (package_name, fmri) in packages {
+                let versions: Vec<(String, Option<String>)> = catalog_origins
+                    .iter()
+                    .map(|catalog_origin| {
+                        (
+                            catalog_origin.origin.clone(),
+                            catalog_origin
+                                .packages
+                                .get(package_name)
+                                .and_then(|fmri| fmri.get_version_as_string()),
+                        )
+                    })
+                    .collect();
+
+                if versions
+                    .iter()
+                    .any(|(_, version)| version != &versions[0].1)
+                {
+                    let mut fmri = fmri.clone();
+                    fmri.remove_publisher();
+                    fmri.remove_version();
+                    problems.add_problem(MirrorSkew(fmri, publisher.clone(), versions));
+                }
+            }
+        }
+    }
+
+    /// Finds require dependencies without version of packages not allowed to have them, does
+    /// nothing if the check is not enabled
+    pub fn check_unversioned_requires(
+        &self,
+        problems: &mut Problems,
+        unversioned_requires: &UnversionedRequires,
+    ) {
+        if !unversioned_requires.enabled {
+            return;
+        }
+
+        let allowed_packages = match unversioned_requires.regexes() {
+            Ok(regexes) => regexes,
+            Err(e) => {
+                error!("unversioned requires are not checked: {}", e);
+                return;
+            }
+        };
+
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_renamed() || package_versions.is_obsolete() {
+                    continue;
+                }
+
+                let package_name = package_versions.fmri_ref().get_package_name_as_ref_string();
+                if allowed_packages
+                    .iter()
+                    .any(|regex| regex.is_match(package_name))
+                    || (unversioned_requires.allow_metapackages
+                        && self.is_metapackage(package_versions))
+                {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+
+                for dependency in package.get_runtime_dependencies() {
+                    if let DependTypes::Require(fmri) = dependency.get_ref() {
+                        if !fmri.has_version() {
+                            problems.add_problem(UnversionedRequire(
+                                fmri.clone(),
+                                package.fmri_ref().clone(),
+                                component.get_name_ref().clone(),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reports non-obsoleted packages that are not reachable by runtime dependencies (incorporate
+    /// dependencies are not followed) from root packages
+    pub fn check_orphan_packages(&self, problems: &mut Problems, orphan_packages: &OrphanPackages) {
+        if !orphan_packages.enabled {
+            return;
+        }
+
+        let roots = match orphan_packages.regexes() {
+            Ok(regexes) => regexes,
+            Err(e) => {
+                error!("orphan packages are not checked: {}", e);
+                return;
+            }
+        };
+
+        let mut packages: HashMap<&String, &PackageVersions> = HashMap::new();
+        let mut stack: Vec<&String> = vec![];
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                let package_name = package_versions.fmri_ref().get_package_name_as_ref_string();
+                packages.insert(package_name, package_versions);
+
+                if package_versions.is_obsolete() || package_versions.is_renamed() {
+                    continue;
+                }
+
+                let is_incorporation = || {
+                    package_versions
+                        .get_packages_ref()
+                        .last()
+                        .is_some_and(|package| {
+                            package.get_runtime_dependencies().iter().any(|dependency| {
+                                matches!(dependency.get_ref(), DependTypes::Incorporate(_))
+                            })
+                        })
+                };
+
+                if roots.iter().any(|regex| regex.is_match(package_name))
+                    || (orphan_packages.metapackages && self.is_metapackage(package_versions))
+                    || (orphan_packages.incorporations && is_incorporation())
+                {
+                    stack.push(package_name);
+                }
+            }
+        }
+
+        let mut reachable: HashSet<&String> = stack.iter().copied().collect();
+        while let Some(package_name) = stack.pop() {
+            let Some(package) = packages
+                .get(package_name)
+                .and_then(|package_versions| package_versions.get_packages_ref().last())
+            else {
+                continue;
+            };
+
+            for dependency in package.get_runtime_dependencies() {
+                if let DependTypes::Incorporate(_) = dependency.get_ref() {
+                    continue;
+                }
+
+                for fmri in dependency.get_ref().get_fmris_ref() {
+                    if reachable.insert(fmri.get_package_name_as_ref_string()) {
+                        stack.push(fmri.get_package_name_as_ref_string());
+                    }
+                }
+            }
+        }
+
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_obsolete()
+                    || package_versions.is_renamed()
+                    || reachable
+                        .contains(package_versions.fmri_ref().get_package_name_as_ref_string())
+                {
+                    continue;
+                }
+
+                problems.add_problem(OrphanPackage(
+                    package_versions.fmri_ref().clone(),
+                    component.get_name_ref().clone(),
+                ));
+            }
+        }
+    }
+
+    /// Finds components with more build dependencies than mean plus two standard deviations of
+    /// at least five components with the same BUILD_STYLE, build dependencies least referenced
+    /// by runtime dependencies of component packages are listed first
+    pub fn check_dependency_bloat(
+        &self,
+        problems: &mut Problems,
+        build_styles: &HashMap<String, String>,
+    ) {
+        let mut peers: BTreeMap<&String, Vec<(&String, Vec<&FMRI>)>> = BTreeMap::new();
+
+        for component in self.get_ref() {
+            let component_name = component.get_name_ref();
+            let Some(build_style) = build_styles.get(component_name) else {
+                continue;
+            };
+
+            let mut runtime: Vec<&String> = vec![];
+            let mut build: Vec<&FMRI> = vec![];
+
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_renamed() || package_versions.is_obsolete() {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+
+                for dependency in package.get_runtime_dependencies() {
+                    for fmri in dependency.get_ref().get_fmris_ref() {
+                        runtime.push(fmri.get_package_name_as_ref_string());
+                    }
+                }
+
+                for dependency in package.get_build_dependencies() {
+                    for fmri in dependency.get_ref().get_fmris_ref() {
+                        if !build.iter().any(|added| added.package_name_eq(fmri)) {
+                            build.push(fmri);
+                        }
+                    }
+                }
+            }
+
+            let references = |fmri: &FMRI| {
+                runtime
+                    .iter()
+                    .filter(|name| **name == fmri.get_package_name_as_ref_string())
+                    .count()
+            };
+            build.sort_by(|a, b| references(a).cmp(&references(b)).then_with(|| a.cmp(b)));
+
+            peers
+                .entry(build_style)
+                .or_default()
+                .push((component_name, build));
+        }
+
+        for (build_style, components) in peers {
+            if components.len() < 5 {
+                continue;
+            }
+
+            let counts: Vec<f64> = components
+                .iter()
+                .map(|(_, build)| build.len() as f64)
+                .collect();
+            let mean = counts.iter().sum::<f64>() / counts.len() as f64;
+            let deviation = (counts
+                .iter()
+                .map(|count| (count - mean).powi(2))
+                .sum::<f64>()
+                / counts.len() as f64)
+                .sqrt();
+
+            for (component_name, build) in components {
+                if build.len() as f64 > mean + 2.0 * deviation {
+                    problems.add_problem(DependencyBloat(
+                        component_name.clone(),
+                        build_style.clone(),
+                        build.len(),
+                        mean.round() as usize,
+                        build.into_iter().cloned().collect(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Finds packages with runtime dependency on themselves and components with build, test,
+    /// system build or system test dependency on package they deliver
+    pub fn check_self_dependencies(&self, problems: &mut Problems) {
+        for component in self.get_ref() {
+            let delivered: Vec<&FMRI> = component
+                .get_versions_ref()
+                .iter()
+                .map(|package_versions| package_versions.fmri_ref())
+                .collect();
+
+            for package_versions in component.get_versions_ref() {
+                for package in package_versions.get_packages_ref() {
+                    for (dependencies, dependency_type) in [
+                        (package.get_runtime_dependencies(), DependencyTypes::Runtime),
+                        (package.get_build_dependencies(), DependencyTypes::Build),
+                        (package.get_test_dependencies(), DependencyTypes::Test),
+                        (
+                            package.get_system_build_dependencies(),
+                            DependencyTypes::SystemBuild,
+                        ),
+                        (
+                            package.get_system_test_dependencies(),
+                            DependencyTypes::SystemTest,
+                        ),
+                    ] {
+                        for dependency in dependencies {
+                            for fmri in dependency.get_ref().get_fmris_ref() {
+                                let is_self = match dependency_type {
+                                    DependencyTypes::Runtime => {
+                                        fmri.package_name_eq(package.fmri_ref())
+                                    }
+                                    _ => delivered
+                                        .iter()
+                                        .any(|delivered| fmri.package_name_eq(delivered)),
+                                };
+
+                                if is_self {
+                                    problems.add_problem(SelfDependency(
+                                        fmri.clone(),
+                                        dependency_type.clone(),
+                                        component.get_name_ref().clone(),
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns names of components needed by every named component with `dependency_types`
+    /// dependencies (incorporate dependencies are skipped)
+    pub fn get_component_edges(
+        &self,
+        dependency_types: Vec<DependencyTypes>,
+    ) -> BTreeMap<String, BTreeSet<String>> {
+        let mut package_components: HashMap<&String, &String> = HashMap::new();
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                package_components.insert(
+                    package_versions.fmri_ref().get_package_name_as_ref_string(),
+                    component.get_name_ref(),
+                );
+            }
+        }
+
+        let mut edges: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for component in self.get_ref() {
+            if component.get_name_ref().is_empty() || component.get_versions_ref().is_empty() {
+                continue;
+            }
+
+            let targets = edges.entry(component.get_name_ref().clone()).or_default();
+            for (dependency, _) in component.get_dependencies(dependency_types.clone()) {
+                if let DependTypes::Incorporate(_) = dependency.get_ref() {
+                    continue;
+                }
+
+                for fmri in dependency.get_ref().get_fmris_ref() {
+                    if let Some(target) =
+                        package_components.get(fmri.get_package_name_as_ref_string())
+                    {
+                        if !target.is_empty() {
+                            targets.insert((*target).clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        edges
+    }
+
+    /// Returns dependency graph in Graphviz DOT format, packages (`pkg:/name`) are ellipses and
+    /// components (`component:name`) are boxes, packages not passing
+    /// [namespace filter][Components::set_namespace_filter] are left out
+    pub fn to_dot(&self, options: &DotOptions) -> String {
+        let namespace_filter = self.get_namespace_filter_ref();
+        let quote = |id: &str| format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""));
+        let package_id = |name: &str| format!("pkg:/{}", name);
+        let component_id = |name: &str| format!("component:{}", name);
+        let package_attributes = |name: &str, obsolete: bool, renamed: bool| {
+            let fill = match (options.color, obsolete, renamed) {
+                (true, true, _) => ", style=filled, fillcolor=gray",
+                (true, false, true) => ", style=filled, fillcolor=orange",
+                _ => "",
+            };
+            format!("label={}{}", quote(name), fill)
+        };
+        let component_attributes = |name: &str| format!("label={}, shape=box", quote(name));
+
+        // node id and its attributes, edges (from, to, attributes)
+        let mut nodes: BTreeMap<String, String> = BTreeMap::new();
+        let mut edges: BTreeSet<(String, String, String)> = BTreeSet::new();
+        // packages of components (component id, package id)
+        let mut members: Vec<(String, String)> = vec![];
+
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                let name = package_versions.fmri_ref().get_package_name_as_ref_string();
+                if !namespace_filter.allows(name) {
+                    continue;
+                }
+                if !component.get_name_ref().is_empty() {
+                    members.push((component_id(component.get_name_ref()), package_id(name)));
+                }
+                if !options.runtime {
+                    continue;
+                }
+
+                nodes.insert(
+                    package_id(name),
+                    package_attributes(
+                        name,
+                        package_versions.is_obsolete(),
+                        package_versions.is_renamed(),
+                    ),
+                );
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+                for edge in package_edges(name, package) {
+                    if edge.dependency_type != DependencyTypes::Runtime
+                        || !namespace_filter.allows(&edge.to)
+                    {
+                        continue;
+                    }
+
+                    let attributes = match edge.depend_type.as_str() {
+                        "require" => String::new(),
+                        depend_type => format!(" [label={}]", quote(depend_type)),
+                    };
+                    // package that is only required (e.g. obsoleted package)
+                    nodes.entry(package_id(&edge.to)).or_insert_with(|| {
+                        let obsolete = FMRI::parse_raw(&edge.to).is_ok_and(|fmri| {
+                            self.get_alternative_state(&fmri) == AlternativeState::Obsolete
+                        });
+                        package_attributes(&edge.to, obsolete, false)
+                    });
+                    edges.insert((package_id(&edge.from), package_id(&edge.to), attributes));
+                }
+            }
+        }
+
+        if options.build {
+            for (from, targets) in
+                self.get_component_edges(vec![DependencyTypes::Build, DependencyTypes::SystemBuild])
+            {
+                nodes.insert(component_id(&from), component_attributes(&from));
+                for target in targets {
+                    nodes.insert(component_id(&target), component_attributes(&target));
+                    edges.insert((
+                        component_id(&from),
+                        component_id(&target),
+                        " [style=dashed]".to_owned(),
+                    ));
+                }
+            }
+        }
+
+        if let Some(focus) = &options.focus {
+            // focused package or component with its component or packages
+            let mut kept: BTreeSet<String> = [package_id(focus), component_id(focus)]
+                .into_iter()
+                .chain(
+                    members
+                        .iter()
+                        .filter(|(component, package)| {
+                            *component == component_id(focus) || *package == package_id(focus)
+                        })
+                        .flat_map(|(component, package)| [component.clone(), package.clone()]),
+                )
+                .filter(|id| nodes.contains_key(id))
+                .collect();
+
+            for _ in 0..options.depth {
+                let neighbors: Vec<String> = edges
+                    .iter()
+                    .filter_map(
+                        |(from, to, _)| match (kept.contains(from), kept.contains(to)) {
+                            (true, false) => Some(to.clone()),
+                            (false, true) => Some(from.clone()),
+                            _ => None,
+                        },
+                    )
+                    .collect();
+                if neighbors.is_empty() {
+                    break;
+                }
+                kept.extend(neighbors);
+            }
+
+            nodes.retain(|id, _| kept.contains(id));
+            edges.retain(|(from, to, _)| kept.contains(from) && kept.contains(to));
+        }
+
+        let mut dot = "digraph dependencies {\n".to_owned();
+        for (id, attributes) in &nodes {
+            dot.push_str(&format!("    {} [{}];\n", quote(id), attributes));
+        }
+        for (from, to, attributes) in &edges {
+            dot.push_str(&format!(
+                "    {} -> {}{};\n",
+                quote(from),
+                quote(to),
+                attributes
+            ));
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+
+    /// Finds cycles between components in build and system build dependencies
+    pub fn check_build_cycles(&self, problems: &mut Problems) {
+        for cycle in cycles(
+            &self.get_component_edges(vec![DependencyTypes::Build, DependencyTypes::SystemBuild]),
+        ) {
+            problems.add_problem(BuildDependencyCycle(cycle))
+        }
+    }
+
+    /// Finds cycles of incorporate dependencies between newest versions of packages, every cycle
+    /// is reported with incorporated versions in the cycle that don't match published versions
+    pub fn check_incorporation_cycles(&self, problems: &mut Problems) {
+        let mut newest: BTreeMap<&String, &FMRI> = BTreeMap::new();
+        let mut incorporated: BTreeMap<&String, Vec<&FMRI>> = BTreeMap::new();
+        let mut edges: BTreeMap<&String, BTreeSet<&String>> = BTreeMap::new();
+
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_obsolete() || package_versions.is_renamed() {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+                let name = package.fmri_ref().get_package_name_as_ref_string();
+                newest.insert(name, package.fmri_ref());
+
+                for dependency in package.get_runtime_dependencies() {
+                    if let DependTypes::Incorporate(fmri) = dependency.get_ref() {
+                        incorporated.entry(name).or_default().push(fmri);
+                        edges
+                            .entry(name)
+                            .or_default()
+                            .insert(fmri.get_package_name_as_ref_string());
+                    }
+                }
+            }
+        }
+
+        for cycle in cycles(&edges) {
+            let conflicts = cycle
+                .iter()
+                .flat_map(|name| incorporated.get(name).into_iter().flatten())
+                .filter(|fmri| {
+                    let target = fmri.get_package_name_as_ref_string();
+                    if !cycle.contains(&target) {
+                        return false;
+                    }
+
+                    match (
+                        newest
+                            .get(target)
+                            .and_then(|fmri| VersionParts::from_fmri(fmri)),
+                        VersionParts::from_fmri(fmri),
+                    ) {
+                        (Some(version), Some(constraint)) => !version.matches(&constraint),
+                        _ => false,
+                    }
+                })
+                .map(|fmri| (*fmri).clone())
+                .collect();
+
+            problems.add_problem(IncorporationCycle(
+                cycle
+                    .iter()
+                    .filter_map(|name| newest.get(name).map(|fmri| (*fmri).clone()))
+                    .collect(),
+                conflicts,
+            ));
+        }
+    }
+
+    /// Returns incorporate dependencies of the newest versions of all non-obsolete packages, key
+    /// is name of incorporated package and value is list of incorporating packages and
+    /// incorporated [`FMRI`]s (package can be incorporated by more packages)
+    pub fn get_incorporations(&self) -> HashMap<String, Vec<(FMRI, FMRI)>> {
+        let mut incorporations: HashMap<String, Vec<(FMRI, FMRI)>> = HashMap::new();
+
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_obsolete() || package_versions.is_renamed() {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+
+                for dependency in package.get_runtime_dependencies() {
+                    if let DependTypes::Incorporate(incorporated) = dependency.get_ref() {
+                        incorporations
+                            .entry(incorporated.get_package_name_as_ref_string().clone())
+                            .or_default()
+                            .push((package.fmri_ref().clone(), incorporated.clone()));
+                    }
+                }
+            }
+        }
+
+        incorporations
+    }
+
+    /// Finds versioned require and optional dependencies, that pin more parts of version than
+    /// incorporation that governs the required package
+    pub fn check_version_constraints(&self, problems: &mut Problems) {
+        let incorporations = self.get_incorporations();
+
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                for package in package_versions.get_packages_ref() {
+                    for dependency in package.get_runtime_dependencies() {
+                        let fmri = match dependency.get_ref() {
+                            DependTypes::Require(fmri) | DependTypes::Optional(fmri) => fmri,
+                            _ => continue,
+                        };
+
+                        let Some(incorporations) =
+                            incorporations.get(fmri.get_package_name_as_ref_string())
+                        else {
+                            continue;
+                        };
+
+                        for (incorporation, incorporated) in incorporations {
+                            if incorporation.package_name_eq(package.fmri_ref()) {
+                                continue;
+                            }
+
+                            if let (Some(required), Some(constraint)) = (
+                                VersionParts::from_fmri(fmri),
+                                VersionParts::from_fmri(incorporated),
+                            ) {
+                                if required.is_tighter_than(&constraint) {
+                                    problems.add_problem(TighterThanIncorporation(
+                                        Box::new(dependency.get_ref().clone()),
+                                        package.fmri_ref().clone(),
+                                        incorporation.clone(),
+                                        incorporated.clone(),
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Finds incorporate dependencies that published version of incorporated package doesn't
+    /// satisfy
+    pub fn check_incorporation_constraints(&self, problems: &mut Problems) {
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_obsolete() || package_versions.is_renamed() {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+
+                for dependency in package.get_runtime_dependencies() {
+                    let DependTypes::Incorporate(incorporated) = dependency.get_ref() else {
+                        continue;
+                    };
+
+                    // missing and obsoleted packages are reported by dependency validity check
+                    let Some(published) = self
+                        .get_package_versions_from_fmri(incorporated)
+                        .filter(|published| !published.is_obsolete() && !published.is_renamed())
+                        .and_then(|published| published.get_packages_ref().last().cloned())
+                    else {
+                        continue;
+                    };
+
+                    if let (Some(version), Some(constraint)) = (
+                        VersionParts::from_fmri(published.fmri_ref()),
+                        VersionParts::from_fmri(incorporated),
+                    ) {
+                        if !version.matches(&constraint) {
+                            problems.add_problem(IncorporationMismatch(
+                                package.fmri_ref().clone(),
+                                incorporated.clone(),
+                                published.fmri_ref().clone(),
+                                self.get_component_name_by_package(published.fmri_ref())
+                                    .cloned()
+                                    .unwrap_or_default(),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks installed packages of image (see [`crate::load_installed_packages`]) against
+    /// loaded catalogs, finds installed packages that are obsolete, renamed or not published and
+    /// packages older than the newest published version that installed incorporation (its newest
+    /// published version) doesn't allow to update
+    pub fn check_installed_packages(&self, problems: &mut Problems, installed: &[FMRI]) {
+        for fmri in installed {
+            let Some(package_versions) = self.get_package_versions_from_fmri(fmri) else {
+                problems.add_problem(UnavailableInstalledPackage(
+                    fmri.clone(),
+                    AlternativeState::Missing,
+                ));
+                continue;
+            };
+
+            if package_versions.is_obsolete() || package_versions.is_renamed() {
+                problems.add_problem(UnavailableInstalledPackage(
+                    fmri.clone(),
+                    match package_versions.is_obsolete() {
+                        true => AlternativeState::Obsolete,
+                        false => AlternativeState::Renamed,
+                    },
+                ));
+                continue;
+            }
+
+            let (Some(newest), Some(version)) = (
+                package_versions.get_packages_ref().last(),
+                VersionParts::from_fmri(fmri),
+            ) else {
+                continue;
+            };
+            let Some(newest_version) = VersionParts::from_fmri(newest.fmri_ref()) else {
+                continue;
+            };
+            if newest_version.matches(&version) {
+                continue;
+            }
+
+            for incorporation in installed {
+                let Some(incorporation_package) = self
+                    .get_package_versions_from_fmri(incorporation)
+                    .and_then(|versions| versions.get_packages_ref().last().cloned())
+                else {
+                    continue;
+                };
+
+                for dependency in incorporation_package.get_runtime_dependencies() {
+                    let DependTypes::Incorporate(incorporated) = dependency.get_ref() else {
+                        continue;
+                    };
+                    let Some(constraint) = VersionParts::from_fmri(incorporated) else {
+                        continue;
+                    };
+
+                    if incorporated.package_name_eq(fmri)
+                        && version.matches(&constraint)
+                        && !newest_version.matches(&constraint)
+                    {
+                        problems.add_problem(HeldBackInstalledPackage(
+                            fmri.clone(),
+                            newest.fmri_ref().clone(),
+                            incorporation.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Finds incorporate dependencies on versions that are not published or are obsoleted, such
+    /// incorporation prevents installation of any version of incorporated package
+    pub fn check_incorporated_versions(&self, problems: &mut Problems) {
+        let index = |list: &FMRIList| {
+            let mut versions: HashMap<String, Vec<VersionParts>> = HashMap::new();
+            for fmri in list.get_ref() {
+                if let Some(version) = VersionParts::from_fmri(fmri) {
+                    versions
+                        .entry(fmri.get_package_name_as_ref_string().clone())
+                        .or_default()
+                        .push(version);
+                }
+            }
+            versions
+        };
+        let published = index(&self.published);
+        let obsoleted = index(&self.obsolete);
+
+        let matches = |versions: &HashMap<String, Vec<VersionParts>>,
+                       fmri: &FMRI,
+                       constraint: &VersionParts| {
+            versions
+                .get(fmri.get_package_name_as_ref_string())
+                .is_some_and(|versions| versions.iter().any(|version| version.matches(constraint)))
+        };
+
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_obsolete() || package_versions.is_renamed() {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+
+                for dependency in package.get_runtime_dependencies() {
+                    let DependTypes::Incorporate(incorporated) = dependency.get_ref() else {
+                        continue;
+                    };
+
+                    // missing and obsoleted packages are reported by dependency validity check
+                    if self
+                        .get_package_versions_from_fmri(incorporated)
+                        .is_none_or(|versions| versions.is_obsolete() || versions.is_renamed())
+                    {
+                        continue;
+                    }
+
+                    let Some(constraint) = VersionParts::from_fmri(incorporated) else {
+                        continue;
+                    };
+
+                    if matches(&published, incorporated, &constraint) {
+                        continue;
+                    }
+
+                    problems.add_problem(UnavailableIncorporatedVersion(
+                        package.fmri_ref().clone(),
+                        incorporated.clone(),
+                        if matches(&obsoleted, incorporated, &constraint) {
+                            AlternativeState::Obsolete
+                        } else {
+                            AlternativeState::Missing
+                        },
+                        component.get_name_ref().clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Finds versioned require and conditional dependencies on versions newer than the newest
+    /// published version of required package
+    pub fn check_required_versions(&self, problems: &mut Problems) {
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_obsolete() || package_versions.is_renamed() {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+
+                for dependency in package.get_runtime_dependencies() {
+                    let (DependTypes::Require(required) | DependTypes::Conditional(required, _)) =
+                        dependency.get_ref()
+                    else {
+                        continue;
+                    };
+
+                    if !required.has_version() {
+                        continue;
+                    }
+
+                    // missing and obsoleted packages are reported by dependency validity check
+                    let Some(published) = self
+                        .get_package_versions_from_fmri(required)
+                        .filter(|published| !published.is_obsolete() && !published.is_renamed())
+                        .and_then(|published| published.get_packages_ref().iter().max().cloned())
+                    else {
+                        continue;
+                    };
+
+                    if published.fmri_ref() < required {
+                        problems.add_problem(RequiredVersionNotPublished(
+                            dependency.get_ref().clone(),
+                            package.fmri_ref().clone(),
+                            published.fmri_ref().clone(),
+                            component.get_name_ref().clone(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns state of `fmri` as alternative of require-any dependency
+    pub fn get_alternative_state(&self, fmri: &FMRI) -> AlternativeState {
+        let Some(package_versions) = self.get_package_versions_from_fmri(fmri) else {
+            if let Some(record) = self.get_history_record(fmri) {
+                return match record.is_renamed() {
+                    true => AlternativeState::Renamed,
+                    false => AlternativeState::Obsolete,
+                };
+            }
+            if self.is_fmri_obsoleted(fmri) {
+                return AlternativeState::Obsolete;
+            }
+            return AlternativeState::Missing;
+        };
+
+        if package_versions.is_obsolete() {
+            return AlternativeState::Obsolete;
+        }
+
+        if package_versions.is_renamed() {
+            return AlternativeState::Renamed;
+        }
+
+        if fmri.has_version()
+            && package_versions
+                .get_packages_ref()
+                .iter()
+                .all(|package| package.fmri_ref() < fmri)
+        {
+            return AlternativeState::Stale;
+        }
+
+        AlternativeState::Viable
+    }
+
+    /// Finds require-any dependencies with alternatives that are not viable or with only one
+    /// viable alternative
+    pub fn check_require_any_alternatives(&self, problems: &mut Problems) {
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                for package in package_versions.get_packages_ref() {
+                    for dependency in package.get_runtime_dependencies() {
+                        let DependTypes::RequireAny(fmri_list) = dependency.get_ref() else {
+                            continue;
+                        };
+
+                        let alternatives: Vec<(FMRI, AlternativeState)> = fmri_list
+                            .get_ref()
+                            .iter()
+                            .map(|fmri| (fmri.clone(), self.get_alternative_state(fmri)))
+                            .collect();
+
+                        let viable = alternatives
+                            .iter()
+                            .filter(|(_, state)| state == &AlternativeState::Viable)
+                            .count();
+
+                        // require-any without any existing alternative is reported by
+                        // dependency validity check
+                        let unsatisfiable = alternatives.iter().all(|(_, state)| {
+                            matches!(
+                                state,
+                                AlternativeState::Missing | AlternativeState::Obsolete
+                            )
+                        });
+
+                        if !unsatisfiable && (viable == 1 || viable < alternatives.len()) {
+                            problems.add_problem(RequireAnyAlternatives(
+                                package.fmri_ref().clone(),
+                                alternatives,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn get_package_versions_from_fmri(&self, fmri: &FMRI) -> Option<PackageVersions> {
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.fmri_ref().package_name_eq(fmri) {
+                    return Some(package_versions.clone());
+                }
+            }
+        }
+
+        None
+    }
+
+    pub fn check_if_fmri_exists_as_package(&self, fmri: &FMRI) -> bool {
+        for component in self.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.fmri_ref().package_name_eq(fmri) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    pub fn add(&mut self, component: Component) {
+        self.components.push(component)
+    }
+
+    pub fn get_obsoleted_ref(&self) -> &FMRIList {
+        &self.obsolete
+    }
+
+    pub fn add_obsoleted(&mut self, fmri: FMRI) {
+        self.obsolete.add(fmri)
+    }
+
+    pub fn is_fmri_obsoleted(&self, fmri: &FMRI) -> bool {
+        self.obsolete.contains(fmri)
+    }
+
+    pub fn get_published_ref(&self) -> &FMRIList {
+        &self.published
+    }
+
+    pub fn add_published(&mut self, fmri: FMRI) {
+        self.published.add(fmri)
+    }
+
+    /// Sets [`RewriteRules`] used during [loading][Components::load]
+    pub fn set_rewrite_rules(&mut self, rewrite_rules: RewriteRules) {
+        self.rewrite_rules = rewrite_rules
+    }
+
+    pub fn get_rewrite_rules_ref(&self) -> &RewriteRules {
+        &self.rewrite_rules
+    }
+
+    /// Enables validation of timestamps during [loading][Components::load] of catalogs
+    pub fn set_strict_timestamps(&mut self, strict_timestamps: bool) {
+        self.strict_timestamps = strict_timestamps
+    }
+
+    pub fn is_strict_timestamps(&self) -> bool {
+        self.strict_timestamps
+    }
+
+    /// Build and test dependencies of components are parsed from Makefiles (see
+    /// [`crate::ComponentPackagesList`]), gmake is run only if Makefile is too complex
+    pub fn set_native_makefiles(&mut self, native_makefiles: bool) {
+        self.native_makefiles = native_makefiles
+    }
+
+    pub fn is_native_makefiles(&self) -> bool {
+        self.native_makefiles
+    }
+
+    /// Dependencies of components whose Makefile is too complex to parse (and that are not in
+    /// make cache) are not loaded instead of running gmake (e.g. for snapshot of oi-userland)
+    pub fn set_without_make(&mut self, without_make: bool) {
+        self.without_make = without_make
+    }
+
+    pub fn is_without_make(&self) -> bool {
+        self.without_make
+    }
+
+    pub fn set_jobs(&mut self, jobs: usize) {
+        self.jobs = jobs
+    }
+
+    pub fn get_jobs(&self) -> usize {
+        self.jobs
+    }
+
+    pub fn set_publishers(&mut self, publishers: Vec<PublisherConfig>) {
+        self.publishers = publishers
+    }
+
+    /// Sets filter of packages in graph queries (e.g. [`Components::get_dependencies_with_fmri`])
+    /// and exports, checks are not filtered
+    pub fn set_namespace_filter(&mut self, namespace_filter: NamespaceFilter) {
+        self.namespace_filter = namespace_filter
+    }
+
+    pub fn get_namespace_filter_ref(&self) -> &NamespaceFilter {
+        &self.namespace_filter
+    }
+
+    /// Returns true if [`FMRI`] is published by external publisher (see
+    /// [`PublisherConfig::external`])
+    pub fn is_external(&self, fmri: &FMRI) -> bool {
+        fmri.get_publisher_as_ref_string().is_some_and(|publisher| {
+            self.publishers.iter().any(|publisher_config| {
+                publisher_config.external && publisher_config.is_named(publisher)
+            })
+        })
+    }
+
+    /// Returns name of publisher with name or alias `publisher`
+    pub fn get_publisher_name(&self, publisher: &str) -> String {
+        self.publishers
+            .iter()
+            .find(|publisher_config| publisher_config.is_named(publisher))
+            .map(|publisher_config| publisher_config.name.clone())
+            .unwrap_or_else(|| publisher.to_owned())
+    }
+
+    /// Returns publisher and origin url of catalog
+    pub fn get_origin_of_catalog(&self, catalog: &Path) -> Option<(&String, &String)> {
+        self.publishers.iter().find_map(|publisher_config| {
+            publisher_config
+                .origins
+                .iter()
+                .find(|origin| origin.catalog == catalog)
+                .map(|origin| (&publisher_config.name, &origin.url))
+        })
+    }
+
+    pub fn add_catalog_origin(&mut self, catalog_origin: CatalogOrigin) {
+        self.catalog_origins.push(catalog_origin)
+    }
+
+    pub fn get_catalog_origins_ref(&self) -> &Vec<CatalogOrigin> {
+        &self.catalog_origins
+    }
+
+    pub fn add_catalog_timestamp(&mut self, catalog_timestamp: CatalogTimestamp) {
+        self.catalog_timestamps.push(catalog_timestamp)
+    }
+
+    pub fn set_packages_with_files(&mut self, packages_with_files: BTreeSet<String>) {
+        self.packages_with_files = Some(packages_with_files)
+    }
+
+    pub fn set_commit(&mut self, commit: Option<String>) {
+        self.commit = commit
+    }
+
+    /// Returns commit of oi-userland that data correspond to, [`None`] if it is unknown
+    pub fn get_commit(&self) -> Option<&String> {
+        self.commit.as_ref()
+    }
+
+    pub fn set_history(&mut self, history: Vec<HistoryRecord>) {
+        self.history = history
+    }
+
+    pub fn get_history_ref(&self) -> &Vec<HistoryRecord> {
+        &self.history
+    }
+
+    /// Returns the latest record of package with name of `fmri` in history
+    pub fn get_history_record(&self, fmri: &FMRI) -> Option<&HistoryRecord> {
+        self.history
+            .iter()
+            .filter(|record| record.fmri.package_name_eq(fmri))
+            .max_by_key(|record| &record.fmri)
+    }
+
+    /// Replaces runtime dependencies of the newest versions of packages with dependencies loaded
+    /// from manifests, see [`crate::load_manifest_dependencies`], packages that are not published
+    /// (or are obsoleted or renamed) are skipped
+    pub fn set_manifest_dependencies(&mut self, manifest_dependencies: Vec<(FMRI, Dependencies)>) {
+        for (mut fmri, dependencies) in manifest_dependencies {
+            self.rewrite_fmri(&mut fmri);
+            let mut rewritten = Dependencies::new();
+            for dependency in dependencies.get() {
+                let mut depend_type = dependency.get();
+                self.rewrite_depend_type(&mut depend_type);
+                rewritten.add(Dependency::new(&depend_type));
+            }
+
+            let mut published = false;
+            for component in self.get_ref_mut() {
+                for package_versions in component.get_versions_ref_mut() {
+                    if !package_versions.fmri_ref().package_name_eq(&fmri)
+                        || package_versions.is_obsolete()
+                        || package_versions.is_renamed()
+                    {
+                        continue;
+                    }
+
+                    if let Some(package) = package_versions.get_packages_ref_mut().last_mut() {
+                        package.set_runtime_dependencies(rewritten.clone());
+                        published = true;
+                    }
+                }
+            }
+
+            if !published {
+                debug!(
+                    "skipping dependencies of {} from manifest, it is not published",
+                    fmri
+                );
+            }
+        }
+    }
+
+    /// Sets actions of packages from their manifests, see [`crate::load_manifest_actions`]
+    pub fn set_package_actions(&mut self, package_actions: Vec<(FMRI, Vec<Action>)>) {
+        for (mut fmri, actions) in package_actions {
+            self.rewrite_fmri(&mut fmri);
+
+            let mut found = false;
+            for component in self.get_ref_mut() {
+                for package_versions in component.get_versions_ref_mut() {
+                    if package_versions.fmri_ref().package_name_eq(&fmri) {
+                        package_versions.set_actions(actions.clone());
+                        found = true;
+                    }
+                }
+            }
+
+            if !found {
+                debug!(
+                    "skipping actions of {} from manifest, it is not known",
+                    fmri
+                );
+            }
+        }
+    }
+
+    /// Returns true if package is metapackage: it is not obsoleted or renamed, its newest version
+    /// has only require and group runtime dependencies (at least one) and it doesn't deliver any
+    /// path in manifests (if they were loaded)
+    pub fn is_metapackage(&self, package_versions: &PackageVersions) -> bool {
+        if package_versions.is_obsolete() || package_versions.is_renamed() {
+            return false;
+        }
+
+        let Some(package) = package_versions.get_packages_ref().last() else {
+            return false;
+        };
+
+        let dependencies = package.get_runtime_dependencies();
+        if dependencies.is_empty()
+            || !dependencies.iter().all(|dependency| {
+                matches!(
+                    dependency.get_ref(),
+                    DependTypes::Require(_) | DependTypes::Group(_)
+                )
+            })
+        {
+            return false;
+        }
+
+        match &self.packages_with_files {
+            Some(packages_with_files) => !packages_with_files
+                .contains(package_versions.fmri_ref().get_package_name_as_ref_string()),
+            None => true,
+        }
+    }
+
+    pub fn set_make_cache(&mut self, make_cache: MakeCache) {
+        self.make_cache = make_cache
+    }
+
+    /// Returns make cache and leaves empty cache in [`Components`]
+    pub fn take_make_cache(&mut self) -> MakeCache {
+        std::mem::take(&mut self.make_cache)
+    }
+
+    /// Rewrites [`FMRI`] with [`RewriteRules`] and records applied [`Rewrite`]
+    pub fn rewrite_fmri(&mut self, fmri: &mut FMRI) {
+        if let Some(rewrite) = self.rewrite_rules.rewrite(fmri) {
+            self.add_rewrite(rewrite)
+        }
+    }
+
+    /// Rewrites all [fmris][FMRI] in [`DependTypes`], see [`Components::rewrite_fmri`]
+    pub fn rewrite_depend_type(&mut self, depend_type: &mut DependTypes) {
+        for fmri in depend_type.get_fmris_ref_mut() {
+            self.rewrite_fmri(fmri)
+        }
+    }
+
+    pub fn add_rewrite(&mut self, rewrite: Rewrite) {
+        if !self.rewrites.contains(&rewrite) {
+            self.rewrites.push(rewrite)
+        }
+    }
+
+    /// Returns applied rewrites (provenance)
+    pub fn get_rewrites_ref(&self) -> &Vec<Rewrite> {
+        &self.rewrites
     }
 
     pub fn change(&mut self, new_components: Vec<Component>) {
@@ -20,15 +20,20 @@ pub enum DependTypes {
 
 impl DependTypes {
     /// Returns name of depend action and content
-    pub fn get_name_and_content_as_string(self) -> (String, String) {
+    pub fn get_name_and_content_as_string(&self) -> (String, String) {
         match self {
-            DependTypes::Require(fmri) => ("require".to_owned(), fmri.get_package_name_as_string()),
-            DependTypes::Optional(fmri) => {
-                ("optional".to_owned(), fmri.get_package_name_as_string())
-            }
-            DependTypes::Incorporate(fmri) => {
-                ("incorporate".to_owned(), fmri.get_package_name_as_string())
-            }
+            DependTypes::Require(fmri) => (
+                "require".to_owned(),
+                fmri.get_package_name_as_ref_string().clone(),
+            ),
+            DependTypes::Optional(fmri) => (
+                "optional".to_owned(),
+                fmri.get_package_name_as_ref_string().clone(),
+            ),
+            DependTypes::Incorporate(fmri) => (
+                "incorporate".to_owned(),
+                fmri.get_package_name_as_ref_string().clone(),
+            ),
             DependTypes::RequireAny(fmri_list) => {
                 let mut string = String::new();
                 let len = fmri_list.get_ref().len();
@@ -40,22 +45,49 @@ impl DependTypes {
                 }
                 ("require-any".to_owned(), string)
             }
-            DependTypes::Conditional(fmri, predicate) => {
-                // TODO: remove this
-                if fmri.package_name_eq(&FMRI::parse_raw("none").unwrap()) {
-                    return ("conditional".to_owned(), format!("predicate={}", predicate));
-                }
-                if predicate.package_name_eq(&FMRI::parse_raw("none").unwrap()) {
-                    return ("conditional".to_owned(), format!("fmri={}", fmri));
-                }
+            DependTypes::Conditional(fmri, predicate) => (
+                "conditional".to_owned(),
+                format!("fmri={}, predicate={}", fmri, predicate),
+            ),
+            DependTypes::Group(fmri) => (
+                "group".to_owned(),
+                fmri.get_package_name_as_ref_string().clone(),
+            ),
+            _ => unimplemented!(),
+        }
+    }
 
-                (
-                    "conditional".to_owned(),
-                    format!("fmri={}, predicate={}", fmri, predicate),
-                )
+    /// Returns all [fmris][FMRI] in depend action (including predicate)
+    pub fn get_fmris_ref(&self) -> Vec<&FMRI> {
+        match self {
+            DependTypes::RequireAny(fmri_list) | DependTypes::GroupAny(fmri_list) => {
+                fmri_list.get_ref().iter().collect()
             }
-            DependTypes::Group(fmri) => ("group".to_owned(), fmri.get_package_name_as_string()),
-            _ => unimplemented!(),
+            DependTypes::Conditional(fmri, predicate) => vec![fmri, predicate],
+            DependTypes::Require(fmri)
+            | DependTypes::Optional(fmri)
+            | DependTypes::Exclude(fmri)
+            | DependTypes::Incorporate(fmri)
+            | DependTypes::Origin(fmri)
+            | DependTypes::Group(fmri)
+            | DependTypes::Parent(fmri) => vec![fmri],
+        }
+    }
+
+    /// Returns all [fmris][FMRI] in depend action (including predicate)
+    pub fn get_fmris_ref_mut(&mut self) -> Vec<&mut FMRI> {
+        match self {
+            DependTypes::RequireAny(fmri_list) | DependTypes::GroupAny(fmri_list) => {
+                fmri_list.get_ref_mut().iter_mut().collect()
+            }
+            DependTypes::Conditional(fmri, predicate) => vec![fmri, predicate],
+            DependTypes::Require(fmri)
+            | DependTypes::Optional(fmri)
+            | DependTypes::Exclude(fmri)
+            | DependTypes::Incorporate(fmri)
+            | DependTypes::Origin(fmri)
+            | DependTypes::Group(fmri)
+            | DependTypes::Parent(fmri) => vec![fmri],
         }
     }
 
@@ -31,6 +31,24 @@ impl Dependencies {
         self.0.push(dependency)
     }
 
+    /// Returns [dependencies][Dependency] that are already declared before them (versions are
+    /// ignored)
+    pub fn get_duplicates(&self) -> Vec<&Dependency> {
+        let mut seen: Vec<(String, String)> = vec![];
+        let mut duplicates = vec![];
+
+        for dependency in self.get_ref() {
+            let key = dependency.get_ref().get_name_and_content_as_string();
+            if seen.contains(&key) {
+                duplicates.push(dependency)
+            } else {
+                seen.push(key)
+            }
+        }
+
+        duplicates
+    }
+
     /// Returns [`Vec`] of [dependencies][Dependency]
     pub fn get(self) -> Vec<Dependency> {
         self.0
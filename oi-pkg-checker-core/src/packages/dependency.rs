@@ -1,6 +1,6 @@
 use std::cell::RefCell;
 
-use fmri::{FMRI, fmri_list::FMRIList};
+use fmri::FMRI;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -9,9 +9,13 @@ use crate::{
         package::Package,
     },
     Problems,
-    problems::Problem::{
-        NonExistingRequired, NonExistingRequiredByRenamed, ObsoletedRequired,
-        ObsoletedRequiredByRenamed, PartlyObsoletedRequired, PartlyObsoletedRequiredByRenamed,
+    problems::{
+        AlternativeState,
+        Problem::{
+            BrokenConditional, NonExistingRequired, NonExistingRequiredByRenamed,
+            ObsoletedRequired, ObsoletedRequiredByRenamed, PartlyObsoletedRequired,
+            PartlyObsoletedRequiredByRenamed, UnsatisfiableRequireAny,
+        },
     },
 };
 
@@ -159,72 +163,64 @@ impl Dependency {
                     non_existing(DependTypes::Incorporate(fmri.clone()));
                 }
             }
+            // require-any is broken only when no alternative can be installed, missing
+            // alternatives alone are reported by require-any-alternatives check
             DependTypes::RequireAny(fmri_list) => {
-                let mut obsolete_list = FMRIList::new();
-                let mut partly_obsolete_list = FMRIList::new();
-                let mut non_existing_list = FMRIList::new();
-
-                for fmri in fmri_list.get_ref() {
-                    if components.is_fmri_obsoleted(fmri) {
-                        if !components.check_if_fmri_exists_as_package(fmri) {
-                            obsolete_list.add(fmri.clone());
-                        } else {
-                            partly_obsolete_list.add(fmri.clone());
-                        }
-                    } else if !components.check_if_fmri_exists_as_package(fmri) {
-                        non_existing_list.add(fmri.clone())
-                    }
-                }
-
-                if !obsolete_list.is_empty() {
-                    obsoleted(DependTypes::RequireAny(obsolete_list));
-                }
-
-                if !partly_obsolete_list.is_empty() {
-                    partly(DependTypes::RequireAny(partly_obsolete_list));
-                }
-
-                if !non_existing_list.is_empty() {
-                    non_existing(DependTypes::RequireAny(non_existing_list));
+                if !fmri_list
+                    .get_ref()
+                    .iter()
+                    .any(|fmri| components.check_if_fmri_exists_as_package(fmri))
+                {
+                    let package_fmri = package.clone().fmri();
+                    let component_name = components
+                        .get_component_name_by_package(&package_fmri)
+                        .cloned()
+                        .unwrap_or_default();
+
+                    problems.borrow_mut().add_problem(UnsatisfiableRequireAny(
+                        self.get_ref().clone(),
+                        dependency_type.clone(),
+                        package_fmri,
+                        component_name,
+                    ));
                 }
             }
             DependTypes::Conditional(fmri, predicate) => {
-                if components.is_fmri_obsoleted(fmri) {
-                    if !components.check_if_fmri_exists_as_package(fmri) {
-                        obsoleted(DependTypes::Conditional(
-                            fmri.clone(),
-                            FMRI::parse_raw("none").unwrap(),
-                        ));
-                    } else {
-                        partly(DependTypes::Conditional(
-                            fmri.clone(),
-                            FMRI::parse_raw("none").unwrap(),
-                        ));
+                let broken = |fmri: &FMRI| {
+                    if components.check_if_fmri_exists_as_package(fmri) {
+                        return None;
                     }
-                } else if !components.check_if_fmri_exists_as_package(fmri) {
-                    non_existing(DependTypes::Conditional(
-                        fmri.clone(),
-                        FMRI::parse_raw("none").unwrap(),
-                    ));
-                }
 
-                if components.is_fmri_obsoleted(predicate) {
-                    if !components.check_if_fmri_exists_as_package(predicate) {
-                        obsoleted(DependTypes::Conditional(
-                            FMRI::parse_raw("none").unwrap(),
-                            fmri.clone(),
-                        ));
-                    } else {
-                        partly(DependTypes::Conditional(
-                            FMRI::parse_raw("none").unwrap(),
-                            fmri.clone(),
-                        ));
-                    }
-                } else if !components.check_if_fmri_exists_as_package(predicate) {
-                    non_existing(DependTypes::Conditional(
-                        FMRI::parse_raw("none").unwrap(),
+                    Some((
                         fmri.clone(),
+                        if components.is_fmri_obsoleted(fmri) {
+                            AlternativeState::Obsolete
+                        } else {
+                            AlternativeState::Missing
+                        },
+                    ))
+                };
+
+                let (broken_fmri, broken_predicate) = (broken(fmri), broken(predicate));
+
+                if broken_fmri.is_some() || broken_predicate.is_some() {
+                    let package_fmri = package.clone().fmri();
+                    let component_name = components
+                        .get_component_name_by_package(&package_fmri)
+                        .cloned()
+                        .unwrap_or_default();
+
+                    problems.borrow_mut().add_problem(BrokenConditional(
+                        broken_fmri,
+                        broken_predicate,
+                        dependency_type.clone(),
+                        package_fmri,
+                        component_name,
                     ));
+                } else if components.is_fmri_obsoleted(fmri)
+                    || components.is_fmri_obsoleted(predicate)
+                {
+                    partly(self.get_ref().clone());
                 }
             }
             DependTypes::Group(fmri) => {
@@ -146,6 +146,10 @@ impl Package {
         self.runtime += dependencies
     }
 
+    pub fn set_runtime_dependencies(&mut self, dependencies: Dependencies) {
+        self.runtime = dependencies
+    }
+
     pub fn add_build_dependencies(&mut self, dependencies: Dependencies) {
         self.build += dependencies
     }
@@ -161,6 +165,14 @@ impl Package {
     pub fn add_system_test_dependencies(&mut self, dependencies: Dependencies) {
         self.system_test += dependencies
     }
+
+    /// Removes dependencies loaded from component (build, test, system build and system test)
+    pub fn clear_component_dependencies(&mut self) {
+        self.build = Dependencies::new();
+        self.test = Dependencies::new();
+        self.system_build = Dependencies::new();
+        self.system_test = Dependencies::new();
+    }
 }
 
 impl PartialOrd<Self> for Package {
@@ -3,7 +3,7 @@ use std::cmp::Ordering;
 use fmri::FMRI;
 use serde::{Deserialize, Serialize};
 
-use crate::packages::package::Package;
+use crate::packages::{actions::Action, package::Package};
 
 /// PackageVersions has 1 or more versions of package
 #[derive(PartialEq, Serialize, Deserialize, Clone, Debug)]
@@ -16,6 +16,8 @@ pub struct PackageVersions {
     pub(crate) renamed: bool,
     /// version, package
     pub(crate) packages: Vec<Package>,
+    /// actions of pkg5 manifest of package (depend actions are in [`Package`])
+    pub(crate) actions: Vec<Action>,
 }
 
 impl PackageVersions {
@@ -29,6 +31,7 @@ impl PackageVersions {
             obsolete: false,
             renamed: false,
             packages: vec![],
+            actions: vec![],
         }
     }
 
@@ -114,6 +117,14 @@ impl PackageVersions {
         self.renamed
     }
 
+    pub fn set_actions(&mut self, actions: Vec<Action>) {
+        self.actions = actions
+    }
+
+    pub fn get_actions_ref(&self) -> &Vec<Action> {
+        &self.actions
+    }
+
     /// Returns newer [`Package`] in [`PackageVersions`] if there is at least one
     pub fn get_newer_package(&self) -> Option<Package> {
         let packages = self.get_packages_ref();
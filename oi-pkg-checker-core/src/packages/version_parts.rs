@@ -0,0 +1,163 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use fmri::FMRI;
+
+/// Version of [`FMRI`] split into parts
+///
+/// [`fmri::Version`] doesn't expose its segments, so they are parsed from its string form
+/// (`@1.2.3,5.11-2022.0.0.1:20220126T070330Z`)
+#[derive(PartialEq, Clone, Debug)]
+pub struct VersionParts {
+    pub component: Vec<u64>,
+    pub build: Vec<u64>,
+    pub branch: Vec<u64>,
+    pub timestamp: Option<String>,
+}
+
+impl VersionParts {
+    /// Returns [`None`] if [`FMRI`] has no version or the version is not numeric
+    pub fn from_fmri(fmri: &FMRI) -> Option<Self> {
+        Self::parse(&fmri.get_version_as_string()?)
+    }
+
+    /// Parses "@1.2.3,5.11-2022.0.0.1:20220126T070330Z" into [`VersionParts`]
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.trim_start_matches('@');
+
+        let (version, timestamp) = match version.split_once(':') {
+            Some((version, timestamp)) => (version, Some(timestamp.to_owned())),
+            None => (version, None),
+        };
+
+        let (version, branch) = version.split_once('-').unwrap_or((version, ""));
+        let (component, build) = version.split_once(',').unwrap_or((version, ""));
+
+        let numbers = |segment: &str| -> Option<Vec<u64>> {
+            if segment.is_empty() {
+                return Some(vec![]);
+            }
+            segment.split('.').map(|part| part.parse().ok()).collect()
+        };
+
+        Some(Self {
+            component: numbers(component)?,
+            build: numbers(build)?,
+            branch: numbers(branch)?,
+            timestamp,
+        })
+    }
+
+    /// Returns true if [`self`] is allowed by `constraint` at the precision of `constraint`
+    /// (semantics of incorporate dependency), build version is ignored
+    pub fn matches(&self, constraint: &Self) -> bool {
+        if !self.component.starts_with(&constraint.component) {
+            return false;
+        }
+
+        if constraint.branch.is_empty() {
+            return true;
+        }
+
+        self.component == constraint.component && self.branch.starts_with(&constraint.branch)
+    }
+
+    /// Returns true if [`self`] is allowed by `constraint`, but pins more parts of version
+    pub fn is_tighter_than(&self, constraint: &Self) -> bool {
+        self.matches(constraint)
+            && (self.component.len() > constraint.component.len()
+                || self.branch.len() > constraint.branch.len())
+    }
+}
+
+/// Returns current time in format of [`FMRI`] timestamp (`20220126T070330Z`)
+pub fn current_timestamp() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is before unix epoch")
+        .as_secs();
+
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let days = (seconds / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        seconds % 86400 / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// Returns number of days since unix epoch of timestamp (`20220126T070330Z`), [`None`] if
+/// timestamp is not valid
+pub fn timestamp_days(timestamp: &str) -> Option<i64> {
+    if !is_plausible_timestamp(timestamp, "99991231T235959Z") {
+        return None;
+    }
+
+    let number =
+        |range: std::ops::Range<usize>| -> Option<i64> { timestamp.get(range)?.parse().ok() };
+    let (year, month, day) = (number(0..4)?, number(4..6)?, number(6..8)?);
+
+    // days from civil, see http://howardhinnant.github.io/date_algorithms.html
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    Some(era * 146097 + day_of_era - 719468)
+}
+
+/// Checks if timestamp (`20220126T070330Z`) is valid date and time between year 2000 and `now`
+pub fn is_plausible_timestamp(timestamp: &str, now: &str) -> bool {
+    let bytes = timestamp.as_bytes();
+
+    if bytes.len() != 16 || bytes[8] != b'T' || bytes[15] != b'Z' {
+        return false;
+    }
+
+    let number =
+        |range: std::ops::Range<usize>| -> Option<u32> { timestamp.get(range)?.parse().ok() };
+
+    let (Some(year), Some(month), Some(day), Some(hour), Some(minute), Some(second)) = (
+        number(0..4),
+        number(4..6),
+        number(6..8),
+        number(9..11),
+        number(11..13),
+        number(13..15),
+    ) else {
+        return false;
+    };
+
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    year >= 2000
+        && (1..=days_in_month).contains(&day)
+        && hour < 24
+        && minute < 60
+        && second < 60
+        && timestamp <= now
+}
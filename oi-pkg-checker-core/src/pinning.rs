@@ -0,0 +1,106 @@
+use std::{cmp::Ordering, fs::read_to_string, path::PathBuf};
+
+use fmri::FMRI;
+
+use crate::packages::{components::Components, depend_types::DependTypes};
+
+/// Impact of package version that is pinned (frozen) by site
+#[derive(Debug)]
+pub struct PinImpact {
+    pub pin: FMRI,
+    /// newer versions in catalog that can't be installed because of pin
+    pub blocked_updates: Vec<FMRI>,
+    /// packages with dependency (second) that needs newer version than pinned one
+    pub unsatisfiable: Vec<(FMRI, DependTypes)>,
+    /// incorporations that incorporate other version than pinned one
+    pub incorporation_conflicts: Vec<(FMRI, FMRI)>,
+}
+
+impl PinImpact {
+    /// Returns [`None`] if pinned package doesn't exist
+    pub fn new(components: &Components, pin: &FMRI) -> Option<Self> {
+        let package_versions = components.get_package_versions_from_fmri(pin)?;
+
+        let mut pin_impact = Self {
+            pin: pin.clone(),
+            blocked_updates: package_versions
+                .get_packages_ref()
+                .iter()
+                .map(|package| package.fmri_ref())
+                .filter(|fmri| pin.cmp(fmri) == Ordering::Less)
+                .cloned()
+                .collect(),
+            unsatisfiable: vec![],
+            incorporation_conflicts: vec![],
+        };
+
+        for component in components.get_ref() {
+            for package_versions in component.get_versions_ref() {
+                if package_versions.is_obsolete()
+                    || package_versions.is_renamed()
+                    || !components
+                        .get_namespace_filter_ref()
+                        .allows(package_versions.fmri_ref().get_package_name_as_ref_string())
+                {
+                    continue;
+                }
+
+                let Some(package) = package_versions.get_packages_ref().last() else {
+                    continue;
+                };
+
+                for dependency in package.get_runtime_dependencies() {
+                    match dependency.get_ref() {
+                        DependTypes::Require(fmri)
+                        | DependTypes::Optional(fmri)
+                        | DependTypes::Conditional(fmri, _)
+                        | DependTypes::Group(fmri)
+                            if fmri.package_name_eq(pin)
+                                && fmri.has_version()
+                                && pin.cmp(fmri) == Ordering::Less =>
+                        {
+                            pin_impact
+                                .unsatisfiable
+                                .push((package.fmri_ref().clone(), dependency.get_ref().clone()))
+                        }
+                        DependTypes::Incorporate(fmri)
+                            if fmri.package_name_eq(pin)
+                                && fmri.has_version()
+                                && pin.cmp(fmri) != Ordering::Equal =>
+                        {
+                            pin_impact
+                                .incorporation_conflicts
+                                .push((package.fmri_ref().clone(), fmri.clone()))
+                        }
+                        // other alternatives of require-any can be used, other versions are not affected
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        Some(pin_impact)
+    }
+}
+
+/// Loads pinned packages from file with one fmri with version per line, empty lines and lines
+/// starting with `#` are ignored
+pub fn load_pins(path: PathBuf) -> Result<Vec<FMRI>, String> {
+    let content =
+        read_to_string(&path).map_err(|e| format!("failed to read pins {:?}: {}", path, e))?;
+
+    let mut pins = vec![];
+    for line in content.lines().map(|line| line.trim()) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fmri = FMRI::parse_raw(line).map_err(|e| format!("invalid pin {}: {}", line, e))?;
+        if !fmri.has_version() {
+            return Err(format!("pin without version: {}", line));
+        }
+        pins.push(fmri);
+    }
+
+    Ok(pins)
+}
@@ -0,0 +1,11 @@
+//! Supported public API of the crate
+//!
+//! Modules of the crate are internal and can change with any release, tools using this library
+//! should import types from here (`use oi_pkg_checker_core::prelude::*;`). Types in the prelude
+//! are changed only with a new major version.
+
+pub use crate::{
+    report, report_by_component, AssetTypes, Check, ComponentPackagesList, Components, Config,
+    DependTypes, Dependencies, Dependency, DependencyTypes, Dependents, Package, PackageVersions,
+    Problem, ProblemKind, Problems, ProblemsSummary, RewriteRules, Severity, CHECKS,
+};
@@ -1,32 +1,51 @@
 use std::{
-    fs::File,
-    io::{Read, Write},
+    collections::{BTreeMap, HashMap},
+    fmt::{Display, Formatter},
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
 use bincode::{deserialize, serialize};
 use fmri::FMRI;
-use log::{error, info, warn};
+use log::{debug, error, info, log, warn, Level};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 
 use crate::{
-    packages::{depend_types::DependTypes, dependency_type::DependencyTypes},
+    packages::{
+        components::Components, depend_types::DependTypes, dependency_type::DependencyTypes,
+    },
     problems::Problem::{
-        MissingComponentForPackage, NonExistingPackageInPkg5, NonExistingRequired,
-        NonExistingRequiredByRenamed, ObsoletedPackageInComponent, ObsoletedRequired,
-        ObsoletedRequiredByRenamed, PackageInMultipleComponents, PartlyObsoletedRequired,
-        PartlyObsoletedRequiredByRenamed, RenamedNeedsRenamed, RenamedPackageInComponent,
-        UnRunnableMakeCommand, UselessComponent,
+        BrokenConditional, BrokenRenameChain, BuildDependencyCycle, CatalogAgeSkew,
+        CrossPublisherDependency, DependencyBloat, DivergentDependencies, DuplicateComponentName,
+        DuplicateDependency, DuplicatePkg5Entry, EncumberedPlacement, ExternalDependency,
+        FileConflict, ForbiddenCategoryDependency, ForeignPackageInPkg5, HeldBackInstalledPackage,
+        HistoryNameCollision, IncorporationCycle, IncorporationMismatch, InvalidClassification,
+        InvalidFmri, InvalidPkg5Key, MalformedPkg5, MediatorConflict, MirrorSkew,
+        MissingComponentForPackage, MissingComponentLicense, MissingLicenseAction,
+        NonExistingPackageInPkg5, NonExistingRequired, NonExistingRequiredByRenamed,
+        ObsoleteComponent, ObsoleteWithDependencies, ObsoletedPackageBuilt,
+        ObsoletedPackageInComponent, ObsoletedRequired, ObsoletedRequiredByRenamed,
+        OnlyStubDependents, OrphanPackage, PackageInMultipleComponents, PackageMissingInPkg5,
+        PackageNameViolation, PackageNotAllowedInCategory, PackageVersionsInMultipleComponents,
+        PartlyObsoletedRequired, PartlyObsoletedRequiredByRenamed, Pkg5NameMismatch,
+        PublisherVersionSkew, ReclassifyDependency, RenameLoop, RenamedNeedsRenamed,
+        RenamedPackageInComponent, RequireAnyAlternatives, RequiredFromOtherDepot,
+        RequiredVersionNotPublished, SelfDependency, StaleCatalog, StalePkg5Entry,
+        StaleRequiredPackage, TighterThanIncorporation, TimestampAnomaly, UnRunnableMakeCommand,
+        UnavailableIncorporatedVersion, UnavailableInstalledPackage, UnsatisfiableRequireAny,
+        UnversionedModule, UnversionedRequire, UselessComponent,
     },
+    state_file::{read_locked, write_atomic},
 };
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub enum Problem {
     MissingComponentForPackage(FMRI),
     RenamedNeedsRenamed(FMRI, FMRI),
     RenamedPackageInComponent(FMRI, String),
     ObsoletedPackageInComponent(FMRI, String),
-    UnRunnableMakeCommand(String, PathBuf),
+    UnRunnableMakeCommand(String, PathBuf, MakeFailure, String),
     NonExistingRequired(DependTypes, DependencyTypes, FMRI, String),
     NonExistingRequiredByRenamed(DependTypes, DependencyTypes, FMRI),
     ObsoletedRequired(DependTypes, DependencyTypes, FMRI, String),
@@ -36,21 +55,1749 @@ pub enum Problem {
     UselessComponent(String),
     PackageInMultipleComponents(FMRI, Vec<String>),
     NonExistingPackageInPkg5(FMRI, String),
+    TighterThanIncorporation(Box<DependTypes>, FMRI, FMRI, FMRI),
+    TimestampAnomaly(FMRI, Option<FMRI>),
+    ForbiddenCategoryDependency(String, String, FMRI, String),
+    PackageNotAllowedInCategory(String, String, FMRI),
+    InvalidFmri(String, String),
+    RequireAnyAlternatives(FMRI, Vec<(FMRI, AlternativeState)>),
+    MalformedPkg5(PathBuf, String),
+    PackageNameViolation(FMRI, String, String),
+    BuildDependencyCycle(Vec<String>),
+    ReclassifyDependency(FMRI, DependencyTypes, DependencyTypes, String),
+    SelfDependency(FMRI, DependencyTypes, String),
+    DuplicateDependency(DependTypes, DependencyTypes, Option<FMRI>, String),
+    BrokenRenameChain(Vec<FMRI>, AlternativeState),
+    RenameLoop(Vec<FMRI>),
+    ObsoleteWithDependencies(FMRI, Vec<DependTypes>),
+    UnversionedModule(FMRI, String, Option<String>),
+    UnsatisfiableRequireAny(DependTypes, DependencyTypes, FMRI, String),
+    BrokenConditional(
+        Option<(FMRI, AlternativeState)>,
+        Option<(FMRI, AlternativeState)>,
+        DependencyTypes,
+        FMRI,
+        String,
+    ),
+    IncorporationMismatch(FMRI, FMRI, FMRI, String),
+    DependencyBloat(String, String, usize, usize, Vec<FMRI>),
+    UnversionedRequire(FMRI, FMRI, String),
+    FileConflict(String, Vec<FMRI>, Vec<String>),
+    MirrorSkew(FMRI, String, Vec<(String, Option<String>)>),
+    MediatorConflict(String, Vec<FMRI>, Vec<String>, String),
+    StaleRequiredPackage(FMRI, String),
+    PackageMissingInPkg5(FMRI, String),
+    StalePkg5Entry(FMRI, String),
+    OnlyStubDependents(FMRI, Vec<FMRI>, String),
+    ObsoleteComponent(String, Vec<FMRI>),
+    OrphanPackage(FMRI, String),
+    CrossPublisherDependency(FMRI, DependencyTypes, FMRI, String, String),
+    RequiredVersionNotPublished(DependTypes, FMRI, FMRI, String),
+    UnavailableIncorporatedVersion(FMRI, FMRI, AlternativeState, String),
+    ForeignPackageInPkg5(FMRI, String, String, PathBuf, PathBuf),
+    DuplicateComponentName(String, Vec<PathBuf>),
+    PublisherVersionSkew(FMRI, AlternativeState, FMRI, AlternativeState, String),
+    DivergentDependencies(FMRI, FMRI, Vec<DependTypes>, Vec<DependTypes>, String),
+    IncorporationCycle(Vec<FMRI>, Vec<FMRI>),
+    DuplicatePkg5Entry(FMRI, PathBuf),
+    Pkg5NameMismatch(String, String, String),
+    ObsoletedPackageBuilt(FMRI, String, bool),
+    ExternalDependency(FMRI, DependencyTypes, FMRI, String, String),
+    InvalidClassification(FMRI, String, Option<String>, String),
+    MissingLicenseAction(FMRI, String),
+    MissingComponentLicense(String, PathBuf),
+    PackageVersionsInMultipleComponents(FMRI, Vec<(String, Option<String>)>),
+    UnavailableInstalledPackage(FMRI, AlternativeState),
+    HeldBackInstalledPackage(FMRI, FMRI, FMRI),
+    StaleCatalog(PathBuf, String, u64),
+    CatalogAgeSkew(PathBuf, PathBuf, u64),
+    InvalidPkg5Key(PathBuf, String, String),
+    RequiredFromOtherDepot(DependTypes, DependencyTypes, FMRI, String, String),
+    HistoryNameCollision(FMRI, FMRI, String),
+    EncumberedPlacement(String, bool, Vec<String>),
+}
+
+/// Kind of [`Problem`] without its payload, [`Display`] returns problem code
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum ProblemKind {
+    MissingComponentForPackage,
+    RenamedNeedsRenamed,
+    RenamedPackageInComponent,
+    ObsoletedPackageInComponent,
+    UnRunnableMakeCommand,
+    NonExistingRequired,
+    NonExistingRequiredByRenamed,
+    ObsoletedRequired,
+    ObsoletedRequiredByRenamed,
+    PartlyObsoletedRequired,
+    PartlyObsoletedRequiredByRenamed,
+    UselessComponent,
+    PackageInMultipleComponents,
+    NonExistingPackageInPkg5,
+    TighterThanIncorporation,
+    TimestampAnomaly,
+    ForbiddenCategoryDependency,
+    PackageNotAllowedInCategory,
+    InvalidFmri,
+    RequireAnyAlternatives,
+    MalformedPkg5,
+    PackageNameViolation,
+    BuildDependencyCycle,
+    ReclassifyDependency,
+    SelfDependency,
+    DuplicateDependency,
+    BrokenRenameChain,
+    RenameLoop,
+    ObsoleteWithDependencies,
+    UnversionedModule,
+    UnsatisfiableRequireAny,
+    BrokenConditional,
+    IncorporationMismatch,
+    DependencyBloat,
+    UnversionedRequire,
+    FileConflict,
+    MirrorSkew,
+    MediatorConflict,
+    StaleRequiredPackage,
+    PackageMissingInPkg5,
+    StalePkg5Entry,
+    OnlyStubDependents,
+    ObsoleteComponent,
+    OrphanPackage,
+    CrossPublisherDependency,
+    RequiredVersionNotPublished,
+    UnavailableIncorporatedVersion,
+    ForeignPackageInPkg5,
+    DuplicateComponentName,
+    PublisherVersionSkew,
+    DivergentDependencies,
+    IncorporationCycle,
+    DuplicatePkg5Entry,
+    Pkg5NameMismatch,
+    ObsoletedPackageBuilt,
+    ExternalDependency,
+    InvalidClassification,
+    MissingLicenseAction,
+    MissingComponentLicense,
+    PackageVersionsInMultipleComponents,
+    UnavailableInstalledPackage,
+    HeldBackInstalledPackage,
+    StaleCatalog,
+    CatalogAgeSkew,
+    InvalidPkg5Key,
+    RequiredFromOtherDepot,
+    HistoryNameCollision,
+    EncumberedPlacement,
+}
+
+impl Display for ProblemKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Severity of [`Problem`]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn level(&self) -> Level {
+        match self {
+            Severity::Info => Level::Info,
+            Severity::Warning => Level::Warn,
+            Severity::Error => Level::Error,
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Severity::Info => "info",
+                Severity::Warning => "warning",
+                Severity::Error => "error",
+            }
+        )
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "info" => Ok(Severity::Info),
+            "warning" => Ok(Severity::Warning),
+            "error" => Ok(Severity::Error),
+            _ => Err(format!("unknown severity: {}", s)),
+        }
+    }
+}
+
+/// State of alternative in require-any dependency
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum AlternativeState {
+    Viable,
+    Obsolete,
+    Renamed,
+    Missing,
+    /// required version is newer than any published version
+    Stale,
+}
+
+impl Display for AlternativeState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                AlternativeState::Viable => "viable",
+                AlternativeState::Obsolete => "obsolete",
+                AlternativeState::Renamed => "renamed",
+                AlternativeState::Missing => "missing",
+                AlternativeState::Stale => "stale version",
+            }
+        )
+    }
+}
+
+/// Cause of failed gmake command classified by its stderr
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum MakeFailure {
+    /// included makefile doesn't exist
+    MissingInclude,
+    SyntaxError,
+    /// command run by gmake doesn't exist
+    MissingTool,
+    /// gmake isn't run (analysis of snapshot) and Makefile can't be parsed natively
+    NeedsGmake,
+    /// exit status of gmake if the failure is not recognized
+    Other(Option<i32>),
+}
+
+impl MakeFailure {
+    pub fn classify(stderr: &str, exit_status: Option<i32>) -> Self {
+        let contains = |messages: &[&str]| messages.iter().any(|message| stderr.contains(message));
+
+        if contains(&[
+            "missing separator",
+            "unterminated variable reference",
+            "recipe commences before first target",
+            "invalid syntax in conditional",
+            "missing `endif'",
+            "extraneous `endif'",
+        ]) {
+            MakeFailure::SyntaxError
+        } else if stderr.lines().any(|line| {
+            line.contains(".mk: No such file or directory")
+                || (line.contains("No rule to make target") && line.contains(".mk"))
+        }) {
+            MakeFailure::MissingInclude
+        } else if contains(&["command not found", "Command not found", ": not found"]) {
+            MakeFailure::MissingTool
+        } else {
+            MakeFailure::Other(exit_status)
+        }
+    }
+}
+
+impl Display for MakeFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MakeFailure::MissingInclude => write!(f, "missing include"),
+            MakeFailure::SyntaxError => write!(f, "syntax error"),
+            MakeFailure::MissingTool => write!(f, "missing tool"),
+            MakeFailure::NeedsGmake => write!(f, "needs gmake"),
+            MakeFailure::Other(Some(code)) => write!(f, "exit status {}", code),
+            MakeFailure::Other(None) => write!(f, "killed by signal"),
+        }
+    }
+}
+
+impl ProblemKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            ProblemKind::UselessComponent => Severity::Info,
+            ProblemKind::PartlyObsoletedRequired
+            | ProblemKind::PartlyObsoletedRequiredByRenamed
+            | ProblemKind::MissingComponentForPackage
+            | ProblemKind::NonExistingRequired
+            | ProblemKind::NonExistingRequiredByRenamed => Severity::Warning,
+            ProblemKind::RenamedNeedsRenamed
+            | ProblemKind::RenamedPackageInComponent
+            | ProblemKind::ObsoletedPackageInComponent
+            | ProblemKind::ObsoletedRequired
+            | ProblemKind::ObsoletedRequiredByRenamed
+            | ProblemKind::UnRunnableMakeCommand
+            | ProblemKind::PackageInMultipleComponents
+            | ProblemKind::NonExistingPackageInPkg5 => Severity::Error,
+            ProblemKind::TighterThanIncorporation => Severity::Warning,
+            ProblemKind::TimestampAnomaly => Severity::Warning,
+            ProblemKind::ForbiddenCategoryDependency => Severity::Error,
+            ProblemKind::PackageNotAllowedInCategory => Severity::Error,
+            ProblemKind::InvalidFmri => Severity::Error,
+            ProblemKind::RequireAnyAlternatives => Severity::Warning,
+            ProblemKind::MalformedPkg5 => Severity::Error,
+            ProblemKind::PackageNameViolation => Severity::Warning,
+            ProblemKind::BuildDependencyCycle => Severity::Warning,
+            ProblemKind::ReclassifyDependency => Severity::Info,
+            ProblemKind::SelfDependency => Severity::Warning,
+            ProblemKind::DuplicateDependency => Severity::Info,
+            ProblemKind::BrokenRenameChain => Severity::Error,
+            ProblemKind::RenameLoop => Severity::Error,
+            ProblemKind::ObsoleteWithDependencies => Severity::Warning,
+            ProblemKind::UnversionedModule => Severity::Warning,
+            ProblemKind::UnsatisfiableRequireAny => Severity::Error,
+            ProblemKind::BrokenConditional => Severity::Error,
+            ProblemKind::IncorporationMismatch => Severity::Error,
+            ProblemKind::DependencyBloat => Severity::Info,
+            ProblemKind::UnversionedRequire => Severity::Warning,
+            ProblemKind::FileConflict => Severity::Error,
+            ProblemKind::MirrorSkew => Severity::Warning,
+            ProblemKind::MediatorConflict => Severity::Error,
+            ProblemKind::StaleRequiredPackage => Severity::Info,
+            ProblemKind::PackageMissingInPkg5 => Severity::Warning,
+            ProblemKind::StalePkg5Entry => Severity::Warning,
+            ProblemKind::OnlyStubDependents => Severity::Info,
+            ProblemKind::ObsoleteComponent => Severity::Warning,
+            ProblemKind::OrphanPackage => Severity::Info,
+            ProblemKind::CrossPublisherDependency => Severity::Error,
+            ProblemKind::RequiredVersionNotPublished => Severity::Error,
+            ProblemKind::UnavailableIncorporatedVersion => Severity::Error,
+            ProblemKind::ForeignPackageInPkg5 => Severity::Warning,
+            ProblemKind::DuplicateComponentName => Severity::Error,
+            ProblemKind::PublisherVersionSkew => Severity::Warning,
+            ProblemKind::DivergentDependencies => Severity::Warning,
+            ProblemKind::IncorporationCycle => Severity::Error,
+            ProblemKind::DuplicatePkg5Entry => Severity::Warning,
+            ProblemKind::Pkg5NameMismatch => Severity::Warning,
+            ProblemKind::ObsoletedPackageBuilt => Severity::Warning,
+            ProblemKind::ExternalDependency => Severity::Info,
+            ProblemKind::InvalidClassification => Severity::Warning,
+            ProblemKind::MissingLicenseAction => Severity::Warning,
+            ProblemKind::MissingComponentLicense => Severity::Warning,
+            ProblemKind::PackageVersionsInMultipleComponents => Severity::Warning,
+            ProblemKind::UnavailableInstalledPackage => Severity::Warning,
+            ProblemKind::HeldBackInstalledPackage => Severity::Info,
+            ProblemKind::StaleCatalog => Severity::Warning,
+            ProblemKind::CatalogAgeSkew => Severity::Warning,
+            ProblemKind::InvalidPkg5Key => Severity::Warning,
+            ProblemKind::RequiredFromOtherDepot => Severity::Info,
+            ProblemKind::HistoryNameCollision => Severity::Warning,
+            ProblemKind::EncumberedPlacement => Severity::Info,
+        }
+    }
+
+    /// Returns id of [check][crate::checks::CHECKS] that produces problems of this kind
+    pub fn check_id(&self) -> &'static str {
+        match self {
+            ProblemKind::InvalidFmri | ProblemKind::MalformedPkg5 => "input-validity",
+            ProblemKind::MissingComponentForPackage
+            | ProblemKind::RenamedPackageInComponent
+            | ProblemKind::ObsoletedPackageInComponent => "component-packages",
+            ProblemKind::UnRunnableMakeCommand => "make-commands",
+            ProblemKind::TimestampAnomaly => "timestamps",
+            ProblemKind::NonExistingRequired
+            | ProblemKind::NonExistingRequiredByRenamed
+            | ProblemKind::ObsoletedRequired
+            | ProblemKind::ObsoletedRequiredByRenamed
+            | ProblemKind::PartlyObsoletedRequired
+            | ProblemKind::PartlyObsoletedRequiredByRenamed => "dependency-validity",
+            ProblemKind::UselessComponent => "useless-components",
+            ProblemKind::RenamedNeedsRenamed => "renamed-needs-renamed",
+            ProblemKind::TighterThanIncorporation => "version-constraints",
+            ProblemKind::RequireAnyAlternatives => "require-any-alternatives",
+            ProblemKind::ForbiddenCategoryDependency | ProblemKind::PackageNotAllowedInCategory => {
+                "category-policies"
+            }
+            ProblemKind::PackageNameViolation => "package-names",
+            ProblemKind::BuildDependencyCycle => "build-cycles",
+            ProblemKind::ReclassifyDependency => "dependency-kinds",
+            ProblemKind::PackageInMultipleComponents => "same-packages-in-components",
+            ProblemKind::NonExistingPackageInPkg5 => "non-existing-packages-in-pkg5",
+            ProblemKind::SelfDependency => "self-dependencies",
+            ProblemKind::DuplicateDependency => "duplicate-dependencies",
+            ProblemKind::BrokenRenameChain => "rename-chains",
+            ProblemKind::RenameLoop => "rename-chains",
+            ProblemKind::ObsoleteWithDependencies => "obsolete-dependencies",
+            ProblemKind::UnversionedModule => "versioned-modules",
+            ProblemKind::UnsatisfiableRequireAny => "dependency-validity",
+            ProblemKind::BrokenConditional => "dependency-validity",
+            ProblemKind::IncorporationMismatch => "incorporation-constraints",
+            ProblemKind::DependencyBloat => "dependency-bloat",
+            ProblemKind::UnversionedRequire => "unversioned-requires",
+            ProblemKind::FileConflict => "file-conflicts",
+            ProblemKind::MirrorSkew => "mirror-skew",
+            ProblemKind::MediatorConflict => "mediator-conflicts",
+            ProblemKind::StaleRequiredPackage => "stale-required-packages",
+            ProblemKind::PackageMissingInPkg5 => "pkg5-consistency",
+            ProblemKind::StalePkg5Entry => "pkg5-consistency",
+            ProblemKind::OnlyStubDependents => "only-stub-dependents",
+            ProblemKind::ObsoleteComponent => "obsolete-components",
+            ProblemKind::OrphanPackage => "orphan-packages",
+            ProblemKind::CrossPublisherDependency => "cross-publisher-dependencies",
+            ProblemKind::RequiredVersionNotPublished => "required-versions",
+            ProblemKind::UnavailableIncorporatedVersion => "incorporated-versions",
+            ProblemKind::ForeignPackageInPkg5 => "foreign-packages-in-pkg5",
+            ProblemKind::DuplicateComponentName => "duplicate-component-names",
+            ProblemKind::PublisherVersionSkew => "publisher-version-skew",
+            ProblemKind::DivergentDependencies => "divergent-dependencies",
+            ProblemKind::IncorporationCycle => "incorporation-cycles",
+            ProblemKind::DuplicatePkg5Entry => "input-validity",
+            ProblemKind::Pkg5NameMismatch => "pkg5-names",
+            ProblemKind::ObsoletedPackageBuilt => "obsolete-components",
+            ProblemKind::ExternalDependency => "external-dependencies",
+            ProblemKind::InvalidClassification => "classifications",
+            ProblemKind::MissingLicenseAction => "licenses",
+            ProblemKind::MissingComponentLicense => "licenses",
+            ProblemKind::PackageVersionsInMultipleComponents => "same-packages-in-components",
+            ProblemKind::UnavailableInstalledPackage => "installed-image",
+            ProblemKind::HeldBackInstalledPackage => "installed-image",
+            ProblemKind::StaleCatalog => "catalog-staleness",
+            ProblemKind::CatalogAgeSkew => "catalog-staleness",
+            ProblemKind::InvalidPkg5Key => "input-validity",
+            ProblemKind::RequiredFromOtherDepot => "dependency-validity",
+            ProblemKind::HistoryNameCollision => "history-collisions",
+            ProblemKind::EncumberedPlacement => "encumbered-placement",
+        }
+    }
+}
+
+impl Problem {
+    pub fn severity(&self) -> Severity {
+        self.kind().severity()
+    }
+
+    pub fn check_id(&self) -> &'static str {
+        self.kind().check_id()
+    }
+
+    pub fn kind(&self) -> ProblemKind {
+        match self {
+            MissingComponentForPackage(_) => ProblemKind::MissingComponentForPackage,
+            RenamedNeedsRenamed(_, _) => ProblemKind::RenamedNeedsRenamed,
+            RenamedPackageInComponent(_, _) => ProblemKind::RenamedPackageInComponent,
+            ObsoletedPackageInComponent(_, _) => ProblemKind::ObsoletedPackageInComponent,
+            UnRunnableMakeCommand(_, _, _, _) => ProblemKind::UnRunnableMakeCommand,
+            NonExistingRequired(_, _, _, _) => ProblemKind::NonExistingRequired,
+            NonExistingRequiredByRenamed(_, _, _) => ProblemKind::NonExistingRequiredByRenamed,
+            ObsoletedRequired(_, _, _, _) => ProblemKind::ObsoletedRequired,
+            ObsoletedRequiredByRenamed(_, _, _) => ProblemKind::ObsoletedRequiredByRenamed,
+            PartlyObsoletedRequired(_, _, _, _) => ProblemKind::PartlyObsoletedRequired,
+            PartlyObsoletedRequiredByRenamed(_, _, _) => {
+                ProblemKind::PartlyObsoletedRequiredByRenamed
+            }
+            UselessComponent(_) => ProblemKind::UselessComponent,
+            PackageInMultipleComponents(_, _) => ProblemKind::PackageInMultipleComponents,
+            NonExistingPackageInPkg5(_, _) => ProblemKind::NonExistingPackageInPkg5,
+            TighterThanIncorporation(_, _, _, _) => ProblemKind::TighterThanIncorporation,
+            TimestampAnomaly(_, _) => ProblemKind::TimestampAnomaly,
+            ForbiddenCategoryDependency(_, _, _, _) => ProblemKind::ForbiddenCategoryDependency,
+            PackageNotAllowedInCategory(_, _, _) => ProblemKind::PackageNotAllowedInCategory,
+            InvalidFmri(_, _) => ProblemKind::InvalidFmri,
+            RequireAnyAlternatives(_, _) => ProblemKind::RequireAnyAlternatives,
+            MalformedPkg5(_, _) => ProblemKind::MalformedPkg5,
+            PackageNameViolation(_, _, _) => ProblemKind::PackageNameViolation,
+            BuildDependencyCycle(_) => ProblemKind::BuildDependencyCycle,
+            ReclassifyDependency(_, _, _, _) => ProblemKind::ReclassifyDependency,
+            SelfDependency(_, _, _) => ProblemKind::SelfDependency,
+            DuplicateDependency(_, _, _, _) => ProblemKind::DuplicateDependency,
+            BrokenRenameChain(_, _) => ProblemKind::BrokenRenameChain,
+            RenameLoop(_) => ProblemKind::RenameLoop,
+            ObsoleteWithDependencies(_, _) => ProblemKind::ObsoleteWithDependencies,
+            UnversionedModule(_, _, _) => ProblemKind::UnversionedModule,
+            UnsatisfiableRequireAny(_, _, _, _) => ProblemKind::UnsatisfiableRequireAny,
+            BrokenConditional(_, _, _, _, _) => ProblemKind::BrokenConditional,
+            IncorporationMismatch(_, _, _, _) => ProblemKind::IncorporationMismatch,
+            DependencyBloat(_, _, _, _, _) => ProblemKind::DependencyBloat,
+            UnversionedRequire(_, _, _) => ProblemKind::UnversionedRequire,
+            FileConflict(_, _, _) => ProblemKind::FileConflict,
+            MirrorSkew(_, _, _) => ProblemKind::MirrorSkew,
+            MediatorConflict(_, _, _, _) => ProblemKind::MediatorConflict,
+            StaleRequiredPackage(_, _) => ProblemKind::StaleRequiredPackage,
+            PackageMissingInPkg5(_, _) => ProblemKind::PackageMissingInPkg5,
+            StalePkg5Entry(_, _) => ProblemKind::StalePkg5Entry,
+            OnlyStubDependents(_, _, _) => ProblemKind::OnlyStubDependents,
+            ObsoleteComponent(_, _) => ProblemKind::ObsoleteComponent,
+            OrphanPackage(_, _) => ProblemKind::OrphanPackage,
+            CrossPublisherDependency(_, _, _, _, _) => ProblemKind::CrossPublisherDependency,
+            RequiredVersionNotPublished(_, _, _, _) => ProblemKind::RequiredVersionNotPublished,
+            UnavailableIncorporatedVersion(_, _, _, _) => {
+                ProblemKind::UnavailableIncorporatedVersion
+            }
+            ForeignPackageInPkg5(_, _, _, _, _) => ProblemKind::ForeignPackageInPkg5,
+            DuplicateComponentName(_, _) => ProblemKind::DuplicateComponentName,
+            PublisherVersionSkew(_, _, _, _, _) => ProblemKind::PublisherVersionSkew,
+            DivergentDependencies(_, _, _, _, _) => ProblemKind::DivergentDependencies,
+            IncorporationCycle(_, _) => ProblemKind::IncorporationCycle,
+            DuplicatePkg5Entry(_, _) => ProblemKind::DuplicatePkg5Entry,
+            Pkg5NameMismatch(_, _, _) => ProblemKind::Pkg5NameMismatch,
+            ObsoletedPackageBuilt(_, _, _) => ProblemKind::ObsoletedPackageBuilt,
+            ExternalDependency(_, _, _, _, _) => ProblemKind::ExternalDependency,
+            InvalidClassification(_, _, _, _) => ProblemKind::InvalidClassification,
+            MissingLicenseAction(_, _) => ProblemKind::MissingLicenseAction,
+            MissingComponentLicense(_, _) => ProblemKind::MissingComponentLicense,
+            PackageVersionsInMultipleComponents(_, _) => {
+                ProblemKind::PackageVersionsInMultipleComponents
+            }
+            UnavailableInstalledPackage(_, _) => ProblemKind::UnavailableInstalledPackage,
+            HeldBackInstalledPackage(_, _, _) => ProblemKind::HeldBackInstalledPackage,
+            StaleCatalog(_, _, _) => ProblemKind::StaleCatalog,
+            CatalogAgeSkew(_, _, _) => ProblemKind::CatalogAgeSkew,
+            InvalidPkg5Key(_, _, _) => ProblemKind::InvalidPkg5Key,
+            RequiredFromOtherDepot(_, _, _, _, _) => ProblemKind::RequiredFromOtherDepot,
+            HistoryNameCollision(_, _, _) => ProblemKind::HistoryNameCollision,
+            EncumberedPlacement(_, _, _) => ProblemKind::EncumberedPlacement,
+        }
+    }
+
+    /// Returns all [fmris][FMRI] in problem (problematic packages and packages that need them)
+    pub fn get_fmris_ref(&self) -> Vec<&FMRI> {
+        match self {
+            MissingComponentForPackage(fmri)
+            | RenamedPackageInComponent(fmri, _)
+            | ObsoletedPackageInComponent(fmri, _)
+            | PackageInMultipleComponents(fmri, _)
+            | NonExistingPackageInPkg5(fmri, _) => vec![fmri],
+            RenamedNeedsRenamed(fmri_a, fmri_b) => vec![fmri_a, fmri_b],
+            NonExistingRequired(depend_type, _, required_by, _)
+            | NonExistingRequiredByRenamed(depend_type, _, required_by)
+            | ObsoletedRequired(depend_type, _, required_by, _)
+            | ObsoletedRequiredByRenamed(depend_type, _, required_by)
+            | PartlyObsoletedRequired(depend_type, _, required_by, _)
+            | PartlyObsoletedRequiredByRenamed(depend_type, _, required_by) => {
+                let mut fmris = depend_type.get_fmris_ref();
+                fmris.push(required_by);
+                fmris
+            }
+            UnRunnableMakeCommand(_, _, _, _) | UselessComponent(_) => vec![],
+            TighterThanIncorporation(depend_type, required_by, incorporation, _) => {
+                let mut fmris = depend_type.get_fmris_ref();
+                fmris.push(required_by);
+                fmris.push(incorporation);
+                fmris
+            }
+            TimestampAnomaly(fmri, older) => {
+                let mut fmris = vec![fmri];
+                fmris.extend(older);
+                fmris
+            }
+            ForbiddenCategoryDependency(_, _, fmri, _) => vec![fmri],
+            PackageNotAllowedInCategory(_, _, fmri) => vec![fmri],
+            InvalidFmri(_, _) => vec![],
+            RequireAnyAlternatives(required_by, alternatives) => {
+                let mut fmris = vec![required_by];
+                fmris.extend(alternatives.iter().map(|(fmri, _)| fmri));
+                fmris
+            }
+            MalformedPkg5(_, _) => vec![],
+            PackageNameViolation(fmri, _, _) => vec![fmri],
+            BuildDependencyCycle(_) => vec![],
+            ReclassifyDependency(fmri, _, _, _) => vec![fmri],
+            SelfDependency(fmri, _, _) => vec![fmri],
+            DuplicateDependency(depend_type, _, required_by, _) => {
+                let mut fmris = depend_type.get_fmris_ref();
+                fmris.extend(required_by);
+                fmris
+            }
+            BrokenRenameChain(chain, _) => chain.iter().collect(),
+            RenameLoop(packages) => packages.iter().collect(),
+            ObsoleteWithDependencies(fmri, depend_types) => {
+                let mut fmris = vec![fmri];
+                for depend_type in depend_types {
+                    fmris.extend(depend_type.get_fmris_ref());
+                }
+                fmris
+            }
+            UnversionedModule(fmri, _, _) => vec![fmri],
+            UnsatisfiableRequireAny(depend_type, _, required_by, _) => {
+                let mut fmris = depend_type.get_fmris_ref();
+                fmris.push(required_by);
+                fmris
+            }
+            BrokenConditional(fmri, predicate, _, required_by, _) => {
+                let mut fmris: Vec<&FMRI> =
+                    fmri.iter().chain(predicate).map(|(fmri, _)| fmri).collect();
+                fmris.push(required_by);
+                fmris
+            }
+            IncorporationMismatch(incorporation, incorporated, published, _) => {
+                vec![incorporation, incorporated, published]
+            }
+            DependencyBloat(_, _, _, _, dependencies) => dependencies.iter().collect(),
+            UnversionedRequire(required, required_by, _) => vec![required, required_by],
+            FileConflict(_, packages, _) => packages.iter().collect(),
+            MirrorSkew(fmri, _, _) => vec![fmri],
+            MediatorConflict(_, packages, _, _) => packages.iter().collect(),
+            StaleRequiredPackage(fmri, _) => vec![fmri],
+            PackageMissingInPkg5(fmri, _) => vec![fmri],
+            StalePkg5Entry(fmri, _) => vec![fmri],
+            OnlyStubDependents(fmri, dependents, _) => {
+                let mut fmris = vec![fmri];
+                fmris.extend(dependents);
+                fmris
+            }
+            ObsoleteComponent(_, packages) => packages.iter().collect(),
+            OrphanPackage(fmri, _) => vec![fmri],
+            CrossPublisherDependency(required, _, required_by, _, _) => vec![required, required_by],
+            RequiredVersionNotPublished(depend_type, required_by, published, _) => {
+                let mut fmris = depend_type.get_fmris_ref();
+                fmris.push(required_by);
+                fmris.push(published);
+                fmris
+            }
+            UnavailableIncorporatedVersion(incorporation, incorporated, _, _) => {
+                vec![incorporation, incorporated]
+            }
+            ForeignPackageInPkg5(fmri, _, _, _, _) => vec![fmri],
+            DuplicateComponentName(_, _) => vec![],
+            PublisherVersionSkew(newer, _, older, _, _) => vec![newer, older],
+            DivergentDependencies(fmri, other, only_in_fmri, only_in_other, _) => {
+                let mut fmris = vec![fmri, other];
+                for depend_type in only_in_fmri.iter().chain(only_in_other) {
+                    fmris.extend(depend_type.get_fmris_ref());
+                }
+                fmris
+            }
+            IncorporationCycle(incorporations, conflicts) => {
+                incorporations.iter().chain(conflicts).collect()
+            }
+            DuplicatePkg5Entry(fmri, _) => vec![fmri],
+            Pkg5NameMismatch(_, _, _) => vec![],
+            ObsoletedPackageBuilt(fmri, _, _) => vec![fmri],
+            ExternalDependency(required, _, required_by, _, _) => vec![required, required_by],
+            InvalidClassification(fmri, _, _, _) => vec![fmri],
+            MissingLicenseAction(fmri, _) => vec![fmri],
+            MissingComponentLicense(_, _) => vec![],
+            PackageVersionsInMultipleComponents(fmri, _) => vec![fmri],
+            UnavailableInstalledPackage(fmri, _) => vec![fmri],
+            HeldBackInstalledPackage(fmri, newest, incorporation) => {
+                vec![fmri, newest, incorporation]
+            }
+            StaleCatalog(_, _, _) => vec![],
+            CatalogAgeSkew(_, _, _) => vec![],
+            InvalidPkg5Key(_, _, _) => vec![],
+            RequiredFromOtherDepot(depend_type, _, required_by, _, _) => {
+                let mut fmris = depend_type.get_fmris_ref();
+                fmris.push(required_by);
+                fmris
+            }
+            HistoryNameCollision(fmri, record, _) => vec![fmri, record],
+            EncumberedPlacement(_, _, _) => vec![],
+        }
+    }
+
+    /// Returns names of components in problem
+    pub fn get_component_names_ref(&self) -> Vec<&String> {
+        match self {
+            RenamedPackageInComponent(_, component_name)
+            | ObsoletedPackageInComponent(_, component_name)
+            | NonExistingPackageInPkg5(_, component_name)
+            | UselessComponent(component_name) => vec![component_name],
+            NonExistingRequired(_, _, _, component_name)
+            | ObsoletedRequired(_, _, _, component_name)
+            | PartlyObsoletedRequired(_, _, _, component_name) => vec![component_name],
+            PackageInMultipleComponents(_, components) => components.iter().collect(),
+            MissingComponentForPackage(_)
+            | RenamedNeedsRenamed(_, _)
+            | UnRunnableMakeCommand(_, _, _, _)
+            | NonExistingRequiredByRenamed(_, _, _)
+            | ObsoletedRequiredByRenamed(_, _, _)
+            | PartlyObsoletedRequiredByRenamed(_, _, _) => vec![],
+            TighterThanIncorporation(_, _, _, _) => vec![],
+            TimestampAnomaly(_, _) => vec![],
+            ForbiddenCategoryDependency(_, component_name, _, dependency_component) => {
+                vec![component_name, dependency_component]
+            }
+            PackageNotAllowedInCategory(_, component_name, _) => vec![component_name],
+            InvalidFmri(_, _) => vec![],
+            RequireAnyAlternatives(_, _) => vec![],
+            MalformedPkg5(_, _) => vec![],
+            PackageNameViolation(_, component_name, _) => vec![component_name],
+            BuildDependencyCycle(components) => components.iter().collect(),
+            ReclassifyDependency(_, _, _, component_name) => vec![component_name],
+            SelfDependency(_, _, component_name) => vec![component_name],
+            DuplicateDependency(_, _, _, component_name) if component_name.is_empty() => vec![],
+            DuplicateDependency(_, _, _, component_name) => vec![component_name],
+            BrokenRenameChain(_, _) => vec![],
+            RenameLoop(_) => vec![],
+            ObsoleteWithDependencies(_, _) => vec![],
+            UnversionedModule(_, component_name, _) => vec![component_name],
+            UnsatisfiableRequireAny(_, _, _, component_name) if component_name.is_empty() => vec![],
+            UnsatisfiableRequireAny(_, _, _, component_name) => vec![component_name],
+            BrokenConditional(_, _, _, _, component_name) if component_name.is_empty() => vec![],
+            BrokenConditional(_, _, _, _, component_name) => vec![component_name],
+            IncorporationMismatch(_, _, _, component_name) => vec![component_name],
+            DependencyBloat(component_name, _, _, _, _) => vec![component_name],
+            UnversionedRequire(_, _, component_name) => vec![component_name],
+            FileConflict(_, _, component_names) => component_names.iter().collect(),
+            MirrorSkew(_, _, _) => vec![],
+            MediatorConflict(_, _, component_names, _) => component_names.iter().collect(),
+            StaleRequiredPackage(_, component_name) => vec![component_name],
+            PackageMissingInPkg5(_, component_name) => vec![component_name],
+            StalePkg5Entry(_, component_name) => vec![component_name],
+            OnlyStubDependents(_, _, component_name) => vec![component_name],
+            ObsoleteComponent(component_name, _) => vec![component_name],
+            OrphanPackage(_, component_name) => vec![component_name],
+            CrossPublisherDependency(_, _, _, _, component_name) => vec![component_name],
+            RequiredVersionNotPublished(_, _, _, component_name) => vec![component_name],
+            UnavailableIncorporatedVersion(_, _, _, component_name) => vec![component_name],
+            ForeignPackageInPkg5(_, component_name, builder_name, _, _) => {
+                vec![component_name, builder_name]
+            }
+            DuplicateComponentName(component_name, _) => vec![component_name],
+            PublisherVersionSkew(_, _, _, _, component_name) => vec![component_name],
+            DivergentDependencies(_, _, _, _, component_name) => vec![component_name],
+            IncorporationCycle(_, _) => vec![],
+            DuplicatePkg5Entry(_, _) => vec![],
+            Pkg5NameMismatch(component_name, _, _) => vec![component_name],
+            ObsoletedPackageBuilt(_, component_name, _) => vec![component_name],
+            ExternalDependency(_, _, _, _, component_name) => vec![component_name],
+            InvalidClassification(_, component_name, _, _) => vec![component_name],
+            MissingLicenseAction(_, component_name) => vec![component_name],
+            MissingComponentLicense(component_name, _) => vec![component_name],
+            PackageVersionsInMultipleComponents(_, components) => components
+                .iter()
+                .map(|(component_name, _)| component_name)
+                .collect(),
+            UnavailableInstalledPackage(_, _) => vec![],
+            HeldBackInstalledPackage(_, _, _) => vec![],
+            StaleCatalog(_, _, _) => vec![],
+            CatalogAgeSkew(_, _, _) => vec![],
+            InvalidPkg5Key(_, _, _) => vec![],
+            RequiredFromOtherDepot(_, _, _, component_name, _) => vec![component_name],
+            HistoryNameCollision(_, _, component_name) => vec![component_name],
+            EncumberedPlacement(component_name, _, _) => vec![component_name],
+        }
+    }
+
+    /// Returns type of dependency in problem
+    pub fn get_dependency_type_ref(&self) -> Option<&DependencyTypes> {
+        match self {
+            NonExistingRequired(_, dependency_type, _, _)
+            | NonExistingRequiredByRenamed(_, dependency_type, _)
+            | ObsoletedRequired(_, dependency_type, _, _)
+            | ObsoletedRequiredByRenamed(_, dependency_type, _)
+            | PartlyObsoletedRequired(_, dependency_type, _, _)
+            | PartlyObsoletedRequiredByRenamed(_, dependency_type, _) => Some(dependency_type),
+            MissingComponentForPackage(_)
+            | RenamedNeedsRenamed(_, _)
+            | RenamedPackageInComponent(_, _)
+            | ObsoletedPackageInComponent(_, _)
+            | UnRunnableMakeCommand(_, _, _, _)
+            | UselessComponent(_)
+            | PackageInMultipleComponents(_, _)
+            | NonExistingPackageInPkg5(_, _) => None,
+            TighterThanIncorporation(_, _, _, _) => Some(&DependencyTypes::Runtime),
+            TimestampAnomaly(_, _) => None,
+            ForbiddenCategoryDependency(_, _, _, _) => None,
+            PackageNotAllowedInCategory(_, _, _) => None,
+            InvalidFmri(_, _) => None,
+            RequireAnyAlternatives(_, _) => Some(&DependencyTypes::Runtime),
+            MalformedPkg5(_, _) => None,
+            PackageNameViolation(_, _, _) => None,
+            BuildDependencyCycle(_) => Some(&DependencyTypes::Build),
+            ReclassifyDependency(_, dependency_type, _, _) => Some(dependency_type),
+            SelfDependency(_, dependency_type, _) => Some(dependency_type),
+            DuplicateDependency(_, dependency_type, _, _) => Some(dependency_type),
+            BrokenRenameChain(_, _) => None,
+            RenameLoop(_) => None,
+            ObsoleteWithDependencies(_, _) => Some(&DependencyTypes::Runtime),
+            UnversionedModule(_, _, _) => None,
+            UnsatisfiableRequireAny(_, dependency_type, _, _) => Some(dependency_type),
+            BrokenConditional(_, _, dependency_type, _, _) => Some(dependency_type),
+            IncorporationMismatch(_, _, _, _) => Some(&DependencyTypes::Runtime),
+            DependencyBloat(_, _, _, _, _) => Some(&DependencyTypes::Build),
+            UnversionedRequire(_, _, _) => Some(&DependencyTypes::Runtime),
+            FileConflict(_, _, _) => None,
+            MirrorSkew(_, _, _) => None,
+            MediatorConflict(_, _, _, _) => None,
+            StaleRequiredPackage(_, _) => Some(&DependencyTypes::Build),
+            PackageMissingInPkg5(_, _) => None,
+            StalePkg5Entry(_, _) => None,
+            OnlyStubDependents(_, _, _) => None,
+            ObsoleteComponent(_, _) => None,
+            OrphanPackage(_, _) => Some(&DependencyTypes::Runtime),
+            CrossPublisherDependency(_, dependency_type, _, _, _) => Some(dependency_type),
+            RequiredVersionNotPublished(_, _, _, _) => Some(&DependencyTypes::Runtime),
+            UnavailableIncorporatedVersion(_, _, _, _) => Some(&DependencyTypes::Runtime),
+            ForeignPackageInPkg5(_, _, _, _, _) => None,
+            DuplicateComponentName(_, _) => None,
+            PublisherVersionSkew(_, _, _, _, _) => None,
+            DivergentDependencies(_, _, _, _, _) => Some(&DependencyTypes::Runtime),
+            IncorporationCycle(_, _) => Some(&DependencyTypes::Runtime),
+            DuplicatePkg5Entry(_, _) => None,
+            Pkg5NameMismatch(_, _, _) => None,
+            ObsoletedPackageBuilt(_, _, _) => None,
+            ExternalDependency(_, dependency_type, _, _, _) => Some(dependency_type),
+            InvalidClassification(_, _, _, _) => None,
+            MissingLicenseAction(_, _) => None,
+            MissingComponentLicense(_, _) => None,
+            PackageVersionsInMultipleComponents(_, _) => None,
+            UnavailableInstalledPackage(_, _) => None,
+            HeldBackInstalledPackage(_, _, _) => None,
+            StaleCatalog(_, _, _) => None,
+            CatalogAgeSkew(_, _, _) => None,
+            InvalidPkg5Key(_, _, _) => None,
+            RequiredFromOtherDepot(_, dependency_type, _, _, _) => Some(dependency_type),
+            HistoryNameCollision(_, _, _) => None,
+            EncumberedPlacement(_, _, _) => None,
+        }
+    }
+
+    /// Returns hint how to fix the problem
+    pub fn suggestion(&self) -> String {
+        let dependency = |depend_type: &DependTypes| depend_type.get_name_and_content_as_string().1;
+
+        match self {
+            MissingComponentForPackage(fmri) => format!(
+                "add {} into pkg5 of component that publishes it or obsolete the package",
+                fmri.get_package_name_as_ref_string()
+            ),
+            RenamedNeedsRenamed(fmri_a, fmri_b) => format!(
+                "re-point dependency of {} at the rename target of {}",
+                fmri_a.get_package_name_as_ref_string(),
+                fmri_b.get_package_name_as_ref_string()
+            ),
+            RenamedPackageInComponent(fmri, component_name) => format!(
+                "remove renamed package {} from pkg5 of component {}",
+                fmri.get_package_name_as_ref_string(),
+                component_name
+            ),
+            ObsoletedPackageInComponent(fmri, component_name) => format!(
+                "remove obsoleted package {} from pkg5 of component {}",
+                fmri.get_package_name_as_ref_string(),
+                component_name
+            ),
+            UnRunnableMakeCommand(command, path, failure, _) => match failure {
+                MakeFailure::MissingTool => format!(
+                    "install tool needed by '{}' in {:?} or fix PATH",
+                    command, path
+                ),
+                MakeFailure::NeedsGmake => format!(
+                    "analyze {:?} in oi-userland checkout, not in snapshot, so that '{}' can run",
+                    path, command
+                ),
+                _ => format!("fix Makefile in {:?} so that '{}' can run", path, command),
+            },
+            NonExistingRequired(depend_type, _, _, _)
+            | NonExistingRequiredByRenamed(depend_type, _, _) => format!(
+                "remove dependency on {} or publish the package",
+                dependency(depend_type)
+            ),
+            ObsoletedRequired(depend_type, _, _, _)
+            | ObsoletedRequiredByRenamed(depend_type, _, _) => format!(
+                "remove dependency on obsoleted {} or replace it with its successor",
+                dependency(depend_type)
+            ),
+            PartlyObsoletedRequired(depend_type, _, _, _)
+            | PartlyObsoletedRequiredByRenamed(depend_type, _, _) => format!(
+                "replace dependency on {} (newest version is obsoleted) with its successor",
+                dependency(depend_type)
+            ),
+            UselessComponent(component_name) => {
+                format!("remove component {} if nothing needs it", component_name)
+            }
+            PackageInMultipleComponents(fmri, components) => format!(
+                "keep {} in pkg5 of only one of components: {}",
+                fmri.get_package_name_as_ref_string(),
+                components.join(",")
+            ),
+            NonExistingPackageInPkg5(fmri, component_name) => format!(
+                "publish {} or regenerate pkg5 of component {}",
+                fmri.get_package_name_as_ref_string(),
+                component_name
+            ),
+            TighterThanIncorporation(depend_type, _, incorporation, incorporated) => format!(
+                "loosen version of {} to {} or leave version to incorporation {}",
+                depend_type.get_fmris_ref()[0],
+                incorporated,
+                incorporation.get_package_name_as_ref_string()
+            ),
+            TimestampAnomaly(fmri, _) => format!(
+                "check clock of build machine and republish {}",
+                fmri.get_package_name_as_ref_string()
+            ),
+            ForbiddenCategoryDependency(category, _, fmri, _) => format!(
+                "remove dependency on {} or move component out of category {}",
+                fmri.get_package_name_as_ref_string(),
+                category
+            ),
+            PackageNotAllowedInCategory(category, _, fmri) => format!(
+                "move {} out of category {}",
+                fmri.get_package_name_as_ref_string(),
+                category
+            ),
+            InvalidFmri(raw_fmri, location) => format!("fix fmri '{}' in {}", raw_fmri, location),
+            RequireAnyAlternatives(_, alternatives) => {
+                let viable: Vec<&FMRI> = alternatives
+                    .iter()
+                    .filter(|(_, state)| state == &AlternativeState::Viable)
+                    .map(|(fmri, _)| fmri)
+                    .collect();
+
+                match viable.as_slice() {
+                    [fmri] => format!("replace require-any with require of {}", fmri),
+                    _ => format!(
+                        "remove or fix alternatives {}",
+                        alternatives
+                            .iter()
+                            .filter(|(_, state)| state != &AlternativeState::Viable)
+                            .map(|(fmri, _)| fmri.get_package_name_as_ref_string().clone())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                }
+            }
+            MalformedPkg5(path, _) => format!("fix or regenerate {:?}", path),
+            PackageNameViolation(fmri, _, _) => format!(
+                "rename {} to follow naming conventions",
+                fmri.get_package_name_as_ref_string()
+            ),
+            BuildDependencyCycle(_) => {
+                "break the cycle (e.g. build one of components without some dependency first)"
+                    .to_owned()
+            }
+            ReclassifyDependency(fmri, from, to, component_name) => format!(
+                "change {} dependency {} of component {} to {} dependency",
+                from,
+                fmri.get_package_name_as_ref_string(),
+                component_name,
+                to
+            ),
+            SelfDependency(fmri, dependency_type, component_name) => match dependency_type {
+                DependencyTypes::Runtime => format!(
+                    "remove dependency on {} from its manifest",
+                    fmri.get_package_name_as_ref_string()
+                ),
+                _ => format!(
+                    "remove {} from {} dependencies in Makefile of component {}",
+                    fmri.get_package_name_as_ref_string(),
+                    dependency_type,
+                    component_name
+                ),
+            },
+            DuplicateDependency(depend_type, dependency_type, required_by, component_name) => {
+                let (_, content) = depend_type.get_name_and_content_as_string();
+                match required_by {
+                    Some(required_by) => format!(
+                        "remove duplicate dependency on {} from manifest of {}",
+                        content,
+                        required_by.get_package_name_as_ref_string()
+                    ),
+                    None => format!(
+                        "remove duplicate {} from {} dependencies in Makefile of component {}",
+                        content, dependency_type, component_name
+                    ),
+                }
+            }
+            BrokenRenameChain(chain, state) => format!(
+                "rename {} to existing package or obsolete it, chain ends in {} package {}",
+                chain
+                    .first()
+                    .expect("empty rename chain")
+                    .get_package_name_as_ref_string(),
+                state,
+                chain
+                    .last()
+                    .expect("empty rename chain")
+                    .get_package_name_as_ref_string()
+            ),
+            RenameLoop(packages) => format!(
+                "rename one of {} to package that is not renamed",
+                packages
+                    .iter()
+                    .map(|fmri| fmri.get_package_name_as_ref_string().clone())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            ObsoleteWithDependencies(fmri, _) => format!(
+                "remove depend actions from manifest of obsoleted package {}",
+                fmri
+            ),
+            UnversionedModule(fmri, _, suggested_name) => match suggested_name {
+                Some(suggested_name) => format!(
+                    "publish {} as {}",
+                    fmri.get_package_name_as_ref_string(),
+                    suggested_name
+                ),
+                None => format!(
+                    "publish {} with interpreter version suffix",
+                    fmri.get_package_name_as_ref_string()
+                ),
+            },
+            UnsatisfiableRequireAny(depend_type, _, required_by, _) => format!(
+                "replace require-any dependency {} of {} with existing package or remove it",
+                depend_type.get_name_and_content_as_string().1,
+                required_by.get_package_name_as_ref_string()
+            ),
+            BrokenConditional(fmri, predicate, _, required_by, _) => match (fmri, predicate) {
+                (Some((fmri, _)), None) => format!(
+                    "replace fmri {} in conditional dependency of {} with existing package",
+                    fmri.get_package_name_as_ref_string(),
+                    required_by.get_package_name_as_ref_string()
+                ),
+                _ => format!(
+                    "remove conditional dependency of {}, its predicate can't be installed",
+                    required_by.get_package_name_as_ref_string()
+                ),
+            },
+            IncorporationMismatch(incorporation, incorporated, published, _) => format!(
+                "update incorporate dependency on {} in {} to published version {} or publish the incorporated version",
+                incorporated.get_package_name_as_ref_string(),
+                incorporation.get_package_name_as_ref_string(),
+                published
+            ),
+            DependencyBloat(component_name, _, _, _, _) => format!(
+                "review build dependencies of {} starting with least referenced ones",
+                component_name
+            ),
+            UnversionedRequire(required, required_by, _) => format!(
+                "add version to require dependency on {} of {}",
+                required.get_package_name_as_ref_string(),
+                required_by.get_package_name_as_ref_string()
+            ),
+            FileConflict(path, _, _) => format!(
+                "deliver {} by only one package or use mediated links",
+                path
+            ),
+            MirrorSkew(_, publisher, _) => format!("synchronize origins of publisher {}", publisher),
+            MediatorConflict(path, _, _, _) => format!(
+                "use the same mediator with distinct mediator-version or mediator-implementation for {} in all packages",
+                path
+            ),
+            StaleRequiredPackage(fmri, component_name) => format!(
+                "remove {} from REQUIRED_PACKAGES of {} if it is not needed for build",
+                fmri.get_package_name_as_ref_string(),
+                component_name
+            ),
+            PackageMissingInPkg5(_, component_name) => format!("regenerate pkg5 of component {}", component_name),
+            StalePkg5Entry(fmri, component_name) => format!(
+                "regenerate pkg5 of component {} or obsolete {} in history",
+                component_name,
+                fmri.get_package_name_as_ref_string()
+            ),
+            OnlyStubDependents(fmri, _, _) => format!(
+                "consider obsoleting {} together with its obsoleted or renamed dependents",
+                fmri.get_package_name_as_ref_string()
+            ),
+            ObsoleteComponent(component_name, _) => format!(
+                "remove component {} from oi-userland, its packages stay obsoleted in history",
+                component_name
+            ),
+            OrphanPackage(fmri, _) => format!(
+                "add {} to some metapackage or group package, or obsolete it",
+                fmri.get_package_name_as_ref_string()
+            ),
+            CrossPublisherDependency(required, _, required_by, publisher, _) => format!(
+                "publish {} in the primary publisher or move {} to {}",
+                required.get_package_name_as_ref_string(),
+                required_by.get_package_name_as_ref_string(),
+                publisher
+            ),
+            RequiredVersionNotPublished(depend_type, _, published, _) => format!(
+                "lower required version of {} to published {} or publish the required version",
+                depend_type.get_fmris_ref()[0].get_package_name_as_ref_string(),
+                published
+            ),
+            UnavailableIncorporatedVersion(incorporation, incorporated, _, _) => format!(
+                "update incorporate dependency on {} in {} to published non-obsoleted version",
+                incorporated.get_package_name_as_ref_string(),
+                incorporation.get_package_name_as_ref_string()
+            ),
+            ForeignPackageInPkg5(fmri, _, _, component_path, _) => format!(
+                "remove {} from {:?}",
+                fmri.get_package_name_as_ref_string(),
+                component_path.join("pkg5")
+            ),
+            DuplicateComponentName(component_name, _) => format!(
+                "keep only one directory of component {} in components.mk",
+                component_name
+            ),
+            PublisherVersionSkew(newer, _, older, older_state, _) => match older_state {
+                AlternativeState::Viable => format!(
+                    "obsolete {} in publisher {} or publish version {} there",
+                    older.get_package_name_as_ref_string(),
+                    older.get_publisher_as_ref_string().cloned().unwrap_or_default(),
+                    newer.get_version_as_string().unwrap_or_default()
+                ),
+                _ => format!(
+                    "remove {} from publisher {}, it is already {} there",
+                    older.get_package_name_as_ref_string(),
+                    older.get_publisher_as_ref_string().cloned().unwrap_or_default(),
+                    older_state
+                ),
+            },
+            DivergentDependencies(fmri, other, _, _, _) => format!(
+                "republish {} to publisher {} or {} to publisher {} with the same dependencies",
+                fmri.get_package_name_as_ref_string(),
+                other.get_publisher_as_ref_string().cloned().unwrap_or_default(),
+                other.get_package_name_as_ref_string(),
+                fmri.get_publisher_as_ref_string().cloned().unwrap_or_default()
+            ),
+            IncorporationCycle(_, _) => "remove incorporate dependency of one incorporation in the cycle on another".to_owned(),
+            DuplicatePkg5Entry(fmri, path) => format!(
+                "remove duplicate {} from {:?}",
+                fmri.get_package_name_as_ref_string(),
+                path
+            ),
+            Pkg5NameMismatch(component_name, _, _) => format!(
+                "regenerate pkg5 of component {} (it is probably copied from other component)",
+                component_name
+            ),
+            ObsoletedPackageBuilt(fmri, component_name, only_obsoleted) => match only_obsoleted {
+                true => format!("remove directory of component {}", component_name),
+                false => format!(
+                    "remove manifest of {} from component {}",
+                    fmri.get_package_name_as_ref_string(),
+                    component_name
+                ),
+            },
+            ExternalDependency(required, _, _, publisher, _) => format!(
+                "make sure {} of external publisher {} is available on target systems",
+                required.get_package_name_as_ref_string(),
+                publisher
+            ),
+            InvalidClassification(fmri, _, _, _) => format!(
+                "set info.classification of {} to value from approved scheme",
+                fmri.get_package_name_as_ref_string()
+            ),
+            MissingLicenseAction(fmri, _) => format!(
+                "add license action to manifest of {}",
+                fmri.get_package_name_as_ref_string()
+            ),
+            MissingComponentLicense(_, makefile) => format!(
+                "set COMPONENT_LICENSE and COMPONENT_LICENSE_FILE in {:?}",
+                makefile
+            ),
+            PackageVersionsInMultipleComponents(fmri, components) => format!(
+                "finish transition of {} and keep it in pkg5 of only one of components: {}",
+                fmri.get_package_name_as_ref_string(),
+                components
+                    .iter()
+                    .map(|(component_name, _)| component_name.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(",")
+            ),
+            UnavailableInstalledPackage(fmri, state) => match state {
+                AlternativeState::Renamed => format!(
+                    "update image to install package replacing {}",
+                    fmri.get_package_name_as_ref_string()
+                ),
+                _ => format!(
+                    "uninstall {} from image",
+                    fmri.get_package_name_as_ref_string()
+                ),
+            },
+            HeldBackInstalledPackage(fmri, _, incorporation) => format!(
+                "update {} in image or relax its incorporate dependency on {}",
+                incorporation.get_package_name_as_ref_string(),
+                fmri.get_package_name_as_ref_string()
+            ),
+            StaleCatalog(catalog, _, _) => format!("download catalog {:?} again", catalog),
+            CatalogAgeSkew(catalog, _, _) => format!("download catalog {:?} again", catalog),
+            InvalidPkg5Key(path, _, _) => format!("fix or regenerate {:?}", path),
+            RequiredFromOtherDepot(depend_type, _, _, _, url) => format!(
+                "add catalog of {} or make sure {} is installable",
+                url,
+                dependency(depend_type)
+            ),
+            HistoryNameCollision(fmri, record, _) => format!(
+                "rename package {} or remove {} from history",
+                fmri.get_package_name_as_ref_string(),
+                record
+            ),
+            EncumberedPlacement(component_name, encumbered, _) => match encumbered {
+                true => format!("move component {} to encumbered components", component_name),
+                false => format!("move component {} out of encumbered components", component_name),
+            },
+        }
+    }
+
+    /// Returns mutable references to every [`FMRI`] in problem, including [fmris][FMRI] in
+    /// dependencies and those that [`Problem::get_fmris_ref`] skips
+    fn get_fmris_ref_mut(&mut self) -> Vec<&mut FMRI> {
+        match self {
+            MissingComponentForPackage(fmri)
+            | RenamedPackageInComponent(fmri, _)
+            | ObsoletedPackageInComponent(fmri, _)
+            | PackageInMultipleComponents(fmri, _)
+            | NonExistingPackageInPkg5(fmri, _) => vec![fmri],
+            RenamedNeedsRenamed(fmri_a, fmri_b) => vec![fmri_a, fmri_b],
+            NonExistingRequired(depend_type, _, required_by, _)
+            | NonExistingRequiredByRenamed(depend_type, _, required_by)
+            | ObsoletedRequired(depend_type, _, required_by, _)
+            | ObsoletedRequiredByRenamed(depend_type, _, required_by)
+            | PartlyObsoletedRequired(depend_type, _, required_by, _)
+            | PartlyObsoletedRequiredByRenamed(depend_type, _, required_by)
+            | UnsatisfiableRequireAny(depend_type, _, required_by, _)
+            | RequiredFromOtherDepot(depend_type, _, required_by, _, _) => {
+                let mut fmris = depend_type.get_fmris_ref_mut();
+                fmris.push(required_by);
+                fmris
+            }
+            UnRunnableMakeCommand(_, _, _, _) | UselessComponent(_) => vec![],
+            TighterThanIncorporation(depend_type, required_by, incorporation, incorporated) => {
+                let mut fmris = depend_type.get_fmris_ref_mut();
+                fmris.extend([required_by, incorporation, incorporated]);
+                fmris
+            }
+            TimestampAnomaly(fmri, older) => {
+                let mut fmris = vec![fmri];
+                fmris.extend(older);
+                fmris
+            }
+            ForbiddenCategoryDependency(_, _, fmri, _) => vec![fmri],
+            PackageNotAllowedInCategory(_, _, fmri) => vec![fmri],
+            InvalidFmri(_, _) => vec![],
+            RequireAnyAlternatives(required_by, alternatives) => {
+                let mut fmris = vec![required_by];
+                fmris.extend(alternatives.iter_mut().map(|(fmri, _)| fmri));
+                fmris
+            }
+            MalformedPkg5(_, _) => vec![],
+            PackageNameViolation(fmri, _, _) => vec![fmri],
+            BuildDependencyCycle(_) => vec![],
+            ReclassifyDependency(fmri, _, _, _) => vec![fmri],
+            SelfDependency(fmri, _, _) => vec![fmri],
+            DuplicateDependency(depend_type, _, required_by, _) => {
+                let mut fmris = depend_type.get_fmris_ref_mut();
+                fmris.extend(required_by);
+                fmris
+            }
+            BrokenRenameChain(chain, _) => chain.iter_mut().collect(),
+            RenameLoop(packages) => packages.iter_mut().collect(),
+            ObsoleteWithDependencies(fmri, depend_types) => {
+                let mut fmris = vec![fmri];
+                fmris.extend(
+                    depend_types
+                        .iter_mut()
+                        .flat_map(DependTypes::get_fmris_ref_mut),
+                );
+                fmris
+            }
+            UnversionedModule(fmri, _, _) => vec![fmri],
+            BrokenConditional(fmri, predicate, _, required_by, _) => {
+                let mut fmris: Vec<&mut FMRI> = fmri
+                    .iter_mut()
+                    .chain(predicate)
+                    .map(|(fmri, _)| fmri)
+                    .collect();
+                fmris.push(required_by);
+                fmris
+            }
+            IncorporationMismatch(incorporation, incorporated, published, _) => {
+                vec![incorporation, incorporated, published]
+            }
+            DependencyBloat(_, _, _, _, dependencies) => dependencies.iter_mut().collect(),
+            UnversionedRequire(required, required_by, _) => vec![required, required_by],
+            FileConflict(_, packages, _) => packages.iter_mut().collect(),
+            MirrorSkew(fmri, _, _) => vec![fmri],
+            MediatorConflict(_, packages, _, _) => packages.iter_mut().collect(),
+            StaleRequiredPackage(fmri, _) => vec![fmri],
+            PackageMissingInPkg5(fmri, _) => vec![fmri],
+            StalePkg5Entry(fmri, _) => vec![fmri],
+            OnlyStubDependents(fmri, dependents, _) => {
+                let mut fmris = vec![fmri];
+                fmris.extend(dependents);
+                fmris
+            }
+            ObsoleteComponent(_, packages) => packages.iter_mut().collect(),
+            OrphanPackage(fmri, _) => vec![fmri],
+            CrossPublisherDependency(required, _, required_by, _, _)
+            | ExternalDependency(required, _, required_by, _, _) => vec![required, required_by],
+            RequiredVersionNotPublished(depend_type, required_by, published, _) => {
+                let mut fmris = depend_type.get_fmris_ref_mut();
+                fmris.extend([required_by, published]);
+                fmris
+            }
+            UnavailableIncorporatedVersion(incorporation, incorporated, _, _) => {
+                vec![incorporation, incorporated]
+            }
+            ForeignPackageInPkg5(fmri, _, _, _, _) => vec![fmri],
+            DuplicateComponentName(_, _) => vec![],
+            PublisherVersionSkew(newer, _, older, _, _) => vec![newer, older],
+            DivergentDependencies(fmri, other, only_in_fmri, only_in_other, _) => {
+                let mut fmris = vec![fmri, other];
+                for depend_type in only_in_fmri.iter_mut().chain(only_in_other) {
+                    fmris.extend(depend_type.get_fmris_ref_mut());
+                }
+                fmris
+            }
+            IncorporationCycle(incorporations, conflicts) => {
+                incorporations.iter_mut().chain(conflicts).collect()
+            }
+            DuplicatePkg5Entry(fmri, _) => vec![fmri],
+            Pkg5NameMismatch(_, _, _) => vec![],
+            ObsoletedPackageBuilt(fmri, _, _) => vec![fmri],
+            InvalidClassification(fmri, _, _, _) => vec![fmri],
+            MissingLicenseAction(fmri, _) => vec![fmri],
+            MissingComponentLicense(_, _) => vec![],
+            PackageVersionsInMultipleComponents(fmri, _) => vec![fmri],
+            UnavailableInstalledPackage(fmri, _) => vec![fmri],
+            HeldBackInstalledPackage(fmri, newest, incorporation) => {
+                vec![fmri, newest, incorporation]
+            }
+            StaleCatalog(_, _, _) => vec![],
+            CatalogAgeSkew(_, _, _) => vec![],
+            InvalidPkg5Key(_, _, _) => vec![],
+            HistoryNameCollision(fmri, record, _) => vec![fmri, record],
+            EncumberedPlacement(_, _, _) => vec![],
+        }
+    }
+
+    /// Returns [`Problem`] without versions in any [`FMRI`] (and without versions of packages
+    /// in origins or components), two problems with the same key are the same problem even if
+    /// versions of packages changed between analyses
+    pub fn key(&self) -> Self {
+        let mut key = self.clone();
+        for fmri in key.get_fmris_ref_mut() {
+            fmri.remove_version();
+        }
+
+        match &mut key {
+            MirrorSkew(_, _, versions) | PackageVersionsInMultipleComponents(_, versions) => {
+                for (_, version) in versions {
+                    *version = None;
+                }
+            }
+            _ => {}
+        }
+
+        key
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct Problems(Vec<Problem>);
+pub struct Problems {
+    problems: Vec<Problem>,
+    #[serde(skip)]
+    observers: Observers,
+    /// owners are not saved, see [`Problems::set_owners`]
+    #[serde(skip)]
+    owners: Owners,
+    /// ids of disabled checks, see [`Problems::disable_checks`]
+    #[serde(skip)]
+    disabled_checks: Vec<String>,
+}
+
+/// Callback registered with [`Problems::on_problem`]
+type Observer = Box<dyn FnMut(&Problem)>;
+
+#[derive(Default)]
+struct Observers(Vec<Observer>);
+
+impl std::fmt::Debug for Observers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} observers", self.0.len())
+    }
+}
+
+/// Owners of components, see [`Problems::set_owners`]
+#[derive(Default, Debug)]
+struct Owners {
+    /// owner by component name
+    owners: HashMap<String, String>,
+    /// component name by package name
+    components: HashMap<String, String>,
+}
+
+/// Result of [`Problems::diff`]
+#[derive(Debug)]
+pub struct ProblemsDiff<'a> {
+    /// problems that are only in the new analysis
+    pub introduced: Vec<&'a Problem>,
+    /// problems that are only in the old analysis
+    pub fixed: Vec<&'a Problem>,
+    /// problems that are in both analyses
+    pub persisting: Vec<&'a Problem>,
+}
+
+/// Result of [`Problems::summary`]
+#[derive(Serialize, PartialEq, Default, Debug)]
+pub struct ProblemsSummary {
+    pub total: usize,
+    pub by_kind: BTreeMap<ProblemKind, usize>,
+    /// by id of check that produced problem
+    pub by_check: BTreeMap<&'static str, usize>,
+    pub by_severity: BTreeMap<Severity, usize>,
+    /// problem with multiple components is counted in each of them
+    pub by_component: BTreeMap<String, usize>,
+    pub by_dependency_type: BTreeMap<DependencyTypes, usize>,
+}
+
+/// Same [`Problem`] found in multiple dependents, see [`Problems::deduplicate`]
+#[derive(Debug)]
+pub struct DeduplicatedProblem<'a> {
+    /// first occurrence of problem
+    pub problem: &'a Problem,
+    pub occurrences: usize,
+    /// dependents (packages or components) in which problem occurs
+    pub contexts: Vec<String>,
+}
+
+/// Identity of [`Problem`] used for deduplication
+#[derive(PartialEq)]
+enum Identity {
+    Dependency(ProblemKind, Box<DependTypes>, DependencyTypes),
+    Other(Box<Problem>),
+}
+
+impl Problem {
+    /// Returns identity of problem and dependent in which problem occurs
+    fn identity(&self) -> (Identity, Option<String>) {
+        let dependent = |required_by: &FMRI, dependency_type: &DependencyTypes, component_name| {
+            if dependency_type == &DependencyTypes::Runtime {
+                format!("package {}", required_by.get_package_name_as_ref_string())
+            } else {
+                format!("component {}", component_name)
+            }
+        };
+
+        match self.key() {
+            NonExistingRequired(depend_type, dependency_type, required_by, component_name)
+            | ObsoletedRequired(depend_type, dependency_type, required_by, component_name)
+            | PartlyObsoletedRequired(depend_type, dependency_type, required_by, component_name) => {
+                let dependent = dependent(&required_by, &dependency_type, component_name);
+                (
+                    Identity::Dependency(self.kind(), Box::new(depend_type), dependency_type),
+                    Some(dependent),
+                )
+            }
+            NonExistingRequiredByRenamed(depend_type, dependency_type, required_by)
+            | ObsoletedRequiredByRenamed(depend_type, dependency_type, required_by)
+            | PartlyObsoletedRequiredByRenamed(depend_type, dependency_type, required_by) => (
+                Identity::Dependency(self.kind(), Box::new(depend_type), dependency_type),
+                Some(format!(
+                    "renamed package {}",
+                    required_by.get_package_name_as_ref_string()
+                )),
+            ),
+            key => (Identity::Other(Box::new(key)), None),
+        }
+    }
+}
+
+impl ProblemsDiff<'_> {
+    /// Returns true if the new analysis didn't introduce any problem
+    pub fn is_clean(&self) -> bool {
+        self.introduced.is_empty()
+    }
+}
 
 impl Problems {
     pub fn new() -> Self {
-        Self(vec![])
+        Self {
+            problems: vec![],
+            observers: Observers::default(),
+            owners: Owners::default(),
+            disabled_checks: vec![],
+        }
+    }
+
+    /// Registers callback that is called synchronously with every problem added by
+    /// [`Problems::add_problem`] (duplicates are not reported)
+    pub fn on_problem<F: FnMut(&Problem) + 'static>(&mut self, observer: F) {
+        self.observers.0.push(Box::new(observer))
     }
 
     pub fn get_ref(&self) -> &Vec<Problem> {
-        &self.0
+        &self.problems
+    }
+
+    /// Removes problems for which `keep` returns false
+    pub fn retain<F: FnMut(&Problem) -> bool>(&mut self, keep: F) {
+        self.problems.retain(keep)
+    }
+
+    /// Problems produced by [checks][crate::checks::CHECKS] with `check_ids` will not be added
+    pub fn disable_checks(&mut self, check_ids: Vec<String>) {
+        self.disabled_checks = check_ids
+    }
+
+    /// Sets owners of components (owner by component name), packages of `components` are used to
+    /// find component of problems without component name, owners are not serialized, so they
+    /// must be set again after [`Problems::deserialize`]
+    pub fn set_owners(&mut self, components: &Components, owners: HashMap<String, String>) {
+        let mut package_components = HashMap::new();
+        for component in components.get_ref() {
+            if component.get_name_ref().is_empty() {
+                continue;
+            }
+
+            for package_versions in component.get_versions_ref() {
+                package_components.insert(
+                    package_versions
+                        .fmri_ref()
+                        .get_package_name_as_ref_string()
+                        .clone(),
+                    component.get_name_ref().clone(),
+                );
+            }
+        }
+
+        self.owners = Owners {
+            owners,
+            components: package_components,
+        }
+    }
+
+    /// Returns owners of components in which subject of `problem` lives
+    pub fn get_owners(&self, problem: &Problem) -> Vec<&String> {
+        let mut component_names = problem.get_component_names_ref();
+        for fmri in problem.get_fmris_ref() {
+            if let Some(component_name) = self
+                .owners
+                .components
+                .get(fmri.get_package_name_as_ref_string())
+            {
+                component_names.push(component_name)
+            }
+        }
+
+        let mut owners: Vec<&String> = vec![];
+        for component_name in component_names {
+            if let Some(owner) = self.owners.owners.get(component_name) {
+                if !owners.contains(&owner) {
+                    owners.push(owner)
+                }
+            }
+        }
+        owners
+    }
+
+    /// Groups problems by owning component (the first component in problem), problems without
+    /// component are in the last group with [`None`]
+    pub fn group_by_component(&self) -> Vec<(Option<&String>, Vec<&Problem>)> {
+        let mut groups: BTreeMap<Option<&String>, Vec<&Problem>> = BTreeMap::new();
+
+        for problem in self.get_ref() {
+            groups
+                .entry(problem.get_component_names_ref().first().copied())
+                .or_default()
+                .push(problem)
+        }
+
+        let mut groups: Vec<(Option<&String>, Vec<&Problem>)> = groups.into_iter().collect();
+        // None is the smallest key, move it to the end
+        if groups
+            .first()
+            .is_some_and(|(component_name, _)| component_name.is_none())
+        {
+            groups.rotate_left(1);
+        }
+        groups
+    }
+
+    /// Returns number of problems for every [`Severity`]
+    pub fn count_by_severity(&self) -> BTreeMap<Severity, usize> {
+        let mut counts = BTreeMap::new();
+        for problem in self.get_ref() {
+            *counts.entry(problem.severity()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Returns number of problems per kind, severity, component and dependency type
+    pub fn summary(&self) -> ProblemsSummary {
+        let mut summary = ProblemsSummary {
+            total: self.get_ref().len(),
+            by_severity: self.count_by_severity(),
+            ..Default::default()
+        };
+
+        for problem in self.get_ref() {
+            *summary.by_kind.entry(problem.kind()).or_default() += 1;
+            *summary.by_check.entry(problem.check_id()).or_default() += 1;
+
+            for component_name in problem.get_component_names_ref() {
+                *summary
+                    .by_component
+                    .entry(component_name.clone())
+                    .or_default() += 1;
+            }
+
+            if let Some(dependency_type) = problem.get_dependency_type_ref() {
+                *summary
+                    .by_dependency_type
+                    .entry(dependency_type.clone())
+                    .or_default() += 1;
+            }
+        }
+
+        summary
+    }
+
+    /// Returns the highest [`Severity`] of all problems, [`None`] if there are no problems
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.get_ref().iter().map(Problem::severity).max()
+    }
+
+    /// Returns problems in which component with `component_name` occurs
+    pub fn filter_by_component<'a>(
+        &'a self,
+        component_name: &'a str,
+    ) -> impl Iterator<Item = &'a Problem> + 'a {
+        self.get_ref().iter().filter(move |problem| {
+            problem
+                .get_component_names_ref()
+                .iter()
+                .any(|name| name.as_str() == component_name)
+        })
+    }
+
+    /// Returns problems in which package occurs (versions are ignored)
+    pub fn filter_by_package<'a>(
+        &'a self,
+        fmri: &'a FMRI,
+    ) -> impl Iterator<Item = &'a Problem> + 'a {
+        self.get_ref().iter().filter(move |problem| {
+            problem
+                .get_fmris_ref()
+                .iter()
+                .any(|problem_fmri| problem_fmri.package_name_eq(fmri))
+        })
+    }
+
+    /// Returns problems of given kind
+    pub fn filter_by_kind(&self, kind: ProblemKind) -> impl Iterator<Item = &Problem> {
+        self.get_ref()
+            .iter()
+            .filter(move |problem| problem.kind() == kind)
+    }
+
+    /// Compares [`self`] (new analysis) with `old` analysis, problems are compared by
+    /// [`Problem::key`]
+    pub fn diff<'a>(&'a self, old: &'a Problems) -> ProblemsDiff<'a> {
+        let new_keys: Vec<Problem> = self.get_ref().iter().map(Problem::key).collect();
+        let old_keys: Vec<Problem> = old.get_ref().iter().map(Problem::key).collect();
+
+        let mut diff = ProblemsDiff {
+            introduced: vec![],
+            fixed: vec![],
+            persisting: vec![],
+        };
+
+        for (problem, key) in self.get_ref().iter().zip(&new_keys) {
+            if old_keys.contains(key) {
+                diff.persisting.push(problem)
+            } else {
+                diff.introduced.push(problem)
+            }
+        }
+
+        for (problem, key) in old.get_ref().iter().zip(&old_keys) {
+            if !new_keys.contains(key) {
+                diff.fixed.push(problem)
+            }
+        }
+
+        diff
+    }
+
+    /// Groups problems with the same identity (e.g. the same obsoleted package required by many
+    /// packages) into one [`DeduplicatedProblem`] with occurrence count
+    pub fn deduplicate(&self) -> Vec<DeduplicatedProblem<'_>> {
+        let mut deduplicated: Vec<(Identity, DeduplicatedProblem)> = vec![];
+
+        for problem in self.get_ref() {
+            let (identity, context) = problem.identity();
+
+            match deduplicated.iter_mut().find(|(i, _)| i == &identity) {
+                Some((_, existing)) => {
+                    existing.occurrences += 1;
+                    existing.contexts.extend(context);
+                }
+                None => deduplicated.push((
+                    identity,
+                    DeduplicatedProblem {
+                        problem,
+                        occurrences: 1,
+                        contexts: context.into_iter().collect(),
+                    },
+                )),
+            }
+        }
+
+        deduplicated.into_iter().map(|(_, d)| d).collect()
+    }
+
+    /// Returns problems as JSON array, every problem has kind, data and suggestion
+    pub fn to_json(&self) -> Value {
+        Value::Array(
+            self.get_ref()
+                .iter()
+                .map(|problem| self.problem_to_json(problem))
+                .collect(),
+        )
+    }
+
+    /// Returns JSON object with at most `limit` problems of every kind, full number of problems
+    /// per kind is in `counts`
+    pub fn to_json_with_limit(&self, limit: usize) -> Value {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        let mut problems = vec![];
+
+        for problem in self.get_ref() {
+            let count = counts.entry(problem.kind().to_string()).or_default();
+            *count += 1;
+            if *count <= limit {
+                problems.push(self.problem_to_json(problem));
+            }
+        }
+
+        json!({
+            "problems": problems,
+            "counts": counts,
+        })
+    }
+
+    /// Returns JSON object with problems introduced and fixed since `old` problems and number of
+    /// persisting problems
+    pub fn diff_to_json(&self, old: &Problems) -> Value {
+        let diff = self.diff(old);
+        json!({
+            "introduced": diff
+                .introduced
+                .iter()
+                .map(|problem| self.problem_to_json(problem))
+                .collect::<Vec<Value>>(),
+            "fixed": diff
+                .fixed
+                .iter()
+                .map(|problem| old.problem_to_json(problem))
+                .collect::<Vec<Value>>(),
+            "persisting": diff.persisting.len(),
+        })
+    }
+
+    fn problem_to_json(&self, problem: &Problem) -> Value {
+        json!({
+            "kind": problem.kind().to_string(),
+            "severity": problem.severity().to_string(),
+            "check": problem.check_id(),
+            "problem": problem,
+            "suggestion": problem.suggestion(),
+            "owners": self.get_owners(problem),
+        })
     }
 
     pub fn add_problem(&mut self, mut problem: Problem) {
+        if self
+            .disabled_checks
+            .iter()
+            .any(|check_id| check_id == problem.check_id())
+        {
+            return;
+        }
+
         match &mut problem {
             MissingComponentForPackage(fmri) => {
                 fmri.remove_version();
@@ -65,7 +1812,7 @@ impl Problems {
             ObsoletedPackageInComponent(fmri, _) => {
                 fmri.remove_version();
             }
-            UnRunnableMakeCommand(_, _) => {}
+            UnRunnableMakeCommand(_, _, _, _) => {}
             NonExistingRequired(_, _, required_by, _) => {
                 required_by.remove_version();
             }
@@ -91,20 +1838,142 @@ impl Problems {
             NonExistingPackageInPkg5(fmri, _) => {
                 fmri.remove_version();
             }
+            TighterThanIncorporation(_, required_by, incorporation, _) => {
+                required_by.remove_version();
+                incorporation.remove_version();
+            }
+            TimestampAnomaly(_, _) => {}
+            ForbiddenCategoryDependency(_, _, fmri, _) => {
+                fmri.remove_version();
+            }
+            PackageNotAllowedInCategory(_, _, fmri) => {
+                fmri.remove_version();
+            }
+            InvalidFmri(_, _) => {}
+            RequireAnyAlternatives(required_by, _) => {
+                required_by.remove_version();
+            }
+            MalformedPkg5(_, _) => {}
+            PackageNameViolation(fmri, _, _) => {
+                fmri.remove_version();
+            }
+            BuildDependencyCycle(_) => {}
+            ReclassifyDependency(fmri, _, _, _) => {
+                fmri.remove_version();
+            }
+            SelfDependency(fmri, _, _) => {
+                fmri.remove_version();
+            }
+            DuplicateDependency(_, _, required_by, _) => {
+                if let Some(required_by) = required_by {
+                    required_by.remove_version();
+                }
+            }
+            BrokenRenameChain(chain, _) => {
+                for fmri in chain {
+                    fmri.remove_version();
+                }
+            }
+            RenameLoop(packages) => {
+                for fmri in packages {
+                    fmri.remove_version();
+                }
+            }
+            ObsoleteWithDependencies(_, _) => {}
+            UnversionedModule(fmri, _, _) => {
+                fmri.remove_version();
+            }
+            UnsatisfiableRequireAny(_, _, required_by, _) => {
+                required_by.remove_version();
+            }
+            BrokenConditional(_, _, _, required_by, _) => {
+                required_by.remove_version();
+            }
+            IncorporationMismatch(_, _, _, _) => {}
+            DependencyBloat(_, _, _, _, dependencies) => {
+                for fmri in dependencies {
+                    fmri.remove_version();
+                }
+            }
+            UnversionedRequire(_, required_by, _) => {
+                required_by.remove_version();
+            }
+            FileConflict(_, _, _) => {}
+            MirrorSkew(_, _, _) => {}
+            MediatorConflict(_, _, _, _) => {}
+            StaleRequiredPackage(fmri, _) => {
+                fmri.remove_version();
+            }
+            PackageMissingInPkg5(fmri, _) => {
+                fmri.remove_version();
+            }
+            StalePkg5Entry(fmri, _) => {
+                fmri.remove_version();
+            }
+            OnlyStubDependents(fmri, dependents, _) => {
+                fmri.remove_version();
+                for dependent in dependents {
+                    dependent.remove_version();
+                }
+            }
+            ObsoleteComponent(_, packages) => {
+                for fmri in packages {
+                    fmri.remove_version();
+                }
+            }
+            OrphanPackage(fmri, _) => {
+                fmri.remove_version();
+            }
+            CrossPublisherDependency(_, _, required_by, _, _) => {
+                required_by.remove_version();
+            }
+            RequiredVersionNotPublished(_, required_by, _, _) => {
+                required_by.remove_version();
+            }
+            UnavailableIncorporatedVersion(_, _, _, _) => {}
+            ForeignPackageInPkg5(_, _, _, _, _) => {}
+            DuplicateComponentName(_, _) => {}
+            PublisherVersionSkew(_, _, _, _, _) => {}
+            DivergentDependencies(_, _, _, _, _) => {}
+            IncorporationCycle(_, _) => {}
+            DuplicatePkg5Entry(_, _) => {}
+            Pkg5NameMismatch(_, _, _) => {}
+            ObsoletedPackageBuilt(_, _, _) => {}
+            ExternalDependency(_, _, required_by, _, _) => {
+                required_by.remove_version();
+            }
+            InvalidClassification(_, _, _, _) => {}
+            MissingLicenseAction(_, _) => {}
+            MissingComponentLicense(_, _) => {}
+            PackageVersionsInMultipleComponents(_, _) => {}
+            UnavailableInstalledPackage(_, _) => {}
+            HeldBackInstalledPackage(_, _, _) => {}
+            StaleCatalog(_, _, _) => {}
+            CatalogAgeSkew(_, _, _) => {}
+            InvalidPkg5Key(_, _, _) => {}
+            RequiredFromOtherDepot(_, _, required_by, _, _) => {
+                required_by.remove_version();
+            }
+            HistoryNameCollision(_, _, _) => {}
+            EncumberedPlacement(_, _, _) => {}
         }
 
         if !self.contains(&problem) {
-            self.0.push(problem)
+            for observer in &mut self.observers.0 {
+                observer(&problem)
+            }
+            self.problems.push(problem)
         }
     }
 
+    #[allow(clippy::collapsible_match)]
     fn contains(&self, problem: &Problem) -> bool {
         let contains_component = |depend_type: &DependTypes,
                                   dependency_type: &DependencyTypes,
                                   component_name: &String|
          -> bool {
             if dependency_type != &DependencyTypes::Runtime {
-                return self.0.iter().any(|p| {
+                return self.problems.iter().any(|p| {
                     if let NonExistingRequired(a, b, _, c) = p {
                         if a == depend_type && b == dependency_type && c == component_name {
                             return true;
@@ -149,31 +2018,28 @@ impl Problems {
             _ => {}
         };
 
-        self.0.contains(problem)
+        self.problems.contains(problem)
     }
 
+    /// Writes problems atomically under lock, see [`write_atomic`]
     pub fn serialize<P: AsRef<Path> + ?Sized + std::fmt::Display>(
         &self,
         path: &P,
     ) -> Result<(), String> {
-        File::create(path)
-            .unwrap()
-            .write_all(&serialize(self).map_err(|e| {
+        write_atomic(
+            path,
+            &serialize(self).map_err(|e| {
                 format!("failed to serialize file with problems into binary: {}", e)
-            })?)
-            .map_err(|e| format!("failed to write problems to {}: {}", path, e))?;
-        Ok(())
+            })?,
+        )
+        .map_err(|e| format!("failed to write problems to {}: {}", path, e))
     }
 
     pub fn deserialize<P: AsRef<Path> + ?Sized + std::fmt::Display>(
         path: &P,
     ) -> Result<Self, String> {
-        let data = &mut Vec::new();
-        File::open(path)
-            .map_err(|e| format!("failed to open file with problems: {}", e))?
-            .read_to_end(data)
-            .map_err(|e| format!("failed to read problems: {}", e))?;
-        deserialize(data).map_err(|e| format!("failed to deserialize data from {}: {}", path, e))
+        let data = read_locked(path).map_err(|e| format!("failed to read problems: {}", e))?;
+        deserialize(&data).map_err(|e| format!("failed to deserialize data from {}: {}", path, e))
     }
 
     fn sort(&mut self) {
@@ -190,17 +2056,71 @@ impl Problems {
                 ObsoletedPackageInComponent(_, _) => 8,
                 ObsoletedRequired(_, _, _, _) => 9,
                 ObsoletedRequiredByRenamed(_, _, _) => 10,
-                UnRunnableMakeCommand(_, _) => 11,
+                UnRunnableMakeCommand(_, _, _, _) => 11,
                 PackageInMultipleComponents(_, _) => 12,
                 NonExistingPackageInPkg5(_, _) => 13,
+                TighterThanIncorporation(_, _, _, _) => 14,
+                TimestampAnomaly(_, _) => 15,
+                ForbiddenCategoryDependency(_, _, _, _) => 16,
+                PackageNotAllowedInCategory(_, _, _) => 17,
+                InvalidFmri(_, _) => 18,
+                RequireAnyAlternatives(_, _) => 19,
+                MalformedPkg5(_, _) => 20,
+                PackageNameViolation(_, _, _) => 21,
+                BuildDependencyCycle(_) => 22,
+                ReclassifyDependency(_, _, _, _) => 23,
+                SelfDependency(_, _, _) => 24,
+                DuplicateDependency(_, _, _, _) => 25,
+                BrokenRenameChain(_, _) => 26,
+                RenameLoop(_) => 27,
+                ObsoleteWithDependencies(_, _) => 28,
+                UnversionedModule(_, _, _) => 29,
+                UnsatisfiableRequireAny(_, _, _, _) => 30,
+                BrokenConditional(_, _, _, _, _) => 31,
+                IncorporationMismatch(_, _, _, _) => 32,
+                DependencyBloat(_, _, _, _, _) => 33,
+                UnversionedRequire(_, _, _) => 34,
+                FileConflict(_, _, _) => 35,
+                MirrorSkew(_, _, _) => 36,
+                MediatorConflict(_, _, _, _) => 37,
+                StaleRequiredPackage(_, _) => 38,
+                PackageMissingInPkg5(_, _) => 39,
+                StalePkg5Entry(_, _) => 40,
+                OnlyStubDependents(_, _, _) => 41,
+                ObsoleteComponent(_, _) => 42,
+                OrphanPackage(_, _) => 43,
+                CrossPublisherDependency(_, _, _, _, _) => 44,
+                RequiredVersionNotPublished(_, _, _, _) => 45,
+                UnavailableIncorporatedVersion(_, _, _, _) => 46,
+                ForeignPackageInPkg5(_, _, _, _, _) => 47,
+                DuplicateComponentName(_, _) => 48,
+                PublisherVersionSkew(_, _, _, _, _) => 49,
+                DivergentDependencies(_, _, _, _, _) => 50,
+                IncorporationCycle(_, _) => 51,
+                DuplicatePkg5Entry(_, _) => 52,
+                Pkg5NameMismatch(_, _, _) => 53,
+                ObsoletedPackageBuilt(_, _, _) => 54,
+                ExternalDependency(_, _, _, _, _) => 55,
+                InvalidClassification(_, _, _, _) => 56,
+                MissingLicenseAction(_, _) => 57,
+                MissingComponentLicense(_, _) => 58,
+                PackageVersionsInMultipleComponents(_, _) => 59,
+                UnavailableInstalledPackage(_, _) => 60,
+                HeldBackInstalledPackage(_, _, _) => 61,
+                StaleCatalog(_, _, _) => 62,
+                CatalogAgeSkew(_, _, _) => 63,
+                InvalidPkg5Key(_, _, _) => 64,
+                RequiredFromOtherDepot(_, _, _, _, _) => 65,
+                HistoryNameCollision(_, _, _) => 66,
+                EncumberedPlacement(_, _, _) => 67,
             }
         };
 
-        self.0.sort_by_key(priority)
+        self.problems.sort_by_key(priority)
     }
 
     fn count(&self) {
-        let mut counter: [i16; 14] = [0; 14];
+        let mut counter: [i16; 68] = [0; 68];
         for problem in self.get_ref() {
             match problem {
                 UselessComponent(_) => counter[0] += 1,
@@ -214,9 +2134,63 @@ impl Problems {
                 ObsoletedPackageInComponent(_, _) => counter[8] += 1,
                 ObsoletedRequired(_, _, _, _) => counter[9] += 1,
                 ObsoletedRequiredByRenamed(_, _, _) => counter[10] += 1,
-                UnRunnableMakeCommand(_, _) => counter[11] += 1,
+                UnRunnableMakeCommand(_, _, _, _) => counter[11] += 1,
                 PackageInMultipleComponents(_, _) => counter[12] += 1,
                 NonExistingPackageInPkg5(_, _) => counter[13] += 1,
+                TighterThanIncorporation(_, _, _, _) => counter[14] += 1,
+                TimestampAnomaly(_, _) => counter[15] += 1,
+                ForbiddenCategoryDependency(_, _, _, _) => counter[16] += 1,
+                PackageNotAllowedInCategory(_, _, _) => counter[17] += 1,
+                InvalidFmri(_, _) => counter[18] += 1,
+                RequireAnyAlternatives(_, _) => counter[19] += 1,
+                MalformedPkg5(_, _) => counter[20] += 1,
+                PackageNameViolation(_, _, _) => counter[21] += 1,
+                BuildDependencyCycle(_) => counter[22] += 1,
+                ReclassifyDependency(_, _, _, _) => counter[23] += 1,
+                SelfDependency(_, _, _) => counter[24] += 1,
+                DuplicateDependency(_, _, _, _) => counter[25] += 1,
+                BrokenRenameChain(_, _) => counter[26] += 1,
+                RenameLoop(_) => counter[27] += 1,
+                ObsoleteWithDependencies(_, _) => counter[28] += 1,
+                UnversionedModule(_, _, _) => counter[29] += 1,
+                UnsatisfiableRequireAny(_, _, _, _) => counter[30] += 1,
+                BrokenConditional(_, _, _, _, _) => counter[31] += 1,
+                IncorporationMismatch(_, _, _, _) => counter[32] += 1,
+                DependencyBloat(_, _, _, _, _) => counter[33] += 1,
+                UnversionedRequire(_, _, _) => counter[34] += 1,
+                FileConflict(_, _, _) => counter[35] += 1,
+                MirrorSkew(_, _, _) => counter[36] += 1,
+                MediatorConflict(_, _, _, _) => counter[37] += 1,
+                StaleRequiredPackage(_, _) => counter[38] += 1,
+                PackageMissingInPkg5(_, _) => counter[39] += 1,
+                StalePkg5Entry(_, _) => counter[40] += 1,
+                OnlyStubDependents(_, _, _) => counter[41] += 1,
+                ObsoleteComponent(_, _) => counter[42] += 1,
+                OrphanPackage(_, _) => counter[43] += 1,
+                CrossPublisherDependency(_, _, _, _, _) => counter[44] += 1,
+                RequiredVersionNotPublished(_, _, _, _) => counter[45] += 1,
+                UnavailableIncorporatedVersion(_, _, _, _) => counter[46] += 1,
+                ForeignPackageInPkg5(_, _, _, _, _) => counter[47] += 1,
+                DuplicateComponentName(_, _) => counter[48] += 1,
+                PublisherVersionSkew(_, _, _, _, _) => counter[49] += 1,
+                DivergentDependencies(_, _, _, _, _) => counter[50] += 1,
+                IncorporationCycle(_, _) => counter[51] += 1,
+                DuplicatePkg5Entry(_, _) => counter[52] += 1,
+                Pkg5NameMismatch(_, _, _) => counter[53] += 1,
+                ObsoletedPackageBuilt(_, _, _) => counter[54] += 1,
+                ExternalDependency(_, _, _, _, _) => counter[55] += 1,
+                InvalidClassification(_, _, _, _) => counter[56] += 1,
+                MissingLicenseAction(_, _) => counter[57] += 1,
+                MissingComponentLicense(_, _) => counter[58] += 1,
+                PackageVersionsInMultipleComponents(_, _) => counter[59] += 1,
+                UnavailableInstalledPackage(_, _) => counter[60] += 1,
+                HeldBackInstalledPackage(_, _, _) => counter[61] += 1,
+                StaleCatalog(_, _, _) => counter[62] += 1,
+                CatalogAgeSkew(_, _, _) => counter[63] += 1,
+                InvalidPkg5Key(_, _, _) => counter[64] += 1,
+                RequiredFromOtherDepot(_, _, _, _, _) => counter[65] += 1,
+                HistoryNameCollision(_, _, _) => counter[66] += 1,
+                EncumberedPlacement(_, _, _) => counter[67] += 1,
             }
         }
 
@@ -236,6 +2210,60 @@ impl Problems {
                 11 => error!("Number of un-runnable make commands: {}", count),
                 12 => error!("Number of packages that are in multiple components: {}", count),
                 13 => error!("Number of packages that are in pkg5 file but do not exist: {}", count),
+                14 => warn!("Number of version constraints tighter than incorporation: {}", count),
+                15 => warn!("Number of packages with implausible or non-monotonic timestamp: {}", count),
+                16 => error!("Number of dependencies forbidden by category policy: {}", count),
+                17 => error!("Number of packages not allowed by category policy: {}", count),
+                18 => error!("Number of invalid fmris: {}", count),
+                19 => warn!("Number of require-any dependencies with weak alternatives: {}", count),
+                20 => error!("Number of malformed pkg5 files: {}", count),
+                21 => warn!("Number of package names violating naming conventions: {}", count),
+                22 => warn!("Number of build dependency cycles between components: {}", count),
+                23 => info!("Number of dependencies that should be reclassified: {}", count),
+                24 => warn!("Number of packages that depend on themselves: {}", count),
+                25 => info!("Number of duplicate dependencies: {}", count),
+                26 => error!("Number of broken rename chains: {}", count),
+                27 => error!("Number of rename loops: {}", count),
+                28 => warn!("Number of obsolete packages with dependencies: {}", count),
+                29 => warn!("Number of modules without versioned variants: {}", count),
+                30 => error!("Number of require-any dependencies without any existing alternative: {}", count),
+                31 => error!("Number of conditional dependencies with missing or obsoleted fmri or predicate: {}", count),
+                32 => error!("Number of incorporate dependencies not satisfied by published version: {}", count),
+                33 => info!("Number of components with much more build dependencies than their peers: {}", count),
+                34 => warn!("Number of require dependencies without version: {}", count),
+                35 => error!("Number of paths delivered by more packages: {}", count),
+                36 => warn!("Number of packages that differ between origins of publisher: {}", count),
+                37 => error!("Number of mediated paths with conflicting mediation: {}", count),
+                38 => info!("Number of REQUIRED_PACKAGES not referenced by runtime dependencies: {}", count),
+                39 => warn!("Number of published packages with manifest missing in pkg5: {}", count),
+                40 => warn!("Number of packages in pkg5 without manifest in component: {}", count),
+                41 => info!("Number of packages required only by obsoleted or renamed packages: {}", count),
+                42 => warn!("Number of components with only obsoleted packages: {}", count),
+                43 => info!("Number of packages not reachable from root packages: {}", count),
+                44 => error!("Number of dependencies available only from other publisher: {}", count),
+                45 => error!("Number of dependencies on versions newer than published: {}", count),
+                46 => error!("Number of incorporate dependencies on unavailable versions: {}", count),
+                47 => warn!("Number of packages in pkg5 of components not building them: {}", count),
+                48 => error!("Number of component names used by more directories: {}", count),
+                49 => warn!("Number of packages with different versions in publishers: {}", count),
+                50 => warn!("Number of package versions with different dependencies in publishers: {}", count),
+                51 => error!("Number of incorporation cycles: {}", count),
+                52 => warn!("Number of packages listed more times in pkg5: {}", count),
+                53 => warn!("Number of pkg5 files with name of other component: {}", count),
+                54 => warn!("Number of obsoleted packages with manifest in component: {}", count),
+                55 => info!("Number of dependencies satisfied only by external publishers: {}", count),
+                56 => warn!("Number of packages with missing or invalid classification: {}", count),
+                57 => warn!("Number of packages delivering files without license action: {}", count),
+                58 => warn!("Number of components without license declaration: {}", count),
+                59 => warn!("Number of packages in multiple components at different versions: {}", count),
+                60 => warn!("Number of installed packages that are obsolete, renamed or not published: {}", count),
+                61 => info!("Number of installed packages held back by incorporations: {}", count),
+                62 => warn!("Number of stale catalogs: {}", count),
+                63 => warn!("Number of catalogs much older than the newest catalog: {}", count),
+                64 => warn!("Number of invalid keys in pkg5: {}", count),
+                65 => info!("Number of required packages published only in other depots: {}", count),
+                66 => warn!("Number of packages colliding with history: {}", count),
+                67 => info!("Number of components in wrong repository by license: {}", count),
                 _ => panic!("invalid problem type"),
             }
         }
@@ -249,227 +2277,834 @@ impl Default for Problems {
 }
 
 pub fn report(problems: &mut Problems) {
+    report_with_limit(problems, usize::MAX)
+}
+
+/// Reports at most `limit` problems of every kind, the rest is summarized in one line per kind
+pub fn report_with_limit(problems: &mut Problems, limit: usize) {
     problems.sort();
 
-    for problem in problems.get_ref() {
-        match problem {
-            NonExistingPackageInPkg5(fmri, component_name) => {
-                error!(
-                    "package {} does not exist but it is in the pkg5, component: {}",
-                    fmri, component_name
-                )
-            }
-            PackageInMultipleComponents(fmri, components) => {
-                error!(
-                    "package {} is in multiple components: {}",
-                    fmri,
-                    components.join(",")
-                )
+    let mut reported: HashMap<ProblemKind, usize> = HashMap::new();
+    let mut omitted: BTreeMap<ProblemKind, usize> = BTreeMap::new();
+
+    for deduplicated in problems.deduplicate() {
+        let kind = deduplicated.problem.kind();
+        let count = reported.entry(kind).or_default();
+        if *count >= limit {
+            *omitted.entry(kind).or_default() += 1;
+            continue;
+        }
+        *count += 1;
+
+        if deduplicated.occurrences == 1 {
+            report_problem(deduplicated.problem);
+        } else {
+            report_deduplicated(&deduplicated);
+        }
+        debug!("\tcheck: {}", deduplicated.problem.check_id());
+        debug!("\tsuggestion: {}", deduplicated.problem.suggestion());
+        report_owners(problems, deduplicated.problem);
+    }
+
+    for (kind, count) in omitted {
+        info!("{}: and {} more", kind, format_count(count));
+    }
+
+    problems.count()
+}
+
+/// Formats number with thousands separators (3200 -> 3,200)
+pub fn format_count(count: usize) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::new();
+
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+
+    formatted
+}
+
+/// Reports problems grouped by component with number of problems in every component
+pub fn report_by_component(problems: &mut Problems) {
+    problems.sort();
+
+    for (component_name, group) in problems.group_by_component() {
+        match component_name {
+            Some(component_name) => {
+                info!("component {} ({} problems):", component_name, group.len())
             }
-            UselessComponent(name) => info!("component {} is not needed by any package", name),
-            MissingComponentForPackage(fmri) => warn!("missing component for {}", fmri),
-            RenamedNeedsRenamed(fmri_a, fmri_b) => error!(
-                "renamed package {} needs renamed package {}",
-                fmri_a.get_package_name_as_ref_string(),
-                fmri_b.get_package_name_as_ref_string()
-            ),
-            RenamedPackageInComponent(package, component) => error!(
-                "package {} is renamed and is in component {}",
-                package.get_package_name_as_ref_string(),
-                component
-            ),
-            ObsoletedPackageInComponent(package, component) => error!(
-                "package {} is obsolete and is in component {}",
-                package.get_package_name_as_ref_string(),
-                component
-            ),
-            UnRunnableMakeCommand(command, path) => error!("can't run {} in {:?}", command, path),
+            None => info!("no component ({} problems):", group.len()),
+        }
 
-            NonExistingRequired(depend_type, dependency_type, required_by, component_name) => {
-                let (name, fmri) = depend_type.clone().get_name_and_content_as_string();
+        for problem in group {
+            report_problem(problem);
+            debug!("\tcheck: {}", problem.check_id());
+            report_owners(problems, problem);
+        }
+    }
 
-                let package_or_component_name = if dependency_type == &DependencyTypes::Runtime {
-                    required_by.get_package_name_as_ref_string().clone()
-                } else {
-                    format!("component {}", component_name)
-                };
-
-                warn!(
-                    "package {} doesn't exist, but is required by {}",
-                    fmri,
-                    match dependency_type {
-                        DependencyTypes::Runtime => {
-                            format!("package {} (runtime, {})", package_or_component_name, name)
-                        }
-                        DependencyTypes::Build => {
-                            format!("{} (build, component)", package_or_component_name)
-                        }
-                        DependencyTypes::Test => {
-                            format!("{} (test, component)", package_or_component_name)
-                        }
-                        DependencyTypes::SystemBuild => {
-                            format!("{} (build, system)", package_or_component_name)
-                        }
-                        DependencyTypes::SystemTest => {
-                            format!("{} (test, system)", package_or_component_name)
-                        }
-                        DependencyTypes::None => panic!("DependencyTypes can't be None"),
+    problems.count()
+}
+
+fn report_owners(problems: &Problems, problem: &Problem) {
+    let owners = problems.get_owners(problem);
+    if !owners.is_empty() {
+        info!(
+            "\towners: {}",
+            owners
+                .into_iter()
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+    }
+}
+
+/// Reports problem that occurs for multiple dependents in one line, dependents are in debug
+fn report_deduplicated(deduplicated: &DeduplicatedProblem) {
+    let (depend_type, dependency_type) = match deduplicated.problem {
+        NonExistingRequired(depend_type, dependency_type, _, _)
+        | NonExistingRequiredByRenamed(depend_type, dependency_type, _)
+        | ObsoletedRequired(depend_type, dependency_type, _, _)
+        | ObsoletedRequiredByRenamed(depend_type, dependency_type, _)
+        | PartlyObsoletedRequired(depend_type, dependency_type, _, _)
+        | PartlyObsoletedRequiredByRenamed(depend_type, dependency_type, _) => {
+            (depend_type, dependency_type)
+        }
+        // other problems are deduplicated only when they are equal without versions
+        problem => return report_problem(problem),
+    };
+
+    let (name, fmri) = depend_type.get_name_and_content_as_string();
+
+    let message = format!(
+        "{}: package {} is required by {} dependents ({}, {})",
+        deduplicated.problem.kind(),
+        fmri,
+        deduplicated.occurrences,
+        dependency_type,
+        name
+    );
+
+    log!(deduplicated.problem.severity().level(), "{}", message);
+
+    for context in &deduplicated.contexts {
+        debug!("\t{}", context);
+    }
+}
+
+fn report_problem(problem: &Problem) {
+    let level = problem.severity().level();
+    match problem {
+        NonExistingPackageInPkg5(fmri, component_name) => {
+            log!(
+                level,
+                "package {} does not exist but it is in the pkg5, component: {}",
+                fmri, component_name
+            )
+        }
+        PackageInMultipleComponents(fmri, components) => {
+            log!(
+                level,
+                "package {} is in multiple components: {}",
+                fmri,
+                components.join(",")
+            )
+        }
+        UselessComponent(name) => log!(level, "component {} is not needed by any package", name),
+        MissingComponentForPackage(fmri) => log!(level, "missing component for {}", fmri),
+        RenamedNeedsRenamed(fmri_a, fmri_b) => log!(
+            level,
+            "renamed package {} needs renamed package {}",
+            fmri_a.get_package_name_as_ref_string(),
+            fmri_b.get_package_name_as_ref_string()
+        ),
+        RenamedPackageInComponent(package, component) => log!(
+            level,
+            "package {} is renamed and is in component {}",
+            package.get_package_name_as_ref_string(),
+            component
+        ),
+        ObsoletedPackageInComponent(package, component) => log!(
+            level,
+            "package {} is obsolete and is in component {}",
+            package.get_package_name_as_ref_string(),
+            component
+        ),
+        UnRunnableMakeCommand(command, path, failure, stderr) => log!(
+            level,
+            "can't run {} in {:?} ({}): {}",
+            command, path, failure, stderr
+        ),
+
+        NonExistingRequired(depend_type, dependency_type, required_by, component_name) => {
+            let (name, fmri) = depend_type.get_name_and_content_as_string();
+
+            let package_or_component_name = if dependency_type == &DependencyTypes::Runtime {
+                required_by.get_package_name_as_ref_string().clone()
+            } else {
+                format!("component {}", component_name)
+            };
+
+            log!(
+                level,
+                "package {} doesn't exist, but is required by {}",
+                fmri,
+                match dependency_type {
+                    DependencyTypes::Runtime => {
+                        format!("package {} (runtime, {})", package_or_component_name, name)
                     }
-                )
-            }
-            NonExistingRequiredByRenamed(depend_type, dependency_type, required_by) => {
-                let (name, fmri) = depend_type.clone().get_name_and_content_as_string();
+                    DependencyTypes::Build => {
+                        format!("{} (build, component)", package_or_component_name)
+                    }
+                    DependencyTypes::Test => {
+                        format!("{} (test, component)", package_or_component_name)
+                    }
+                    DependencyTypes::SystemBuild => {
+                        format!("{} (build, system)", package_or_component_name)
+                    }
+                    DependencyTypes::SystemTest => {
+                        format!("{} (test, system)", package_or_component_name)
+                    }
+                    DependencyTypes::None => panic!("DependencyTypes can't be None"),
+                }
+            )
+        }
+        NonExistingRequiredByRenamed(depend_type, dependency_type, required_by) => {
+            let (name, fmri) = depend_type.get_name_and_content_as_string();
 
-                let package_name = required_by.get_package_name_as_ref_string();
+            let package_name = required_by.get_package_name_as_ref_string();
 
-                warn!(
-                    "package {} doesn't exist, but is required by renamed package {}",
-                    fmri,
-                    match dependency_type {
-                        DependencyTypes::Runtime => {
-                            format!("{} (runtime, {})", package_name, name)
-                        }
-                        DependencyTypes::Build => {
-                            format!("{} (build, {})", package_name, name)
-                        }
-                        DependencyTypes::Test => {
-                            format!("{} (test, {})", package_name, name)
-                        }
-                        DependencyTypes::SystemBuild => {
-                            format!("{} (system-build)", package_name)
-                        }
-                        DependencyTypes::SystemTest => {
-                            format!("{} (system-test)", package_name)
-                        }
-                        DependencyTypes::None => panic!("DependencyTypes can't be None"),
+            log!(
+                level,
+                "package {} doesn't exist, but is required by renamed package {}",
+                fmri,
+                match dependency_type {
+                    DependencyTypes::Runtime => {
+                        format!("{} (runtime, {})", package_name, name)
                     }
-                )
-            }
+                    DependencyTypes::Build => {
+                        format!("{} (build, {})", package_name, name)
+                    }
+                    DependencyTypes::Test => {
+                        format!("{} (test, {})", package_name, name)
+                    }
+                    DependencyTypes::SystemBuild => {
+                        format!("{} (system-build)", package_name)
+                    }
+                    DependencyTypes::SystemTest => {
+                        format!("{} (system-test)", package_name)
+                    }
+                    DependencyTypes::None => panic!("DependencyTypes can't be None"),
+                }
+            )
+        }
 
-            ObsoletedRequired(depend_type, dependency_type, required_by, component_name) => {
-                let (name, fmri) = depend_type.clone().get_name_and_content_as_string();
+        ObsoletedRequired(depend_type, dependency_type, required_by, component_name) => {
+            let (name, fmri) = depend_type.get_name_and_content_as_string();
 
-                let package_or_component_name = if dependency_type == &DependencyTypes::Runtime {
-                    required_by.get_package_name_as_ref_string().clone()
-                } else {
-                    format!("component {}", component_name)
-                };
-
-                error!(
-                    "obsoleted package {} is required by {}",
-                    fmri,
-                    match dependency_type {
-                        DependencyTypes::Runtime => {
-                            format!("package {} (runtime, {})", package_or_component_name, name)
-                        }
-                        DependencyTypes::Build => {
-                            format!("{} (build, component)", package_or_component_name)
-                        }
-                        DependencyTypes::Test => {
-                            format!("{} (test, component)", package_or_component_name)
-                        }
-                        DependencyTypes::SystemBuild => {
-                            format!("{} (build, system)", package_or_component_name)
-                        }
-                        DependencyTypes::SystemTest => {
-                            format!("{} (test, system)", package_or_component_name)
-                        }
-                        DependencyTypes::None => panic!("DependencyTypes can't be None"),
+            let package_or_component_name = if dependency_type == &DependencyTypes::Runtime {
+                required_by.get_package_name_as_ref_string().clone()
+            } else {
+                format!("component {}", component_name)
+            };
+
+            log!(
+                level,
+                "obsoleted package {} is required by {}",
+                fmri,
+                match dependency_type {
+                    DependencyTypes::Runtime => {
+                        format!("package {} (runtime, {})", package_or_component_name, name)
                     }
-                );
-            }
+                    DependencyTypes::Build => {
+                        format!("{} (build, component)", package_or_component_name)
+                    }
+                    DependencyTypes::Test => {
+                        format!("{} (test, component)", package_or_component_name)
+                    }
+                    DependencyTypes::SystemBuild => {
+                        format!("{} (build, system)", package_or_component_name)
+                    }
+                    DependencyTypes::SystemTest => {
+                        format!("{} (test, system)", package_or_component_name)
+                    }
+                    DependencyTypes::None => panic!("DependencyTypes can't be None"),
+                }
+            );
+        }
 
-            ObsoletedRequiredByRenamed(depend_type, dependency_type, required_by) => {
-                let (name, fmri) = depend_type.clone().get_name_and_content_as_string();
+        ObsoletedRequiredByRenamed(depend_type, dependency_type, required_by) => {
+            let (name, fmri) = depend_type.get_name_and_content_as_string();
 
-                let package_name = required_by.get_package_name_as_ref_string();
+            let package_name = required_by.get_package_name_as_ref_string();
 
-                error!(
-                    "obsoleted package {} is required by renamed package {}",
-                    fmri,
-                    match dependency_type {
-                        DependencyTypes::Runtime => {
-                            format!("{} (runtime, {})", package_name, name)
-                        }
-                        DependencyTypes::Build => {
-                            format!("{} (build, component)", package_name)
-                        }
-                        DependencyTypes::Test => {
-                            format!("{} (test, component)", package_name)
-                        }
-                        DependencyTypes::SystemBuild => {
-                            format!("{} (system-build, system)", package_name)
-                        }
-                        DependencyTypes::SystemTest => {
-                            format!("{} (system-test, system)", package_name)
-                        }
-                        DependencyTypes::None => panic!("DependencyTypes can't be None"),
+            log!(
+                level,
+                "obsoleted package {} is required by renamed package {}",
+                fmri,
+                match dependency_type {
+                    DependencyTypes::Runtime => {
+                        format!("{} (runtime, {})", package_name, name)
                     }
-                );
-            }
+                    DependencyTypes::Build => {
+                        format!("{} (build, component)", package_name)
+                    }
+                    DependencyTypes::Test => {
+                        format!("{} (test, component)", package_name)
+                    }
+                    DependencyTypes::SystemBuild => {
+                        format!("{} (system-build, system)", package_name)
+                    }
+                    DependencyTypes::SystemTest => {
+                        format!("{} (system-test, system)", package_name)
+                    }
+                    DependencyTypes::None => panic!("DependencyTypes can't be None"),
+                }
+            );
+        }
 
-            PartlyObsoletedRequired(depend_type, dependency_type, required_by, component_name) => {
-                let (name, fmri) = depend_type.clone().get_name_and_content_as_string();
+        PartlyObsoletedRequired(depend_type, dependency_type, required_by, component_name) => {
+            let (name, fmri) = depend_type.get_name_and_content_as_string();
 
-                let package_or_component_name = if dependency_type == &DependencyTypes::Runtime {
-                    required_by.get_package_name_as_ref_string().clone()
-                } else {
-                    format!("component {}", component_name)
-                };
-
-                warn!(
-                    "obsoleted package {} is required by {}",
-                    fmri,
-                    match dependency_type {
-                        DependencyTypes::Runtime => {
-                            format!("package {} (runtime, {})", package_or_component_name, name)
-                        }
-                        DependencyTypes::Build => {
-                            format!("{} (build, component)", package_or_component_name)
-                        }
-                        DependencyTypes::Test => {
-                            format!("{} (test, component)", package_or_component_name)
-                        }
-                        DependencyTypes::SystemBuild => {
-                            format!("{} (build, system)", package_or_component_name)
-                        }
-                        DependencyTypes::SystemTest => {
-                            format!("{} (test, system)", package_or_component_name)
-                        }
-                        DependencyTypes::None => panic!("DependencyTypes can't be None"),
+            let package_or_component_name = if dependency_type == &DependencyTypes::Runtime {
+                required_by.get_package_name_as_ref_string().clone()
+            } else {
+                format!("component {}", component_name)
+            };
+
+            log!(
+                level,
+                "obsoleted package {} is required by {}",
+                fmri,
+                match dependency_type {
+                    DependencyTypes::Runtime => {
+                        format!("package {} (runtime, {})", package_or_component_name, name)
                     }
-                );
-            }
-            PartlyObsoletedRequiredByRenamed(depend_type, dependency_type, required_by) => {
-                let (name, fmri) = depend_type.clone().get_name_and_content_as_string();
+                    DependencyTypes::Build => {
+                        format!("{} (build, component)", package_or_component_name)
+                    }
+                    DependencyTypes::Test => {
+                        format!("{} (test, component)", package_or_component_name)
+                    }
+                    DependencyTypes::SystemBuild => {
+                        format!("{} (build, system)", package_or_component_name)
+                    }
+                    DependencyTypes::SystemTest => {
+                        format!("{} (test, system)", package_or_component_name)
+                    }
+                    DependencyTypes::None => panic!("DependencyTypes can't be None"),
+                }
+            );
+        }
+        PartlyObsoletedRequiredByRenamed(depend_type, dependency_type, required_by) => {
+            let (name, fmri) = depend_type.get_name_and_content_as_string();
 
-                let package_name = required_by.get_package_name_as_ref_string();
+            let package_name = required_by.get_package_name_as_ref_string();
 
-                warn!(
-                    "obsoleted package {} is required by renamed package {}",
-                    fmri,
-                    match dependency_type {
-                        DependencyTypes::Runtime => {
-                            format!("{} (runtime, {})", package_name, name)
-                        }
-                        DependencyTypes::Build => {
-                            format!("{} (build, component)", package_name)
-                        }
-                        DependencyTypes::Test => {
-                            format!("{} (test, component)", package_name)
-                        }
-                        DependencyTypes::SystemBuild => {
-                            format!("{} (system-build, system)", package_name)
-                        }
-                        DependencyTypes::SystemTest => {
-                            format!("{} (system-test, system)", package_name)
-                        }
-                        DependencyTypes::None => panic!("DependencyTypes can't be None"),
+            log!(
+                level,
+                "obsoleted package {} is required by renamed package {}",
+                fmri,
+                match dependency_type {
+                    DependencyTypes::Runtime => {
+                        format!("{} (runtime, {})", package_name, name)
                     }
-                );
+                    DependencyTypes::Build => {
+                        format!("{} (build, component)", package_name)
+                    }
+                    DependencyTypes::Test => {
+                        format!("{} (test, component)", package_name)
+                    }
+                    DependencyTypes::SystemBuild => {
+                        format!("{} (system-build, system)", package_name)
+                    }
+                    DependencyTypes::SystemTest => {
+                        format!("{} (system-test, system)", package_name)
+                    }
+                    DependencyTypes::None => panic!("DependencyTypes can't be None"),
+                }
+            );
+        }
+        TighterThanIncorporation(depend_type, required_by, incorporation, incorporated) => log!(
+            level,
+            "package {} requires {} tighter than incorporation {} ({})",
+            required_by.get_package_name_as_ref_string(),
+            depend_type.get_fmris_ref()[0],
+            incorporation.get_package_name_as_ref_string(),
+            incorporated
+        ),
+        TimestampAnomaly(fmri, older) => match older {
+            None => log!(level, "package {} has implausible timestamp", fmri),
+            Some(older) => log!(
+                level,
+                "package {} has newer timestamp than {}, but older version",
+                fmri, older
+            ),
+        },
+        ForbiddenCategoryDependency(category, component_name, fmri, dependency_component) => {
+            log!(
+                level,
+                "[{}] component {} needs {} from component {}, which is forbidden by policy",
+                category,
+                component_name,
+                fmri.get_package_name_as_ref_string(),
+                dependency_component
+            )
+        }
+        PackageNotAllowedInCategory(category, component_name, fmri) => log!(
+            level,
+            "[{}] component {} delivers {}, which is not allowed by policy",
+            category,
+            component_name,
+            fmri.get_package_name_as_ref_string()
+        ),
+        InvalidFmri(raw_fmri, location) => log!(level, "invalid fmri '{}' in {}", raw_fmri, location),
+        RequireAnyAlternatives(required_by, alternatives) => {
+            let viable = alternatives
+                .iter()
+                .filter(|(_, state)| state == &AlternativeState::Viable)
+                .count();
+
+            log!(
+                level,
+                "require-any of package {} has {} viable alternative(s){}: {}",
+                required_by.get_package_name_as_ref_string(),
+                viable,
+                if viable == 1 {
+                    " (effectively require)"
+                } else {
+                    ""
+                },
+                alternatives
+                    .iter()
+                    .map(|(fmri, state)| format!("{} ({})", fmri, state))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        }
+        MalformedPkg5(path, reason) => log!(level, "malformed pkg5 {:?}: {}", path, reason),
+        PackageNameViolation(fmri, component_name, reason) => log!(
+            level,
+            "package name {} violates naming conventions ({}), component: {}",
+            fmri.get_package_name_as_ref_string(),
+            reason,
+            component_name
+        ),
+        BuildDependencyCycle(components) => log!(
+            level,
+            "build dependency cycle between components: {}",
+            components.join(", ")
+        ),
+        ReclassifyDependency(fmri, from, to, component_name) => log!(
+            level,
+            "{} dependency {} of component {} looks like {} dependency",
+            from,
+            fmri.get_package_name_as_ref_string(),
+            component_name,
+            to
+        ),
+        SelfDependency(fmri, dependency_type, component_name) => log!(
+            level,
+            "package {} has {} dependency on itself, component: {}",
+            fmri.get_package_name_as_ref_string(),
+            dependency_type,
+            component_name
+        ),
+        DuplicateDependency(depend_type, dependency_type, required_by, component_name) => {
+            let (name, content) = depend_type.get_name_and_content_as_string();
+            match required_by {
+                Some(required_by) => log!(
+                    level,
+                    "package {} has duplicate {} dependency ({}) {}, component: {}",
+                    required_by.get_package_name_as_ref_string(),
+                    dependency_type,
+                    name,
+                    content,
+                    component_name
+                ),
+                None => log!(
+                    level,
+                    "component {} has duplicate {} dependency ({}) {}",
+                    component_name, dependency_type, name, content
+                ),
             }
         }
+        BrokenRenameChain(chain, state) => log!(
+            level,
+            "rename chain {} ends in {} package",
+            chain
+                .iter()
+                .map(|fmri| fmri.get_package_name_as_ref_string().clone())
+                .collect::<Vec<String>>()
+                .join(" -> "),
+            state
+        ),
+        RenameLoop(packages) => log!(
+            level,
+            "rename loop: {} -> {}",
+            packages
+                .iter()
+                .map(|fmri| fmri.get_package_name_as_ref_string().clone())
+                .collect::<Vec<String>>()
+                .join(" -> "),
+            packages
+                .first()
+                .expect("empty rename loop")
+                .get_package_name_as_ref_string()
+        ),
+        ObsoleteWithDependencies(fmri, depend_types) => log!(
+            level,
+            "obsolete package {} has {} dependencies: {}",
+            fmri,
+            depend_types.len(),
+            depend_types
+                .iter()
+                .map(|depend_type| depend_type.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        UnversionedModule(fmri, component_name, suggested_name) => log!(
+            level,
+            "module package {} has no interpreter-versioned variant{}, component: {}",
+            fmri.get_package_name_as_ref_string(),
+            suggested_name
+                .as_ref()
+                .map(|name| format!(" (e.g. {})", name))
+                .unwrap_or_default(),
+            component_name
+        ),
+        UnsatisfiableRequireAny(depend_type, dependency_type, required_by, component_name) => log!(
+            level,
+            "package {} has {} require-any dependency {} without any existing non-obsoleted alternative, component: {}",
+            required_by.get_package_name_as_ref_string(),
+            dependency_type,
+            depend_type.get_name_and_content_as_string().1,
+            component_name
+        ),
+        BrokenConditional(fmri, predicate, dependency_type, required_by, component_name) => {
+            let describe = |half: &Option<(FMRI, AlternativeState)>| match half {
+                Some((fmri, state)) => format!("{} ({})", fmri, state),
+                None => "ok".to_owned(),
+            };
+            log!(
+                level,
+                "package {} has broken {} conditional dependency, fmri: {}, predicate: {}, component: {}",
+                required_by.get_package_name_as_ref_string(),
+                dependency_type,
+                describe(fmri),
+                describe(predicate),
+                component_name
+            )
+        }
+        IncorporationMismatch(incorporation, incorporated, published, component_name) => log!(
+            level,
+            "incorporation {} incorporates {}, but published version is {}, component: {}",
+            incorporation.get_package_name_as_ref_string(),
+            incorporated,
+            published,
+            component_name
+        ),
+        DependencyBloat(component_name, build_style, count, peer_average, dependencies) => log!(
+            level,
+            "component {} has {} build dependencies, {} style components have {} on average, least referenced: {}",
+            component_name,
+            count,
+            build_style,
+            peer_average,
+            dependencies
+                .iter()
+                .map(|fmri| fmri.get_package_name_as_ref_string().clone())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        UnversionedRequire(required, required_by, component_name) => log!(
+            level,
+            "package {} requires {} without version, component: {}",
+            required_by.get_package_name_as_ref_string(),
+            required.get_package_name_as_ref_string(),
+            component_name
+        ),
+        FileConflict(path, packages, component_names) => log!(
+            level,
+            "path {} is delivered by packages {}, components: {}",
+            path,
+            packages
+                .iter()
+                .map(|fmri| fmri.get_package_name_as_ref_string().clone())
+                .collect::<Vec<String>>()
+                .join(", "),
+            component_names.join(", ")
+        ),
+        MirrorSkew(fmri, publisher, versions) => log!(
+            level,
+            "origins of publisher {} disagree on package {}: {}",
+            publisher,
+            fmri.get_package_name_as_ref_string(),
+            versions
+                .iter()
+                .map(|(origin, version)| format!(
+                    "{} has {}",
+                    origin,
+                    version.as_deref().unwrap_or("nothing")
+                ))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        MediatorConflict(path, packages, component_names, reason) => log!(
+            level,
+            "mediated path {} of packages {}: {}, components: {}",
+            path,
+            packages
+                .iter()
+                .map(|fmri| fmri.get_package_name_as_ref_string().clone())
+                .collect::<Vec<String>>()
+                .join(", "),
+            reason,
+            component_names.join(", ")
+        ),
+        StaleRequiredPackage(fmri, component_name) => log!(
+            level,
+            "component {} has {} in REQUIRED_PACKAGES, but no runtime dependency of its packages references it",
+            component_name,
+            fmri.get_package_name_as_ref_string()
+        ),
+        PackageMissingInPkg5(fmri, component_name) => log!(
+            level,
+            "package {} is published and has manifest in component {}, but it is not in the pkg5",
+            fmri, component_name
+        ),
+        StalePkg5Entry(fmri, component_name) => log!(
+            level,
+            "package {} is in the pkg5, but component {} has no manifest of it",
+            fmri, component_name
+        ),
+        OnlyStubDependents(fmri, dependents, component_name) => log!(
+            level,
+            "package {} is required only by obsoleted or renamed packages: {}, component: {}",
+            fmri.get_package_name_as_ref_string(),
+            dependents
+                .iter()
+                .map(|fmri| fmri.get_package_name_as_ref_string().clone())
+                .collect::<Vec<String>>()
+                .join(", "),
+            component_name
+        ),
+        ObsoleteComponent(component_name, packages) => log!(
+            level,
+            "all {} packages of component {} are obsoleted",
+            packages.len(),
+            component_name
+        ),
+        OrphanPackage(fmri, component_name) => log!(
+            level,
+            "package {} is not reachable from root packages by runtime dependencies, component: {}",
+            fmri, component_name
+        ),
+        CrossPublisherDependency(required, dependency_type, required_by, publisher, component_name) => log!(
+            level,
+            "{} dependency {} of {} is published only by {}, component: {}",
+            dependency_type,
+            required.get_package_name_as_ref_string(),
+            required_by,
+            publisher,
+            component_name
+        ),
+        RequiredVersionNotPublished(depend_type, required_by, published, component_name) => log!(
+            level,
+            "{} needs {}, but the newest published version is {}, component: {}",
+            required_by,
+            depend_type.get_fmris_ref()[0],
+            published,
+            component_name
+        ),
+        UnavailableIncorporatedVersion(incorporation, incorporated, state, component_name) => log!(
+            level,
+            "incorporation {} incorporates {}, but this version is {}, component: {}",
+            incorporation.get_package_name_as_ref_string(),
+            incorporated,
+            match state {
+                AlternativeState::Missing => "not published".to_owned(),
+                state => state.to_string(),
+            },
+            component_name
+        ),
+        ForeignPackageInPkg5(fmri, component_name, builder_name, component_path, builder_path) => log!(
+            level,
+            "package {} is in pkg5 of component {} ({:?}), but it is built by component {} ({:?})",
+            fmri,
+            component_name,
+            component_path,
+            builder_name,
+            builder_path
+        ),
+        DuplicateComponentName(component_name, paths) => log!(
+            level,
+            "component name {} is used by more directories: {:?}",
+            component_name, paths
+        ),
+        PublisherVersionSkew(newer, newer_state, older, older_state, component_name) => log!(
+            level,
+            "package {} has newer version in publisher {} ({}, {}) than in publisher {} ({}, {}), component: {}",
+            newer.get_package_name_as_ref_string(),
+            newer.get_publisher_as_ref_string().cloned().unwrap_or_default(),
+            newer.get_version_as_string().unwrap_or_default(),
+            newer_state,
+            older.get_publisher_as_ref_string().cloned().unwrap_or_default(),
+            older.get_version_as_string().unwrap_or_default(),
+            older_state,
+            component_name
+        ),
+        DivergentDependencies(fmri, other, only_in_fmri, only_in_other, component_name) => {
+            let describe = |depend_types: &Vec<DependTypes>| {
+                depend_types
+                    .iter()
+                    .map(|depend_type| depend_type.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            };
+            log!(
+                level,
+                "package {} has different runtime dependencies than {}, only in the first: [{}], only in the second: [{}], component: {}",
+                fmri,
+                other,
+                describe(only_in_fmri),
+                describe(only_in_other),
+                component_name
+            )
+        }
+        IncorporationCycle(incorporations, conflicts) => log!(
+            level,
+            "incorporation cycle between: {}, incorporated versions not matching published ones: [{}]",
+            incorporations
+                .iter()
+                .map(|fmri| fmri.to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+            conflicts
+                .iter()
+                .map(|fmri| fmri.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        DuplicatePkg5Entry(fmri, path) => log!(
+            level,
+            "package {} is listed more times in {:?}",
+            fmri, path
+        ),
+        Pkg5NameMismatch(component_name, pkg5_name, expected) => log!(
+            level,
+            "pkg5 of component {} has name {}, but component name is {}",
+            component_name, pkg5_name, expected
+        ),
+        ObsoletedPackageBuilt(fmri, component_name, only_obsoleted) => log!(
+            level,
+            "package {} is obsolete, but component {} still has its manifest{}",
+            fmri.get_package_name_as_ref_string(),
+            component_name,
+            if *only_obsoleted {
+                " (component has manifests only of obsoleted packages)"
+            } else {
+                ""
+            }
+        ),
+        ExternalDependency(required, dependency_type, required_by, publisher, component_name) => log!(
+            level,
+            "{} dependency {} of {} is published only by external publisher {}, component: {}",
+            dependency_type,
+            required.get_package_name_as_ref_string(),
+            required_by,
+            publisher,
+            component_name
+        ),
+        InvalidClassification(fmri, component_name, value, reason) => match value {
+            None => log!(
+                level,
+                "package {} has no info.classification, component: {}",
+                fmri, component_name
+            ),
+            Some(value) => log!(
+                level,
+                "package {} has invalid info.classification {} ({}), component: {}",
+                fmri, value, reason, component_name
+            ),
+        },
+        MissingLicenseAction(fmri, component_name) => log!(
+            level,
+            "package {} delivers files, but has no license action, component: {}",
+            fmri, component_name
+        ),
+        MissingComponentLicense(component_name, makefile) => log!(
+            level,
+            "component {} doesn't declare COMPONENT_LICENSE or COMPONENT_LICENSE_FILE in {:?}",
+            component_name, makefile
+        ),
+        PackageVersionsInMultipleComponents(fmri, components) => log!(
+            level,
+            "package {} is in multiple components at different versions: {}",
+            fmri,
+            components
+                .iter()
+                .map(|(component_name, version)| match version {
+                    Some(version) => format!("{}@{}", component_name, version),
+                    None => component_name.clone(),
+                })
+                .collect::<Vec<String>>()
+                .join(",")
+        ),
+        UnavailableInstalledPackage(fmri, state) => log!(level, "installed package {} is {}", fmri, match state {
+            AlternativeState::Missing => "not published".to_owned(),
+            state => state.to_string(),
+        }),
+        HeldBackInstalledPackage(fmri, newest, incorporation) => log!(
+            level,
+            "installed package {} is held back by incorporation {}, newest published is {}",
+            fmri, incorporation, newest
+        ),
+        StaleCatalog(catalog, last_modified, days) => log!(
+            level,
+            "catalog {:?} is {} days old (last modified {})",
+            catalog, days, last_modified
+        ),
+        CatalogAgeSkew(catalog, newest, days) => log!(
+            level,
+            "catalog {:?} is {} days older than catalog {:?} of other publisher",
+            catalog, days, newest
+        ),
+        InvalidPkg5Key(path, key, reason) => log!(level, "key \"{}\" of pkg5 {:?} {}", key, path, reason),
+        RequiredFromOtherDepot(depend_type, dependency_type, required_by, component_name, url) => log!(
+            level,
+            "package {} isn't in loaded catalogs, but depot {} has it, required by {} ({})",
+            depend_type.get_name_and_content_as_string().1,
+            url,
+            match dependency_type {
+                DependencyTypes::Runtime => {
+                    format!("package {}", required_by.get_package_name_as_ref_string())
+                }
+                _ => format!("component {}", component_name),
+            },
+            dependency_type
+        ),
+        HistoryNameCollision(fmri, record, component_name) => log!(
+            level,
+            "package {} (component {}) has name of package obsoleted or renamed in history ({})",
+            fmri.get_package_name_as_ref_string(),
+            component_name,
+            record
+        ),
+        EncumberedPlacement(component_name, encumbered, evidence) => log!(
+            level,
+            "component {} should {}be in encumbered repository, licenses: {}",
+            component_name,
+            match encumbered {
+                true => "",
+                false => "not ",
+            },
+            evidence.join(", ")
+        ),
     }
-
-    problems.count()
 }
@@ -0,0 +1,168 @@
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    fs::metadata,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use fmri::FMRI;
+
+use crate::{
+    graph_delta::{graph_delta, GraphChange},
+    packages::components::{Components, Dependents},
+    problems::{AlternativeState, Problem, Problems},
+};
+
+/// Event sent to listeners of [`AnalysisSession`] after reload
+#[derive(Debug)]
+pub enum SessionEvent {
+    /// data file changed, contains changes of dependency graph since previous data
+    DataReloaded(Vec<GraphChange>),
+    /// problems file changed
+    ProblemsReloaded { introduced: usize, fixed: usize },
+}
+
+/// Listener of [`SessionEvent`]s, see [`AnalysisSession::subscribe`]
+pub type SessionListener = Box<dyn FnMut(&SessionEvent)>;
+
+/// Long-lived analysis for tools like editor integrations
+///
+/// Data and problems are reloaded only when their files change, results of queries are cached
+/// until the next reload.
+pub struct AnalysisSession {
+    data_path: PathBuf,
+    problems_path: PathBuf,
+    components: Components,
+    problems: Problems,
+    data_modified: Option<SystemTime>,
+    problems_modified: Option<SystemTime>,
+    alternative_states: HashMap<String, AlternativeState>,
+    dependents: HashMap<String, Option<Dependents>>,
+    listeners: Vec<SessionListener>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+impl AnalysisSession {
+    /// Loads data (data.bin) and problems (problems.bin, optional) of analysis
+    pub fn open(data_path: PathBuf, problems_path: PathBuf) -> Result<Self, String> {
+        let mut session = Self {
+            components: Components::try_deserialize(&data_path)?,
+            problems: Problems::new(),
+            data_modified: modified(&data_path),
+            problems_modified: None,
+            data_path,
+            problems_path,
+            alternative_states: HashMap::new(),
+            dependents: HashMap::new(),
+            listeners: vec![],
+        };
+
+        if session.problems_path.exists() {
+            session.problems = Problems::deserialize(&*session.problems_path.to_string_lossy())?;
+            session.problems_modified = modified(&session.problems_path);
+        }
+
+        Ok(session)
+    }
+
+    /// Registers listener called after every reload
+    pub fn subscribe<F: FnMut(&SessionEvent) + 'static>(&mut self, listener: F) {
+        self.listeners.push(Box::new(listener))
+    }
+
+    /// Reloads changed files, clears cached queries and notifies listeners, returns whether
+    /// something was reloaded
+    pub fn reload(&mut self) -> Result<bool, String> {
+        let mut events = vec![];
+
+        let data_modified = modified(&self.data_path);
+        if data_modified != self.data_modified {
+            let components = Components::try_deserialize(&self.data_path)?;
+            events.push(SessionEvent::DataReloaded(graph_delta(
+                &self.components,
+                &components,
+            )));
+            self.components = components;
+            self.data_modified = data_modified;
+            self.alternative_states.clear();
+            self.dependents.clear();
+        }
+
+        let problems_modified = modified(&self.problems_path);
+        if problems_modified != self.problems_modified {
+            let problems = match problems_modified {
+                Some(_) => Problems::deserialize(&*self.problems_path.to_string_lossy())?,
+                None => Problems::new(),
+            };
+            let diff = problems.diff(&self.problems);
+            events.push(SessionEvent::ProblemsReloaded {
+                introduced: diff.introduced.len(),
+                fixed: diff.fixed.len(),
+            });
+            self.problems = problems;
+            self.problems_modified = problems_modified;
+        }
+
+        for event in &events {
+            for listener in &mut self.listeners {
+                listener(event)
+            }
+        }
+
+        Ok(!events.is_empty())
+    }
+
+    pub fn components(&self) -> &Components {
+        &self.components
+    }
+
+    pub fn problems(&self) -> &Problems {
+        &self.problems
+    }
+
+    /// Cached [`Components::get_alternative_state`], e.g. to warn about obsoleted package in
+    /// REQUIRED_PACKAGES
+    pub fn alternative_state(&mut self, fmri: &FMRI) -> AlternativeState {
+        let components = &self.components;
+        *self
+            .alternative_states
+            .entry(fmri.get_package_name_as_ref_string().clone())
+            .or_insert_with(|| components.get_alternative_state(fmri))
+    }
+
+    /// Cached [`Components::get_dependencies_with_fmri`] (incorporate dependencies are counted)
+    pub fn dependents(&mut self, fmri: &FMRI) -> Option<&Dependents> {
+        let components = &self.components;
+        self.dependents
+            .entry(fmri.get_package_name_as_ref_string().clone())
+            .or_insert_with(|| components.get_dependencies_with_fmri(fmri, false))
+            .as_ref()
+    }
+
+    pub fn problems_of_component(&self, component_name: &str) -> Vec<&Problem> {
+        self.problems
+            .get_ref()
+            .iter()
+            .filter(|problem| {
+                problem
+                    .get_component_names_ref()
+                    .iter()
+                    .any(|name| *name == component_name)
+            })
+            .collect()
+    }
+}
+
+impl Debug for AnalysisSession {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnalysisSession")
+            .field("data_path", &self.data_path)
+            .field("problems_path", &self.problems_path)
+            .field("listeners", &self.listeners.len())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,85 @@
+use std::{
+    ffi::OsString,
+    fs::{rename, File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+/// Lock of state file, it is held until drop
+///
+/// Lock is taken on `<path>.lock`, so the state file itself can be replaced by rename.
+pub struct StateLock(File);
+
+impl StateLock {
+    /// Waits for lock that can be held by multiple readers
+    pub fn shared<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Self, String> {
+        let file = open_lock_file(path.as_ref())?;
+        file.lock_shared()
+            .map_err(|e| format!("failed to lock {:?}: {}", path.as_ref(), e))?;
+        Ok(Self(file))
+    }
+
+    /// Waits for lock that can be held only by one writer
+    pub fn exclusive<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Self, String> {
+        let file = open_lock_file(path.as_ref())?;
+        file.lock()
+            .map_err(|e| format!("failed to lock {:?}: {}", path.as_ref(), e))?;
+        Ok(Self(file))
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
+    }
+}
+
+fn open_lock_file(path: &Path) -> Result<File, String> {
+    let mut lock_path: OsString = path.as_os_str().to_owned();
+    lock_path.push(".lock");
+
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| format!("failed to open lock file {:?}: {}", lock_path, e))
+}
+
+/// Reads state file under shared lock
+pub fn read_locked<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Vec<u8>, String> {
+    let _lock = StateLock::shared(path)?;
+
+    let mut data = Vec::new();
+    File::open(path)
+        .map_err(|e| format!("failed to open {:?}: {}", path.as_ref(), e))?
+        .read_to_end(&mut data)
+        .map_err(|e| format!("failed to read {:?}: {}", path.as_ref(), e))?;
+    Ok(data)
+}
+
+/// Writes state file under exclusive lock
+///
+/// Data are written into temporary file next to `path` and renamed over it, so readers never see
+/// partially written file.
+pub fn write_atomic<P: AsRef<Path> + ?Sized>(path: &P, data: &[u8]) -> Result<(), String> {
+    let path = path.as_ref();
+    let _lock = StateLock::exclusive(path)?;
+
+    let mut temporary_path: OsString = path.as_os_str().to_owned();
+    temporary_path.push(format!(".{}.tmp", process::id()));
+    let temporary_path = PathBuf::from(temporary_path);
+
+    let write = || -> std::io::Result<()> {
+        let mut file = File::create(&temporary_path)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+        rename(&temporary_path, path)
+    };
+
+    write().map_err(|e| {
+        let _ = std::fs::remove_file(&temporary_path);
+        format!("failed to write {:?}: {}", path, e)
+    })
+}
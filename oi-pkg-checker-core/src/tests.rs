@@ -1,3 +1,139 @@
+mod actions_test;
+mod artifacts_test;
+mod catalogs_c_test;
+mod components_test;
+mod config_test;
+#[allow(
+    clippy::bool_assert_comparison,
+    clippy::unnecessary_mut_passed,
+    clippy::unnecessary_to_owned
+)]
 mod dependencies_test;
+mod dependency_paths_test;
+mod depot_test;
+mod dossier_test;
+mod fix_plan_test;
+mod force_graph_test;
+mod git_test;
+mod graph_delta_test;
+mod history_test;
+mod incremental_test;
+mod init_test;
+mod installed_image_test;
+mod make_cache_test;
+mod manifest_dependencies_test;
+mod metadata_test;
+mod open_indiana_oi_userland_git_test;
+#[allow(
+    clippy::bool_assert_comparison,
+    clippy::unnecessary_mut_passed,
+    clippy::unnecessary_to_owned
+)]
 mod package_test;
+#[allow(clippy::unnecessary_to_owned)]
 mod package_versions_test;
+mod pinning_test;
+mod pkg_repository_test;
+mod problems_test;
+mod rewrite_rules_test;
+mod session_test;
+mod snapshot_test;
+mod transforms_test;
+mod version_parts_test;
+
+use fmri::FMRI;
+
+use crate::packages::{
+    component::Component, components::Components, depend_types::DependTypes,
+    dependencies::Dependencies, dependency::Dependency, package::Package,
+    package_versions::PackageVersions,
+};
+
+/// Package used as fixture in tests, [`TestPackage::add`] adds it as the only package of new
+/// component (`""` by default) without dependencies (unless set)
+pub(crate) struct TestPackage {
+    fmri: FMRI,
+    component_name: String,
+    obsolete: bool,
+    renamed: bool,
+    runtime: Vec<DependTypes>,
+    build: Vec<DependTypes>,
+}
+
+impl TestPackage {
+    pub(crate) fn new(fmri: &str) -> Self {
+        Self {
+            fmri: FMRI::parse_raw(fmri).unwrap(),
+            component_name: String::new(),
+            obsolete: false,
+            renamed: false,
+            runtime: vec![],
+            build: vec![],
+        }
+    }
+
+    pub(crate) fn component(mut self, component_name: &str) -> Self {
+        self.component_name = component_name.to_owned();
+        self
+    }
+
+    pub(crate) fn obsolete(mut self, obsolete: bool) -> Self {
+        self.obsolete = obsolete;
+        self
+    }
+
+    pub(crate) fn renamed(mut self, renamed: bool) -> Self {
+        self.renamed = renamed;
+        self
+    }
+
+    pub(crate) fn runtime(mut self, runtime: Vec<DependTypes>) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    pub(crate) fn build(mut self, build: Vec<DependTypes>) -> Self {
+        self.build = build;
+        self
+    }
+
+    /// Adds require dependencies on `fmris` into runtime dependencies
+    pub(crate) fn requires(mut self, fmris: &[&str]) -> Self {
+        for fmri in fmris {
+            self.runtime
+                .push(DependTypes::Require(FMRI::parse_raw(fmri).unwrap()));
+        }
+        self
+    }
+
+    /// Adds incorporate dependencies on `fmris` into runtime dependencies
+    pub(crate) fn incorporates(mut self, fmris: &[&str]) -> Self {
+        for fmri in fmris {
+            self.runtime
+                .push(DependTypes::Incorporate(FMRI::parse_raw(fmri).unwrap()));
+        }
+        self
+    }
+
+    pub(crate) fn add(self, components: &mut Components) {
+        let mut package = Package::new(self.fmri.clone(), self.obsolete, self.renamed);
+
+        let mut dependencies = Dependencies::new();
+        for depend_type in &self.runtime {
+            dependencies.add(Dependency::new(depend_type));
+        }
+        package.add_runtime_dependencies(dependencies);
+
+        let mut dependencies = Dependencies::new();
+        for depend_type in &self.build {
+            dependencies.add(Dependency::new(depend_type));
+        }
+        package.add_build_dependencies(dependencies);
+
+        let mut package_versions = PackageVersions::new(self.fmri);
+        package_versions.add_package(package);
+        let mut component = Component::new(self.component_name);
+        component.add(package_versions);
+        components.add(component);
+    }
+}
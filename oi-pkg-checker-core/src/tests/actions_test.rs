@@ -0,0 +1,193 @@
+use std::fs::{create_dir_all, remove_dir_all, write};
+
+use fmri::{FMRIList, FMRI};
+
+use crate::{
+    assets::{
+        manifest_dependencies::load_manifest_actions,
+        open_indiana_oi_userland_git::{ComponentPackages, ComponentPackagesList},
+    },
+    packages::{
+        actions::{
+            parse_actions, Action, Attributes, DirAction, DriverAction, FileAction, GroupAction,
+            LegacyAction, LicenseAction, LinkAction, SetAction, UserAction,
+        },
+        component::Component,
+        components::Components,
+        package_versions::PackageVersions,
+    },
+};
+
+fn attributes(attributes: &[(&str, &[&str])]) -> Attributes {
+    attributes
+        .iter()
+        .map(|(name, values)| {
+            (
+                name.to_string(),
+                values.iter().map(|value| value.to_string()).collect(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn parse_manifest_actions() {
+    let actions = parse_actions(
+        "# comment\n\
+        set name=pkg.fmri value=pkg:/library/a@1.0\n\
+        set name=pkg.summary value=\"Library A\"\n\
+        set name=info.classification \\\n    value=org.opensolaris.category.2008:System/Libraries \\\n    value=\"Development/Other Languages\"\n\
+        dir path=usr/lib owner=root group=bin mode=0755\n\
+        file usr/lib/liba.so.1 path=usr/lib/liba.so.1 mode=0555 variant.arch=i386 facet.devel=true\n\
+        link path=usr/lib/liba.so target=liba.so.1 mediator=a\n\
+        hardlink path=usr/bin/b target=a\n\
+        driver name=a perms=\"* 0666 root sys\" alias=pci1 alias=pci2\n\
+        user username=a uid=10 group=a gcos-field=\"User A\"\n\
+        group groupname=a gid=10\n\
+        legacy pkg=SUNWa name=\"Library A\"\n\
+        license a.license license=MIT\n\
+        depend type=require fmri=pkg:/library/b\n\
+        file path=usr/lib/liba.so.2 mode=0555\n\
+        link path=usr/lib/liba.so.2\n\
+        unknown name=a\n",
+    );
+
+    assert_eq!(
+        actions,
+        vec![
+            Action::Set(SetAction {
+                name: "pkg.fmri".to_owned(),
+                values: vec!["pkg:/library/a@1.0".to_owned()],
+            }),
+            Action::Set(SetAction {
+                name: "pkg.summary".to_owned(),
+                values: vec!["Library A".to_owned()],
+            }),
+            Action::Set(SetAction {
+                name: "info.classification".to_owned(),
+                values: vec![
+                    "org.opensolaris.category.2008:System/Libraries".to_owned(),
+                    "Development/Other Languages".to_owned()
+                ],
+            }),
+            Action::Dir(DirAction {
+                path: "usr/lib".to_owned(),
+                mode: Some("0755".to_owned()),
+                owner: Some("root".to_owned()),
+                group: Some("bin".to_owned()),
+                attributes: Attributes::new(),
+            }),
+            Action::File(FileAction {
+                path: "usr/lib/liba.so.1".to_owned(),
+                payload: Some("usr/lib/liba.so.1".to_owned()),
+                mode: Some("0555".to_owned()),
+                owner: None,
+                group: None,
+                attributes: attributes(&[("facet.devel", &["true"]), ("variant.arch", &["i386"])]),
+            }),
+            Action::Link(LinkAction {
+                path: "usr/lib/liba.so".to_owned(),
+                target: "liba.so.1".to_owned(),
+                attributes: attributes(&[("mediator", &["a"])]),
+            }),
+            Action::Hardlink(LinkAction {
+                path: "usr/bin/b".to_owned(),
+                target: "a".to_owned(),
+                attributes: Attributes::new(),
+            }),
+            Action::Driver(DriverAction {
+                name: "a".to_owned(),
+                attributes: attributes(&[
+                    ("alias", &["pci1", "pci2"]),
+                    ("perms", &["* 0666 root sys"])
+                ]),
+            }),
+            Action::User(UserAction {
+                username: "a".to_owned(),
+                uid: Some("10".to_owned()),
+                group: Some("a".to_owned()),
+                attributes: attributes(&[("gcos-field", &["User A"])]),
+            }),
+            Action::Group(GroupAction {
+                groupname: "a".to_owned(),
+                gid: Some("10".to_owned()),
+                attributes: Attributes::new(),
+            }),
+            Action::Legacy(LegacyAction {
+                pkg: "SUNWa".to_owned(),
+                attributes: attributes(&[("name", &["Library A"])]),
+            }),
+            Action::License(LicenseAction {
+                license: "MIT".to_owned(),
+                payload: Some("a.license".to_owned()),
+                attributes: Attributes::new(),
+            }),
+            Action::File(FileAction {
+                path: "usr/lib/liba.so.2".to_owned(),
+                payload: None,
+                mode: Some("0555".to_owned()),
+                owner: None,
+                group: None,
+                attributes: Attributes::new(),
+            }),
+        ]
+    );
+    assert_eq!(actions[4].path(), Some(&"usr/lib/liba.so.1".to_owned()));
+    assert_eq!(actions[0].path(), None);
+}
+
+#[test]
+fn set_actions_from_manifests() {
+    let directory = std::env::temp_dir().join(format!("manifest-actions-{}", std::process::id()));
+    let path_to_component = directory.join("library/a");
+    create_dir_all(path_to_component.join("build")).unwrap();
+    write(
+        path_to_component.join("a.p5m"),
+        "set name=pkg.fmri value=pkg:/$(COMPONENT_FMRI)@$(IPS_COMPONENT_VERSION)\n\
+        file path=usr/lib/$(MACH64)/liba.so.1\n",
+    )
+    .unwrap();
+    write(
+        path_to_component.join("build/manifest-i386-a.mogrified"),
+        "set name=pkg.fmri value=pkg:/library/a@1.0\n\
+        file path=usr/lib/amd64/liba.so.1\n",
+    )
+    .unwrap();
+    write(
+        path_to_component.join("Makefile"),
+        "COMPONENT_FMRI= library/a\n",
+    )
+    .unwrap();
+
+    let package_actions =
+        load_manifest_actions(&ComponentPackagesList::from(vec![ComponentPackages {
+            component_name: "library/a".to_owned(),
+            path_to_component,
+            packages_in_component: FMRIList::new(),
+        }]));
+    remove_dir_all(&directory).unwrap();
+
+    let mut components = Components::new();
+    let mut component = Component::new("library/a".to_owned());
+    component.add(PackageVersions::new(FMRI::parse_raw("library/a").unwrap()));
+    components.add(component);
+    components.set_package_actions(package_actions);
+
+    assert_eq!(
+        components.get_ref()[0].get_versions_ref()[0].get_actions_ref(),
+        &vec![
+            Action::Set(SetAction {
+                name: "pkg.fmri".to_owned(),
+                values: vec!["pkg:/library/a@1.0".to_owned()],
+            }),
+            Action::File(FileAction {
+                path: "usr/lib/amd64/liba.so.1".to_owned(),
+                payload: None,
+                mode: None,
+                owner: None,
+                group: None,
+                attributes: Attributes::new(),
+            }),
+        ]
+    );
+}
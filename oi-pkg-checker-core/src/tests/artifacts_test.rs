@@ -0,0 +1,48 @@
+use std::fs::{create_dir_all, remove_dir_all};
+
+use crate::artifacts::Artifacts;
+
+#[test]
+fn rotate_snapshots() {
+    let directory = std::env::temp_dir().join(format!("artifacts-test-{}", std::process::id()));
+    let artifacts = Artifacts::new(directory.clone());
+
+    assert!(artifacts.snapshots().unwrap().is_empty());
+
+    let oldest = artifacts.create_snapshot(9).unwrap();
+    let middle = artifacts.create_snapshot(10).unwrap();
+    let newest = artifacts.create_snapshot(100).unwrap();
+    create_dir_all(directory.join("other")).unwrap();
+
+    assert_eq!(
+        artifacts.snapshots().unwrap(),
+        vec![oldest.clone(), middle.clone(), newest.clone()]
+    );
+    assert_eq!(artifacts.latest().unwrap(), Some(newest.clone()));
+
+    assert_eq!(artifacts.rotate(2).unwrap(), vec![oldest.clone()]);
+    assert!(!oldest.exists());
+    assert!(directory.join("other").exists());
+    assert_eq!(artifacts.snapshots().unwrap(), vec![middle, newest]);
+
+    assert!(artifacts.rotate(5).unwrap().is_empty());
+
+    remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn snapshots_in_same_second() {
+    let directory =
+        std::env::temp_dir().join(format!("artifacts-same-second-{}", std::process::id()));
+    let artifacts = Artifacts::new(directory.clone());
+
+    let first = artifacts.create_snapshot(10).unwrap();
+    let second = artifacts.create_snapshot(10).unwrap();
+    let third = artifacts.create_snapshot(10).unwrap();
+    let snapshots = artifacts.snapshots().unwrap();
+    remove_dir_all(directory).unwrap();
+
+    assert_ne!(first, second);
+    assert!(second.ends_with("snapshot-00000000000000000010-0001"));
+    assert_eq!(snapshots, vec![first, second, third]);
+}
@@ -0,0 +1,189 @@
+use std::fs::{create_dir_all, remove_dir_all, write};
+
+use fmri::FMRI;
+
+use crate::{
+    assets::{catalogs_c::load_catalog_c, open_indiana_oi_userland_git::ComponentPackagesList},
+    packages::{components::Components, dependency::Dependency},
+    problems::{Problem, Problems},
+    DependTypes,
+};
+
+#[test]
+fn load_catalog_as_stream() {
+    let directory = std::env::temp_dir().join(format!("catalog-stream-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let catalog = directory.join("catalog.dependency.C");
+    write(
+        &catalog,
+        r#"{
+            "openindiana.org": {
+                "library/b": [
+                    {"version": "1.0,5.11-2024.0.0.0:20240101T000000Z"},
+                    {
+                        "actions": ["depend fmri=library/c@2.0 type=require"],
+                        "version": "2.0,5.11-2024.0.0.0:20240201T000000Z"
+                    }
+                ],
+                "library/a": [
+                    {
+                        "actions": ["set name=pkg.renamed value=true", "depend fmri=library/b type=require"],
+                        "version": "1.0,5.11-2024.0.0.0:20240101T000000Z"
+                    }
+                ],
+                "library/@": [{"version": "1.0"}]
+            },
+            "_SIGNATURE": {"sha-1": "a"}
+        }"#,
+    )
+    .unwrap();
+
+    let mut components = Components::new();
+    let mut problems = Problems::new();
+    load_catalog_c(
+        &mut components,
+        catalog,
+        &mut problems,
+        &ComponentPackagesList::from(vec![]),
+    );
+    remove_dir_all(&directory).unwrap();
+
+    let package_versions = |name: &str| {
+        components
+            .get_package_versions_from_fmri(&FMRI::parse_raw(name).unwrap())
+            .unwrap()
+    };
+    let b = package_versions("library/b");
+    assert_eq!(
+        b.get_packages_ref()
+            .iter()
+            .map(|package| package.fmri_ref().to_string())
+            .collect::<Vec<_>>(),
+        vec!["pkg://openindiana.org/library/b@2.0,5.11-2024.0.0.0:20240201T000000Z"]
+    );
+    assert_eq!(
+        b.get_packages_ref()[0].get_runtime_dependencies(),
+        &vec![Dependency::new(&DependTypes::Require(
+            FMRI::parse_raw("library/c@2.0").unwrap()
+        ))]
+    );
+    assert!(package_versions("library/a").is_renamed());
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::InvalidFmri(
+            "library/@".to_owned(),
+            "catalog (publisher openindiana.org)".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn obsolete_with_dependencies() {
+    let directory =
+        std::env::temp_dir().join(format!("catalog-obsolete-deps-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let catalog = directory.join("catalog.dependency.C");
+    write(
+        &catalog,
+        r#"{
+            "openindiana.org": {
+                "library/a": [
+                    {
+                        "actions": ["set name=pkg.obsolete value=true", "depend fmri=library/c@1.0 type=require"],
+                        "version": "1.0,5.11-2024.0.0.0:20240101T000000Z"
+                    }
+                ],
+                "library/b": [
+                    {
+                        "actions": ["set name=pkg.obsolete value=true"],
+                        "version": "1.0,5.11-2024.0.0.0:20240101T000000Z"
+                    }
+                ]
+            },
+            "_SIGNATURE": {"sha-1": "a"}
+        }"#,
+    )
+    .unwrap();
+
+    let mut components = Components::new();
+    let mut problems = Problems::new();
+    load_catalog_c(
+        &mut components,
+        catalog,
+        &mut problems,
+        &ComponentPackagesList::from(vec![]),
+    );
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::ObsoleteWithDependencies(
+            FMRI::parse_raw("pkg://openindiana.org/library/a@1.0,5.11-2024.0.0.0:20240101T000000Z")
+                .unwrap(),
+            vec![DependTypes::Require(
+                FMRI::parse_raw("library/c@1.0").unwrap()
+            )]
+        )]
+    );
+}
+
+#[test]
+fn invalid_publisher_and_version() {
+    let directory =
+        std::env::temp_dir().join(format!("catalog-invalid-version-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let catalog = directory.join("catalog.dependency.C");
+    write(
+        &catalog,
+        r#"{
+            "bad@publisher": {
+                "library/a": [{"version": "1.0,5.11-2024.0.0.0:20240101T000000Z"}]
+            },
+            "openindiana.org": {
+                "library/b": [
+                    {"version": "1.0,5.11-2024.0.0.0:20240101T000000Z"},
+                    {"version": "1.a,5.11-2024.0.0.0:20240201T000000Z"}
+                ]
+            },
+            "_SIGNATURE": {"sha-1": "a"}
+        }"#,
+    )
+    .unwrap();
+
+    let mut components = Components::new();
+    let mut problems = Problems::new();
+    load_catalog_c(
+        &mut components,
+        catalog,
+        &mut problems,
+        &ComponentPackagesList::from(vec![]),
+    );
+    remove_dir_all(&directory).unwrap();
+
+    assert!(components
+        .get_package_versions_from_fmri(&FMRI::parse_raw("library/a").unwrap())
+        .is_none());
+    assert_eq!(
+        components
+            .get_package_versions_from_fmri(&FMRI::parse_raw("library/b").unwrap())
+            .unwrap()
+            .get_packages_ref()
+            .iter()
+            .map(|package| package.fmri_ref().to_string())
+            .collect::<Vec<_>>(),
+        vec!["pkg://openindiana.org/library/b@1.0,5.11-2024.0.0.0:20240101T000000Z"]
+    );
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::InvalidFmri(
+                "pkg://bad@publisher/library/a".to_owned(),
+                "catalog (publisher bad@publisher)".to_owned()
+            ),
+            Problem::InvalidFmri(
+                "library/b@1.a,5.11-2024.0.0.0:20240201T000000Z".to_owned(),
+                "catalog (publisher openindiana.org)".to_owned()
+            ),
+        ]
+    );
+}
@@ -0,0 +1,1341 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::PathBuf,
+};
+
+use fmri::{FMRIList, FMRI};
+
+use crate::assets::catalogs_c::{CatalogOrigin, CatalogTimestamp};
+use crate::config::{
+    CatalogStaleness, CategoryPolicy, NamingConventions, Origin, OrphanPackages, PublisherConfig,
+    Reclassification, UnversionedRequires,
+};
+use crate::packages::component::Component;
+use crate::packages::components::{Components, DotOptions};
+use crate::packages::depend_types::DependTypes;
+use crate::packages::dependency_type::DependencyTypes;
+use crate::packages::package_versions::PackageVersions;
+use crate::packages::version_parts::{current_timestamp, timestamp_days};
+use crate::problems::{AlternativeState, Problem, Problems};
+use crate::tests::TestPackage;
+
+#[test]
+fn get_renamed_required_by_components() {
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/old@1.0")
+        .renamed(true)
+        .runtime(vec![DependTypes::Require(
+            FMRI::parse_raw("pkg:/library/new@1.0").unwrap(),
+        )])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/new@1.0")
+        .component("library/new")
+        .add(&mut components);
+    TestPackage::new("pkg:/application@1.0")
+        .component("application")
+        .build(vec![DependTypes::Require(
+            FMRI::parse_raw("pkg:/library/old").unwrap(),
+        )])
+        .add(&mut components);
+
+    assert_eq!(
+        components.get_rename_target(&FMRI::parse_raw("pkg:/library/old").unwrap()),
+        Some(FMRI::parse_raw("pkg:/library/new@1.0").unwrap())
+    );
+
+    assert_eq!(
+        components.get_renamed_required_by_components(),
+        vec![(
+            FMRI::parse_raw("pkg:/library/old").unwrap(),
+            DependencyTypes::Build,
+            "application".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn get_incorporations() {
+    let mut components = Components::new();
+    for (fmri, incorporated) in [
+        ("pkg:/incorporation/a@1.0", "pkg:/library/x@1"),
+        ("pkg:/incorporation/b@1.0", "pkg:/library/x@1.2.3"),
+    ] {
+        TestPackage::new(fmri)
+            .runtime(vec![DependTypes::Incorporate(
+                FMRI::parse_raw(incorporated).unwrap(),
+            )])
+            .add(&mut components);
+    }
+    TestPackage::new("pkg:/application@1.0")
+        .runtime(vec![DependTypes::Require(
+            FMRI::parse_raw("pkg:/library/x@1.2").unwrap(),
+        )])
+        .add(&mut components);
+
+    assert_eq!(components.get_incorporations()["library/x"].len(), 2);
+
+    let mut problems = Problems::new();
+    components.check_version_constraints(&mut problems);
+
+    assert_eq!(problems.get_ref().len(), 1);
+    assert_eq!(
+        problems.get_ref()[0].get_fmris_ref()[2].get_package_name_as_ref_string(),
+        "incorporation/a"
+    );
+}
+
+#[test]
+fn get_dependencies_with_fmri() {
+    let needed = FMRI::parse_raw("pkg:/library/a@1.0").unwrap();
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .add(&mut components);
+    TestPackage::new("pkg:/library/b@1.0")
+        .component("library/b")
+        .runtime(vec![DependTypes::Require(needed.clone())])
+        .add(&mut components);
+    for incorporation in ["pkg:/x-incorporation@1.0", "pkg:/y-incorporation@1.0"] {
+        TestPackage::new(incorporation)
+            .component("meta-packages/incorporations")
+            .runtime(vec![DependTypes::Incorporate(needed.clone())])
+            .add(&mut components);
+    }
+
+    let dependents = components
+        .get_dependencies_with_fmri(&needed, false)
+        .unwrap();
+    assert_eq!(dependents.dependencies.len(), 1);
+    assert_eq!(
+        dependents.dependencies[0].0,
+        FMRI::parse_raw("pkg:/library/b@1.0").unwrap()
+    );
+    assert_eq!(
+        dependents.incorporations,
+        HashMap::from([
+            (FMRI::parse_raw("pkg:/x-incorporation@1.0").unwrap(), 1),
+            (FMRI::parse_raw("pkg:/y-incorporation@1.0").unwrap(), 1),
+        ])
+    );
+    assert_eq!(dependents.aggregated_edges(), 2);
+
+    let dependents = components
+        .get_dependencies_with_fmri(&needed, true)
+        .unwrap();
+    assert_eq!(dependents.dependencies.len(), 3);
+    assert!(dependents.incorporations.is_empty());
+
+    assert!(components
+        .get_dependencies_with_fmri(&FMRI::parse_raw("pkg:/library/b@1.0").unwrap(), false)
+        .is_none());
+}
+
+#[test]
+fn check_category_policies() {
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/flash@1.0")
+        .component("encumbered/flash")
+        .add(&mut components);
+    TestPackage::new("pkg:/web/browser@1.0")
+        .component("openindiana/browser")
+        .build(vec![DependTypes::Require(
+            FMRI::parse_raw("pkg:/library/flash").unwrap(),
+        )])
+        .add(&mut components);
+    TestPackage::new("pkg:/desktop@1.0")
+        .component("meta-packages/desktop")
+        .add(&mut components);
+
+    let policies = vec![
+        CategoryPolicy {
+            category: "openindiana/".to_owned(),
+            forbidden_dependency_categories: vec!["encumbered/".to_owned()],
+            ..Default::default()
+        },
+        CategoryPolicy {
+            category: "meta-packages/".to_owned(),
+            allowed_package_prefixes: vec!["group/".to_owned()],
+            ..Default::default()
+        },
+    ];
+
+    let mut problems = Problems::new();
+    components.check_category_policies(&mut problems, &policies);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::ForbiddenCategoryDependency(
+                "openindiana/".to_owned(),
+                "openindiana/browser".to_owned(),
+                FMRI::parse_raw("pkg:/library/flash").unwrap(),
+                "encumbered/flash".to_owned()
+            ),
+            Problem::PackageNotAllowedInCategory(
+                "meta-packages/".to_owned(),
+                "meta-packages/desktop".to_owned(),
+                FMRI::parse_raw("pkg:/desktop").unwrap(),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn check_require_any_alternatives() {
+    let mut components = Components::new();
+    TestPackage::new("pkg:/shell/app@1.0")
+        .component("shell/app")
+        .runtime(vec![DependTypes::RequireAny(FMRIList::from(vec![
+            FMRI::parse_raw("pkg:/shell/bash@5.0").unwrap(),
+            FMRI::parse_raw("pkg:/shell/zsh@6.0").unwrap(),
+            FMRI::parse_raw("pkg:/shell/ksh").unwrap(),
+        ]))])
+        .add(&mut components);
+    TestPackage::new("pkg:/shell/bash@5.1")
+        .component("shell/bash")
+        .add(&mut components);
+    TestPackage::new("pkg:/shell/zsh@5.9")
+        .component("shell/zsh")
+        .add(&mut components);
+
+    let mut problems = Problems::new();
+    components.check_require_any_alternatives(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::RequireAnyAlternatives(
+            FMRI::parse_raw("pkg:/shell/app").unwrap(),
+            vec![
+                (
+                    FMRI::parse_raw("pkg:/shell/bash@5.0").unwrap(),
+                    AlternativeState::Viable
+                ),
+                (
+                    FMRI::parse_raw("pkg:/shell/zsh@6.0").unwrap(),
+                    AlternativeState::Stale
+                ),
+                (
+                    FMRI::parse_raw("pkg:/shell/ksh").unwrap(),
+                    AlternativeState::Missing
+                ),
+            ],
+        )]
+    );
+}
+
+#[test]
+fn check_package_names() {
+    let mut components = Components::new();
+    for fmri in [
+        "pkg:/library/python/pip-311",
+        "pkg:/library/python/pip",
+        "pkg:/library/python/pip-3.11",
+        "pkg:/Library/foo",
+        "pkg:/entire",
+        "pkg:/library/foo@1.0-2024.0.0.1",
+        "pkg:/library/bar@1.0-0.151.1",
+    ] {
+        TestPackage::new(fmri).component("a").add(&mut components);
+    }
+
+    let mut problems = Problems::new();
+    components.check_package_names(&mut problems, &NamingConventions::default());
+
+    let names: Vec<&String> = problems
+        .get_ref()
+        .iter()
+        .map(|problem| problem.get_fmris_ref()[0].get_package_name_as_ref_string())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "library/python/pip-3.11",
+            "Library/foo",
+            "Library/foo",
+            "library/bar"
+        ]
+    );
+
+    let naming = NamingConventions {
+        forbidden_prefixes: vec!["library/py".to_owned()],
+        branch_pattern: String::new(),
+        ..NamingConventions::default()
+    };
+    let mut problems = Problems::new();
+    components.check_package_names(&mut problems, &naming);
+    assert_eq!(problems.get_ref().len(), 6);
+}
+
+#[test]
+fn check_build_cycles() {
+    let mut components = Components::new();
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .build(vec![require("pkg:/library/b")])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/b@1.0")
+        .component("library/b")
+        .build(vec![require("pkg:/library/a"), require("pkg:/library/c")])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/c@1.0")
+        .component("library/c")
+        .runtime(vec![require("pkg:/library/a")])
+        .add(&mut components);
+
+    let mut problems = Problems::new();
+    components.check_build_cycles(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::BuildDependencyCycle(vec![
+            "library/a".to_owned(),
+            "library/b".to_owned()
+        ])]
+    );
+}
+
+#[test]
+fn check_dependency_kinds() {
+    let mut components = Components::new();
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .runtime(vec![
+            require("pkg:/developer/build/pkg-config"),
+            require("pkg:/library/b"),
+        ])
+        .build(vec![
+            require("pkg:/library/b"),
+            require("pkg:/library/c"),
+            require("pkg:/library/python/d-311"),
+        ])
+        .add(&mut components);
+
+    let mut problems = Problems::new();
+    components.check_dependency_kinds(&mut problems, &Reclassification::default());
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::ReclassifyDependency(
+                FMRI::parse_raw("pkg:/developer/build/pkg-config").unwrap(),
+                DependencyTypes::Runtime,
+                DependencyTypes::Build,
+                "library/a".to_owned()
+            ),
+            Problem::ReclassifyDependency(
+                FMRI::parse_raw("pkg:/library/c").unwrap(),
+                DependencyTypes::Build,
+                DependencyTypes::Runtime,
+                "library/a".to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn check_self_dependencies() {
+    let mut components = Components::new();
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .runtime(vec![require("pkg:/library/a@1.0")])
+        .build(vec![require("pkg:/library/a"), require("pkg:/library/b")])
+        .add(&mut components);
+
+    let mut problems = Problems::new();
+    components.check_self_dependencies(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::SelfDependency(
+                FMRI::parse_raw("pkg:/library/a").unwrap(),
+                DependencyTypes::Runtime,
+                "library/a".to_owned()
+            ),
+            Problem::SelfDependency(
+                FMRI::parse_raw("pkg:/library/a").unwrap(),
+                DependencyTypes::Build,
+                "library/a".to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn check_rename_chains() {
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/a@1.0")
+        .renamed(true)
+        .runtime(vec![DependTypes::Require(
+            FMRI::parse_raw("pkg:/library/b@1.0").unwrap(),
+        )])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/b@1.0")
+        .renamed(true)
+        .runtime(vec![DependTypes::Require(
+            FMRI::parse_raw("pkg:/library/c@1.0").unwrap(),
+        )])
+        .add(&mut components);
+
+    assert_eq!(
+        components.resolve_rename(&FMRI::parse_raw("pkg:/library/a").unwrap()),
+        FMRI::parse_raw("pkg:/library/c@1.0").unwrap()
+    );
+
+    let mut problems = Problems::new();
+    components.check_rename_chains(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::BrokenRenameChain(
+                vec![
+                    FMRI::parse_raw("pkg:/library/a").unwrap(),
+                    FMRI::parse_raw("pkg:/library/b").unwrap(),
+                    FMRI::parse_raw("pkg:/library/c").unwrap(),
+                ],
+                AlternativeState::Missing
+            ),
+            Problem::BrokenRenameChain(
+                vec![
+                    FMRI::parse_raw("pkg:/library/b").unwrap(),
+                    FMRI::parse_raw("pkg:/library/c").unwrap(),
+                ],
+                AlternativeState::Missing
+            ),
+        ]
+    );
+}
+
+#[test]
+fn dot_export() {
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .runtime(vec![
+            require("pkg:/library/b@1.0"),
+            DependTypes::Optional(FMRI::parse_raw("pkg:/library/gone").unwrap()),
+        ])
+        .build(vec![require("pkg:/library/c@1.0")])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/b@1.0")
+        .component("library/b")
+        .renamed(true)
+        .runtime(vec![require("pkg:/library/d@1.0")])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/c@1.0")
+        .component("library/c")
+        .add(&mut components);
+    components.add_obsoleted(FMRI::parse_raw("pkg:/library/gone@1.0").unwrap());
+
+    assert_eq!(
+        components.to_dot(&DotOptions::default()),
+        "digraph dependencies {
+    \"pkg:/library/a\" [label=\"library/a\"];
+    \"pkg:/library/b\" [label=\"library/b\", style=filled, fillcolor=orange];
+    \"pkg:/library/c\" [label=\"library/c\"];
+    \"pkg:/library/d\" [label=\"library/d\"];
+    \"pkg:/library/gone\" [label=\"library/gone\", style=filled, fillcolor=gray];
+    \"pkg:/library/a\" -> \"pkg:/library/b\";
+    \"pkg:/library/a\" -> \"pkg:/library/gone\" [label=\"optional\"];
+    \"pkg:/library/b\" -> \"pkg:/library/d\";
+}
+"
+    );
+
+    assert_eq!(
+        components.to_dot(&DotOptions {
+            build: true,
+            color: false,
+            focus: Some("library/c".to_owned()),
+            ..DotOptions::default()
+        }),
+        "digraph dependencies {
+    \"component:library/a\" [label=\"library/a\", shape=box];
+    \"component:library/c\" [label=\"library/c\", shape=box];
+    \"pkg:/library/c\" [label=\"library/c\"];
+    \"component:library/a\" -> \"component:library/c\" [style=dashed];
+}
+"
+    );
+
+    assert_eq!(
+        components.to_dot(&DotOptions {
+            focus: Some("library/d".to_owned()),
+            depth: 2,
+            ..DotOptions::default()
+        }),
+        "digraph dependencies {
+    \"pkg:/library/a\" [label=\"library/a\"];
+    \"pkg:/library/b\" [label=\"library/b\", style=filled, fillcolor=orange];
+    \"pkg:/library/d\" [label=\"library/d\"];
+    \"pkg:/library/a\" -> \"pkg:/library/b\";
+    \"pkg:/library/b\" -> \"pkg:/library/d\";
+}
+"
+    );
+}
+
+#[test]
+fn check_rename_loops() {
+    let mut components = Components::new();
+    for (fmri, target) in [
+        ("pkg:/library/a@1.0", "pkg:/library/c@1.0"),
+        ("pkg:/library/c@1.0", "pkg:/library/b@1.0"),
+        ("pkg:/library/b@1.0", "pkg:/library/c@1.0"),
+    ] {
+        TestPackage::new(fmri)
+            .renamed(true)
+            .runtime(vec![DependTypes::Require(FMRI::parse_raw(target).unwrap())])
+            .add(&mut components);
+    }
+
+    let mut problems = Problems::new();
+    components.check_rename_chains(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::RenameLoop(vec![
+            FMRI::parse_raw("pkg:/library/b").unwrap(),
+            FMRI::parse_raw("pkg:/library/c").unwrap(),
+        ])]
+    );
+}
+
+#[test]
+fn check_versioned_modules() {
+    let mut components = Components::new();
+    for fmri in [
+        "pkg:/library/python/requests@2.31",
+        "pkg:/library/python/requests-311@2.31",
+        "pkg:/library/python/six-39@1.16",
+        "pkg:/library/python/attrs@23.1",
+        "pkg:/library/perl-5/xml-parser@2.46",
+    ] {
+        TestPackage::new(fmri).add(&mut components);
+    }
+
+    let mut problems = Problems::new();
+    components.check_versioned_modules(&mut problems, &NamingConventions::default());
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::UnversionedModule(
+                FMRI::parse_raw("pkg:/library/python/attrs").unwrap(),
+                "".to_owned(),
+                Some("library/python/attrs-311".to_owned())
+            ),
+            Problem::UnversionedModule(
+                FMRI::parse_raw("pkg:/library/perl-5/xml-parser").unwrap(),
+                "".to_owned(),
+                None
+            ),
+        ]
+    );
+}
+
+#[test]
+fn check_unsatisfiable_require_any() {
+    let mut components = Components::new();
+    TestPackage::new("pkg:/shell/app@1.0")
+        .component("shell/app")
+        .runtime(vec![
+            DependTypes::RequireAny(FMRIList::from(vec![
+                FMRI::parse_raw("pkg:/shell/bash").unwrap(),
+                FMRI::parse_raw("pkg:/shell/ksh").unwrap(),
+            ])),
+            DependTypes::RequireAny(FMRIList::from(vec![
+                FMRI::parse_raw("pkg:/shell/ksh").unwrap(),
+                FMRI::parse_raw("pkg:/shell/csh").unwrap(),
+            ])),
+        ])
+        .add(&mut components);
+    TestPackage::new("pkg:/shell/bash@5.1")
+        .component("shell/bash")
+        .add(&mut components);
+    components.add_obsoleted(FMRI::parse_raw("pkg:/shell/csh@1.0").unwrap());
+
+    let mut problems = Problems::new();
+    components.check_dependency_validity(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::UnsatisfiableRequireAny(
+            DependTypes::RequireAny(FMRIList::from(vec![
+                FMRI::parse_raw("pkg:/shell/ksh").unwrap(),
+                FMRI::parse_raw("pkg:/shell/csh").unwrap(),
+            ])),
+            DependencyTypes::Runtime,
+            FMRI::parse_raw("pkg:/shell/app").unwrap(),
+            "shell/app".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn check_broken_conditional() {
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/app@1.0")
+        .component("library/app")
+        .runtime(vec![DependTypes::Conditional(
+            FMRI::parse_raw("pkg:/library/plugin").unwrap(),
+            FMRI::parse_raw("pkg:/library/gone").unwrap(),
+        )])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/plugin@1.0")
+        .component("library/plugin")
+        .add(&mut components);
+    components.add_obsoleted(FMRI::parse_raw("pkg:/library/gone@1.0").unwrap());
+
+    let mut problems = Problems::new();
+    components.check_dependency_validity(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::BrokenConditional(
+            None,
+            Some((
+                FMRI::parse_raw("pkg:/library/gone").unwrap(),
+                AlternativeState::Obsolete
+            )),
+            DependencyTypes::Runtime,
+            FMRI::parse_raw("pkg:/library/app").unwrap(),
+            "library/app".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn check_incorporation_constraints() {
+    let mut components = Components::new();
+    TestPackage::new("pkg:/consolidation/userland/userland-incorporation@0.5.11-2024.0.0.1")
+        .component("consolidation/userland")
+        .runtime(vec![
+            DependTypes::Incorporate(FMRI::parse_raw("pkg:/library/zlib@1.3.1").unwrap()),
+            DependTypes::Incorporate(
+                FMRI::parse_raw("pkg:/library/libpng@1.6-2024.0.0.0").unwrap(),
+            ),
+        ])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/zlib@1.3.1-2024.0.0.0")
+        .component("library/zlib")
+        .add(&mut components);
+    TestPackage::new("pkg:/library/libpng@1.6.43-2024.0.0.1")
+        .component("library/libpng")
+        .add(&mut components);
+
+    let mut problems = Problems::new();
+    components.check_incorporation_constraints(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::IncorporationMismatch(
+            FMRI::parse_raw("pkg:/consolidation/userland/userland-incorporation@0.5.11-2024.0.0.1")
+                .unwrap(),
+            FMRI::parse_raw("pkg:/library/libpng@1.6-2024.0.0.0").unwrap(),
+            FMRI::parse_raw("pkg:/library/libpng@1.6.43-2024.0.0.1").unwrap(),
+            "library/libpng".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn check_incorporation_cycles() {
+    let incorporate = |fmri: &str| DependTypes::Incorporate(FMRI::parse_raw(fmri).unwrap());
+    let mut components = Components::new();
+    for (fmri, runtime) in [
+        (
+            "pkg:/incorporation/a@1.0-2024.0.0.0",
+            vec![incorporate("pkg:/incorporation/b@2.0")],
+        ),
+        (
+            "pkg:/incorporation/b@1.0-2024.0.0.0",
+            vec![
+                incorporate("pkg:/incorporation/a@1.0"),
+                incorporate("pkg:/library/c@1.0"),
+            ],
+        ),
+        ("pkg:/library/c@1.0-2024.0.0.0", vec![]),
+    ] {
+        TestPackage::new(fmri)
+            .component("incorporation")
+            .runtime(runtime)
+            .add(&mut components);
+    }
+
+    let mut problems = Problems::new();
+    components.check_incorporation_cycles(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::IncorporationCycle(
+            vec![
+                FMRI::parse_raw("pkg:/incorporation/a@1.0-2024.0.0.0").unwrap(),
+                FMRI::parse_raw("pkg:/incorporation/b@1.0-2024.0.0.0").unwrap(),
+            ],
+            vec![FMRI::parse_raw("pkg:/incorporation/b@2.0").unwrap()]
+        )]
+    );
+}
+
+#[test]
+fn check_incorporated_versions() {
+    let mut components = Components::new();
+    TestPackage::new("pkg:/consolidation/userland/userland-incorporation@0.5.11-2024.0.0.1")
+        .component("consolidation/userland")
+        .runtime(vec![
+            DependTypes::Incorporate(FMRI::parse_raw("pkg:/library/zlib@1.3-2024.0.0.0").unwrap()),
+            DependTypes::Incorporate(FMRI::parse_raw("pkg:/library/zlib@1.2-2023.0.0.0").unwrap()),
+            DependTypes::Incorporate(FMRI::parse_raw("pkg:/library/zlib@1.1").unwrap()),
+        ])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/zlib@1.3.1-2024.0.0.0")
+        .component("library/zlib")
+        .add(&mut components);
+    components.add_published(FMRI::parse_raw("pkg:/library/zlib@1.3-2024.0.0.0").unwrap());
+    components.add_published(FMRI::parse_raw("pkg:/library/zlib@1.3.1-2024.0.0.0").unwrap());
+    components.add_obsoleted(FMRI::parse_raw("pkg:/library/zlib@1.2-2023.0.0.0").unwrap());
+
+    let mut problems = Problems::new();
+    components.check_incorporated_versions(&mut problems);
+
+    let incorporation =
+        FMRI::parse_raw("pkg:/consolidation/userland/userland-incorporation@0.5.11-2024.0.0.1")
+            .unwrap();
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::UnavailableIncorporatedVersion(
+                incorporation.clone(),
+                FMRI::parse_raw("pkg:/library/zlib@1.2-2023.0.0.0").unwrap(),
+                AlternativeState::Obsolete,
+                "consolidation/userland".to_owned()
+            ),
+            Problem::UnavailableIncorporatedVersion(
+                incorporation,
+                FMRI::parse_raw("pkg:/library/zlib@1.1").unwrap(),
+                AlternativeState::Missing,
+                "consolidation/userland".to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn check_dependency_bloat() {
+    let mut components = Components::new();
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    let mut build_styles = HashMap::new();
+
+    for name in [
+        "library/a",
+        "library/c",
+        "library/d",
+        "library/e",
+        "library/f",
+    ] {
+        TestPackage::new(&format!("pkg:/{}@1.0", name))
+            .component(name)
+            .build(vec![require("pkg:/developer/gcc")])
+            .add(&mut components);
+        build_styles.insert(name.to_owned(), "configure".to_owned());
+    }
+
+    let mut build = vec![require("pkg:/library/b")];
+    for i in 0..9 {
+        build.push(require(&format!("pkg:/library/x{}", i)));
+    }
+    TestPackage::new("pkg:/library/bloated@1.0")
+        .component("library/bloated")
+        .runtime(vec![require("pkg:/library/b")])
+        .build(build)
+        .add(&mut components);
+    build_styles.insert("library/bloated".to_owned(), "configure".to_owned());
+
+    let mut problems = Problems::new();
+    components.check_dependency_bloat(&mut problems, &build_styles);
+
+    let mut dependencies: Vec<FMRI> = (0..9)
+        .map(|i| FMRI::parse_raw(&format!("pkg:/library/x{}", i)).unwrap())
+        .collect();
+    dependencies.push(FMRI::parse_raw("pkg:/library/b").unwrap());
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::DependencyBloat(
+            "library/bloated".to_owned(),
+            "configure".to_owned(),
+            10,
+            3,
+            dependencies
+        )]
+    );
+}
+
+#[test]
+fn check_unversioned_requires() {
+    let mut components = Components::new();
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .runtime(vec![
+            require("pkg:/library/b"),
+            require("pkg:/library/c@1.0"),
+        ])
+        .add(&mut components);
+    TestPackage::new("pkg:/metapackages/build-essential@1.0")
+        .component("metapackages/build-essential")
+        .runtime(vec![require("pkg:/library/b")])
+        .add(&mut components);
+
+    components.set_packages_with_files(BTreeSet::from(["library/a".to_owned()]));
+
+    let mut problems = Problems::new();
+    let mut unversioned_requires = UnversionedRequires::default();
+    components.check_unversioned_requires(&mut problems, &unversioned_requires);
+    assert!(problems.get_ref().is_empty());
+
+    unversioned_requires.enabled = true;
+    components.check_unversioned_requires(&mut problems, &unversioned_requires);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::UnversionedRequire(
+            FMRI::parse_raw("pkg:/library/b").unwrap(),
+            FMRI::parse_raw("pkg:/library/a").unwrap(),
+            "library/a".to_owned()
+        )]
+    );
+    assert!(std::ptr::eq(
+        unversioned_requires.regexes().unwrap(),
+        unversioned_requires.regexes().unwrap()
+    ));
+
+    let invalid = UnversionedRequires {
+        enabled: true,
+        allowed_packages: vec!["(".to_owned()],
+        ..Default::default()
+    };
+    let mut problems = Problems::new();
+    components.check_unversioned_requires(&mut problems, &invalid);
+    assert!(problems.get_ref().is_empty());
+}
+
+#[test]
+fn check_mirror_skew() {
+    let mut components = Components::new();
+    components.set_publishers(vec![PublisherConfig {
+        name: "openindiana.org".to_owned(),
+        aliases: vec!["oi".to_owned()],
+        origins: vec![Origin {
+            url: "https://pkg.openindiana.org/hipster".to_owned(),
+            catalog: "catalog.dependency.C".into(),
+            ..Origin::default()
+        }],
+        ..PublisherConfig::default()
+    }]);
+    assert_eq!(components.get_publisher_name("oi"), "openindiana.org");
+    assert_eq!(
+        components.get_origin_of_catalog("catalog.dependency.C".as_ref()),
+        Some((
+            &"openindiana.org".to_owned(),
+            &"https://pkg.openindiana.org/hipster".to_owned()
+        ))
+    );
+
+    for (origin, version) in [("https://a", "1.0"), ("https://b", "1.1")] {
+        components.add_catalog_origin(CatalogOrigin {
+            catalog: format!("{}.C", origin).into(),
+            publisher: "openindiana.org".to_owned(),
+            origin: origin.to_owned(),
+            packages: BTreeMap::from([
+                (
+                    "library/a".to_owned(),
+                    FMRI::parse_raw(&format!("pkg://openindiana.org/library/a@{}", version))
+                        .unwrap(),
+                ),
+                (
+                    "library/b".to_owned(),
+                    FMRI::parse_raw("pkg://openindiana.org/library/b@1.0").unwrap(),
+                ),
+            ]),
+        });
+    }
+
+    let mut problems = Problems::new();
+    components.check_mirror_skew(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::MirrorSkew(
+            FMRI::parse_raw("pkg:/library/a").unwrap(),
+            "openindiana.org".to_owned(),
+            vec![
+                ("https://a".to_owned(), Some("@1.0".to_owned())),
+                ("https://b".to_owned(), Some("@1.1".to_owned())),
+            ]
+        )]
+    );
+}
+
+#[test]
+fn check_publisher_version_skew() {
+    let mut components = Components::new();
+    for fmri in [
+        "pkg://openindiana.org/library/a@1.1",
+        "pkg://extra/library/a@1.0",
+        "pkg://openindiana.org/library/b@1.0",
+        "pkg://extra/library/b@1.0",
+    ] {
+        TestPackage::new(fmri)
+            .component("library/a")
+            .add(&mut components);
+    }
+    let fmri = FMRI::parse_raw("pkg://other/library/a@0.9").unwrap();
+    let mut package_versions = PackageVersions::new(fmri.clone());
+    package_versions.set_obsolete(true);
+    let mut component = Component::new("library/a".to_owned());
+    component.add(package_versions);
+    components.add(component);
+    components.add_obsoleted(fmri);
+
+    let mut problems = Problems::new();
+    components.check_publisher_version_skew(&mut problems);
+
+    let newer = FMRI::parse_raw("pkg://openindiana.org/library/a@1.1").unwrap();
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::PublisherVersionSkew(
+                newer.clone(),
+                AlternativeState::Viable,
+                FMRI::parse_raw("pkg://extra/library/a@1.0").unwrap(),
+                AlternativeState::Viable,
+                "library/a".to_owned()
+            ),
+            Problem::PublisherVersionSkew(
+                newer,
+                AlternativeState::Viable,
+                FMRI::parse_raw("pkg://other/library/a@0.9").unwrap(),
+                AlternativeState::Obsolete,
+                "library/a".to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn apply_publisher_priority() {
+    let mut components = Components::new();
+    for fmri in [
+        "pkg://local/library/a@1.2",
+        "pkg://openindiana.org/library/a@1.1",
+        "pkg://openindiana.org/library/b@1.0",
+        "pkg://hipster-encumbered/library/b@1.1",
+        "pkg://extra/library/b@0.9",
+    ] {
+        TestPackage::new(fmri).add(&mut components);
+        components.add_published(FMRI::parse_raw(fmri).unwrap());
+    }
+    components.set_publishers(vec![PublisherConfig {
+        name: "openindiana.org".to_owned(),
+        aliases: vec!["oi".to_owned()],
+        ..Default::default()
+    }]);
+
+    components.apply_publisher_priority(&[
+        "local".to_owned(),
+        "oi".to_owned(),
+        "hipster-encumbered".to_owned(),
+    ]);
+
+    let expected = vec![
+        "pkg://local/library/a@1.2",
+        "pkg://openindiana.org/library/b@1.0",
+        "pkg://extra/library/b@0.9",
+    ];
+    assert_eq!(
+        components
+            .get_ref()
+            .iter()
+            .flat_map(|component| component.get_versions_ref())
+            .map(|package_versions| package_versions.fmri_ref().to_string())
+            .collect::<Vec<_>>(),
+        vec![
+            "pkg://local/library/a",
+            "pkg://openindiana.org/library/b",
+            "pkg://extra/library/b",
+        ]
+    );
+    assert_eq!(
+        components
+            .get_published_ref()
+            .get_ref()
+            .iter()
+            .map(|fmri| fmri.to_string())
+            .collect::<Vec<_>>(),
+        expected
+    );
+}
+
+#[test]
+fn check_catalog_staleness() {
+    let now = current_timestamp();
+    let mut components = Components::new();
+    for (catalog, publisher, last_modified) in [
+        ("hipster", "openindiana.org", now.as_str()),
+        ("encumbered", "hipster-encumbered", "20000101T000000Z"),
+        ("old-hipster", "openindiana.org", "20000101T000000Z"),
+    ] {
+        components.add_catalog_timestamp(CatalogTimestamp {
+            catalog: PathBuf::from(catalog),
+            publishers: vec![publisher.to_owned()],
+            last_modified: last_modified.to_owned(),
+        });
+    }
+
+    let mut problems = Problems::new();
+    components.check_catalog_staleness(&mut problems, &CatalogStaleness::default());
+
+    let age = (timestamp_days(&now).unwrap() - timestamp_days("20000101T000000Z").unwrap()) as u64;
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::StaleCatalog(
+                PathBuf::from("encumbered"),
+                "20000101T000000Z".to_owned(),
+                age
+            ),
+            Problem::StaleCatalog(
+                PathBuf::from("old-hipster"),
+                "20000101T000000Z".to_owned(),
+                age
+            ),
+            Problem::CatalogAgeSkew(PathBuf::from("encumbered"), PathBuf::from("hipster"), age),
+        ]
+    );
+}
+
+#[test]
+fn check_divergent_dependencies() {
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    let mut components = Components::new();
+    for (fmri, runtime) in [
+        (
+            "pkg://openindiana.org/library/a@1.0-2024.0.0.0:20240101T000000Z",
+            vec![require("pkg:/library/b@1.0"), require("pkg:/library/c")],
+        ),
+        (
+            "pkg://extra/library/a@1.0-2024.0.0.0:20240201T000000Z",
+            vec![require("pkg:/library/b@1.1"), require("pkg:/library/c")],
+        ),
+        (
+            "pkg://other/library/a@1.1-2024.0.0.0",
+            vec![require("pkg:/library/c")],
+        ),
+    ] {
+        TestPackage::new(fmri)
+            .component("library/a")
+            .runtime(runtime)
+            .add(&mut components);
+    }
+
+    let mut problems = Problems::new();
+    components.check_divergent_dependencies(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::DivergentDependencies(
+            FMRI::parse_raw("pkg://openindiana.org/library/a@1.0-2024.0.0.0:20240101T000000Z")
+                .unwrap(),
+            FMRI::parse_raw("pkg://extra/library/a@1.0-2024.0.0.0:20240201T000000Z").unwrap(),
+            vec![require("pkg:/library/b@1.0")],
+            vec![require("pkg:/library/b@1.1")],
+            "library/a".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn is_metapackage() {
+    let mut components = Components::new();
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    TestPackage::new("pkg:/metapackages/build-essential@1.0")
+        .component("metapackages/build-essential")
+        .runtime(vec![
+            require("pkg:/developer/gcc"),
+            require("pkg:/developer/build/gnu-make"),
+        ])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .runtime(vec![require("pkg:/library/b")])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/b@1.0")
+        .component("library/b")
+        .add(&mut components);
+
+    let is_metapackage = |components: &Components, fmri: &str| {
+        components.is_metapackage(
+            &components
+                .get_package_versions_from_fmri(&FMRI::parse_raw(fmri).unwrap())
+                .unwrap(),
+        )
+    };
+
+    assert!(is_metapackage(&components, "pkg:/library/a"));
+    assert!(!is_metapackage(&components, "pkg:/library/b"));
+
+    components.set_packages_with_files(BTreeSet::from(["library/a".to_owned()]));
+
+    assert!(is_metapackage(
+        &components,
+        "pkg:/metapackages/build-essential"
+    ));
+    assert!(!is_metapackage(&components, "pkg:/library/a"));
+}
+
+#[test]
+fn check_stale_required_packages() {
+    let mut components = Components::new();
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .runtime(vec![require("pkg:/library/b")])
+        .build(vec![
+            require("pkg:/library/b"),
+            require("pkg:/library/c"),
+            require("pkg:/library/d"),
+            require("pkg:/developer/gcc"),
+        ])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/b@1.0")
+        .component("library/b")
+        .runtime(vec![require("pkg:/library/c")])
+        .add(&mut components);
+
+    let mut problems = Problems::new();
+    components.check_stale_required_packages(&mut problems, &Reclassification::default());
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::StaleRequiredPackage(
+            FMRI::parse_raw("pkg:/library/d").unwrap(),
+            "library/a".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn check_only_stub_dependents() {
+    let mut components = Components::new();
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    TestPackage::new("pkg:/library/old@1.0")
+        .component("library/old")
+        .renamed(true)
+        .runtime(vec![require("pkg:/library/a"), require("pkg:/library/b")])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .add(&mut components);
+    TestPackage::new("pkg:/library/b@1.0")
+        .component("library/b")
+        .add(&mut components);
+    TestPackage::new("pkg:/library/c@1.0")
+        .component("library/c")
+        .runtime(vec![DependTypes::Incorporate(
+            FMRI::parse_raw("pkg:/library/a@1.0").unwrap(),
+        )])
+        .build(vec![require("pkg:/library/b")])
+        .add(&mut components);
+
+    let mut problems = Problems::new();
+    components.check_only_stub_dependents(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::OnlyStubDependents(
+            FMRI::parse_raw("pkg:/library/a").unwrap(),
+            vec![FMRI::parse_raw("pkg:/library/old").unwrap()],
+            "library/a".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn check_orphan_packages() {
+    let mut components = Components::new();
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    TestPackage::new("pkg:/group/desktop@1.0")
+        .component("meta-packages/group")
+        .runtime(vec![require("pkg:/library/old")])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/old@1.0")
+        .component("library/old")
+        .renamed(true)
+        .runtime(vec![require("pkg:/library/a")])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .add(&mut components);
+    TestPackage::new("pkg:/library/b@1.0")
+        .component("library/b")
+        .build(vec![require("pkg:/library/a")])
+        .add(&mut components);
+    TestPackage::new("pkg:/consolidation/userland/userland-incorporation@1.0")
+        .component("consolidation/userland")
+        .runtime(vec![DependTypes::Incorporate(
+            FMRI::parse_raw("pkg:/library/b@1.0").unwrap(),
+        )])
+        .add(&mut components);
+
+    let mut problems = Problems::new();
+    components.check_orphan_packages(&mut problems, &OrphanPackages::default());
+    assert!(problems.get_ref().is_empty());
+
+    let orphan_packages = OrphanPackages {
+        enabled: true,
+        ..OrphanPackages::default()
+    };
+    components.check_orphan_packages(&mut problems, &orphan_packages);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::OrphanPackage(
+            FMRI::parse_raw("pkg:/library/b").unwrap(),
+            "library/b".to_owned()
+        )]
+    );
+    assert!(std::ptr::eq(
+        orphan_packages.regexes().unwrap(),
+        orphan_packages.regexes().unwrap()
+    ));
+
+    let invalid = OrphanPackages {
+        enabled: true,
+        roots: vec!["(".to_owned()],
+        ..OrphanPackages::default()
+    };
+    let mut problems = Problems::new();
+    components.check_orphan_packages(&mut problems, &invalid);
+    assert!(problems.get_ref().is_empty());
+}
+
+#[test]
+fn check_cross_publisher_dependencies() {
+    let mut components = Components::new();
+    components.set_publishers(vec![PublisherConfig {
+        name: "openindiana.org".to_owned(),
+        aliases: vec!["oi".to_owned()],
+        primary: true,
+        ..PublisherConfig::default()
+    }]);
+
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    TestPackage::new("pkg://oi/library/a@1.0")
+        .component("library/a")
+        .runtime(vec![require("pkg:/library/b"), require("pkg:/library/c")])
+        .build(vec![require("pkg:/library/d")])
+        .add(&mut components);
+    TestPackage::new("pkg://openindiana.org/library/b@1.0")
+        .component("library/b")
+        .add(&mut components);
+    TestPackage::new("pkg://hipster-encumbered/library/b@1.0").add(&mut components);
+    TestPackage::new("pkg://hipster-encumbered/library/d@1.0")
+        .runtime(vec![require("pkg:/library/c")])
+        .add(&mut components);
+
+    let mut problems = Problems::new();
+    components.check_cross_publisher_dependencies(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::CrossPublisherDependency(
+            FMRI::parse_raw("pkg:/library/d").unwrap(),
+            DependencyTypes::Build,
+            FMRI::parse_raw("pkg://oi/library/a").unwrap(),
+            "hipster-encumbered".to_owned(),
+            "library/a".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn check_external_dependencies() {
+    let mut components = Components::new();
+    components.set_publishers(vec![
+        PublisherConfig {
+            name: "openindiana.org".to_owned(),
+            primary: true,
+            ..PublisherConfig::default()
+        },
+        PublisherConfig {
+            name: "sfe".to_owned(),
+            external: true,
+            ..PublisherConfig::default()
+        },
+    ]);
+
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    TestPackage::new("pkg://openindiana.org/library/a@1.0")
+        .component("library/a")
+        .runtime(vec![require("pkg:/library/b"), require("pkg:/library/c")])
+        .add(&mut components);
+    TestPackage::new("pkg://openindiana.org/library/b@1.0")
+        .component("library/b")
+        .add(&mut components);
+    TestPackage::new("pkg://sfe/library/b@1.0").add(&mut components);
+    TestPackage::new("pkg://sfe/library/c@1.0")
+        .runtime(vec![require("pkg:/library/d")])
+        .add(&mut components);
+
+    let mut problems = Problems::new();
+    components.check_cross_publisher_dependencies(&mut problems);
+    components.check_external_dependencies(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::ExternalDependency(
+            FMRI::parse_raw("pkg:/library/c").unwrap(),
+            DependencyTypes::Runtime,
+            FMRI::parse_raw("pkg://openindiana.org/library/a").unwrap(),
+            "sfe".to_owned(),
+            "library/a".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn check_required_versions() {
+    let mut components = Components::new();
+    let require = |fmri: &str| DependTypes::Require(FMRI::parse_raw(fmri).unwrap());
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .runtime(vec![
+            require("pkg:/library/b@1.2"),
+            require("pkg:/library/b@1.1"),
+            require("pkg:/library/b"),
+        ])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/b@1.1")
+        .component("library/b")
+        .add(&mut components);
+
+    let mut problems = Problems::new();
+    components.check_required_versions(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::RequiredVersionNotPublished(
+            require("pkg:/library/b@1.2"),
+            FMRI::parse_raw("pkg:/library/a").unwrap(),
+            FMRI::parse_raw("pkg:/library/b@1.1").unwrap(),
+            "library/a".to_owned()
+        )]
+    );
+}
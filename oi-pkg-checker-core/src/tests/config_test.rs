@@ -0,0 +1,72 @@
+use std::fs::{create_dir_all, remove_dir_all, write};
+
+use fmri::FMRI;
+
+use crate::config::Config;
+
+#[test]
+fn load_config() {
+    let directory = std::env::temp_dir().join(format!("config-test-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let path = directory.join("config.json");
+
+    write(&path, r#"{"disabled_checks": ["useless-components"]}"#).unwrap();
+    assert_eq!(
+        Config::load(path.clone()).unwrap().disabled_checks,
+        vec!["useless-components"]
+    );
+
+    write(&path, r#"{"disabled_checks": ["nonexistent-check"]}"#).unwrap();
+    assert_eq!(
+        Config::load(path.clone()).unwrap_err(),
+        "unknown check: nonexistent-check"
+    );
+
+    write(&path, r#"{"disabled_checks": "#).unwrap();
+    assert!(Config::load(path.clone())
+        .unwrap_err()
+        .starts_with("invalid config"));
+
+    assert!(Config::load(directory.join("missing.json"))
+        .unwrap_err()
+        .starts_with("failed to read config"));
+
+    remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn naming_regexes_compiled_once() {
+    let directory = std::env::temp_dir().join(format!("config-naming-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let path = directory.join("config.json");
+
+    write(
+        &path,
+        r#"{"naming": {"patterns": [{"prefix": "library/", "pattern": "^library/[a-z]+$"}]}}"#,
+    )
+    .unwrap();
+    let config = Config::load(path.clone()).unwrap();
+
+    write(
+        &path,
+        r#"{"naming": {"patterns": [{"prefix": "library/", "pattern": "("}]}}"#,
+    )
+    .unwrap();
+    let invalid = Config::load(path.clone());
+    remove_dir_all(&directory).unwrap();
+
+    assert!(invalid
+        .unwrap_err()
+        .starts_with("invalid pattern for library/"));
+    assert!(config.naming.regexes.get().is_some());
+    assert!(std::ptr::eq(
+        config.naming.regexes().unwrap(),
+        config.naming.regexes().unwrap()
+    ));
+    assert_eq!(
+        config
+            .naming
+            .check(&FMRI::parse_raw("pkg:/library/foo2@1.0-2024.0.0.1").unwrap()),
+        vec!["name doesn't match ^library/[a-z]+$"]
+    );
+}
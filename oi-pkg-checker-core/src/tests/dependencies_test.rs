@@ -94,3 +94,27 @@ fn is_fmri_needed_as_dependency() {
         false
     );
 }
+
+#[test]
+fn get_duplicates() {
+    let mut dependencies = Dependencies::new();
+    dependencies.add(Dependency::new(&DependTypes::Require(
+        FMRI::parse_raw("pkg:/library/libvorbis@1.3.7-2022.0.0.0").unwrap(),
+    )));
+    dependencies.add(Dependency::new(&DependTypes::Require(
+        FMRI::parse_raw("pkg:/library/zlib").unwrap(),
+    )));
+    dependencies.add(Dependency::new(&DependTypes::Require(
+        FMRI::parse_raw("pkg:/library/libvorbis@1.3.6").unwrap(),
+    )));
+    dependencies.add(Dependency::new(&DependTypes::Optional(
+        FMRI::parse_raw("pkg:/library/zlib").unwrap(),
+    )));
+
+    let duplicates = dependencies.get_duplicates();
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(
+        duplicates[0].get_ref(),
+        &DependTypes::Require(FMRI::parse_raw("pkg:/library/libvorbis@1.3.6").unwrap())
+    );
+}
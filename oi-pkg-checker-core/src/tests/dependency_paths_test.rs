@@ -0,0 +1,143 @@
+use fmri::FMRI;
+
+use crate::dependency_paths::{package_edges, DependencyEdge, DependencyPaths};
+use crate::packages::components::Components;
+use crate::packages::depend_types::DependTypes;
+use crate::packages::dependencies::Dependencies;
+use crate::packages::dependency::Dependency;
+use crate::packages::dependency_type::DependencyTypes;
+use crate::packages::package::Package;
+use crate::tests::TestPackage;
+
+fn edge(from: &str, to: &str, dependency_type: DependencyTypes) -> DependencyEdge {
+    DependencyEdge {
+        from: from.to_owned(),
+        to: to.to_owned(),
+        dependency_type,
+        depend_type: "require".to_owned(),
+    }
+}
+
+fn components() -> Components {
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/a@1.0")
+        .requires(&["pkg:/library/b"])
+        .build(vec![DependTypes::Require(
+            FMRI::parse_raw("pkg:/library/c").unwrap(),
+        )])
+        .add(&mut components);
+    // cycle between library/b and library/a
+    TestPackage::new("pkg:/library/b@1.0")
+        .requires(&["pkg:/library/c", "pkg:/library/a"])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/c@1.0")
+        .obsolete(true)
+        .add(&mut components);
+    components
+}
+
+#[test]
+fn dependency_paths() {
+    let components = components();
+    let fmri = |fmri: &str| FMRI::parse_raw(fmri).unwrap();
+
+    let dependency_paths = DependencyPaths::new(
+        &components,
+        &fmri("pkg:/library/a"),
+        &fmri("pkg:/library/c"),
+        5,
+    );
+    assert_eq!(
+        dependency_paths.paths,
+        vec![
+            vec![
+                edge("library/a", "library/b", DependencyTypes::Runtime),
+                edge("library/b", "library/c", DependencyTypes::Runtime),
+            ],
+            vec![edge("library/a", "library/c", DependencyTypes::Build)],
+        ]
+    );
+
+    // path is limited by depth
+    let dependency_paths = DependencyPaths::new(
+        &components,
+        &fmri("pkg:/library/a"),
+        &fmri("pkg:/library/c"),
+        1,
+    );
+    assert_eq!(
+        dependency_paths.paths,
+        vec![vec![edge("library/a", "library/c", DependencyTypes::Build)]]
+    );
+
+    // cycle a -> b -> a is not followed
+    let dependency_paths = DependencyPaths::new(
+        &components,
+        &fmri("pkg:/library/b"),
+        &fmri("pkg:/library/a"),
+        5,
+    );
+    assert_eq!(
+        dependency_paths.paths,
+        vec![vec![edge(
+            "library/b",
+            "library/a",
+            DependencyTypes::Runtime
+        )]]
+    );
+
+    // no path
+    let dependency_paths = DependencyPaths::new(
+        &components,
+        &fmri("pkg:/library/c"),
+        &fmri("pkg:/library/a"),
+        5,
+    );
+    assert!(dependency_paths.paths.is_empty());
+}
+
+#[test]
+fn dependency_paths_to_mermaid() {
+    let components = components();
+    let dependency_paths = DependencyPaths::new(
+        &components,
+        &FMRI::parse_raw("pkg:/library/a").unwrap(),
+        &FMRI::parse_raw("pkg:/library/c").unwrap(),
+        5,
+    );
+
+    assert_eq!(
+        dependency_paths.to_mermaid(),
+        "graph LR\n\
+        \x20   n0[\"library/a\"]\n\
+        \x20   n1[\"library/b\"]\n\
+        \x20   n2[\"library/c\"]:::obsolete\n\
+        \x20   n0 -->|runtime require| n1\n\
+        \x20   n1 -->|runtime require| n2\n\
+        \x20   n0 -->|build require| n2\n\
+        \x20   classDef obsolete fill:#f99,stroke:#900\n\
+        \x20   classDef renamed fill:#ff9,stroke:#990\n"
+    );
+}
+
+#[test]
+fn package_edges_of_conditional() {
+    let fmri = |fmri: &str| FMRI::parse_raw(fmri).unwrap();
+    let mut package = Package::new(fmri("pkg:/library/a@1.0"), false, false);
+    let mut dependencies = Dependencies::new();
+    dependencies.add(Dependency::new(&DependTypes::Conditional(
+        fmri("pkg:/library/plugin"),
+        fmri("pkg:/library/host"),
+    )));
+    package.add_runtime_dependencies(dependencies);
+
+    assert_eq!(
+        package_edges("library/a", &package),
+        vec![DependencyEdge {
+            from: "library/a".to_owned(),
+            to: "library/plugin".to_owned(),
+            dependency_type: DependencyTypes::Runtime,
+            depend_type: "conditional".to_owned(),
+        }]
+    );
+}
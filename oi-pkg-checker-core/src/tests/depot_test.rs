@@ -0,0 +1,236 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, read_to_string, remove_dir_all, write},
+};
+
+use fmri::FMRI;
+use sha1_smol::Sha1;
+
+use crate::{
+    assets::depot::{
+        downgrade_non_existing_required, fetch_catalog, fetch_catalogs, part_digest, proxy_for,
+    },
+    problems::{Problem, Problems},
+    DependTypes, DependencyTypes,
+};
+
+/// Returns catalog part with `content` (json object) signed as by pkg(5) and its signature
+fn signed_part(content: &str) -> (String, String) {
+    let signature = Sha1::from(format!("{}\n", content)).digest().to_string();
+    let part = format!(
+        r#"{},"_SIGNATURE":{{"sha-1":"{}"}}}}"#,
+        content.strip_suffix('}').unwrap(),
+        signature
+    );
+    (format!("{}\n", part), signature)
+}
+
+#[test]
+fn digest_of_catalog_part() {
+    assert_eq!(
+        part_digest(
+            b"{\"packages\":{},\"_SIGNATURE\":{\"sha-1\":\"aedf53669b971556a97acea6786d1a8f87cdd9d4\"}}\n"
+        ),
+        Some("aedf53669b971556a97acea6786d1a8f87cdd9d4".to_owned())
+    );
+    assert_eq!(part_digest(b"{\"packages\":{}}\n"), None);
+}
+
+#[test]
+fn fetch_catalog_from_depot() {
+    let directory = std::env::temp_dir().join(format!("depot-test-{}", std::process::id()));
+    let catalog_directory = directory.join("depot/catalog/1");
+    create_dir_all(&catalog_directory).unwrap();
+
+    let (base, base_signature) = signed_part(r#"{"openindiana.org":{}}"#);
+    let (dependency, dependency_signature) = signed_part(r#"{"openindiana.org":{}}"#);
+    write(catalog_directory.join("catalog.base.C"), &base).unwrap();
+    write(catalog_directory.join("catalog.dependency.C"), dependency).unwrap();
+    write(
+        catalog_directory.join("catalog.attrs"),
+        format!(
+            r#"{{"parts": {{
+                "catalog.base.C": {{"signature-sha-1": "{}"}},
+                "catalog.dependency.C": {{"signature-sha-1": "{}"}}
+            }}, "version": 1}}"#,
+            base_signature, dependency_signature
+        ),
+    )
+    .unwrap();
+
+    let url = format!("file://{}/depot/", directory.to_string_lossy());
+    let downloaded = directory.join("downloaded");
+
+    assert_eq!(
+        fetch_catalog(&url, &downloaded, false),
+        Ok(downloaded.join("catalog.dependency.C"))
+    );
+    assert!(downloaded.join("catalog.base.C").exists());
+
+    // content changed, but embedded signature didn't
+    let (tampered, tampered_signature) = signed_part(r#"{"openindiana.org":{"a":[]}}"#);
+    write(
+        catalog_directory.join("catalog.base.C"),
+        tampered.replace(&tampered_signature, &base_signature),
+    )
+    .unwrap();
+    assert_eq!(
+        fetch_catalog(&url, &downloaded, false),
+        Err(format!(
+            "SHA-1 of catalog.base.C is {}, but catalog.attrs expects {}",
+            tampered_signature, base_signature
+        ))
+    );
+
+    remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn fetch_catalog_with_cache() {
+    let directory = std::env::temp_dir().join(format!("depot-cache-test-{}", std::process::id()));
+    let catalog_directory = directory.join("depot/catalog/1");
+    create_dir_all(&catalog_directory).unwrap();
+
+    let write_part = |content: &str| {
+        let (part, signature) = signed_part(&format!(r#"{{"content":"{}"}}"#, content));
+        write(catalog_directory.join("catalog.dependency.C"), part).unwrap();
+        write(
+            catalog_directory.join("catalog.attrs"),
+            format!(
+                r#"{{"parts": {{"catalog.dependency.C": {{"signature-sha-1": "{}"}}}}, "version": 1}}"#,
+                signature
+            ),
+        )
+        .unwrap();
+    };
+
+    let url = format!("file://{}/depot", directory.to_string_lossy());
+    let downloaded = directory.join("downloaded");
+    let part = downloaded.join("catalog.dependency.C");
+
+    write_part("old");
+    assert_eq!(fetch_catalog(&url, &downloaded, true), Ok(part.clone()));
+
+    // cached part still matches signature in catalog.attrs, so it is not downloaded again
+    let cached_attrs = read_to_string(catalog_directory.join("catalog.attrs")).unwrap();
+    write_part("new");
+    write(catalog_directory.join("catalog.attrs"), cached_attrs).unwrap();
+    assert_eq!(fetch_catalog(&url, &downloaded, true), Ok(part.clone()));
+    assert!(read_to_string(&part).unwrap().contains("old"));
+
+    write_part("new");
+    assert_eq!(fetch_catalog(&url, &downloaded, false), Ok(part.clone()));
+    assert!(read_to_string(&part).unwrap().contains("new"));
+
+    remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn fetch_catalogs_of_more_depots() {
+    let directory = std::env::temp_dir().join(format!("depots-test-{}", std::process::id()));
+    let catalog_directory = directory.join("depot/catalog/1");
+    create_dir_all(&catalog_directory).unwrap();
+    let mut signatures = vec![];
+    for part in ["base", "dependency", "summary"] {
+        let (content, signature) = signed_part(&format!(r#"{{"part":"{}"}}"#, part));
+        write(
+            catalog_directory.join(format!("catalog.{}.C", part)),
+            content,
+        )
+        .unwrap();
+        signatures.push(format!(
+            r#""catalog.{}.C": {{"signature-sha-1": "{}"}}"#,
+            part, signature
+        ));
+    }
+    write(
+        catalog_directory.join("catalog.attrs"),
+        format!(
+            r#"{{"parts": {{{}}}, "version": 1}}"#,
+            signatures.join(", ")
+        ),
+    )
+    .unwrap();
+
+    let url = format!("file://{}/depot", directory.to_string_lossy());
+    let missing_url = format!("file://{}/missing", directory.to_string_lossy());
+    let results = fetch_catalogs(
+        &[
+            (vec![url.clone()], directory.join("first")),
+            (vec![missing_url.clone()], directory.join("second")),
+            (vec![missing_url, url], directory.join("third")),
+        ],
+        false,
+    );
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(
+        results[0],
+        Ok(directory.join("first").join("catalog.dependency.C"))
+    );
+    assert!(directory.join("first/catalog.summary.C").exists());
+    assert!(results[1].is_err());
+    assert_eq!(
+        results[2],
+        Ok(directory.join("third").join("catalog.dependency.C"))
+    );
+
+    remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn proxy_for_url() {
+    let var = |name: &str| match name {
+        "http_proxy" => Some("http://proxy:3128".to_owned()),
+        "HTTPS_PROXY" => Some("http://secure-proxy:3128".to_owned()),
+        _ => None,
+    };
+
+    assert_eq!(
+        proxy_for("http://pkg.openindiana.org/hipster", var),
+        Some("http://proxy:3128".to_owned())
+    );
+    assert_eq!(
+        proxy_for("https://pkg.openindiana.org/hipster", var),
+        Some("http://secure-proxy:3128".to_owned())
+    );
+    assert_eq!(proxy_for("file:///var/share/pkg/repositories", var), None);
+}
+
+#[test]
+fn downgrade_required_from_other_depot() {
+    let problem = |package_name: &str| {
+        Problem::NonExistingRequired(
+            DependTypes::Require(FMRI::parse_raw(package_name).unwrap()),
+            DependencyTypes::Build,
+            FMRI::parse_raw("library/a").unwrap(),
+            "a".to_owned(),
+        )
+    };
+
+    let mut problems = Problems::new();
+    problems.add_problem(problem("library/other-consolidation"));
+    problems.add_problem(problem("library/nonexistent"));
+
+    downgrade_non_existing_required(
+        &mut problems,
+        &BTreeMap::from([(
+            "library/other-consolidation".to_owned(),
+            "https://pkg.example.org/other".to_owned(),
+        )]),
+    );
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            problem("library/nonexistent"),
+            Problem::RequiredFromOtherDepot(
+                DependTypes::Require(FMRI::parse_raw("library/other-consolidation").unwrap()),
+                DependencyTypes::Build,
+                FMRI::parse_raw("library/a").unwrap(),
+                "a".to_owned(),
+                "https://pkg.example.org/other".to_owned(),
+            ),
+        ]
+    );
+}
@@ -0,0 +1,102 @@
+use std::fs::{create_dir_all, remove_dir_all, write};
+
+use fmri::FMRI;
+
+use crate::assets::open_indiana_oi_userland_git::ComponentPackagesList;
+use crate::dossier::Dossier;
+use crate::packages::components::Components;
+use crate::packages::depend_types::DependTypes;
+use crate::packages::dependency::Dependency;
+use crate::packages::dependency_type::DependencyTypes;
+use crate::problems::{Problem, Problems};
+use crate::tests::TestPackage;
+
+#[test]
+fn dossier_of_package() {
+    let directory = std::env::temp_dir().join(format!("dossier-test-{}", std::process::id()));
+    let path = directory.join("library/b");
+    create_dir_all(&path).unwrap();
+    write(path.join("Makefile"), "").unwrap();
+    write(
+        path.join("pkg5"),
+        r#"{"fmris": ["library/b"], "name": "library/b"}"#,
+    )
+    .unwrap();
+    let component_packages_list =
+        ComponentPackagesList::scan(&directory, &[], &mut Problems::new());
+    remove_dir_all(&directory).unwrap();
+
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .requires(&["pkg:/library/b"])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/b@2.0")
+        .component("library/b")
+        .build(vec![DependTypes::Require(
+            FMRI::parse_raw("pkg:/developer/gcc").unwrap(),
+        )])
+        .add(&mut components);
+    components.add_obsoleted(FMRI::parse_raw("pkg:/library/b@1.0").unwrap());
+
+    let mut problems = Problems::new();
+    problems.add_problem(Problem::UselessComponent("library/b".to_owned()));
+    problems.add_problem(Problem::UselessComponent("library/c".to_owned()));
+
+    let fmri = FMRI::parse_raw("pkg:/library/b").unwrap();
+    let dossier = Dossier::new(
+        &components,
+        &problems,
+        &component_packages_list,
+        &fmri,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(dossier.fmri, fmri);
+    assert_eq!(
+        dossier.versions,
+        vec![FMRI::parse_raw("pkg:/library/b@2.0").unwrap()]
+    );
+    assert_eq!(
+        dossier.obsoleted_versions,
+        vec![FMRI::parse_raw("pkg:/library/b@1.0").unwrap()]
+    );
+    assert!(!dossier.obsolete);
+    assert!(!dossier.renamed);
+    assert!(!dossier.metapackage);
+    assert_eq!(dossier.component_name, Some("library/b".to_owned()));
+    assert_eq!(dossier.component_path, Some(path));
+    assert_eq!(
+        dossier.dependencies[1],
+        (
+            DependencyTypes::Build,
+            vec![Dependency::new(&DependTypes::Require(
+                FMRI::parse_raw("pkg:/developer/gcc").unwrap()
+            ))]
+        )
+    );
+    assert_eq!(
+        dossier
+            .dependents
+            .unwrap()
+            .dependencies
+            .iter()
+            .map(|(fmri, _, _, _)| fmri.to_string())
+            .collect::<Vec<_>>(),
+        vec!["pkg:/library/a@1.0"]
+    );
+    assert_eq!(
+        dossier.problems,
+        vec![Problem::UselessComponent("library/b".to_owned())]
+    );
+
+    assert!(Dossier::new(
+        &components,
+        &problems,
+        &component_packages_list,
+        &FMRI::parse_raw("pkg:/library/none").unwrap(),
+        false,
+    )
+    .is_none());
+}
@@ -0,0 +1,102 @@
+use std::fs::{create_dir_all, remove_dir_all, write};
+
+use fmri::FMRI;
+
+use crate::assets::open_indiana_oi_userland_git::ComponentPackagesList;
+use crate::fix_plan::{fix_plan, FixOperation, Operation};
+use crate::packages::components::Components;
+use crate::packages::depend_types::DependTypes;
+use crate::problems::{Problem, ProblemKind, Problems};
+use crate::tests::TestPackage;
+
+fn component_packages_list(directory: &std::path::Path) -> ComponentPackagesList {
+    let path = directory.join("application");
+    create_dir_all(&path).unwrap();
+    write(path.join("Makefile"), "").unwrap();
+    write(
+        path.join("pkg5"),
+        r#"{"fmris": ["application", "library/old"], "name": "application"}"#,
+    )
+    .unwrap();
+
+    ComponentPackagesList::scan(directory, &[], &mut Problems::new())
+}
+
+#[test]
+fn fix_plan_removes_from_pkg5() {
+    let directory = std::env::temp_dir().join(format!("fix-plan-pkg5-{}", std::process::id()));
+    let component_packages_list = component_packages_list(&directory);
+
+    let mut problems = Problems::new();
+    problems.add_problem(Problem::RenamedPackageInComponent(
+        FMRI::parse_raw("pkg:/library/old").unwrap(),
+        "application".to_owned(),
+    ));
+    problems.add_problem(Problem::RenamedPackageInComponent(
+        FMRI::parse_raw("pkg:/library/other").unwrap(),
+        "unknown".to_owned(),
+    ));
+
+    let fix_plan = fix_plan(&problems, &Components::new(), &component_packages_list);
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        fix_plan,
+        vec![FixOperation {
+            problem: Some(ProblemKind::RenamedPackageInComponent),
+            file: directory.join("application/pkg5"),
+            variable: None,
+            operation: Operation::Remove,
+            old_value: Some("library/old".to_owned()),
+            new_value: None,
+        }]
+    );
+}
+
+#[test]
+fn fix_plan_retargets_renamed_required_packages() {
+    let directory = std::env::temp_dir().join(format!("fix-plan-makefile-{}", std::process::id()));
+    let component_packages_list = component_packages_list(&directory);
+
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/old@1.0")
+        .renamed(true)
+        .runtime(vec![DependTypes::Require(
+            FMRI::parse_raw("pkg:/library/new@1.0").unwrap(),
+        )])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/new@1.0")
+        .component("library/new")
+        .add(&mut components);
+    TestPackage::new("pkg:/application@1.0")
+        .component("application")
+        .build(vec![DependTypes::Require(
+            FMRI::parse_raw("pkg:/library/old").unwrap(),
+        )])
+        .add(&mut components);
+
+    let fix_plan = fix_plan(&Problems::new(), &components, &component_packages_list);
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        fix_plan,
+        vec![
+            FixOperation {
+                problem: None,
+                file: directory.join("application/Makefile"),
+                variable: Some("REQUIRED_PACKAGES".to_owned()),
+                operation: Operation::Remove,
+                old_value: Some("library/old".to_owned()),
+                new_value: None,
+            },
+            FixOperation {
+                problem: None,
+                file: directory.join("application/Makefile"),
+                variable: Some("REQUIRED_PACKAGES".to_owned()),
+                operation: Operation::Add,
+                old_value: None,
+                new_value: Some("library/new".to_owned()),
+            },
+        ]
+    );
+}
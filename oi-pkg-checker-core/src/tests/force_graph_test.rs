@@ -0,0 +1,59 @@
+use fmri::FMRI;
+use serde_json::json;
+
+use crate::force_graph::ForceGraph;
+use crate::packages::components::Components;
+use crate::problems::{Problem, Problems};
+use crate::tests::TestPackage;
+
+#[test]
+fn force_graph_export() {
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .requires(&["pkg:/library/gone"])
+        .add(&mut components);
+    components.add_obsoleted(FMRI::parse_raw("pkg:/library/gone@1.0").unwrap());
+
+    let mut problems = Problems::new();
+    problems.add_problem(Problem::UselessComponent("library/a".to_owned()));
+    problems.add_problem(Problem::MissingComponentForPackage(
+        FMRI::parse_raw("pkg:/library/a").unwrap(),
+    ));
+
+    let force_graph = ForceGraph::new(&components, &problems);
+
+    assert_eq!(
+        force_graph.to_d3(),
+        json!({
+            "nodes": [
+                {
+                    "id": "library/a",
+                    "component": "library/a",
+                    "obsolete": false,
+                    "renamed": false,
+                    "problems": 1,
+                },
+                {
+                    "id": "library/gone",
+                    "component": null,
+                    "obsolete": true,
+                    "renamed": false,
+                    "problems": 0,
+                },
+            ],
+            "links": [
+                {
+                    "source": "library/a",
+                    "target": "library/gone",
+                    "dependency_type": "Runtime",
+                    "depend_type": "require",
+                },
+            ],
+        })
+    );
+    assert_eq!(
+        force_graph.to_cytoscape()["elements"]["edges"][0]["data"]["id"],
+        "e0"
+    );
+}
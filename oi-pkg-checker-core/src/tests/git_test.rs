@@ -0,0 +1,41 @@
+use std::{
+    fs::{create_dir_all, remove_dir_all, write},
+    path::Path,
+};
+
+use git2::{Repository, Signature};
+
+use crate::git::{changed_files, head_commit};
+
+#[test]
+fn changed_files_since_commit() {
+    let directory = std::env::temp_dir().join(format!("git-test-{}", std::process::id()));
+    create_dir_all(directory.join("components/library/zlib")).unwrap();
+    write(directory.join("components/library/zlib/Makefile"), "a\n").unwrap();
+
+    let repository = Repository::init(&directory).unwrap();
+    let mut index = repository.index().unwrap();
+    index
+        .add_path(Path::new("components/library/zlib/Makefile"))
+        .unwrap();
+    index.write().unwrap();
+    let tree = repository.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = Signature::now("test", "test@example.com").unwrap();
+    let commit = repository
+        .commit(Some("HEAD"), &signature, &signature, "initial", &tree, &[])
+        .unwrap();
+
+    assert_eq!(
+        head_commit(&directory.join("components")),
+        Ok(commit.to_string())
+    );
+    assert_eq!(changed_files(&directory, "HEAD"), Ok(vec![]));
+
+    write(directory.join("components/library/zlib/Makefile"), "b\n").unwrap();
+    assert_eq!(
+        changed_files(&directory, &commit.to_string()),
+        Ok(vec!["components/library/zlib/Makefile".to_owned()])
+    );
+
+    remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,67 @@
+use crate::graph_delta::{graph_delta, graph_delta_to_json_lines, GraphChange};
+use crate::namespace_filter::NamespaceFilter;
+use crate::packages::components::Components;
+use crate::tests::TestPackage;
+
+#[test]
+fn graph_delta_of_two_analyses() {
+    let mut old = Components::new();
+    TestPackage::new("pkg:/library/a@1.0")
+        .requires(&["pkg:/library/b"])
+        .add(&mut old);
+    TestPackage::new("pkg:/library/b@1.0").add(&mut old);
+    TestPackage::new("pkg:/library/c@1.0").add(&mut old);
+
+    let mut new = Components::new();
+    TestPackage::new("pkg:/library/a@1.1")
+        .requires(&["pkg:/library/d"])
+        .add(&mut new);
+    TestPackage::new("pkg:/library/c@1.1")
+        .obsolete(true)
+        .add(&mut new);
+    TestPackage::new("pkg:/library/d@1.0").add(&mut new);
+
+    let changes = graph_delta(&old, &new);
+
+    assert_eq!(
+        graph_delta_to_json_lines(&changes),
+        "{\"change\":\"edge-removed\",\"from\":\"library/a\",\"to\":\"library/b\",\"dependency_type\":\"Runtime\",\"depend_type\":\"require\"}\n\
+        {\"change\":\"node-removed\",\"node\":\"library/b\"}\n\
+        {\"change\":\"flag-changed\",\"node\":\"library/c\",\"flag\":\"obsolete\",\"old\":false,\"new\":true}\n\
+        {\"change\":\"node-added\",\"node\":\"library/d\"}\n\
+        {\"change\":\"edge-added\",\"from\":\"library/a\",\"to\":\"library/d\",\"dependency_type\":\"Runtime\",\"depend_type\":\"require\"}\n"
+    );
+    assert!(graph_delta(&new, &new).is_empty());
+    assert!(matches!(changes[1], GraphChange::NodeRemoved { .. }));
+}
+
+#[test]
+fn graph_delta_with_namespace_filter() {
+    let old = Components::new();
+
+    let mut new = Components::new();
+    TestPackage::new("pkg:/library/a@1.0")
+        .requires(&["pkg:/consolidation/userland/userland-incorporation"])
+        .add(&mut new);
+    TestPackage::new("pkg:/consolidation/userland/userland-incorporation@1.0").add(&mut new);
+    TestPackage::new("pkg:/entire@1.0")
+        .requires(&["pkg:/library/a"])
+        .add(&mut new);
+    new.set_namespace_filter(NamespaceFilter::new(
+        vec![],
+        vec!["consolidation/*".to_owned(), "entire".to_owned()],
+    ));
+
+    assert_eq!(
+        graph_delta(&old, &new),
+        vec![GraphChange::NodeAdded {
+            node: "library/a".to_owned()
+        }]
+    );
+
+    new.set_namespace_filter(NamespaceFilter::new(
+        vec!["pkg:/library/*".to_owned()],
+        vec![],
+    ));
+    assert_eq!(graph_delta(&old, &new).len(), 1);
+}
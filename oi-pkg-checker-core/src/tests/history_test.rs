@@ -0,0 +1,114 @@
+use std::fs::{create_dir_all, remove_dir_all, write};
+
+use fmri::FMRI;
+
+use crate::{
+    assets::history::{load_history, HistoryRecord},
+    packages::{
+        component::Component, components::Components, dependencies::Dependencies,
+        dependency::Dependency, package::Package, package_versions::PackageVersions,
+    },
+    problems::{AlternativeState, Problem, Problems},
+    DependTypes,
+};
+
+#[test]
+fn load_history_of_oi_userland() {
+    let components_path =
+        std::env::temp_dir().join(format!("history-{}/components", std::process::id()));
+    let directory = components_path.join("meta-packages/history");
+    create_dir_all(&directory).unwrap();
+    write(
+        directory.join("history"),
+        "# obsoleted and renamed packages\n\
+        library/b@1.0,5.11-2020.0.1.0 library/c\n\
+        library/d@2.0,5.11-2021.0.1.0 noincorporate\n\
+        library/e@@1 library/f\n",
+    )
+    .unwrap();
+    write(
+        directory.join("SUNWg.p5m"),
+        "set name=pkg.fmri value=pkg:/SUNWg@0.5.11,5.11-2018.0.0.0\n\
+        set name=pkg.renamed value=true\n\
+        depend type=require fmri=pkg:/library/h\n",
+    )
+    .unwrap();
+    write(
+        directory.join("history.p5m"),
+        "set name=pkg.fmri value=pkg:/metapackages/history@0.1\n",
+    )
+    .unwrap();
+
+    let mut problems = Problems::new();
+    let history = load_history(&components_path, &mut problems);
+    remove_dir_all(components_path.parent().unwrap()).unwrap();
+
+    let fmri = |fmri: &str| FMRI::parse_raw(fmri).unwrap();
+    assert_eq!(
+        history,
+        vec![
+            HistoryRecord {
+                fmri: fmri("library/b@1.0,5.11-2020.0.1.0"),
+                renamed_to: vec![fmri("library/c")],
+            },
+            HistoryRecord {
+                fmri: fmri("library/d@2.0,5.11-2021.0.1.0"),
+                renamed_to: vec![],
+            },
+            HistoryRecord {
+                fmri: fmri("pkg:/SUNWg@0.5.11,5.11-2018.0.0.0"),
+                renamed_to: vec![fmri("pkg:/library/h")],
+            },
+        ]
+    );
+    assert_eq!(problems.get_ref().len(), 1);
+
+    let mut components = Components::new();
+    let mut component = Component::new("library/a".to_owned());
+    let mut package = Package::new(fmri("pkg:/library/a@1.0"), false, true);
+    let mut dependencies = Dependencies::new();
+    dependencies.add(Dependency::new(&DependTypes::Require(fmri(
+        "pkg:/library/b@1.0",
+    ))));
+    package.add_runtime_dependencies(dependencies);
+    let mut package_versions = PackageVersions::new(fmri("pkg:/library/a@1.0"));
+    package_versions.add_package(package);
+    component.add(package_versions);
+    let mut package_versions = PackageVersions::new(fmri("pkg:/library/d@3.0"));
+    package_versions.add_package(Package::new(fmri("pkg:/library/d@3.0"), false, false));
+    component.add(package_versions);
+    components.add(component);
+    components.set_history(history);
+
+    // rename chain continues through package that is only in history
+    assert_eq!(
+        components.get_rename_chain(&fmri("pkg:/library/a")),
+        vec![
+            fmri("pkg:/library/a"),
+            fmri("pkg:/library/b@1.0"),
+            fmri("library/c")
+        ]
+    );
+
+    let mut problems = Problems::new();
+    components.check_rename_chains(&mut problems);
+    components.check_history_collisions(&mut problems);
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::BrokenRenameChain(
+                vec![
+                    fmri("pkg:/library/a"),
+                    fmri("pkg:/library/b"),
+                    fmri("library/c")
+                ],
+                AlternativeState::Missing
+            ),
+            Problem::HistoryNameCollision(
+                fmri("pkg:/library/d"),
+                fmri("library/d@2.0,5.11-2021.0.1.0"),
+                "library/a".to_owned()
+            ),
+        ]
+    );
+}
@@ -0,0 +1,73 @@
+use std::path::{Path, PathBuf};
+
+use fmri::{fmri_list::FMRIList, FMRI};
+
+use crate::{
+    assets::open_indiana_oi_userland_git::{ComponentPackages, ComponentPackagesList},
+    incremental::{changed_in, merge_problems},
+    problems::{
+        MakeFailure,
+        Problem::{TimestampAnomaly, UnRunnableMakeCommand, UselessComponent},
+        Problems,
+    },
+};
+
+fn component_packages_list() -> ComponentPackagesList {
+    ComponentPackagesList::from(
+        ["library/zlib", "library/libpng"]
+            .iter()
+            .map(|component_name| ComponentPackages {
+                component_name: component_name.to_string(),
+                path_to_component: PathBuf::from("oi-userland/components/./").join(component_name),
+                packages_in_component: FMRIList::new(),
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+#[test]
+fn reload_changed_components() {
+    let changed = changed_in(
+        Path::new("oi-userland"),
+        &["components/library/zlib/Makefile"],
+        &component_packages_list(),
+    );
+
+    assert_eq!(
+        changed
+            .get()
+            .iter()
+            .map(|component_packages| component_packages.component_name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["library/zlib"]
+    );
+
+    let unrunnable = |component_name: &str| {
+        UnRunnableMakeCommand(
+            "gmake print-value-REQUIRED_PACKAGES".to_owned(),
+            PathBuf::from("oi-userland/components/./").join(component_name),
+            MakeFailure::SyntaxError,
+            "".to_owned(),
+        )
+    };
+
+    let mut previous = Problems::new();
+    previous.add_problem(TimestampAnomaly(
+        FMRI::parse_raw("pkg:/library/zlib@1.3").unwrap(),
+        None,
+    ));
+    previous.add_problem(unrunnable("library/zlib"));
+    previous.add_problem(unrunnable("library/libpng"));
+    previous.add_problem(UselessComponent("library/libpng".to_owned()));
+
+    let mut problems = Problems::new();
+    merge_problems(&mut problems, &previous, &changed);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            TimestampAnomaly(FMRI::parse_raw("pkg:/library/zlib@1.3").unwrap(), None),
+            unrunnable("library/libpng"),
+        ]
+    );
+}
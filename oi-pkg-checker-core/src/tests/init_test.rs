@@ -0,0 +1,38 @@
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+
+use crate::config::Config;
+use crate::init::{missing_tools, write_default_config, DEFAULT_CONFIG};
+
+#[test]
+fn missing_tools_are_detected() {
+    assert_eq!(
+        missing_tools(&["cargo", "oi-pkg-checker-missing-tool"]),
+        vec!["oi-pkg-checker-missing-tool"]
+    );
+    assert!(missing_tools(&["cargo"]).is_empty());
+}
+
+#[test]
+fn default_config_is_written_once() {
+    let directory = std::env::temp_dir().join(format!("init-config-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let config = directory.join("config.json");
+
+    let created = write_default_config(&config);
+    let content = read_to_string(&config).unwrap();
+    let loaded = Config::load(config.clone());
+    write(&config, "{}").unwrap();
+    let kept = write_default_config(&config);
+    let kept_content = read_to_string(&config).unwrap();
+    let failed = write_default_config(&directory.join("missing/config.json"));
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(created, Ok(true));
+    assert_eq!(content, DEFAULT_CONFIG);
+    let loaded = loaded.unwrap();
+    assert_eq!(loaded.publishers.len(), 2);
+    assert!(loaded.disabled_checks.is_empty());
+    assert_eq!(kept, Ok(false));
+    assert_eq!(kept_content, "{}");
+    assert!(failed.unwrap_err().starts_with("failed to write "));
+}
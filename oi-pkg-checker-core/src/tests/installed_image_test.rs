@@ -0,0 +1,52 @@
+use fmri::FMRI;
+
+use crate::{
+    assets::installed_image::parse_pkg_list,
+    packages::components::Components,
+    problems::{
+        AlternativeState,
+        Problem::{HeldBackInstalledPackage, UnavailableInstalledPackage},
+        Problems,
+    },
+    tests::TestPackage,
+};
+
+#[test]
+fn check_installed_packages() {
+    let installed = parse_pkg_list(
+        "library/zlib                      1.2-2024.0.0.0       i--\n\
+         library/old (extra)               1.0-2024.0.0.0       i--\n\
+         pkg://openindiana.org/library/gone@1.0,5.11-2024.0.0.0:20240101T000000Z  i--\n\
+         consolidation/userland/userland-incorporation  2024.0.0.1  i--\n",
+    )
+    .unwrap();
+    assert_eq!(
+        installed[1].get_publisher_as_ref_string(),
+        Some(&"extra".to_owned())
+    );
+
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/zlib@1.3-2024.0.0.0").add(&mut components);
+    TestPackage::new("pkg:/library/old@1.1")
+        .obsolete(true)
+        .add(&mut components);
+    TestPackage::new("pkg:/consolidation/userland/userland-incorporation@2024.0.0.1")
+        .incorporates(&["pkg:/library/zlib@1.2-2024.0.0.0"])
+        .add(&mut components);
+
+    let mut problems = Problems::new();
+    components.check_installed_packages(&mut problems, &installed);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            HeldBackInstalledPackage(
+                installed[0].clone(),
+                FMRI::parse_raw("pkg:/library/zlib@1.3-2024.0.0.0").unwrap(),
+                installed[3].clone(),
+            ),
+            UnavailableInstalledPackage(installed[1].clone(), AlternativeState::Obsolete),
+            UnavailableInstalledPackage(installed[2].clone(), AlternativeState::Missing),
+        ]
+    );
+}
@@ -0,0 +1,77 @@
+use std::{
+    fs::{create_dir_all, remove_dir_all, write},
+    path::{Path, PathBuf},
+};
+
+use crate::assets::make_cache::MakeCache;
+
+#[test]
+fn invalidate_changed() {
+    let repository = Path::new("oi-userland");
+    let target = "print-value-REQUIRED_PACKAGES";
+
+    let mut make_cache = MakeCache::new();
+    for component in ["library/zlib", "library/libpng"] {
+        make_cache.insert(
+            repository.join("components").join(component),
+            target.to_owned(),
+            format!("{}-deps", component),
+        );
+    }
+
+    make_cache.invalidate_changed(repository, &["components/library/zlib/Makefile"]);
+
+    assert_eq!(
+        make_cache.get(
+            &PathBuf::from("oi-userland/components/library/zlib"),
+            target
+        ),
+        None
+    );
+    assert_eq!(
+        make_cache.get(
+            &PathBuf::from("oi-userland/components/library/libpng"),
+            target
+        ),
+        Some(&"library/libpng-deps".to_owned())
+    );
+
+    make_cache.invalidate_changed(repository, &["make-rules/shared-macros.mk"]);
+
+    assert_eq!(make_cache, MakeCache::new());
+}
+
+#[test]
+fn invalidate_changed_hashes() {
+    let directory = std::env::temp_dir().join(format!("make-cache-test-{}", std::process::id()));
+    let component = directory.join("components/library/zlib");
+    create_dir_all(directory.join("make-rules")).unwrap();
+    create_dir_all(&component).unwrap();
+    write(
+        component.join("Makefile"),
+        "include $(WS_MAKE_RULES)/common.mk\nREQUIRED_PACKAGES += system/library\n",
+    )
+    .unwrap();
+    write(
+        directory.join("make-rules/common.mk"),
+        "BUILD_STYLE = configure\n",
+    )
+    .unwrap();
+
+    let target = "print-value-REQUIRED_PACKAGES";
+    let mut make_cache = MakeCache::new();
+    make_cache.insert(component.clone(), target.to_owned(), "deps".to_owned());
+
+    make_cache.invalidate_changed_hashes();
+    assert_eq!(make_cache.get(&component, target), Some(&"deps".to_owned()));
+
+    write(
+        directory.join("make-rules/common.mk"),
+        "BUILD_STYLE = meson\n",
+    )
+    .unwrap();
+    make_cache.invalidate_changed_hashes();
+    assert_eq!(make_cache.get(&component, target), None);
+
+    remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,89 @@
+use std::fs::{create_dir_all, remove_dir_all, write};
+
+use fmri::{FMRIList, FMRI};
+
+use crate::{
+    assets::{
+        manifest_dependencies::load_manifest_dependencies,
+        open_indiana_oi_userland_git::{ComponentPackages, ComponentPackagesList},
+    },
+    packages::{components::Components, dependency::Dependency},
+    problems::Problems,
+    tests::TestPackage,
+    DependTypes,
+};
+
+#[test]
+fn load_manifest_dependencies_from_components() {
+    let directory = std::env::temp_dir().join(format!("manifest-deps-{}", std::process::id()));
+    let path_to_component = directory.join("library/a");
+    create_dir_all(path_to_component.join("build")).unwrap();
+    write(
+        path_to_component.join("a.p5m"),
+        "set name=pkg.fmri value=pkg:/$(COMPONENT_FMRI)@$(IPS_COMPONENT_VERSION)\n\
+        depend type=require \\\n    fmri=pkg:/library/c@1.0\n\
+        depend type=require fmri=pkg:/$(COMPONENT_FMRI)-doc\n\
+        depend fmri=__TBD pkg.debug.depend.file=libz.so.1 type=require\n",
+    )
+    .unwrap();
+    write(
+        path_to_component.join("Makefile"),
+        "COMPONENT_FMRI= library/a\n",
+    )
+    .unwrap();
+    write(
+        path_to_component.join("b.p5m"),
+        "set name=pkg.fmri value=pkg:/library/b@1.0\n",
+    )
+    .unwrap();
+    write(
+        path_to_component.join("build/manifest-i386-b.depend.res"),
+        "depend fmri=pkg:/library/zlib@1.3 type=require\n",
+    )
+    .unwrap();
+
+    let mut problems = Problems::new();
+    let manifest_dependencies = load_manifest_dependencies(
+        &ComponentPackagesList::from(vec![ComponentPackages {
+            component_name: "library/a".to_owned(),
+            path_to_component,
+            packages_in_component: FMRIList::new(),
+        }]),
+        &mut problems,
+    );
+    remove_dir_all(&directory).unwrap();
+
+    let require =
+        |fmri: &str| Dependency::new(&DependTypes::Require(FMRI::parse_raw(fmri).unwrap()));
+    assert_eq!(
+        manifest_dependencies
+            .iter()
+            .map(|(fmri, dependencies)| (fmri.to_string(), dependencies.get_ref().clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "pkg:/library/a".to_owned(),
+                vec![require("pkg:/library/c@1.0")]
+            ),
+            (
+                "pkg:/library/b".to_owned(),
+                vec![require("pkg:/library/zlib@1.3")]
+            ),
+        ]
+    );
+    assert!(problems.get_ref().is_empty());
+
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/a@0.9")
+        .component("library/a")
+        .requires(&["pkg:/library/old"])
+        .add(&mut components);
+
+    components.set_manifest_dependencies(manifest_dependencies);
+
+    assert_eq!(
+        components.get_ref()[0].get_versions_ref()[0].get_packages_ref()[0]
+            .get_runtime_dependencies(),
+        &vec![require("pkg:/library/c@1.0")]
+    );
+}
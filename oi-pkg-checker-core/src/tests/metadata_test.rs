@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use fmri::{FMRIList, FMRI};
+
+use crate::assets::open_indiana_oi_userland_git::{ComponentPackages, ComponentPackagesList};
+use crate::metadata::{component_metadata, component_metadata_to_csv, ComponentMetadata};
+use crate::packages::component::MakefileMetadata;
+use crate::packages::components::Components;
+use crate::packages::depend_types::DependTypes;
+use crate::packages::dependency_type::DependencyTypes;
+use crate::problems::{Problem, Problems};
+use crate::tests::TestPackage;
+
+fn metadata_of_components() -> Vec<ComponentMetadata> {
+    let mut components = Components::new();
+    TestPackage::new("library/a@1.0")
+        .component("library/a")
+        .requires(&["library/b@1.0", "library/c@1.0"])
+        .build(vec![DependTypes::Require(
+            FMRI::parse_raw("library/b@1.0").unwrap(),
+        )])
+        .add(&mut components);
+    TestPackage::new("library/b@1.0")
+        .component("library/b")
+        .add(&mut components);
+    TestPackage::new("library/c@1.0").add(&mut components);
+    for component in components.get_ref_mut() {
+        if component.get_name_ref() == "library/a" {
+            component.set_makefile_metadata(MakefileMetadata {
+                version: Some("1.0".to_owned()),
+                ..Default::default()
+            });
+        }
+    }
+
+    let a = FMRI::parse_raw("library/a@1.0").unwrap();
+    let mut problems = Problems::new();
+    problems.add_problem(Problem::ObsoletedPackageInComponent(
+        a.clone(),
+        "library/a".to_owned(),
+    ));
+    problems.add_problem(Problem::SelfDependency(
+        a.clone(),
+        DependencyTypes::Build,
+        "library/a".to_owned(),
+    ));
+    problems.add_problem(Problem::DuplicateDependency(
+        DependTypes::Require(FMRI::parse_raw("library/b@1.0").unwrap()),
+        DependencyTypes::Build,
+        None,
+        "library/a".to_owned(),
+    ));
+    problems.add_problem(Problem::MissingComponentForPackage(a));
+
+    let component_packages_list = ComponentPackagesList::from(vec![ComponentPackages {
+        component_name: "library/a".to_owned(),
+        path_to_component: PathBuf::from("components/library/a"),
+        packages_in_component: FMRIList::new(),
+    }]);
+
+    component_metadata(
+        &components,
+        &problems,
+        &component_packages_list,
+        &HashMap::from([("library/a".to_owned(), "Doe, John \"jd\"".to_owned())]),
+    )
+}
+
+#[test]
+fn metadata_of_component() {
+    let metadata = metadata_of_components();
+
+    assert_eq!(
+        metadata,
+        vec![
+            ComponentMetadata {
+                name: "library/a".to_owned(),
+                path: Some(PathBuf::from("components/library/a")),
+                version: Some("1.0".to_owned()),
+                packages: vec!["library/a".to_owned()],
+                runtime_dependencies: 2,
+                build_dependencies: 1,
+                test_dependencies: 0,
+                system_build_dependencies: 0,
+                system_test_dependencies: 0,
+                errors: 1,
+                warnings: 2,
+                infos: 1,
+                owner: Some("Doe, John \"jd\"".to_owned()),
+                health_score: 83,
+            },
+            ComponentMetadata {
+                name: "library/b".to_owned(),
+                path: None,
+                version: None,
+                packages: vec!["library/b".to_owned()],
+                runtime_dependencies: 0,
+                build_dependencies: 0,
+                test_dependencies: 0,
+                system_build_dependencies: 0,
+                system_test_dependencies: 0,
+                errors: 0,
+                warnings: 0,
+                // duplicate dependency on library/b in library/a
+                infos: 1,
+                owner: None,
+                health_score: 99,
+            },
+        ]
+    );
+}
+
+#[test]
+fn health_score_saturates() {
+    let mut components = Components::new();
+    TestPackage::new("library/a@1.0")
+        .component("library/a")
+        .add(&mut components);
+    let mut problems = Problems::new();
+    for index in 0..11 {
+        problems.add_problem(Problem::ObsoletedPackageInComponent(
+            FMRI::parse_raw(&format!("library/a{}@1.0", index)).unwrap(),
+            "library/a".to_owned(),
+        ));
+    }
+
+    let metadata = component_metadata(
+        &components,
+        &problems,
+        &ComponentPackagesList::from(vec![]),
+        &HashMap::new(),
+    );
+
+    assert_eq!(metadata[0].errors, 11);
+    assert_eq!(metadata[0].health_score, 0);
+}
+
+#[test]
+fn metadata_to_csv() {
+    assert_eq!(
+        component_metadata_to_csv(&metadata_of_components()),
+        "name,path,version,packages,runtime_dependencies,build_dependencies,test_dependencies,\
+system_build_dependencies,system_test_dependencies,errors,warnings,infos,owner,health_score
+library/a,components/library/a,1.0,library/a,2,1,0,0,0,1,2,1,\"Doe, John \"\"jd\"\"\",83
+library/b,,,library/b,0,0,0,0,0,0,0,1,,99
+"
+    );
+}
@@ -0,0 +1,1082 @@
+use std::fs::{create_dir_all, remove_dir_all, write};
+
+use fmri::{FMRIList, FMRI};
+
+use crate::assets::open_indiana_oi_userland_git::{
+    load_dependencies, load_makefile_metadata, load_packages_in_pkg5, native_makefile_list,
+    parse_classifications, parse_manifest, run_make_parallel, ComponentPackages,
+    ComponentPackagesList, Mediation,
+};
+use crate::config::{Classifications, ComponentDiscovery, EncumberedLicenses};
+use crate::packages::actions::parse_actions;
+use crate::packages::component::{Component, MakefileMetadata};
+use crate::packages::components::Components;
+use crate::packages::package::Package;
+use crate::packages::package_versions::PackageVersions;
+use crate::problems::{MakeFailure, Problem, Problems};
+use crate::tests::TestPackage;
+use crate::DependencyTypes;
+
+#[test]
+fn parse_manifest_paths() {
+    let content = "set name=pkg.fmri \\
+    value=pkg:/$(COMPONENT_FMRI)@$(IPS_COMPONENT_VERSION),$(BUILD_VERSION)
+set name=pkg.summary value=\"$(COMPONENT_SUMMARY)\"
+dir  path=usr/bin
+file path=usr/bin/foo
+file usr/share/man/man1/foo.1 path=/usr/share/man/man1/foo.1
+link path=usr/bin/python target=python3.11 mediator=python mediator-version=3.11
+hardlink path=usr/bin/foo-1 target=foo
+";
+
+    assert_eq!(
+        parse_manifest(content, Some("library/foo")),
+        (
+            Some("library/foo".to_owned()),
+            vec![
+                ("usr/bin/foo".to_owned(), None),
+                ("usr/share/man/man1/foo.1".to_owned(), None),
+                (
+                    "usr/bin/python".to_owned(),
+                    Some(Mediation {
+                        mediator: "python".to_owned(),
+                        version: Some("3.11".to_owned()),
+                        implementation: None,
+                        priority: None,
+                    })
+                ),
+                ("usr/bin/foo-1".to_owned(), None),
+            ]
+        )
+    );
+    assert_eq!(parse_manifest(content, None).0, None);
+}
+
+#[test]
+fn duplicate_pkg5_entries() {
+    let directory = std::env::temp_dir().join(format!("duplicate-pkg5-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let pkg5_path = directory.join("pkg5");
+    write(
+        &pkg5_path,
+        r#"{"fmris": ["library/a", "library/a-doc", "library/a"], "name": "a"}"#,
+    )
+    .unwrap();
+
+    let mut problems = Problems::new();
+    let packages_in_component = load_packages_in_pkg5(&pkg5_path, &mut problems).unwrap();
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(packages_in_component.len(), 2);
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::DuplicatePkg5Entry(
+            FMRI::parse_raw("library/a").unwrap(),
+            pkg5_path
+        )]
+    );
+}
+
+#[test]
+fn invalid_pkg5_keys() {
+    let directory = std::env::temp_dir().join(format!("invalid-pkg5-keys-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let pkg5_path = directory.join("pkg5");
+    write(
+        &pkg5_path,
+        r#"{"fmris": ["library/a"], "dependencies": ["SUNWcs", 1], "obsolete": [], "fmri": []}"#,
+    )
+    .unwrap();
+
+    let mut problems = Problems::new();
+    let packages_in_component = load_packages_in_pkg5(&pkg5_path, &mut problems).unwrap();
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(packages_in_component.len(), 1);
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::InvalidPkg5Key(
+                pkg5_path.clone(),
+                "dependencies".to_owned(),
+                "has invalid value [\"SUNWcs\",1]".to_owned()
+            ),
+            Problem::InvalidPkg5Key(
+                pkg5_path.clone(),
+                "name".to_owned(),
+                "is missing".to_owned()
+            ),
+            Problem::InvalidPkg5Key(pkg5_path, "fmri".to_owned(), "is unknown".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn malformed_pkg5() {
+    let directory = std::env::temp_dir().join(format!("malformed-pkg5-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let pkg5_path = directory.join("pkg5");
+
+    let mut results = vec![];
+    for content in [
+        r#"{"fmris": ["library/a"], "name": "a"}"#,
+        r#"{"fmris": ["library/a"], "name": "a""#,
+        r#"["library/a"]"#,
+        r#"{"name": "a"}"#,
+        r#"{"fmris": "library/a", "name": "a"}"#,
+        r#"{"fmris": ["library/a", 1], "name": "a"}"#,
+    ] {
+        write(&pkg5_path, content).unwrap();
+        let mut problems = Problems::new();
+        let packages_in_component = load_packages_in_pkg5(&pkg5_path, &mut problems);
+        results.push((packages_in_component, problems.get_ref().clone()));
+    }
+    remove_dir_all(&directory).unwrap();
+
+    let malformed = |reason: &str| {
+        (
+            None,
+            vec![Problem::MalformedPkg5(pkg5_path.clone(), reason.to_owned())],
+        )
+    };
+    assert_eq!(
+        results[0],
+        (
+            Some(FMRIList::from(vec![FMRI::parse_raw("library/a").unwrap()])),
+            vec![]
+        )
+    );
+    assert!(matches!(
+        &results[1].1[..],
+        [Problem::MalformedPkg5(path, reason)]
+            if path == &pkg5_path && reason.starts_with("invalid JSON: ")
+    ));
+    assert_eq!(results[2], malformed("expected object"));
+    assert_eq!(results[3], malformed("missing \"fmris\""));
+    assert_eq!(results[4], malformed("\"fmris\" is not array"));
+    assert_eq!(results[5], malformed("fmri 1 is not string"));
+}
+
+#[test]
+fn invalid_fmri_in_pkg5() {
+    let directory = std::env::temp_dir().join(format!("invalid-fmri-pkg5-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let pkg5_path = directory.join("pkg5");
+    write(
+        &pkg5_path,
+        r#"{"fmris": ["library/a", "library/@"], "name": "a"}"#,
+    )
+    .unwrap();
+
+    let mut problems = Problems::new();
+    let packages_in_component = load_packages_in_pkg5(&pkg5_path, &mut problems).unwrap();
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        packages_in_component.get_ref(),
+        &vec![FMRI::parse_raw("library/a").unwrap()]
+    );
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::InvalidFmri(
+            "library/@".to_owned(),
+            pkg5_path.to_string_lossy().to_string()
+        )]
+    );
+}
+
+#[test]
+fn invalid_fmri_in_dependencies() {
+    let directory =
+        std::env::temp_dir().join(format!("invalid-fmri-dependencies-{}", std::process::id()));
+    create_dir_all(directory.join("a")).unwrap();
+    write(
+        directory.join("a/Makefile"),
+        "REQUIRED_PACKAGES += library/b\nREQUIRED_PACKAGES += library/@\n",
+    )
+    .unwrap();
+    write(
+        directory.join("a/pkg5"),
+        r#"{"fmris": ["library/a"], "name": "a"}"#,
+    )
+    .unwrap();
+
+    let mut problems = Problems::new();
+    let component_packages_list = ComponentPackagesList::scan(&directory, &[], &mut problems);
+    let mut components = Components::new();
+    components.set_native_makefiles(true);
+    TestPackage::new("library/a@1.0").add(&mut components);
+    load_dependencies(
+        &mut components,
+        &mut problems,
+        &component_packages_list,
+        &DependencyTypes::Build,
+        None,
+    );
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::InvalidFmri(
+            "library/@".to_owned(),
+            format!(
+                "gmake {}print-value-REQUIRED_PACKAGES in {:?}",
+                if cfg!(target_os = "linux") {
+                    "GSED=/usr/bin/sed "
+                } else {
+                    ""
+                },
+                directory.join("a")
+            )
+        )]
+    );
+}
+
+#[test]
+fn dependencies_without_make() {
+    let directory =
+        std::env::temp_dir().join(format!("dependencies-without-make-{}", std::process::id()));
+    for (component, makefile) in [
+        ("a", "REQUIRED_PACKAGES += library/c\n"),
+        ("b", "REQUIRED_PACKAGES += runtime/python-$(PYV)\n"),
+    ] {
+        create_dir_all(directory.join(component)).unwrap();
+        write(directory.join(component).join("Makefile"), makefile).unwrap();
+        write(
+            directory.join(component).join("pkg5"),
+            format!(
+                r#"{{"fmris": ["library/{}"], "name": "{}"}}"#,
+                component, component
+            ),
+        )
+        .unwrap();
+    }
+
+    let mut problems = Problems::new();
+    let component_packages_list = ComponentPackagesList::scan(&directory, &[], &mut problems);
+    let mut components = Components::new();
+    components.set_native_makefiles(true);
+    components.set_without_make(true);
+    TestPackage::new("library/a@1.0").add(&mut components);
+    TestPackage::new("library/b@1.0").add(&mut components);
+    load_dependencies(
+        &mut components,
+        &mut problems,
+        &component_packages_list,
+        &DependencyTypes::Build,
+        None,
+    );
+    remove_dir_all(&directory).unwrap();
+
+    assert!(matches!(
+        &problems.get_ref()[..],
+        [Problem::UnRunnableMakeCommand(_, path, MakeFailure::NeedsGmake, _)]
+            if path == &directory.join("b")
+    ));
+}
+
+#[test]
+fn scan_components() {
+    let directory = std::env::temp_dir().join(format!("scan-components-{}", std::process::id()));
+    for (component, pkg5) in [
+        ("a", true),
+        ("b", false),
+        ("python/x", true),
+        ("python/y", true),
+        ("perl/z", true),
+    ] {
+        create_dir_all(directory.join(component)).unwrap();
+        write(directory.join(component).join("Makefile"), "").unwrap();
+        if pkg5 {
+            write(
+                directory.join(component).join("pkg5"),
+                r#"{"fmris": [], "name": "a"}"#,
+            )
+            .unwrap();
+        }
+    }
+
+    let component_packages_list = ComponentPackagesList::scan(
+        &directory,
+        &["python/y".to_owned(), "perl/*".to_owned()],
+        &mut Problems::new(),
+    );
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        component_packages_list
+            .get()
+            .iter()
+            .map(|component_packages| component_packages.component_name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["a", "python/x"]
+    );
+}
+
+#[test]
+fn discover_components_with_overlays() {
+    let directory = std::env::temp_dir().join(format!("overlays-{}", std::process::id()));
+    for (tree, component) in [
+        ("upstream", "a"),
+        ("upstream", "python/x"),
+        ("fork", "python/x"),
+        ("fork", "b"),
+        ("downstream", "b"),
+    ] {
+        let path = directory.join(tree).join(component);
+        create_dir_all(&path).unwrap();
+        write(path.join("Makefile"), "").unwrap();
+        write(path.join("pkg5"), r#"{"fmris": [], "name": "a"}"#).unwrap();
+    }
+
+    let component_packages_list = ComponentPackagesList::discover(
+        &directory.join("upstream"),
+        &ComponentDiscovery {
+            scan: true,
+            ignore: vec![],
+            overlays: vec![directory.join("fork"), directory.join("downstream")],
+        },
+        &mut Problems::new(),
+    );
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        component_packages_list
+            .get()
+            .iter()
+            .map(|component_packages| (
+                component_packages.component_name.as_str(),
+                component_packages.path_to_component.clone()
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("a", directory.join("upstream/a")),
+            ("python/x", directory.join("fork/python/x")),
+            ("b", directory.join("downstream/b")),
+        ]
+    );
+}
+
+#[test]
+fn pkg5_names() {
+    let directory = std::env::temp_dir().join(format!("pkg5-names-{}", std::process::id()));
+    let mut component_packages = vec![];
+
+    for (component_name, makefile, pkg5_name) in [
+        ("library/a", Some("COMPONENT_NAME= liba\n"), "liba"),
+        ("library/b", Some("COMPONENT_NAME= $(NAME)\n"), "b"),
+        ("library/c", None, "a"),
+    ] {
+        let path_to_component = directory.join(component_name);
+        create_dir_all(&path_to_component).unwrap();
+        write(
+            path_to_component.join("pkg5"),
+            format!(r#"{{"fmris": [], "name": "{}"}}"#, pkg5_name),
+        )
+        .unwrap();
+        if let Some(makefile) = makefile {
+            write(path_to_component.join("Makefile"), makefile).unwrap();
+        }
+
+        component_packages.push(ComponentPackages {
+            component_name: component_name.to_owned(),
+            path_to_component,
+            packages_in_component: FMRIList::new(),
+        });
+    }
+
+    let mut problems = Problems::new();
+    ComponentPackagesList::from(component_packages).pkg5_names(&mut problems);
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::Pkg5NameMismatch(
+            "library/c".to_owned(),
+            "a".to_owned(),
+            "c".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn duplicate_component_names() {
+    let component_packages: Vec<ComponentPackages> = [
+        ("library/a", "library/a"),
+        ("library/b", "library/b"),
+        ("library/a", "./library/a/"),
+    ]
+    .into_iter()
+    .map(|(component_name, path_to_component)| ComponentPackages {
+        component_name: component_name.to_owned(),
+        path_to_component: path_to_component.into(),
+        packages_in_component: FMRIList::new(),
+    })
+    .collect();
+
+    let mut problems = Problems::new();
+    ComponentPackagesList::from(component_packages).duplicate_component_names(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::DuplicateComponentName(
+            "library/a".to_owned(),
+            vec!["library/a".into(), "./library/a/".into()]
+        )]
+    );
+}
+
+#[test]
+fn file_conflicts() {
+    let directory = std::env::temp_dir().join(format!("file-conflicts-{}", std::process::id()));
+    let mut component_packages = vec![];
+
+    for (component_name, manifest) in [
+        (
+            "library/a",
+            "set name=pkg.fmri value=pkg:/library/a@1.0\n\
+            file path=usr/bin/a\n\
+            link path=usr/bin/python target=python3.11 mediator=python mediator-version=3.11\n\
+            link path=usr/bin/perl target=perl5.36 mediator=perl\n",
+        ),
+        (
+            "library/b",
+            "set name=pkg.fmri value=pkg:/library/b@1.0\n\
+            file path=usr/bin/a\n\
+            link path=usr/bin/python target=python3.9 mediator=python mediator-version=3.9\n\
+            link path=usr/bin/perl target=perl5.38\n",
+        ),
+    ] {
+        let path_to_component = directory.join(component_name);
+        create_dir_all(&path_to_component).unwrap();
+        write(path_to_component.join("a.p5m"), manifest).unwrap();
+
+        component_packages.push(ComponentPackages {
+            component_name: component_name.to_owned(),
+            path_to_component,
+            packages_in_component: FMRIList::new(),
+        });
+    }
+
+    let mut problems = Problems::new();
+    ComponentPackagesList::from(component_packages).file_conflicts(&mut problems);
+    remove_dir_all(directory).unwrap();
+
+    let packages = vec![
+        FMRI::parse_raw("pkg:/library/a").unwrap(),
+        FMRI::parse_raw("pkg:/library/b").unwrap(),
+    ];
+    let component_names = vec!["library/a".to_owned(), "library/b".to_owned()];
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::FileConflict(
+                "usr/bin/a".to_owned(),
+                packages.clone(),
+                component_names.clone()
+            ),
+            Problem::MediatorConflict(
+                "usr/bin/perl".to_owned(),
+                packages,
+                component_names,
+                "path is mediated only in some packages".to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn obsoleted_packages_built() {
+    let directory = std::env::temp_dir().join(format!("obsoleted-built-{}", std::process::id()));
+    let mut components = Components::new();
+    let mut component_packages = vec![];
+
+    for (component_name, packages, listed) in [
+        ("library/a", vec!["library/a", "library/a-doc"], vec![]),
+        ("library/b", vec!["library/b"], vec![]),
+        ("library/c", vec!["library/c"], vec!["pkg:/library/c"]),
+    ] {
+        let path_to_component = directory.join(component_name);
+        create_dir_all(&path_to_component).unwrap();
+        for package in packages {
+            write(
+                path_to_component.join(format!("{}.p5m", package.replace('/', "-"))),
+                format!("set name=pkg.fmri value=pkg:/{}@1.0\n", package),
+            )
+            .unwrap();
+        }
+
+        let mut packages_in_component = FMRIList::new();
+        for fmri in listed {
+            packages_in_component.add(FMRI::parse_raw(fmri).unwrap());
+        }
+        component_packages.push(ComponentPackages {
+            component_name: component_name.to_owned(),
+            path_to_component,
+            packages_in_component,
+        });
+    }
+
+    let mut component = Component::new("library/a".to_owned());
+    let fmri = FMRI::parse_raw("pkg:/library/a-doc@1.0").unwrap();
+    let mut package_versions = PackageVersions::new(fmri.clone());
+    package_versions.add_package(Package::new(fmri, false, false));
+    component.add(package_versions);
+    components.add(component);
+    for fmri in [
+        "pkg:/library/a@1.1",
+        "pkg:/library/b@1.1",
+        "pkg:/library/c@1.1",
+    ] {
+        components.add_obsoleted(FMRI::parse_raw(fmri).unwrap());
+    }
+
+    let mut problems = Problems::new();
+    ComponentPackagesList::from(component_packages)
+        .obsoleted_packages_built(&mut problems, &components);
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::ObsoletedPackageBuilt(
+                FMRI::parse_raw("pkg:/library/a").unwrap(),
+                "library/a".to_owned(),
+                false
+            ),
+            Problem::ObsoletedPackageBuilt(
+                FMRI::parse_raw("pkg:/library/b").unwrap(),
+                "library/b".to_owned(),
+                true
+            ),
+        ]
+    );
+}
+
+#[test]
+fn pkg5_consistency() {
+    let directory = std::env::temp_dir().join(format!("pkg5-consistency-{}", std::process::id()));
+    let path_to_component = directory.join("library/a");
+    create_dir_all(&path_to_component).unwrap();
+    write(
+        path_to_component.join("a.p5m"),
+        "set name=pkg.fmri value=pkg:/$(COMPONENT_FMRI)@1.0\n",
+    )
+    .unwrap();
+    write(
+        path_to_component.join("a-doc.p5m"),
+        "set name=pkg.fmri value=pkg:/library/a-doc@1.0\n",
+    )
+    .unwrap();
+    write(
+        path_to_component.join("Makefile"),
+        "COMPONENT_FMRI= library/a\n",
+    )
+    .unwrap();
+
+    let mut components = Components::new();
+    let mut component = Component::new("library/a".to_owned());
+    for fmri in ["library/a", "library/a-doc", "library/a-old"] {
+        let fmri = FMRI::parse_raw(fmri).unwrap();
+        let mut package_versions = PackageVersions::new(fmri.clone());
+        package_versions.add_package(Package::new(fmri, false, false));
+        component.add(package_versions);
+    }
+    components.add(component);
+
+    let mut packages_in_component = FMRIList::new();
+    packages_in_component.add(FMRI::parse_raw("pkg:/library/a").unwrap());
+    packages_in_component.add(FMRI::parse_raw("pkg:/library/a-old").unwrap());
+
+    let mut problems = Problems::new();
+    ComponentPackagesList::from(vec![ComponentPackages {
+        component_name: "library/a".to_owned(),
+        path_to_component,
+        packages_in_component,
+    }])
+    .pkg5_consistency(&mut problems, &components);
+    remove_dir_all(directory).unwrap();
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::PackageMissingInPkg5(
+                FMRI::parse_raw("pkg:/library/a-doc").unwrap(),
+                "library/a".to_owned()
+            ),
+            Problem::StalePkg5Entry(
+                FMRI::parse_raw("pkg:/library/a-old").unwrap(),
+                "library/a".to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn obsolete_components() {
+    let mut components = Components::new();
+    components.add_obsoleted(FMRI::parse_raw("pkg:/library/a@1.0").unwrap());
+    components.add_obsoleted(FMRI::parse_raw("pkg:/library/a-doc@1.0").unwrap());
+    components.add_obsoleted(FMRI::parse_raw("pkg:/library/b-old@1.0").unwrap());
+
+    let mut component_packages = vec![];
+    for (component_name, packages) in [
+        ("library/a", vec!["pkg:/library/a", "pkg:/library/a-doc"]),
+        ("library/b", vec!["pkg:/library/b", "pkg:/library/b-old"]),
+    ] {
+        let mut packages_in_component = FMRIList::new();
+        for fmri in packages {
+            packages_in_component.add(FMRI::parse_raw(fmri).unwrap());
+        }
+        component_packages.push(ComponentPackages {
+            component_name: component_name.to_owned(),
+            path_to_component: component_name.into(),
+            packages_in_component,
+        });
+    }
+
+    let mut problems = Problems::new();
+    for (fmri, component_name) in [
+        ("pkg:/library/a", "library/a"),
+        ("pkg:/library/b-old", "library/b"),
+    ] {
+        problems.add_problem(Problem::ObsoletedPackageInComponent(
+            FMRI::parse_raw(fmri).unwrap(),
+            component_name.to_owned(),
+        ));
+    }
+
+    let component_packages = ComponentPackagesList::from(component_packages);
+    component_packages.obsolete_components(&mut problems, &components);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::ObsoletedPackageInComponent(
+                FMRI::parse_raw("pkg:/library/b-old").unwrap(),
+                "library/b".to_owned()
+            ),
+            Problem::ObsoleteComponent(
+                "library/a".to_owned(),
+                component_packages.get()[0]
+                    .packages_in_component
+                    .get_ref()
+                    .clone()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn foreign_packages_in_pkg5() {
+    let directory = std::env::temp_dir().join(format!("foreign-packages-{}", std::process::id()));
+    let mut component_packages = vec![];
+
+    for (component_name, manifest, packages) in [
+        (
+            "library/a",
+            Some("set name=pkg.fmri value=pkg:/library/a@1.0\n"),
+            vec!["pkg:/library/a@1.0", "pkg:/library/c"],
+        ),
+        (
+            "library/b",
+            None,
+            vec!["pkg:/library/a@1.1", "pkg:/library/c"],
+        ),
+    ] {
+        let path_to_component = directory.join(component_name);
+        create_dir_all(&path_to_component).unwrap();
+        if let Some(manifest) = manifest {
+            write(path_to_component.join("a.p5m"), manifest).unwrap();
+        }
+
+        let mut packages_in_component = FMRIList::new();
+        for fmri in packages {
+            packages_in_component.add(FMRI::parse_raw(fmri).unwrap());
+        }
+        component_packages.push(ComponentPackages {
+            component_name: component_name.to_owned(),
+            path_to_component,
+            packages_in_component,
+        });
+    }
+
+    let mut problems = Problems::new();
+    ComponentPackagesList::from(component_packages).foreign_packages_in_pkg5(&mut problems);
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![Problem::ForeignPackageInPkg5(
+            FMRI::parse_raw("pkg:/library/a@1.1").unwrap(),
+            "library/b".to_owned(),
+            "library/a".to_owned(),
+            directory.join("library/b"),
+            directory.join("library/a"),
+        )]
+    );
+}
+
+#[test]
+fn classifications() {
+    assert_eq!(
+        parse_classifications(
+            "set name=info.classification \\\n    \
+            value=\"org.opensolaris.category.2008:Desktop (GNOME)/Sessions\"\n"
+        ),
+        vec!["org.opensolaris.category.2008:Desktop (GNOME)/Sessions".to_owned()]
+    );
+
+    let directory = std::env::temp_dir().join(format!("classifications-{}", std::process::id()));
+    let path_to_component = directory.join("library/a");
+    create_dir_all(&path_to_component).unwrap();
+
+    let mut components = Components::new();
+    let mut component = Component::new("library/a".to_owned());
+    for (package, classification) in [
+        (
+            "library/a",
+            "value=\"org.opensolaris.category.2008:Desktop (GNOME)/Libraries\"",
+        ),
+        ("library/a-doc", ""),
+        (
+            "library/a-dev",
+            "value=org.opensolaris.category.2008:Toys/Games",
+        ),
+        ("library/a-old", ""),
+    ] {
+        let mut manifest = format!("set name=pkg.fmri value=pkg:/{}@1.0\n", package);
+        if !classification.is_empty() {
+            manifest.push_str(&format!(
+                "set name=info.classification {}\n",
+                classification
+            ));
+        }
+        write(
+            path_to_component.join(format!("{}.p5m", package.replace('/', "-"))),
+            manifest,
+        )
+        .unwrap();
+
+        if package != "library/a-old" {
+            let fmri = FMRI::parse_raw(package).unwrap();
+            let mut package_versions = PackageVersions::new(fmri.clone());
+            package_versions.add_package(Package::new(fmri, false, false));
+            component.add(package_versions);
+        }
+    }
+    components.add(component);
+    components.add_obsoleted(FMRI::parse_raw("pkg:/library/a-old@1.1").unwrap());
+
+    let mut problems = Problems::new();
+    ComponentPackagesList::from(vec![ComponentPackages {
+        component_name: "library/a".to_owned(),
+        path_to_component,
+        packages_in_component: FMRIList::new(),
+    }])
+    .classifications(&mut problems, &components, &Classifications::default());
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::InvalidClassification(
+                FMRI::parse_raw("pkg:/library/a-dev").unwrap(),
+                "library/a".to_owned(),
+                Some("org.opensolaris.category.2008:Toys/Games".to_owned()),
+                "section is not allowed".to_owned()
+            ),
+            Problem::InvalidClassification(
+                FMRI::parse_raw("pkg:/library/a-doc").unwrap(),
+                "library/a".to_owned(),
+                None,
+                "missing".to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn licenses() {
+    let directory = std::env::temp_dir().join(format!("licenses-{}", std::process::id()));
+    let mut components = Components::new();
+    let mut component_packages = vec![];
+
+    for (component_name, makefile, manifest) in [
+        (
+            "library/a",
+            "COMPONENT_LICENSE= MIT\n",
+            "set name=pkg.fmri value=pkg:/library/a@1.0\n\
+            file path=usr/lib/liba.so.1\n\
+            license a.license license=MIT\n",
+        ),
+        (
+            "library/b",
+            "COMPONENT_LICENSE_FILE= b.license\n",
+            "set name=pkg.fmri value=pkg:/library/b@1.0\nfile path=usr/lib/libb.so.1\n",
+        ),
+        (
+            "library/c",
+            "COMPONENT_NAME= c\n",
+            "set name=pkg.fmri value=pkg:/library/c@1.0\nlink path=usr/lib/libc.so target=x\n",
+        ),
+    ] {
+        let path_to_component = directory.join(component_name);
+        create_dir_all(&path_to_component).unwrap();
+        write(path_to_component.join("Makefile"), makefile).unwrap();
+        write(path_to_component.join("a.p5m"), manifest).unwrap();
+
+        let mut component = Component::new(component_name.to_owned());
+        let fmri = FMRI::parse_raw(component_name).unwrap();
+        let mut package_versions = PackageVersions::new(fmri.clone());
+        package_versions.add_package(Package::new(fmri, false, false));
+        component.add(package_versions);
+        components.add(component);
+
+        component_packages.push(ComponentPackages {
+            component_name: component_name.to_owned(),
+            path_to_component,
+            packages_in_component: FMRIList::new(),
+        });
+    }
+
+    let mut problems = Problems::new();
+    ComponentPackagesList::from(component_packages).licenses(&mut problems, &components);
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::MissingLicenseAction(
+                FMRI::parse_raw("pkg:/library/b").unwrap(),
+                "library/b".to_owned()
+            ),
+            Problem::MissingComponentLicense(
+                "library/c".to_owned(),
+                directory.join("library/c/Makefile")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn encumbered_placement() {
+    let directory = std::env::temp_dir().join(format!("encumbered-{}", std::process::id()));
+    let mut components = Components::new();
+    let mut component_packages = vec![];
+
+    for (component_name, path, makefile, manifest) in [
+        (
+            "library/a",
+            "library/a",
+            "COMPONENT_LICENSE= MIT\n",
+            "license a.license license=\"Oracle BCL\"\n",
+        ),
+        (
+            "library/b",
+            "encumbered/library/b",
+            "COMPONENT_LICENSE= MIT, BSD\n",
+            "license b.license license=MIT\n",
+        ),
+        (
+            "library/c",
+            "encumbered/library/c",
+            "COMPONENT_LICENSE= GPLv2/unrar\n",
+            "license c.license license=GPLv2\n",
+        ),
+        ("library/d", "library/d", "COMPONENT_LICENSE= MIT\n", ""),
+    ] {
+        let path_to_component = directory.join(path);
+        create_dir_all(&path_to_component).unwrap();
+        write(path_to_component.join("Makefile"), makefile).unwrap();
+
+        let mut component = Component::new(component_name.to_owned());
+        let fmri = FMRI::parse_raw(component_name).unwrap();
+        let mut package_versions = PackageVersions::new(fmri.clone());
+        package_versions.add_package(Package::new(fmri.clone(), false, false));
+        package_versions.set_actions(parse_actions(manifest));
+        component.add(package_versions);
+        components.add(component);
+
+        let mut packages_in_component = FMRIList::new();
+        packages_in_component.add(fmri);
+        component_packages.push(ComponentPackages {
+            component_name: component_name.to_owned(),
+            path_to_component,
+            packages_in_component,
+        });
+    }
+
+    let mut problems = Problems::new();
+    ComponentPackagesList::from(component_packages).encumbered_placement(
+        &mut problems,
+        &components,
+        &EncumberedLicenses {
+            licenses: vec!["oracle bcl".to_owned(), "UnRAR".to_owned()],
+            ..EncumberedLicenses::default()
+        },
+    );
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::EncumberedPlacement(
+                "library/a".to_owned(),
+                true,
+                vec!["license Oracle BCL of library/a".to_owned()]
+            ),
+            Problem::EncumberedPlacement(
+                "library/b".to_owned(),
+                false,
+                vec![
+                    "COMPONENT_LICENSE MIT, BSD".to_owned(),
+                    "license MIT of library/b".to_owned()
+                ]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn same_packages_in_components() {
+    let mut component_packages = vec![];
+    for (component_name, packages) in [
+        (
+            "library/a",
+            vec!["pkg:/library/a@1.0", "pkg:/library/c@1.0"],
+        ),
+        (
+            "library/b",
+            vec!["pkg:/library/a@1.0", "pkg:/library/c@2.0"],
+        ),
+    ] {
+        let mut packages_in_component = FMRIList::new();
+        for fmri in packages {
+            packages_in_component.add(FMRI::parse_raw(fmri).unwrap());
+        }
+        component_packages.push(ComponentPackages {
+            component_name: component_name.to_owned(),
+            path_to_component: component_name.into(),
+            packages_in_component,
+        });
+    }
+
+    let mut problems = Problems::new();
+    ComponentPackagesList::from(component_packages).same_packages_in_components(&mut problems);
+
+    assert_eq!(
+        problems.get_ref(),
+        &vec![
+            Problem::PackageInMultipleComponents(
+                FMRI::parse_raw("pkg:/library/a").unwrap(),
+                vec!["library/a".to_owned(), "library/b".to_owned()]
+            ),
+            Problem::PackageVersionsInMultipleComponents(
+                FMRI::parse_raw("pkg:/library/c").unwrap(),
+                vec![
+                    ("library/a".to_owned(), Some("1.0".to_owned())),
+                    ("library/b".to_owned(), Some("2.0".to_owned())),
+                ]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn makefile_metadata() {
+    let directory = std::env::temp_dir().join(format!("makefile-metadata-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let makefile = directory.join("Makefile");
+    write(
+        &makefile,
+        "BUILD_BITS= 64_and_32
+include ../../make-rules/shared-macros.mk
+
+COMPONENT_NAME=		libfoo
+COMPONENT_VERSION=	1.2.3
+COMPONENT_PROJECT_URL=	https://example.org/$(COMPONENT_NAME)/
+COMPONENT_SRC=		$(COMPONENT_NAME)-$(COMPONENT_VERSION)
+TPNO=			12345
+
+BUILD_STYLE= configure
+include $(WS_MAKE_RULES)/common.mk
+",
+    )
+    .unwrap();
+
+    let metadata = load_makefile_metadata(&makefile);
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        metadata,
+        MakefileMetadata {
+            name: Some("libfoo".to_owned()),
+            version: Some("1.2.3".to_owned()),
+            project_url: Some("https://example.org/libfoo/".to_owned()),
+            tpno: Some("12345".to_owned()),
+            build_style: Some("configure".to_owned()),
+        }
+    );
+}
+
+#[test]
+fn native_makefile_lists() {
+    let makefile = "include ../../../make-rules/shared-macros.mk
+
+COMPONENT_NAME= foo
+# REQUIRED_PACKAGES += commented/out
+TEST_REQUIRED_PACKAGES += developer/test
+REQUIRED_PACKAGES += library/a
+REQUIRED_PACKAGES+= library/b \\
+\tlibrary/c
+";
+
+    assert_eq!(
+        native_makefile_list(makefile, "REQUIRED_PACKAGES"),
+        Some(vec![
+            "library/a".to_owned(),
+            "library/b".to_owned(),
+            "library/c".to_owned()
+        ])
+    );
+    assert_eq!(
+        native_makefile_list(makefile, "TEST_REQUIRED_PACKAGES"),
+        Some(vec!["developer/test".to_owned()])
+    );
+    assert_eq!(
+        native_makefile_list(makefile, "USERLAND_REQUIRED_PACKAGES"),
+        Some(vec![])
+    );
+
+    for exotic in [
+        "REQUIRED_PACKAGES += runtime/python-$(PYV)\n",
+        "ifeq ($(MACH),i386)\nREQUIRED_PACKAGES += library/a\nendif\n",
+        "install: REQUIRED_PACKAGES += library/a\n",
+    ] {
+        assert_eq!(native_makefile_list(exotic, "REQUIRED_PACKAGES"), None);
+    }
+}
+
+#[test]
+fn run_make_in_parallel() {
+    let directory = std::env::temp_dir().join(format!("parallel-make-{}", std::process::id()));
+    let component_paths: Vec<_> = ["a", "b", "c", "d"]
+        .into_iter()
+        .map(|name| directory.join(name))
+        .collect();
+    for component_path in &component_paths {
+        create_dir_all(component_path).unwrap();
+    }
+
+    let outputs = run_make_parallel(component_paths.clone(), "basename $(pwd)", 3);
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(outputs.len(), 4);
+    for component_path in component_paths {
+        assert_eq!(
+            String::from_utf8_lossy(&outputs[&component_path].stdout).trim(),
+            component_path.file_name().unwrap().to_string_lossy()
+        );
+    }
+}
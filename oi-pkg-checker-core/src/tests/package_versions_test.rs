@@ -47,6 +47,7 @@ fn add_package_1() {
                     renamed3
                 )
             ],
+            actions: vec![],
         }
     );
 }
@@ -95,6 +96,7 @@ fn add_package_2() {
                 ),
                 // Package::new(FMRI::parse_raw(&"test@3".to_string()), obsolete3, renamed3)
             ],
+            actions: vec![],
         }
     );
 }
@@ -143,6 +145,7 @@ fn add_package_3() {
                     renamed3
                 )
             ],
+            actions: vec![],
         }
     );
 }
@@ -191,6 +194,7 @@ fn add_package_4() {
                     renamed3
                 )
             ],
+            actions: vec![],
         }
     );
 }
@@ -239,6 +243,7 @@ fn add_package_5() {
                     renamed3
                 )
             ],
+            actions: vec![],
         }
     );
 }
@@ -0,0 +1,50 @@
+use fmri::FMRI;
+
+use crate::packages::components::Components;
+use crate::packages::depend_types::DependTypes;
+use crate::pinning::PinImpact;
+use crate::tests::TestPackage;
+
+#[test]
+fn pin_impact() {
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/zlib@1.3").add(&mut components);
+    TestPackage::new("pkg:/image/png@1.6")
+        .runtime(vec![DependTypes::Require(
+            FMRI::parse_raw("pkg:/library/zlib@1.3").unwrap(),
+        )])
+        .add(&mut components);
+    TestPackage::new("pkg:/consolidation/userland@1.0")
+        .runtime(vec![DependTypes::Incorporate(
+            FMRI::parse_raw("pkg:/library/zlib@1.3").unwrap(),
+        )])
+        .add(&mut components);
+
+    let pin = FMRI::parse_raw("pkg:/library/zlib@1.2").unwrap();
+    let pin_impact = PinImpact::new(&components, &pin).unwrap();
+
+    assert_eq!(
+        pin_impact.blocked_updates,
+        vec![FMRI::parse_raw("pkg:/library/zlib@1.3").unwrap()]
+    );
+    assert_eq!(
+        pin_impact.unsatisfiable,
+        vec![(
+            FMRI::parse_raw("pkg:/image/png@1.6").unwrap(),
+            DependTypes::Require(FMRI::parse_raw("pkg:/library/zlib@1.3").unwrap())
+        )]
+    );
+    assert_eq!(
+        pin_impact.incorporation_conflicts,
+        vec![(
+            FMRI::parse_raw("pkg:/consolidation/userland@1.0").unwrap(),
+            FMRI::parse_raw("pkg:/library/zlib@1.3").unwrap()
+        )]
+    );
+
+    assert!(PinImpact::new(
+        &components,
+        &FMRI::parse_raw("pkg:/library/missing@1.0").unwrap()
+    )
+    .is_none());
+}
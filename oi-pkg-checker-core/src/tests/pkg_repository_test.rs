@@ -0,0 +1,121 @@
+use std::{
+    fs::{create_dir_all, read_to_string, remove_dir_all, write},
+    process::Command,
+};
+
+use serde_json::{json, Value};
+
+use crate::assets::pkg_repository::{archive_catalog, repository_catalog};
+
+#[test]
+fn load_repository() {
+    let directory = std::env::temp_dir().join(format!("pkg-repository-{}", std::process::id()));
+    let repository = directory.join("repo");
+
+    assert!(repository_catalog(&repository, &directory.join("out")).is_err());
+
+    write_manifest(
+        &repository,
+        "library%2Fa/1.1%2C5.11-2024.0.0.1%3A20240102T000000Z",
+        "set name=pkg.fmri value=pkg://userland/library/a@1.1,5.11-2024.0.0.1:20240102T000000Z\n\
+        set name=pkg.summary value=\"a\"\n\
+        depend fmri=pkg:/library/b@1.0 \\\n    type=require\n\
+        file path=usr/lib/liba.so.1\n",
+    );
+    write_manifest(
+        &repository,
+        "library%2Fa/1.0%2C5.11-2024.0.0.0%3A20240101T000000Z",
+        "set name=pkg.fmri value=pkg://userland/library/a@1.0,5.11-2024.0.0.0:20240101T000000Z\n",
+    );
+    write_manifest(
+        &repository,
+        "library%2Fb/1.0%2C5.11-2024.0.0.0%3A20240101T000000Z",
+        "set name=pkg.fmri value=pkg://userland/library/b@1.0,5.11-2024.0.0.0:20240101T000000Z\n\
+        set name=pkg.obsolete value=true\n",
+    );
+    write(
+        repository.join("pkg5.repository"),
+        "[repository]\nversion = 4\n",
+    )
+    .unwrap();
+
+    let catalog = repository_catalog(&repository, &directory.join("out")).unwrap();
+    let json: Value = serde_json::from_str(&read_to_string(catalog).unwrap()).unwrap();
+    remove_dir_all(&directory).unwrap();
+
+    assert_eq!(
+        json,
+        json!({
+            "userland": {
+                "library/a": [
+                    {
+                        "version": "1.0,5.11-2024.0.0.0:20240101T000000Z",
+                        "actions": [],
+                    },
+                    {
+                        "version": "1.1,5.11-2024.0.0.1:20240102T000000Z",
+                        "actions": ["depend fmri=pkg:/library/b@1.0 type=require"],
+                    },
+                ],
+                "library/b": [
+                    {
+                        "version": "1.0,5.11-2024.0.0.0:20240101T000000Z",
+                        "actions": ["set name=pkg.obsolete value=true"],
+                    },
+                ],
+            }
+        })
+    );
+}
+
+#[test]
+fn load_archive() {
+    let directory = std::env::temp_dir().join(format!("pkg-archive-{}", std::process::id()));
+    let content = directory.join("content");
+    write_manifest(
+        &content,
+        "library%2Fa/1.0%2C5.11-2024.0.0.0%3A20240101T000000Z",
+        "set name=pkg.fmri value=pkg://userland/library/a@1.0,5.11-2024.0.0.0:20240101T000000Z\n\
+        depend fmri=pkg:/library/b type=require\n",
+    );
+    create_dir_all(content.join("publisher/userland/file/ab")).unwrap();
+    write(content.join("publisher/userland/file/ab/abcdef"), "payload").unwrap();
+
+    let archive = directory.join("a.p5p");
+    assert!(Command::new("tar")
+        .arg("-cf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(&content)
+        .arg("publisher")
+        .status()
+        .unwrap()
+        .success());
+
+    let output = directory.join("out");
+    let catalog = archive_catalog(&archive, &output).unwrap();
+    let json: Value = serde_json::from_str(&read_to_string(catalog).unwrap()).unwrap();
+    let payload_extracted = output.join("archive/publisher/userland/file").exists();
+    remove_dir_all(&directory).unwrap();
+
+    assert!(!payload_extracted);
+    assert_eq!(
+        json,
+        json!({
+            "userland": {
+                "library/a": [
+                    {
+                        "version": "1.0,5.11-2024.0.0.0:20240101T000000Z",
+                        "actions": ["depend fmri=pkg:/library/b type=require"],
+                    },
+                ],
+            }
+        })
+    );
+}
+
+fn write_manifest(repository: &std::path::Path, path: &str, content: &str) {
+    let path = repository.join("publisher/userland/pkg").join(path);
+    create_dir_all(path.parent().unwrap()).unwrap();
+    write(path, content).unwrap();
+}
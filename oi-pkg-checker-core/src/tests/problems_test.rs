@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+
+use fmri::FMRI;
+
+use crate::checks::get_check;
+use crate::packages::component::Component;
+use crate::packages::components::Components;
+use crate::packages::depend_types::DependTypes;
+use crate::packages::dependency_type::DependencyTypes;
+use crate::packages::package_versions::PackageVersions;
+use crate::problems::{
+    format_count, report, MakeFailure, Problem, ProblemKind, Problems, Severity,
+};
+
+#[test]
+fn diff() {
+    let mut old = Problems::new();
+    old.add_problem(Problem::UselessComponent("fixed/component".to_owned()));
+    old.add_problem(Problem::ObsoletedRequired(
+        DependTypes::Require(FMRI::parse_raw("pkg:/library/obsoleted@1.0").unwrap()),
+        DependencyTypes::Runtime,
+        FMRI::parse_raw("pkg:/test@1.0").unwrap(),
+        "test".to_owned(),
+    ));
+
+    let mut new = Problems::new();
+    new.add_problem(Problem::ObsoletedRequired(
+        DependTypes::Require(FMRI::parse_raw("pkg:/library/obsoleted@2.0").unwrap()),
+        DependencyTypes::Runtime,
+        FMRI::parse_raw("pkg:/test@2.0").unwrap(),
+        "test".to_owned(),
+    ));
+    new.add_problem(Problem::UselessComponent("new/component".to_owned()));
+
+    let diff = new.diff(&old);
+
+    assert_eq!(diff.introduced.len(), 1);
+    assert_eq!(
+        diff.introduced[0],
+        &Problem::UselessComponent("new/component".to_owned())
+    );
+    assert_eq!(diff.fixed.len(), 1);
+    assert_eq!(
+        diff.fixed[0],
+        &Problem::UselessComponent("fixed/component".to_owned())
+    );
+    assert_eq!(diff.persisting.len(), 1);
+    assert_eq!(diff.persisting[0].kind(), ProblemKind::ObsoletedRequired);
+    assert!(!diff.is_clean());
+}
+
+#[test]
+fn diff_of_bumped_versions() {
+    let problems = |version: &str| {
+        let fmri = |name: &str| FMRI::parse_raw(&format!("{}@{}", name, version)).unwrap();
+        let mut problems = Problems::new();
+        problems.add_problem(Problem::TimestampAnomaly(
+            fmri("pkg:/a"),
+            Some(fmri("pkg:/a")),
+        ));
+        problems.add_problem(Problem::ObsoleteWithDependencies(
+            fmri("pkg:/b"),
+            vec![DependTypes::Require(fmri("pkg:/c"))],
+        ));
+        problems.add_problem(Problem::TighterThanIncorporation(
+            Box::new(DependTypes::Require(fmri("pkg:/d"))),
+            fmri("pkg:/e"),
+            fmri("pkg:/incorporation"),
+            fmri("pkg:/d"),
+        ));
+        problems.add_problem(Problem::HeldBackInstalledPackage(
+            fmri("pkg:/f"),
+            fmri("pkg:/f"),
+            fmri("pkg:/incorporation"),
+        ));
+        problems.add_problem(Problem::MirrorSkew(
+            FMRI::parse_raw("pkg:/g").unwrap(),
+            "openindiana.org".to_owned(),
+            vec![(
+                "https://pkg.openindiana.org".to_owned(),
+                Some(version.to_owned()),
+            )],
+        ));
+        problems.add_problem(Problem::PackageVersionsInMultipleComponents(
+            FMRI::parse_raw("pkg:/h").unwrap(),
+            vec![("h".to_owned(), Some(version.to_owned()))],
+        ));
+        problems
+    };
+    let (old, new) = (problems("1.0"), problems("1.1"));
+
+    let diff = new.diff(&old);
+
+    assert!(diff.introduced.is_empty());
+    assert!(diff.fixed.is_empty());
+    assert_eq!(diff.persisting.len(), 6);
+}
+
+#[test]
+fn deduplicate() {
+    let mut problems = Problems::new();
+    for required_by in ["pkg:/a@1.0", "pkg:/b@1.0", "pkg:/c@1.0"] {
+        problems.add_problem(Problem::ObsoletedRequired(
+            DependTypes::Require(FMRI::parse_raw("pkg:/library/obsoleted@1.0").unwrap()),
+            DependencyTypes::Runtime,
+            FMRI::parse_raw(required_by).unwrap(),
+            "component".to_owned(),
+        ));
+    }
+    problems.add_problem(Problem::UselessComponent("component".to_owned()));
+
+    let deduplicated = problems.deduplicate();
+
+    assert_eq!(deduplicated.len(), 2);
+    assert_eq!(deduplicated[0].occurrences, 3);
+    assert_eq!(
+        deduplicated[0].contexts,
+        vec!["package a", "package b", "package c"]
+    );
+    assert_eq!(deduplicated[1].occurrences, 1);
+    assert!(deduplicated[1].contexts.is_empty());
+}
+
+#[test]
+fn report_deduplicated_other_problem() {
+    let mut problems = Problems::new();
+    for version in ["1.0", "2.0"] {
+        problems.add_problem(Problem::RequiredFromOtherDepot(
+            DependTypes::Require(
+                FMRI::parse_raw(&format!("pkg:/library/other@{}", version)).unwrap(),
+            ),
+            DependencyTypes::Runtime,
+            FMRI::parse_raw("pkg:/a@1.0").unwrap(),
+            "component".to_owned(),
+            "https://pkg.example.org/other".to_owned(),
+        ));
+    }
+
+    let deduplicated = problems.deduplicate();
+    assert_eq!(deduplicated.len(), 1);
+    assert_eq!(deduplicated[0].occurrences, 2);
+
+    report(&mut problems);
+}
+
+#[test]
+fn to_json() {
+    let mut problems = Problems::new();
+    problems.add_problem(Problem::RenamedNeedsRenamed(
+        FMRI::parse_raw("pkg:/a@1.0").unwrap(),
+        FMRI::parse_raw("pkg:/b@1.0").unwrap(),
+    ));
+
+    let json = problems.to_json();
+
+    assert_eq!(json[0]["kind"], "RenamedNeedsRenamed");
+    assert_eq!(
+        json[0]["suggestion"],
+        "re-point dependency of a at the rename target of b"
+    );
+}
+
+#[test]
+fn to_json_with_limit() {
+    let mut problems = Problems::new();
+    for component_name in ["a", "b", "c"] {
+        problems.add_problem(Problem::UselessComponent(component_name.to_owned()));
+    }
+
+    let json = problems.to_json_with_limit(2);
+
+    assert_eq!(json["problems"].as_array().unwrap().len(), 2);
+    assert_eq!(json["counts"]["UselessComponent"], 3);
+    assert_eq!(format_count(3200), "3,200");
+    assert_eq!(format_count(1234567), "1,234,567");
+    assert_eq!(format_count(999), "999");
+}
+
+#[test]
+fn on_problem() {
+    use std::{cell::RefCell, rc::Rc};
+
+    let found = Rc::new(RefCell::new(vec![]));
+
+    let mut problems = Problems::new();
+    let observed = found.clone();
+    problems.on_problem(move |problem| observed.borrow_mut().push(problem.kind()));
+
+    problems.add_problem(Problem::UselessComponent("component".to_owned()));
+    problems.add_problem(Problem::UselessComponent("component".to_owned()));
+
+    assert_eq!(*found.borrow(), vec![ProblemKind::UselessComponent]);
+}
+
+#[test]
+fn filter() {
+    let mut problems = Problems::new();
+    problems.add_problem(Problem::UselessComponent("library/a".to_owned()));
+    problems.add_problem(Problem::NonExistingPackageInPkg5(
+        FMRI::parse_raw("pkg:/library/b@1.0").unwrap(),
+        "library/a".to_owned(),
+    ));
+    problems.add_problem(Problem::MissingComponentForPackage(
+        FMRI::parse_raw("pkg:/library/b@1.0").unwrap(),
+    ));
+
+    assert_eq!(problems.filter_by_component("library/a").count(), 2);
+    assert_eq!(
+        problems
+            .filter_by_package(&FMRI::parse_raw("pkg:/library/b").unwrap())
+            .count(),
+        2
+    );
+    assert_eq!(
+        problems
+            .filter_by_kind(ProblemKind::MissingComponentForPackage)
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn severity() {
+    let mut problems = Problems::new();
+    assert_eq!(problems.max_severity(), None);
+
+    problems.add_problem(Problem::UselessComponent("a".to_owned()));
+    problems.add_problem(Problem::UselessComponent("b".to_owned()));
+    assert_eq!(problems.max_severity(), Some(Severity::Info));
+
+    problems.add_problem(Problem::NonExistingPackageInPkg5(
+        FMRI::parse_raw("pkg:/c").unwrap(),
+        "c".to_owned(),
+    ));
+    assert_eq!(problems.max_severity(), Some(Severity::Error));
+    assert_eq!(problems.count_by_severity()[&Severity::Info], 2);
+    assert_eq!(problems.count_by_severity()[&Severity::Error], 1);
+}
+
+#[test]
+fn group_by_component() {
+    let mut problems = Problems::new();
+    problems.add_problem(Problem::MissingComponentForPackage(
+        FMRI::parse_raw("pkg:/a").unwrap(),
+    ));
+    problems.add_problem(Problem::UselessComponent("library/b".to_owned()));
+    problems.add_problem(Problem::UselessComponent("library/a".to_owned()));
+
+    let groups = problems.group_by_component();
+
+    assert_eq!(groups.len(), 3);
+    assert_eq!(groups[0].0.unwrap(), "library/a");
+    assert_eq!(groups[1].0.unwrap(), "library/b");
+    assert!(groups[2].0.is_none());
+}
+
+#[test]
+fn summary() {
+    let mut problems = Problems::new();
+    problems.add_problem(Problem::UselessComponent("library/a".to_owned()));
+    problems.add_problem(Problem::SelfDependency(
+        FMRI::parse_raw("pkg:/b").unwrap(),
+        DependencyTypes::Build,
+        "library/a".to_owned(),
+    ));
+    problems.add_problem(Problem::MissingComponentForPackage(
+        FMRI::parse_raw("pkg:/c").unwrap(),
+    ));
+
+    let summary = problems.summary();
+
+    assert_eq!(summary.total, 3);
+    assert_eq!(summary.by_kind[&ProblemKind::UselessComponent], 1);
+    assert_eq!(summary.by_severity[&Severity::Warning], 2);
+    assert_eq!(summary.by_component["library/a"], 2);
+    assert_eq!(summary.by_dependency_type[&DependencyTypes::Build], 1);
+}
+
+#[test]
+fn owners() {
+    let mut components = Components::new();
+    let mut component = Component::new("library/a".to_owned());
+    component.add(PackageVersions::new(FMRI::parse_raw("pkg:/a").unwrap()));
+    components.add(component);
+
+    let mut problems = Problems::new();
+    problems.set_owners(
+        &components,
+        HashMap::from([("library/a".to_owned(), "alice".to_owned())]),
+    );
+    problems.add_problem(Problem::MissingComponentForPackage(
+        FMRI::parse_raw("pkg:/a").unwrap(),
+    ));
+    problems.add_problem(Problem::MissingComponentForPackage(
+        FMRI::parse_raw("pkg:/b").unwrap(),
+    ));
+
+    assert_eq!(problems.get_owners(&problems.get_ref()[0]), vec!["alice"]);
+    assert!(problems.get_owners(&problems.get_ref()[1]).is_empty());
+
+    let deserialized: Problems =
+        bincode::deserialize(&bincode::serialize(&problems).unwrap()).unwrap();
+    assert_eq!(deserialized.get_ref(), problems.get_ref());
+    assert!(deserialized
+        .get_owners(&deserialized.get_ref()[0])
+        .is_empty());
+}
+
+#[test]
+fn serialize_atomic() {
+    let path = std::env::temp_dir().join(format!("problems-{}.bin", std::process::id()));
+    let path = path.to_string_lossy().to_string();
+
+    let mut problems = Problems::new();
+    problems.add_problem(Problem::UselessComponent("library/a".to_owned()));
+    problems.serialize(&path).unwrap();
+    problems.serialize(&path).unwrap();
+
+    assert_eq!(
+        Problems::deserialize(&path).unwrap().get_ref(),
+        problems.get_ref()
+    );
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(format!("{}.lock", path)).unwrap();
+}
+
+#[test]
+fn disable_checks() {
+    let mut problems = Problems::new();
+    problems.disable_checks(vec!["useless-components".to_owned()]);
+    problems.add_problem(Problem::UselessComponent("library/a".to_owned()));
+    problems.add_problem(Problem::MissingComponentForPackage(
+        FMRI::parse_raw("pkg:/a").unwrap(),
+    ));
+
+    assert_eq!(problems.get_ref().len(), 1);
+    assert!(get_check(problems.get_ref()[0].check_id()).is_some());
+    assert_eq!(problems.summary().by_check["component-packages"], 1);
+}
+
+#[test]
+fn classify_make_failure() {
+    for (stderr, failure) in [
+        (
+            "Makefile:27: ../../make-rules/shared-macros.mk: No such file or directory\n\
+            gmake: *** No rule to make target '../../make-rules/shared-macros.mk'.  Stop.",
+            MakeFailure::MissingInclude,
+        ),
+        (
+            "Makefile:30: *** missing separator.  Stop.",
+            MakeFailure::SyntaxError,
+        ),
+        ("sh: 1: gsed: not found", MakeFailure::MissingTool),
+        (
+            "gmake: *** [Makefile:5: all] Error 1",
+            MakeFailure::Other(Some(2)),
+        ),
+    ] {
+        assert_eq!(MakeFailure::classify(stderr, Some(2)), failure);
+    }
+}
@@ -0,0 +1,82 @@
+use std::fs::{create_dir_all, remove_dir_all, write};
+
+use fmri::FMRI;
+
+use crate::assets::rewrite_rules::{RewriteRule, RewriteRules};
+use crate::packages::components::Components;
+use crate::packages::depend_types::DependTypes;
+
+#[test]
+fn rewrite_fmri() {
+    let mut rewrite_rules = RewriteRules::new();
+    rewrite_rules.add(RewriteRule::new("^experimental/", "").unwrap());
+    rewrite_rules.add(RewriteRule::new("^library/python-2/", "library/python/").unwrap());
+
+    let mut components = Components::new();
+    components.set_rewrite_rules(rewrite_rules);
+
+    let mut fmri = FMRI::parse_raw("pkg://openindiana.org/experimental/test@1.0").unwrap();
+    components.rewrite_fmri(&mut fmri);
+    assert_eq!(
+        fmri,
+        FMRI::parse_raw("pkg://openindiana.org/test@1.0").unwrap()
+    );
+
+    let mut depend_type = DependTypes::RequireAny(
+        vec![
+            FMRI::parse_raw("pkg:/library/python-2/six").unwrap(),
+            FMRI::parse_raw("pkg:/library/six").unwrap(),
+        ]
+        .into(),
+    );
+    components.rewrite_depend_type(&mut depend_type);
+    assert_eq!(
+        depend_type,
+        DependTypes::RequireAny(
+            vec![
+                FMRI::parse_raw("pkg:/library/python/six").unwrap(),
+                FMRI::parse_raw("pkg:/library/six").unwrap(),
+            ]
+            .into()
+        )
+    );
+
+    assert_eq!(components.get_rewrites_ref().len(), 2);
+    assert_eq!(
+        components.get_rewrites_ref()[0].original,
+        "experimental/test"
+    );
+    assert_eq!(components.get_rewrites_ref()[0].rewritten, "test");
+}
+
+#[test]
+fn load_rewrite_rules() {
+    let directory = std::env::temp_dir().join(format!("rewrite-rules-test-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let path = directory.join("rules.json");
+
+    write(
+        &path,
+        r#"[{"pattern": "^experimental/", "replacement": ""}]"#,
+    )
+    .unwrap();
+    let mut expected = RewriteRules::new();
+    expected.add(RewriteRule::new("^experimental/", "").unwrap());
+    assert_eq!(RewriteRules::load(path.clone()), Ok(expected));
+
+    write(&path, r#"[{"pattern": "^experimental/"}]"#).unwrap();
+    assert!(RewriteRules::load(path.clone())
+        .unwrap_err()
+        .starts_with("invalid rewrite rules"));
+
+    write(&path, r#"[{"pattern": "(", "replacement": ""}]"#).unwrap();
+    assert!(RewriteRules::load(path.clone())
+        .unwrap_err()
+        .starts_with("invalid rewrite pattern"));
+
+    assert!(RewriteRules::load(directory.join("missing.json"))
+        .unwrap_err()
+        .starts_with("failed to read rewrite rules"));
+
+    remove_dir_all(&directory).unwrap();
+}
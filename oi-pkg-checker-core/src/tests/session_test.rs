@@ -0,0 +1,60 @@
+use std::{
+    cell::RefCell,
+    fs::{create_dir_all, remove_dir_all, File},
+    rc::Rc,
+    time::{Duration, SystemTime},
+};
+
+use fmri::FMRI;
+
+use crate::packages::components::Components;
+use crate::problems::AlternativeState;
+use crate::session::{AnalysisSession, SessionEvent};
+use crate::tests::TestPackage;
+
+#[test]
+fn reload_changed_data() {
+    let directory = std::env::temp_dir().join(format!("session-test-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let data_path = directory.join("data.bin");
+    let problems_path = directory.join("problems.bin");
+
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/a@1.0")
+        .component("library/a")
+        .add(&mut components);
+    components.serialize(&data_path);
+
+    let mut session = AnalysisSession::open(data_path.clone(), problems_path).unwrap();
+    let events = Rc::new(RefCell::new(vec![]));
+    let received = events.clone();
+    session.subscribe(move |event| {
+        if let SessionEvent::DataReloaded(changes) = event {
+            received.borrow_mut().push(changes.len())
+        }
+    });
+
+    let fmri = FMRI::parse_raw("pkg:/library/a").unwrap();
+    assert_eq!(session.alternative_state(&fmri), AlternativeState::Viable);
+    assert!(session.problems().get_ref().is_empty());
+    assert!(!session.reload().unwrap());
+
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/a@1.1")
+        .component("library/a")
+        .obsolete(true)
+        .add(&mut components);
+    components.serialize(&data_path);
+    File::options()
+        .write(true)
+        .open(&data_path)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(10))
+        .unwrap();
+
+    assert!(session.reload().unwrap());
+    assert_eq!(session.alternative_state(&fmri), AlternativeState::Obsolete);
+    assert_eq!(events.borrow().as_slice(), &[1]);
+
+    remove_dir_all(directory).unwrap();
+}
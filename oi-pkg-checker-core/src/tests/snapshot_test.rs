@@ -0,0 +1,60 @@
+use std::{
+    fs::{create_dir_all, remove_dir_all, write},
+    process::Command,
+};
+
+use crate::{
+    assets::{open_indiana_oi_userland_git::ComponentPackagesList, snapshot::extract_snapshot},
+    problems::Problems,
+};
+
+#[test]
+fn analyze_snapshot_components() {
+    let directory = std::env::temp_dir().join(format!("snapshot-test-{}", std::process::id()));
+    let components = directory.join("oi-userland-2024.04/components");
+    for (component, package) in [("a", "library/a"), ("python/requests", "library/requests")] {
+        create_dir_all(components.join(component)).unwrap();
+        write(components.join(component).join("Makefile"), "").unwrap();
+        write(
+            components.join(component).join("pkg5"),
+            format!(r#"{{"fmris": ["{}"], "name": "{}"}}"#, package, component),
+        )
+        .unwrap();
+    }
+    create_dir_all(components.join("python/build")).unwrap();
+
+    let tarball = directory.join("oi-userland-2024.04.tar.gz");
+    assert!(Command::new("tar")
+        .arg("-czf")
+        .arg(&tarball)
+        .arg("-C")
+        .arg(&directory)
+        .arg("oi-userland-2024.04")
+        .status()
+        .unwrap()
+        .success());
+
+    let extracted = directory.join("extracted");
+    assert_eq!(
+        extract_snapshot(&tarball, &extracted),
+        Ok(extracted.join("oi-userland-2024.04/components"))
+    );
+
+    let component_packages_list = ComponentPackagesList::scan(
+        &extracted.join("oi-userland-2024.04/components"),
+        &[],
+        &mut Problems::new(),
+    );
+    assert_eq!(
+        component_packages_list
+            .get()
+            .iter()
+            .map(|component_packages| component_packages.component_name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["a", "python/requests"]
+    );
+
+    assert!(extract_snapshot(&components.join("a/Makefile"), &extracted).is_err());
+
+    remove_dir_all(&directory).unwrap();
+}
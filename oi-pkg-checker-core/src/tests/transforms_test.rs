@@ -0,0 +1,24 @@
+use crate::packages::components::Components;
+use crate::tests::TestPackage;
+use crate::transforms::pkgmogrify_transforms;
+
+#[test]
+fn pkgmogrify_transforms_of_obsolete_and_renamed() {
+    let mut components = Components::new();
+    TestPackage::new("pkg:/library/gtk+@1.0")
+        .obsolete(true)
+        .add(&mut components);
+    TestPackage::new("pkg:/library/old@1.0")
+        .renamed(true)
+        .requires(&["pkg:/library/new@2.0"])
+        .add(&mut components);
+    TestPackage::new("pkg:/library/new@2.0").add(&mut components);
+
+    assert_eq!(
+        pkgmogrify_transforms(&components),
+        "# library/gtk+ is obsolete\n\
+        <transform depend type=require fmri=(pkg:/)?library/gtk\\+(@.*)? -> drop>\n\
+        # library/old is renamed to library/new\n\
+        <transform depend fmri=(pkg:/)?library/old(@.*)? -> edit fmri (pkg:/)?library/old(@.*)? pkg:/library/new>\n"
+    );
+}
@@ -0,0 +1,79 @@
+use fmri::FMRI;
+
+use crate::packages::components::Components;
+use crate::packages::depend_types::DependTypes;
+use crate::packages::version_parts::VersionParts;
+use crate::problems::{ProblemKind, Problems};
+use crate::tests::TestPackage;
+
+#[test]
+fn parse() {
+    let parts = VersionParts::parse("@1.2.3,5.11-2022.0.0.1:20220126T070330Z").unwrap();
+    assert_eq!(parts.component, vec![1, 2, 3]);
+    assert_eq!(parts.build, vec![5, 11]);
+    assert_eq!(parts.branch, vec![2022, 0, 0, 1]);
+    assert_eq!(parts.timestamp, Some("20220126T070330Z".to_owned()));
+
+    let parts = VersionParts::parse("1.2").unwrap();
+    assert!(parts.branch.is_empty());
+    assert!(parts.timestamp.is_none());
+}
+
+#[test]
+fn is_tighter_than() {
+    let constraint = VersionParts::parse("1.2").unwrap();
+    assert!(VersionParts::parse("1.2.3")
+        .unwrap()
+        .is_tighter_than(&constraint));
+    assert!(VersionParts::parse("1.2-2022.0")
+        .unwrap()
+        .is_tighter_than(&constraint));
+    assert!(!VersionParts::parse("1.2")
+        .unwrap()
+        .is_tighter_than(&constraint));
+    assert!(!VersionParts::parse("1.3.1")
+        .unwrap()
+        .is_tighter_than(&constraint));
+}
+
+#[test]
+fn check_version_constraints() {
+    let mut components = Components::new();
+    TestPackage::new("pkg:/incorporation@1.0")
+        .runtime(vec![DependTypes::Incorporate(
+            FMRI::parse_raw("pkg:/library/a@1.2").unwrap(),
+        )])
+        .add(&mut components);
+    TestPackage::new("pkg:/b@1.0")
+        .runtime(vec![DependTypes::Require(
+            FMRI::parse_raw("pkg:/library/a@1.2.3").unwrap(),
+        )])
+        .add(&mut components);
+    TestPackage::new("pkg:/c@1.0")
+        .runtime(vec![DependTypes::Require(
+            FMRI::parse_raw("pkg:/library/a@1.2").unwrap(),
+        )])
+        .add(&mut components);
+
+    let mut problems = Problems::new();
+    components.check_version_constraints(&mut problems);
+
+    assert_eq!(problems.get_ref().len(), 1);
+    assert_eq!(
+        problems.get_ref()[0].kind(),
+        ProblemKind::TighterThanIncorporation
+    );
+}
+
+#[test]
+fn is_plausible_timestamp() {
+    use crate::packages::version_parts::{current_timestamp, is_plausible_timestamp};
+
+    let now = current_timestamp();
+    assert!(is_plausible_timestamp("20220126T070330Z", &now));
+    assert!(is_plausible_timestamp("20200229T000000Z", &now));
+    assert!(!is_plausible_timestamp("20210229T000000Z", &now));
+    assert!(!is_plausible_timestamp("19700101T000000Z", &now));
+    assert!(!is_plausible_timestamp("99990101T000000Z", &now));
+    assert!(!is_plausible_timestamp("20220126070330", &now));
+}
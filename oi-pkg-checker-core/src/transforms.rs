@@ -0,0 +1,62 @@
+use std::collections::BTreeSet;
+
+use fmri::FMRI;
+use regex::escape;
+
+use crate::{packages::components::Components, problems::AlternativeState};
+
+/// Returns pkgmogrify(1) transforms that drop require dependencies on obsoleted packages and
+/// rewrite dependencies on renamed packages to their final rename target
+pub fn pkgmogrify_transforms(components: &Components) -> String {
+    let mut package_names: BTreeSet<&String> = BTreeSet::new();
+    for component in components.get_ref() {
+        for package_versions in component.get_versions_ref() {
+            package_names.insert(package_versions.fmri_ref().get_package_name_as_ref_string());
+        }
+    }
+    for fmri in components.get_obsoleted_ref().get_ref() {
+        package_names.insert(fmri.get_package_name_as_ref_string());
+    }
+
+    package_names.retain(|package_name| components.get_namespace_filter_ref().allows(package_name));
+
+    let mut transforms = String::new();
+
+    for package_name in package_names {
+        let fmri = FMRI::parse_raw(package_name).unwrap();
+        let pattern = format!("(pkg:/)?{}(@.*)?", escape(package_name));
+
+        match components.get_alternative_state(&fmri) {
+            AlternativeState::Obsolete => {
+                transforms.push_str(&format!(
+                    "# {} is obsolete\n<transform depend type=require fmri={} -> drop>\n",
+                    package_name, pattern
+                ));
+            }
+            AlternativeState::Renamed => {
+                let target = components.resolve_rename(&fmri);
+                let target_name = target.get_package_name_as_ref_string();
+
+                match components.get_alternative_state(&target) {
+                    AlternativeState::Viable | AlternativeState::Stale => {
+                        transforms.push_str(&format!(
+                            "# {} is renamed to {}\n<transform depend fmri={} -> edit fmri {} pkg:/{}>\n",
+                            package_name, target_name, pattern, pattern, target_name
+                        ));
+                    }
+                    AlternativeState::Obsolete => {
+                        transforms.push_str(&format!(
+                            "# {} is renamed to obsolete {}\n<transform depend type=require fmri={} -> drop>\n",
+                            package_name, target_name, pattern
+                        ));
+                    }
+                    // broken rename chains and loops need manual fix
+                    AlternativeState::Renamed | AlternativeState::Missing => {}
+                }
+            }
+            AlternativeState::Viable | AlternativeState::Stale | AlternativeState::Missing => {}
+        }
+    }
+
+    transforms
+}
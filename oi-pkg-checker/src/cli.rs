@@ -1,18 +1,48 @@
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
+use oi_pkg_checker_core::Severity;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Args {
     #[command(subcommand)]
     pub command: Option<Commands>,
+
+    /// use only packages matching PATTERN (e.g. `library/*`) in graph queries and exports
+    #[arg(long, global = true, value_name = "PATTERN")]
+    pub include: Vec<String>,
+
+    /// don't use packages matching PATTERN (e.g. `consolidation/*` or `entire`) in graph queries
+    /// and exports
+    #[arg(long, global = true, value_name = "PATTERN")]
+    pub exclude: Vec<String>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Prints all problems and statistics
     PrintProblems {
+        /// print problems with suggestions as JSON
+        #[arg(long, default_value = "false")]
+        json: bool,
+
+        /// group problems by component
+        #[arg(long, default_value = "false")]
+        by_component: bool,
+
+        /// print only number of problems per kind, severity, component and dependency type
+        #[arg(long, default_value = "false")]
+        summary: bool,
+
+        /// print at most N problems of every kind (JSON contains full counts per kind)
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+
+        /// load owners of components from JSON configuration file
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
         /// set debug on
         #[arg(short, long, default_value = "false")]
         debug: bool,
@@ -24,6 +54,147 @@ pub enum Commands {
         #[arg(long, value_name = "FILE")]
         catalog: Vec<PathBuf>,
 
+        /// analyze snapshot of oi-userland (e.g. .tar.gz of release tag) instead of its working
+        /// copy, dependencies are parsed from Makefiles without running gmake
+        #[arg(long, value_name = "TARBALL")]
+        snapshot: Option<PathBuf>,
+
+        /// load packages from manifests of on-disk pkg(5) repositories (pkgrepo) or pkg(5)
+        /// archives (.p5p)
+        #[arg(long, visible_alias = "archive", value_name = "PATH")]
+        repository: Vec<PathBuf>,
+
+        /// load configuration of checks from JSON file
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
+        /// rewrite package names with regex rules from JSON file
+        #[arg(long, value_name = "FILE")]
+        rewrite_rules: Option<PathBuf>,
+
+        /// validate timestamps of packages in catalogs
+        #[arg(long, default_value = "false")]
+        strict_timestamps: bool,
+
+        /// reuse gmake outputs from FILE for components not changed (git diff and hash of
+        /// Makefile with included files) since the cached commit of oi-userland
+        #[arg(long, value_name = "FILE")]
+        make_cache: Option<PathBuf>,
+
+        /// re-evaluate gmake outputs of all components and replace make cache
+        #[arg(long, default_value = "false")]
+        refresh: bool,
+
+        /// directory with catalogs fetched from depots of origins without catalog
+        #[arg(long, value_name = "DIR", default_value = "assets/depots")]
+        depot_cache: PathBuf,
+
+        /// download whole catalogs from depots again instead of only changed parts
+        #[arg(long, default_value = "false")]
+        no_cache: bool,
+
+        /// run N gmake commands in parallel (default is number of CPUs)
+        #[arg(long, value_name = "N")]
+        jobs: Option<usize>,
+
+        /// exit with 1 if there is a problem with this or higher severity (info, warning, error)
+        #[arg(long, value_name = "SEVERITY")]
+        fail_on: Option<Severity>,
+
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Runs analyze, saves it with summary, problems and diff against the previous run into a new
+    /// snapshot in artifacts directory and removes old snapshots (for cron)
+    Unattended {
+        /// load catalogs (absolute paths)
+        #[arg(long, value_name = "FILE")]
+        catalog: Vec<PathBuf>,
+
+        /// load packages from manifests of on-disk pkg(5) repositories (pkgrepo) or pkg(5)
+        /// archives (.p5p)
+        #[arg(long, visible_alias = "archive", value_name = "PATH")]
+        repository: Vec<PathBuf>,
+
+        /// load configuration of checks from JSON file
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
+        /// rewrite package names with regex rules from JSON file
+        #[arg(long, value_name = "FILE")]
+        rewrite_rules: Option<PathBuf>,
+
+        /// validate timestamps of packages in catalogs
+        #[arg(long, default_value = "false")]
+        strict_timestamps: bool,
+
+        /// reuse gmake outputs from FILE for components not changed (git diff and hash of
+        /// Makefile with included files) since the cached commit of oi-userland
+        #[arg(long, value_name = "FILE")]
+        make_cache: Option<PathBuf>,
+
+        /// re-evaluate gmake outputs of all components and replace make cache
+        #[arg(long, default_value = "false")]
+        refresh: bool,
+
+        /// directory with catalogs fetched from depots of origins without catalog
+        #[arg(long, value_name = "DIR", default_value = "assets/depots")]
+        depot_cache: PathBuf,
+
+        /// download whole catalogs from depots again instead of only changed parts
+        #[arg(long, default_value = "false")]
+        no_cache: bool,
+
+        /// run N gmake commands in parallel (default is number of CPUs)
+        #[arg(long, value_name = "N")]
+        jobs: Option<usize>,
+
+        /// directory with snapshots
+        #[arg(long, value_name = "DIR")]
+        artifacts: PathBuf,
+
+        /// keep the newest N snapshots (at least 1)
+        #[arg(long, value_name = "N", default_value = "7")]
+        keep: usize,
+
+        /// exit with 1 if there is a problem with this or higher severity (info, warning, error)
+        #[arg(long, value_name = "SEVERITY")]
+        fail_on: Option<Severity>,
+
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Reloads only components changed in git RANGE of oi-userland into data of the previous run,
+    /// runs all checks again and merges problems with problems of the previous run
+    Reload {
+        /// git range of oi-userland (e.g. origin/master..HEAD)
+        range: String,
+
+        /// load configuration of checks from JSON file
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
+        /// rewrite package names with regex rules from JSON file
+        #[arg(long, value_name = "FILE")]
+        rewrite_rules: Option<PathBuf>,
+
+        /// reuse gmake outputs from FILE for components not changed (git diff and hash of
+        /// Makefile with included files) since the cached commit of oi-userland
+        #[arg(long, value_name = "FILE")]
+        make_cache: Option<PathBuf>,
+
+        /// run N gmake commands in parallel (default is number of CPUs)
+        #[arg(long, value_name = "N")]
+        jobs: Option<usize>,
+
+        /// exit with 1 if there is a problem with this or higher severity (info, warning, error)
+        #[arg(long, value_name = "SEVERITY")]
+        fail_on: Option<Severity>,
+
         /// set debug on
         #[arg(short, long, default_value = "false")]
         debug: bool,
@@ -38,8 +209,183 @@ pub enum Commands {
         #[arg(long, default_value = "false")]
         hide_renamed: bool,
 
+        /// list every incorporate dependency instead of counting them per incorporation
+        #[arg(long, default_value = "false")]
+        full_incorporate: bool,
+
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Checks installed packages of image against analyzed catalogs
+    CheckImage {
+        /// output of `pkg list -H` (or `pkg list -Hv`) saved in file or metadata directory of
+        /// image (/var/pkg)
+        path: PathBuf,
+
+        /// exit with 1 if there is a problem with this or higher severity (info, warning, error)
+        #[arg(long, value_name = "SEVERITY")]
+        fail_on: Option<Severity>,
+
         /// set debug on
         #[arg(short, long, default_value = "false")]
         debug: bool,
     },
+
+    /// Prints complete report about package
+    Dossier {
+        /// fmri of package
+        fmri: String,
+
+        /// list every incorporate dependency instead of counting them per incorporation
+        #[arg(long, default_value = "false")]
+        full_incorporate: bool,
+
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Prints JSON plan of changes (file, operation, old value, new value) that fix some problems
+    FixPlan {
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Prints Mermaid flowchart of all dependency paths from source to target package
+    Paths {
+        /// fmri of source package
+        source: String,
+
+        /// fmri of target package
+        target: String,
+
+        /// maximal number of edges in path
+        #[arg(long, default_value = "5")]
+        max_depth: usize,
+
+        /// print standalone HTML page instead of Mermaid
+        #[arg(long, default_value = "false")]
+        html: bool,
+
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Prints metadata of all components as JSON
+    Metadata {
+        /// print CSV instead of JSON
+        #[arg(long, default_value = "false")]
+        csv: bool,
+
+        /// load owners of components from JSON configuration file
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Prints pkgmogrify transforms that drop dependencies on obsoleted packages and rewrite
+    /// dependencies on renamed packages
+    Transforms {
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Prints updates blocked by pinned packages and dependencies that pins break
+    Pins {
+        /// file with pinned fmris (with versions), one per line
+        #[arg(value_name = "FILE")]
+        pins: PathBuf,
+
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Prints changes of dependency graph since old analysis as JSON lines
+    GraphDelta {
+        /// data file of old analysis (data.bin)
+        #[arg(value_name = "FILE")]
+        old: PathBuf,
+
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Prints dependency graph with obsolete and renamed flags, problem counts and components as
+    /// JSON for d3-force
+    ForceGraph {
+        /// print elements for Cytoscape.js instead of d3-force
+        #[arg(long, default_value = "false")]
+        cytoscape: bool,
+
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Prints dependency graph in Graphviz DOT format
+    Dot {
+        /// leave out runtime dependencies between packages
+        #[arg(long, default_value = "false")]
+        no_runtime: bool,
+
+        /// include build dependencies between components
+        #[arg(long, default_value = "false")]
+        build: bool,
+
+        /// don't color obsolete and renamed packages
+        #[arg(long, default_value = "false")]
+        no_color: bool,
+
+        /// print only neighborhood of package or component NAME
+        #[arg(long, value_name = "NAME")]
+        focus: Option<String>,
+
+        /// maximal number of edges from focused package or component
+        #[arg(long, value_name = "N", default_value = "1")]
+        depth: usize,
+
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Prepares working directory: checks required tools, creates configuration, downloads
+    /// catalogs, optionally clones oi-userland and runs quick analysis of catalogs and pkg5 files
+    Init {
+        /// configuration file to create (existing file is kept)
+        #[arg(long, value_name = "FILE", default_value = "config.json")]
+        config: PathBuf,
+
+        /// clone oi-userland into assets/oi-userland
+        #[arg(long, default_value = "false")]
+        clone: bool,
+
+        /// check out REF (branch, tag or commit) of oi-userland
+        #[arg(long, value_name = "REF")]
+        git_ref: Option<String>,
+
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Prints checked-out commit of oi-userland that the analysis corresponds to
+    Commit {
+        /// set debug on
+        #[arg(short, long, default_value = "false")]
+        debug: bool,
+    },
+
+    /// Lists ids of checks (for disabled_checks in configuration)
+    Checks,
 }
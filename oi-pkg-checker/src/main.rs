@@ -1,6 +1,9 @@
 use std::{
+    fs::{create_dir_all, write},
     path::{Path, PathBuf},
-    process::exit,
+    process::{exit, Command},
+    thread::available_parallelism,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use clap::Parser;
@@ -8,7 +11,15 @@ use fmri::FMRI;
 use log::{debug, error, info, LevelFilter};
 
 use oi_pkg_checker_core::{
-    report, AssetTypes, ComponentPackagesList, Components, DependTypes, PackageVersions, Problems,
+    archive_catalog, changed_components, check_curl, component_metadata, component_metadata_to_csv,
+    extract_snapshot, fetch_catalogs, fix_plan, graph_delta, graph_delta_to_json_lines,
+    head_commit, load_history, load_installed_packages, load_manifest_actions,
+    load_manifest_dependencies, load_pins, lookup_non_existing_required, merge_problems,
+    missing_tools, pkgmogrify_transforms, report, report_by_component, report_with_limit,
+    repository_catalog, write_default_config, Artifacts, AssetTypes, ComponentDiscovery,
+    ComponentPackagesList, Components, Config, DependTypes, DependencyPaths, Dependents, Dossier,
+    DotOptions, ForceGraph, MakeCache, NamespaceFilter, Origin, PackageVersions, PinImpact,
+    Problems, RewriteRules, CHECKS,
 };
 
 use crate::{
@@ -21,6 +32,7 @@ mod logger;
 
 static LOGGER: Logger = Logger;
 
+#[allow(clippy::single_match)]
 fn main() {
     log::set_logger(&LOGGER).unwrap();
     log::set_max_level(LevelFilter::Info);
@@ -29,29 +41,103 @@ fn main() {
     let problems_path = "problems.bin";
     let components_path = &PathBuf::from("assets/oi-userland/components");
 
-    match &Args::parse().command {
+    let args = Args::parse();
+    let namespace_filter = &NamespaceFilter::new(args.include.clone(), args.exclude.clone());
+
+    match &args.command {
         Some(subcommand) => match subcommand {
-            Commands::PrintProblems { debug } => {
+            Commands::PrintProblems {
+                json,
+                by_component,
+                summary,
+                limit,
+                config,
+                debug,
+            } => {
                 debug_on(debug);
-                report(&mut Problems::deserialize(problems_path).unwrap());
+                let mut problems = Problems::deserialize(problems_path).unwrap();
+                // owners are not saved in problems.bin
+                if config.is_some() {
+                    problems.set_owners(
+                        &load_components(data_path, namespace_filter),
+                        load_config(config).component_owners,
+                    );
+                }
+                if *summary {
+                    let summary = problems.summary();
+                    if *json {
+                        println!(
+                            "{:#}",
+                            serde_json::to_value(summary).expect("failed to serialize summary")
+                        );
+                    } else {
+                        info!("total: {}", summary.total);
+                        for (severity, count) in summary.by_severity {
+                            info!("{}: {}", severity, count);
+                        }
+                        info!("by kind:");
+                        for (kind, count) in summary.by_kind {
+                            info!("\t{}: {}", kind, count);
+                        }
+                        info!("by check:");
+                        for (check_id, count) in summary.by_check {
+                            info!("\t{}: {}", check_id, count);
+                        }
+                        info!("by dependency type:");
+                        for (dependency_type, count) in summary.by_dependency_type {
+                            info!("\t{}: {}", dependency_type, count);
+                        }
+                        info!("by component:");
+                        for (component_name, count) in summary.by_component {
+                            info!("\t{}: {}", component_name, count);
+                        }
+                    }
+                } else if *json {
+                    match limit {
+                        Some(limit) => println!("{:#}", problems.to_json_with_limit(*limit)),
+                        None => println!("{:#}", problems.to_json()),
+                    }
+                } else if *by_component {
+                    report_by_component(&mut problems);
+                } else {
+                    report_with_limit(&mut problems, limit.unwrap_or(usize::MAX));
+                }
                 exit(0);
             }
+            Commands::CheckImage {
+                path,
+                fail_on,
+                debug,
+            } => {
+                debug_on(debug);
+
+                let installed = load_installed_packages(path).unwrap_or_else(|e| {
+                    error!("{}", e);
+                    exit(1);
+                });
+                let components = Components::deserialize(data_path);
+
+                let mut problems = Problems::new();
+                components.check_installed_packages(&mut problems, &installed);
+                info!("checked {} installed packages", installed.len());
+                report(&mut problems);
+
+                match (fail_on, problems.max_severity()) {
+                    (Some(fail_on), Some(max_severity)) if max_severity >= *fail_on => exit(1),
+                    _ => exit(0),
+                }
+            }
             Commands::CheckFMRI {
                 fmri,
                 debug,
                 hide_renamed,
+                full_incorporate,
             } => {
                 debug_on(debug);
 
                 let fmri = &FMRI::parse_raw(fmri).unwrap();
 
-                let components = match Path::new(data_path).exists() {
-                    false => {
-                        error!("{} doesn't exist", data_path);
-                        exit(1);
-                    }
-                    _ => Components::deserialize(data_path),
-                };
+                let components = load_components(data_path, namespace_filter);
 
                 if !components.check_if_fmri_exists_as_package(fmri) {
                     error!(
@@ -60,41 +146,22 @@ fn main() {
                     );
                 }
 
-                if let Some(dependencies) = components.get_dependencies_with_fmri(fmri) {
+                if let Some(dependents) =
+                    components.get_dependencies_with_fmri(fmri, *full_incorporate)
+                {
                     info!("fmri {} is required by:", fmri);
-                    for (fmri, dependency_type, dependency, renamed) in dependencies {
-                        let d_type = match dependency.get_ref() {
-                            DependTypes::Require(_) => "require",
-                            DependTypes::Optional(_) => "optional",
-                            DependTypes::Incorporate(_) => "incorporate",
-                            DependTypes::RequireAny(_) => "require-any",
-                            DependTypes::Conditional(_, _) => "conditional",
-                            DependTypes::Group(_) => "group",
-                            _ => unimplemented!(),
-                        };
-
-                        if *hide_renamed && renamed {
-                            continue;
-                        }
-
-                        info!(
-                            "\ttype: {}, dependency: {}, {}package: {}",
-                            d_type,
-                            dependency_type,
-                            if renamed { "renamed " } else { "" },
-                            fmri
-                        );
-                    }
+                    print_dependents(&dependents, *hide_renamed);
                 } else {
                     info!("fmri {} is not required by any package", fmri);
                 }
 
-                if let Some(name) = ComponentPackagesList::new(components_path)
-                    .get_component_packages_of_package_versions(
-                        &mut Problems::new(),
-                        &PackageVersions::new(fmri.clone()),
-                    )
-                    .map(|a| a.component_name)
+                if let Some(name) =
+                    ComponentPackagesList::new(components_path, &mut Problems::new())
+                        .get_component_packages_of_package_versions(
+                            &mut Problems::new(),
+                            &PackageVersions::new(fmri.clone()),
+                        )
+                        .map(|a| a.component_name)
                 {
                     info!("component name: {}", name)
                 } else {
@@ -103,32 +170,315 @@ fn main() {
 
                 exit(0);
             }
-            Commands::Run { catalog, debug } => {
+            Commands::Dossier {
+                fmri,
+                full_incorporate,
+                debug,
+            } => {
                 debug_on(debug);
 
-                let mut problems = Problems::new();
-                let mut components = Components::new();
-                let component_packages = ComponentPackagesList::new(components_path);
+                let fmri = &FMRI::parse_raw(fmri).unwrap();
+                let components = load_components(data_path, namespace_filter);
+                let problems = Problems::deserialize(problems_path).unwrap_or_default();
 
-                components.load(
-                    &mut problems,
-                    AssetTypes::Catalogs(catalog.clone()),
-                    &component_packages,
+                let dossier = match Dossier::new(
+                    &components,
+                    &problems,
+                    &ComponentPackagesList::new(components_path, &mut Problems::new()),
+                    fmri,
+                    *full_incorporate,
+                ) {
+                    Some(dossier) => dossier,
+                    None => {
+                        error!(
+                            "package with name '{}' doesn't exist",
+                            fmri.get_package_name_as_ref_string()
+                        );
+                        exit(1);
+                    }
+                };
+
+                info!("package: {}", dossier.fmri);
+                info!(
+                    "state: {}",
+                    if dossier.obsolete {
+                        "obsolete"
+                    } else if dossier.renamed {
+                        "renamed"
+                    } else if dossier.metapackage {
+                        "metapackage"
+                    } else {
+                        "normal"
+                    }
                 );
-                components.load(
-                    &mut problems,
-                    AssetTypes::OpenIndianaOiUserlandGit,
-                    &component_packages,
+                for version in &dossier.versions {
+                    info!("version: {}", version);
+                }
+                for version in &dossier.obsoleted_versions {
+                    info!("obsoleted version: {}", version);
+                }
+
+                match (&dossier.component_name, &dossier.component_path) {
+                    (Some(name), Some(path)) => info!("component: {} ({:?})", name, path),
+                    (Some(name), None) => info!("component: {}", name),
+                    _ => info!("missing component for package"),
+                }
+
+                info!("dependencies:");
+                for (dependency_type, dependencies) in &dossier.dependencies {
+                    for dependency in dependencies {
+                        info!("\t{}: {}", dependency_type, dependency.get_ref());
+                    }
+                }
+
+                match &dossier.dependents {
+                    Some(dependents) => {
+                        info!("required by:");
+                        print_dependents(dependents, false);
+                    }
+                    None => info!("package is not required by any package"),
+                }
+
+                info!("problems:");
+                for problem in &dossier.problems {
+                    info!(
+                        "\t{} ({}): {}",
+                        problem.kind(),
+                        problem.check_id(),
+                        problem.suggestion()
+                    );
+                }
+
+                exit(0);
+            }
+            Commands::FixPlan { debug } => {
+                debug_on(debug);
+
+                let components = load_components(data_path, namespace_filter);
+                let problems = Problems::deserialize(problems_path).unwrap();
+
+                println!(
+                    "{:#}",
+                    serde_json::to_value(fix_plan(
+                        &problems,
+                        &components,
+                        &ComponentPackagesList::new(components_path, &mut Problems::new()),
+                    ))
+                    .expect("failed to serialize fix plan")
                 );
 
-                components.check_dependency_validity(&mut problems);
-                components.get_useless_components(&mut problems);
-                components.check_if_renamed_needs_renamed(&mut problems);
+                exit(0);
+            }
+            Commands::Metadata { csv, config, debug } => {
+                debug_on(debug);
 
-                component_packages.same_packages_in_components(&mut problems);
-                component_packages.non_existing_packages_in_pkg5(&mut problems, &components);
+                let metadata = component_metadata(
+                    &load_components(data_path, namespace_filter),
+                    &Problems::deserialize(problems_path).unwrap_or_default(),
+                    &ComponentPackagesList::new(components_path, &mut Problems::new()),
+                    &load_config(config).component_owners,
+                );
 
-                report(&mut problems);
+                if *csv {
+                    print!("{}", component_metadata_to_csv(&metadata));
+                } else {
+                    println!(
+                        "{:#}",
+                        serde_json::to_value(metadata).expect("failed to serialize metadata")
+                    );
+                }
+
+                exit(0);
+            }
+            Commands::Transforms { debug } => {
+                debug_on(debug);
+
+                print!(
+                    "{}",
+                    pkgmogrify_transforms(&load_components(data_path, namespace_filter))
+                );
+
+                exit(0);
+            }
+            Commands::Pins { pins, debug } => {
+                debug_on(debug);
+
+                let pins = match load_pins(pins.clone()) {
+                    Ok(pins) => pins,
+                    Err(e) => {
+                        error!("{}", e);
+                        exit(1);
+                    }
+                };
+                let components = load_components(data_path, namespace_filter);
+
+                for pin in &pins {
+                    let Some(pin_impact) = PinImpact::new(&components, pin) else {
+                        error!(
+                            "pinned package '{}' doesn't exist",
+                            pin.get_package_name_as_ref_string()
+                        );
+                        continue;
+                    };
+
+                    info!("pin: {}", pin_impact.pin);
+                    for fmri in &pin_impact.blocked_updates {
+                        info!("\tblocked update: {}", fmri);
+                    }
+                    for (fmri, depend_type) in &pin_impact.unsatisfiable {
+                        info!("\tunsatisfiable: {} needs {}", fmri, depend_type);
+                    }
+                    for (incorporation, incorporated) in &pin_impact.incorporation_conflicts {
+                        info!(
+                            "\tincorporation conflict: {} incorporates {}",
+                            incorporation, incorporated
+                        );
+                    }
+                }
+
+                exit(0);
+            }
+            Commands::GraphDelta { old, debug } => {
+                debug_on(debug);
+
+                let old = load_components(&old.to_string_lossy(), namespace_filter);
+                let new = load_components(data_path, namespace_filter);
+
+                print!("{}", graph_delta_to_json_lines(&graph_delta(&old, &new)));
+
+                exit(0);
+            }
+            Commands::ForceGraph { cytoscape, debug } => {
+                debug_on(debug);
+
+                let force_graph = ForceGraph::new(
+                    &load_components(data_path, namespace_filter),
+                    &Problems::deserialize(problems_path).unwrap_or_default(),
+                );
+
+                if *cytoscape {
+                    println!("{:#}", force_graph.to_cytoscape());
+                } else {
+                    println!("{:#}", force_graph.to_d3());
+                }
+
+                exit(0);
+            }
+            Commands::Dot {
+                no_runtime,
+                build,
+                no_color,
+                focus,
+                depth,
+                debug,
+            } => {
+                debug_on(debug);
+
+                print!(
+                    "{}",
+                    load_components(data_path, namespace_filter).to_dot(&DotOptions {
+                        runtime: !no_runtime,
+                        build: *build,
+                        color: !no_color,
+                        focus: focus.clone(),
+                        depth: *depth,
+                    })
+                );
+
+                exit(0);
+            }
+            Commands::Init {
+                config,
+                clone,
+                git_ref,
+                debug,
+            } => {
+                debug_on(debug);
+
+                if let Err(e) = init(components_path, config, *clone, git_ref) {
+                    error!("{}", e);
+                    exit(1);
+                }
+
+                exit(0);
+            }
+            Commands::Commit { debug } => {
+                debug_on(debug);
+                let components = Components::deserialize(data_path);
+                match components.get_commit() {
+                    Some(commit) => println!("{}", commit),
+                    None => {
+                        error!("commit of oi-userland is unknown");
+                        exit(1);
+                    }
+                }
+
+                exit(0);
+            }
+            Commands::Checks => {
+                for check in CHECKS {
+                    info!("{}: {}", check.id, check.description);
+                }
+
+                exit(0);
+            }
+            Commands::Paths {
+                source,
+                target,
+                max_depth,
+                html,
+                debug,
+            } => {
+                debug_on(debug);
+
+                let dependency_paths = DependencyPaths::new(
+                    &load_components(data_path, namespace_filter),
+                    &FMRI::parse_raw(source).unwrap(),
+                    &FMRI::parse_raw(target).unwrap(),
+                    *max_depth,
+                );
+
+                debug!("found {} paths", dependency_paths.paths.len());
+
+                if *html {
+                    print!("{}", dependency_paths.to_html());
+                } else {
+                    print!("{}", dependency_paths.to_mermaid());
+                }
+
+                exit(0);
+            }
+            Commands::Run {
+                catalog,
+                snapshot,
+                repository,
+                config,
+                rewrite_rules,
+                strict_timestamps,
+                make_cache,
+                refresh,
+                depot_cache,
+                no_cache,
+                jobs,
+                fail_on,
+                debug,
+            } => {
+                debug_on(debug);
+
+                let (components, problems) = analyze(
+                    components_path,
+                    snapshot,
+                    catalog,
+                    repository,
+                    config,
+                    rewrite_rules,
+                    *strict_timestamps,
+                    make_cache,
+                    *refresh,
+                    depot_cache,
+                    *no_cache,
+                    *jobs,
+                );
 
                 components.serialize(data_path);
 
@@ -136,7 +486,102 @@ fn main() {
                     .serialize(problems_path)
                     .expect("TODO: panic message");
 
-                exit(0);
+                for (severity, count) in problems.count_by_severity() {
+                    info!("{}: {}", severity, count);
+                }
+
+                match (fail_on, problems.max_severity()) {
+                    (Some(fail_on), Some(max_severity)) if max_severity >= *fail_on => exit(1),
+                    _ => exit(0),
+                }
+            }
+            Commands::Unattended {
+                catalog,
+                repository,
+                config,
+                rewrite_rules,
+                strict_timestamps,
+                make_cache,
+                refresh,
+                depot_cache,
+                no_cache,
+                jobs,
+                artifacts,
+                keep,
+                fail_on,
+                debug,
+            } => {
+                debug_on(debug);
+
+                let artifacts = Artifacts::new(artifacts.clone());
+                let previous = artifacts.latest().unwrap_or_else(|e| {
+                    error!("{}", e);
+                    exit(1);
+                });
+
+                let (components, problems) = analyze(
+                    components_path,
+                    &None,
+                    catalog,
+                    repository,
+                    config,
+                    rewrite_rules,
+                    *strict_timestamps,
+                    make_cache,
+                    *refresh,
+                    depot_cache,
+                    *no_cache,
+                    *jobs,
+                );
+
+                components.serialize(data_path);
+                if let Err(e) = problems.serialize(problems_path) {
+                    error!("{}", e);
+                    exit(1);
+                }
+
+                if let Err(e) = save_snapshot(&artifacts, previous, &components, &problems, *keep) {
+                    error!("{}", e);
+                    exit(1);
+                }
+
+                match (fail_on, problems.max_severity()) {
+                    (Some(fail_on), Some(max_severity)) if max_severity >= *fail_on => exit(1),
+                    _ => exit(0),
+                }
+            }
+            Commands::Reload {
+                range,
+                config,
+                rewrite_rules,
+                make_cache,
+                jobs,
+                fail_on,
+                debug,
+            } => {
+                debug_on(debug);
+
+                let (components, problems) = reload(
+                    components_path,
+                    range,
+                    data_path,
+                    problems_path,
+                    config,
+                    rewrite_rules,
+                    make_cache,
+                    *jobs,
+                );
+
+                components.serialize(data_path);
+                if let Err(e) = problems.serialize(problems_path) {
+                    error!("{}", e);
+                    exit(1);
+                }
+
+                match (fail_on, problems.max_severity()) {
+                    (Some(fail_on), Some(max_severity)) if max_severity >= *fail_on => exit(1),
+                    _ => exit(0),
+                }
             }
         },
         None => {}
@@ -145,9 +590,609 @@ fn main() {
     exit(0);
 }
 
+/// Loads catalogs and oi-userland, runs all checks and reports problems
+#[allow(clippy::too_many_arguments)]
+fn analyze(
+    components_path: &Path,
+    snapshot: &Option<PathBuf>,
+    catalog: &[PathBuf],
+    repository: &[PathBuf],
+    config: &Option<PathBuf>,
+    rewrite_rules: &Option<PathBuf>,
+    strict_timestamps: bool,
+    make_cache: &Option<PathBuf>,
+    refresh: bool,
+    depot_cache: &Path,
+    no_cache: bool,
+    jobs: Option<usize>,
+) -> (Components, Problems) {
+    let mut config = load_config(config);
+    check_depot_tools(&config);
+    fetch_depot_catalogs(&mut config, depot_cache, !no_cache);
+
+    let mut problems = Problems::new();
+    problems.disable_checks(config.disabled_checks.clone());
+    problems.on_problem(|problem| debug!("found problem: {}", problem.kind()));
+    let mut components = Components::new();
+
+    let snapshot_components;
+    let (components_path, mut component_packages) = match snapshot {
+        Some(tarball) => {
+            let directory =
+                Path::new("assets/snapshots").join(asset_name(&tarball.to_string_lossy()));
+            snapshot_components = extract_snapshot(tarball, &directory).unwrap_or_else(|e| {
+                error!("{}", e);
+                exit(1);
+            });
+            components.set_without_make(true);
+            (
+                snapshot_components.as_path(),
+                ComponentPackagesList::discover(
+                    &snapshot_components,
+                    &ComponentDiscovery {
+                        scan: true,
+                        ..config.component_discovery.clone()
+                    },
+                    &mut problems,
+                ),
+            )
+        }
+        None => (
+            components_path,
+            ComponentPackagesList::discover(
+                components_path,
+                &config.component_discovery,
+                &mut problems,
+            ),
+        ),
+    };
+
+    components.set_strict_timestamps(strict_timestamps);
+    components.set_native_makefiles(config.native_makefiles || snapshot.is_some());
+    components.set_jobs(jobs.unwrap_or_else(|| available_parallelism().map_or(1, |n| n.get())));
+    components.set_publishers(config.publishers.clone());
+    components.set_commit(
+        head_commit(components_path)
+            .map_err(|e| debug!("{}", e))
+            .ok(),
+    );
+
+    if let Some(path) = make_cache {
+        let mut cache = match refresh {
+            true => MakeCache::new(),
+            false => MakeCache::load(path),
+        };
+        if let Some(repository) = components_path.parent() {
+            cache.invalidate(repository);
+        }
+        components.set_make_cache(cache);
+    }
+
+    if let Some(path) = rewrite_rules {
+        match RewriteRules::load(path.clone()) {
+            Ok(rewrite_rules) => components.set_rewrite_rules(rewrite_rules),
+            Err(e) => {
+                error!("{}", e);
+                exit(1);
+            }
+        }
+        component_packages.rewrite(&mut components);
+    }
+
+    let mut catalogs = catalog.to_vec();
+    for repository in repository {
+        let directory =
+            Path::new("assets/repositories").join(asset_name(&repository.to_string_lossy()));
+        let catalog = match repository
+            .extension()
+            .is_some_and(|extension| extension == "p5p")
+        {
+            true => archive_catalog(repository, &directory),
+            false => repository_catalog(repository, &directory),
+        };
+        match catalog {
+            Ok(catalog) => catalogs.push(catalog),
+            Err(e) => {
+                error!("{}", e);
+                exit(1);
+            }
+        }
+    }
+    for publisher in &config.publishers {
+        for origin in &publisher.origins {
+            if !catalogs.contains(&origin.catalog) {
+                catalogs.push(origin.catalog.clone());
+            }
+        }
+    }
+
+    components.load(
+        &mut problems,
+        AssetTypes::Catalogs(catalogs),
+        &component_packages,
+    );
+    components.apply_publisher_priority(&config.publisher_priority);
+    if config.manifest_dependencies {
+        components.set_manifest_dependencies(load_manifest_dependencies(
+            &component_packages,
+            &mut problems,
+        ));
+    }
+    components.load(
+        &mut problems,
+        AssetTypes::OpenIndianaOiUserlandGit,
+        &component_packages,
+    );
+    components.set_package_actions(load_manifest_actions(&component_packages));
+    components.set_history(load_history(components_path, &mut problems));
+
+    components.set_packages_with_files(component_packages.get_packages_with_files(&mut problems));
+
+    if let Some(path) = make_cache {
+        if let Err(e) = components.take_make_cache().save(path) {
+            error!("{}", e);
+        }
+    }
+
+    run_checks(&components, &component_packages, &config, &mut problems);
+
+    problems.set_owners(&components, config.component_owners.clone());
+
+    report(&mut problems);
+
+    for rewrite in components.get_rewrites_ref() {
+        debug!("rewritten {} to {}", rewrite.original, rewrite.rewritten);
+    }
+
+    (components, problems)
+}
+
+/// Reloads components changed in git `range` of oi-userland into data and problems of the
+/// previous run, see [`analyze`]
+#[allow(clippy::too_many_arguments)]
+fn reload(
+    components_path: &Path,
+    range: &str,
+    data_path: &str,
+    problems_path: &str,
+    config: &Option<PathBuf>,
+    rewrite_rules: &Option<PathBuf>,
+    make_cache: &Option<PathBuf>,
+    jobs: Option<usize>,
+) -> (Components, Problems) {
+    let config = load_config(config);
+    check_depot_tools(&config);
+    let previous = Problems::deserialize(problems_path).unwrap_or_else(|e| {
+        error!("{}", e);
+        exit(1);
+    });
+
+    let mut problems = Problems::new();
+    problems.disable_checks(config.disabled_checks.clone());
+    problems.on_problem(|problem| debug!("found problem: {}", problem.kind()));
+    let mut components = Components::try_deserialize(data_path).unwrap_or_else(|e| {
+        error!("{}", e);
+        exit(1);
+    });
+    let mut component_packages = ComponentPackagesList::discover(
+        components_path,
+        &config.component_discovery,
+        &mut problems,
+    );
+
+    components.set_native_makefiles(config.native_makefiles);
+    components.set_jobs(jobs.unwrap_or_else(|| available_parallelism().map_or(1, |n| n.get())));
+    components.set_publishers(config.publishers.clone());
+    components.set_commit(
+        head_commit(components_path)
+            .map_err(|e| debug!("{}", e))
+            .ok(),
+    );
+
+    let repository = components_path.parent().unwrap_or(components_path);
+    if let Some(path) = make_cache {
+        let mut cache = MakeCache::load(path);
+        cache.invalidate(repository);
+        components.set_make_cache(cache);
+    }
+
+    if let Some(path) = rewrite_rules {
+        match RewriteRules::load(path.clone()) {
+            Ok(rewrite_rules) => components.set_rewrite_rules(rewrite_rules),
+            Err(e) => {
+                error!("{}", e);
+                exit(1);
+            }
+        }
+        component_packages.rewrite(&mut components);
+    }
+
+    let changed = match changed_components(repository, range, &component_packages) {
+        Ok(Some(changed)) => changed,
+        Ok(None) => ComponentPackagesList::from(component_packages.get().clone()),
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    };
+    info!("reloading {} components", changed.get().len());
+
+    if config.manifest_dependencies {
+        components.set_manifest_dependencies(load_manifest_dependencies(&changed, &mut problems));
+    }
+    components.reload_components(&mut problems, &component_packages, &changed);
+    components.set_package_actions(load_manifest_actions(&changed));
+    components.set_history(load_history(components_path, &mut problems));
+
+    components.set_packages_with_files(component_packages.get_packages_with_files(&mut problems));
+
+    if let Some(path) = make_cache {
+        if let Err(e) = components.take_make_cache().save(path) {
+            error!("{}", e);
+        }
+    }
+
+    run_checks(&components, &component_packages, &config, &mut problems);
+    merge_problems(&mut problems, &previous, &changed);
+
+    problems.set_owners(&components, config.component_owners.clone());
+
+    report(&mut problems);
+
+    (components, problems)
+}
+
+/// Runs all checks on loaded data
+fn run_checks(
+    components: &Components,
+    component_packages: &ComponentPackagesList,
+    config: &Config,
+    problems: &mut Problems,
+) {
+    components.check_dependency_validity(problems);
+    if !config.depot_lookup.is_empty() {
+        lookup_non_existing_required(problems, &config.depot_lookup);
+    }
+    components.get_useless_components(problems);
+    components.check_if_renamed_needs_renamed(problems);
+    components.check_rename_chains(problems);
+    components.check_history_collisions(problems);
+    components.check_version_constraints(problems);
+    components.check_incorporation_constraints(problems);
+    components.check_incorporated_versions(problems);
+    components.check_incorporation_cycles(problems);
+    components.check_required_versions(problems);
+    components.check_require_any_alternatives(problems);
+    components.check_category_policies(problems, &config.category_policies);
+    components.check_package_names(problems, &config.naming);
+    components.check_versioned_modules(problems, &config.naming);
+    components.check_build_cycles(problems);
+    components.check_dependency_kinds(problems, &config.reclassification);
+    components.check_self_dependencies(problems);
+    components.check_stale_required_packages(problems, &config.reclassification);
+    components.check_only_stub_dependents(problems);
+    components.check_orphan_packages(problems, &config.orphan_packages);
+    components.check_catalog_staleness(problems, &config.catalog_staleness);
+    components.check_mirror_skew(problems);
+    components.check_publisher_version_skew(problems);
+    components.check_divergent_dependencies(problems);
+    components.check_cross_publisher_dependencies(problems);
+    components.check_external_dependencies(problems);
+    components.check_unversioned_requires(problems, &config.unversioned_requires);
+    components.check_dependency_bloat(problems, &component_packages.get_build_styles());
+
+    component_packages.duplicate_component_names(problems);
+    component_packages.same_packages_in_components(problems);
+    component_packages.foreign_packages_in_pkg5(problems);
+    component_packages.file_conflicts(problems);
+    component_packages.non_existing_packages_in_pkg5(problems, components);
+    component_packages.obsolete_components(problems, components);
+    component_packages.obsoleted_packages_built(problems, components);
+    component_packages.pkg5_consistency(problems, components);
+    component_packages.pkg5_names(problems);
+    component_packages.classifications(problems, components, &config.classifications);
+    component_packages.licenses(problems, components);
+    component_packages.encumbered_placement(problems, components, &config.encumbered_licenses);
+}
+
+/// Saves analysis into a new snapshot with summary, problems, diff and graph delta against the
+/// `previous` snapshot and keeps only the newest `keep` snapshots
+fn save_snapshot(
+    artifacts: &Artifacts,
+    previous: Option<PathBuf>,
+    components: &Components,
+    problems: &Problems,
+    keep: usize,
+) -> Result<(), String> {
+    let write_file = |path: PathBuf, content: String| {
+        write(&path, content).map_err(|e| format!("failed to write {:?}: {}", path, e))
+    };
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_secs();
+    let snapshot = artifacts.create_snapshot(timestamp)?;
+    info!("saving snapshot {:?}", snapshot);
+
+    components.try_serialize(&snapshot.join("data.bin"))?;
+    problems.serialize(&*snapshot.join("problems.bin").to_string_lossy())?;
+    write_file(
+        snapshot.join("summary.json"),
+        format!(
+            "{:#}",
+            serde_json::to_value(problems.summary()).map_err(|e| e.to_string())?
+        ),
+    )?;
+    write_file(
+        snapshot.join("problems.json"),
+        format!("{:#}", problems.to_json()),
+    )?;
+
+    match previous.filter(|previous| *previous != snapshot) {
+        Some(previous) => {
+            let old_problems =
+                Problems::deserialize(&*previous.join("problems.bin").to_string_lossy())?;
+            let diff = problems.diff(&old_problems);
+            info!(
+                "since {:?}: introduced: {}, fixed: {}",
+                previous,
+                diff.introduced.len(),
+                diff.fixed.len()
+            );
+            write_file(
+                snapshot.join("diff.json"),
+                format!("{:#}", problems.diff_to_json(&old_problems)),
+            )?;
+
+            let old_components = Components::try_deserialize(&previous.join("data.bin"))?;
+            write_file(
+                snapshot.join("graph-delta.jsonl"),
+                graph_delta_to_json_lines(&graph_delta(&old_components, components)),
+            )?;
+        }
+        None => info!("no previous snapshot, skipping diff"),
+    }
+
+    for removed in artifacts.rotate(keep.max(1))? {
+        info!("removed snapshot {:?}", removed);
+    }
+
+    Ok(())
+}
+
+/// Catalogs of publishers (file in assets, URL)
+const CATALOGS: [(&str, &str); 2] = [
+    (
+        "assets/catalog.dependency.C",
+        "https://pkg.openindiana.org/hipster/catalog/1/catalog.dependency.C",
+    ),
+    (
+        "assets/catalog.encumbered.dependency.C",
+        "https://pkg.openindiana.org/hipster-encumbered/catalog/1/catalog.dependency.C",
+    ),
+];
+
+/// Runs command and returns error with its stderr if it fails
+fn run_command(command: &mut Command) -> Result<(), String> {
+    debug!("running {:?}", command);
+    let output = command
+        .output()
+        .map_err(|e| format!("failed to run {:?}: {}", command, e))?;
+
+    match output.status.success() {
+        true => Ok(()),
+        false => Err(format!(
+            "{:?} failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+/// Prepares assets and configuration for the first run, see [`Commands::Init`]
+fn init(
+    components_path: &Path,
+    config: &Path,
+    clone: bool,
+    git_ref: &Option<String>,
+) -> Result<(), String> {
+    let missing = missing_tools(&["git", "gmake", "curl"]);
+    if !missing.is_empty() {
+        return Err(format!("missing tools: {}", missing.join(", ")));
+    }
+    info!("required tools are installed");
+
+    match write_default_config(config)? {
+        true => info!("created configuration {:?}", config),
+        false => info!("keeping existing configuration {:?}", config),
+    }
+
+    create_dir_all("assets").map_err(|e| format!("failed to create assets: {}", e))?;
+    for (path, url) in CATALOGS {
+        info!("downloading {}", url);
+        run_command(Command::new("curl").args(["-fsSL", "-o", path, url]))?;
+    }
+
+    let Some(repository) = components_path.parent() else {
+        return Err(format!("invalid path to components {:?}", components_path));
+    };
+
+    if clone && !repository.exists() {
+        info!("cloning oi-userland into {:?}", repository);
+        run_command(
+            Command::new("git")
+                .args(["clone", "https://github.com/OpenIndiana/oi-userland.git"])
+                .arg(repository),
+        )?;
+    }
+
+    if !components_path.exists() {
+        return Err(format!(
+            "{:?} doesn't exist, use --clone to clone oi-userland",
+            components_path
+        ));
+    }
+
+    if let Some(git_ref) = git_ref {
+        info!("checking out {} of oi-userland", git_ref);
+        run_command(
+            Command::new("git")
+                .arg("-C")
+                .arg(repository)
+                .args(["checkout", "--quiet", git_ref]),
+        )?;
+    }
+
+    info!("running quick analysis of catalogs and pkg5 files");
+    let mut problems = Problems::new();
+    let mut components = Components::new();
+    let component_packages = ComponentPackagesList::new(components_path, &mut problems);
+    components.load(
+        &mut problems,
+        AssetTypes::Catalogs(CATALOGS.iter().map(|(path, _)| path.into()).collect()),
+        &component_packages,
+    );
+
+    components.check_rename_chains(&mut problems);
+    component_packages.duplicate_component_names(&mut problems);
+    component_packages.same_packages_in_components(&mut problems);
+    component_packages.non_existing_packages_in_pkg5(&mut problems, &components);
+
+    info!(
+        "loaded {} packages and {} components",
+        components.get_ref().len(),
+        component_packages.get().len()
+    );
+    for (severity, count) in problems.count_by_severity() {
+        info!("{}: {}", severity, count);
+    }
+    info!(
+        "ready, run the analysis with: oi-pkg-checker run --config {}{}",
+        config.to_string_lossy(),
+        CATALOGS
+            .iter()
+            .map(|(path, _)| format!(" --catalog $(pwd)/{}", path))
+            .collect::<String>()
+    );
+
+    Ok(())
+}
+
 fn debug_on(debug: &bool) {
     if *debug {
         log::set_max_level(LevelFilter::Debug);
         debug!("debug is on");
     }
 }
+
+fn load_components(data_path: &str, namespace_filter: &NamespaceFilter) -> Components {
+    match Path::new(data_path).exists() {
+        false => {
+            error!("{} doesn't exist", data_path);
+            exit(1);
+        }
+        _ => {
+            let mut components = Components::deserialize(data_path);
+            components.set_namespace_filter(namespace_filter.clone());
+            components
+        }
+    }
+}
+
+fn load_config(config: &Option<PathBuf>) -> Config {
+    match config {
+        Some(path) => Config::load(path.clone()).unwrap_or_else(|e| {
+            error!("{}", e);
+            exit(1);
+        }),
+        None => Config::default(),
+    }
+}
+
+/// Exits if config needs depots, but `curl` can't be run
+fn check_depot_tools(config: &Config) {
+    if config.uses_depots() {
+        check_curl().unwrap_or_else(|e| {
+            error!("{}", e);
+            exit(1);
+        });
+    }
+}
+
+/// Returns name of directory in assets for URL or path with other characters than ASCII
+/// alphanumerics replaced
+fn asset_name(source: &str) -> String {
+    source
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Fetches catalogs of origins without catalog from their depots or mirrors (concurrently) into
+/// `depot_cache`, only changed files are downloaded with `cache`
+fn fetch_depot_catalogs(config: &mut Config, depot_cache: &Path, cache: bool) {
+    let mut origins: Vec<&mut Origin> = config
+        .publishers
+        .iter_mut()
+        .flat_map(|publisher| publisher.origins.iter_mut())
+        .filter(|origin| origin.catalog.as_os_str().is_empty() && !origin.url.is_empty())
+        .collect();
+
+    let depots: Vec<(Vec<String>, PathBuf)> = origins
+        .iter()
+        .map(|origin| {
+            let mut urls = origin.mirrors.clone();
+            urls.push(origin.url.clone());
+            (urls, depot_cache.join(asset_name(&origin.url)))
+        })
+        .collect();
+
+    for (origin, catalog) in origins.iter_mut().zip(fetch_catalogs(&depots, cache)) {
+        match catalog {
+            Ok(catalog) => origin.catalog = catalog,
+            Err(e) => {
+                error!("{}", e);
+                exit(1);
+            }
+        }
+    }
+}
+
+fn print_dependents(dependents: &Dependents, hide_renamed: bool) {
+    for (fmri, dependency_type, dependency, renamed) in &dependents.dependencies {
+        let d_type = match dependency.get_ref() {
+            DependTypes::Require(_) => "require",
+            DependTypes::Optional(_) => "optional",
+            DependTypes::Incorporate(_) => "incorporate",
+            DependTypes::RequireAny(_) => "require-any",
+            DependTypes::Conditional(_, _) => "conditional",
+            DependTypes::Group(_) => "group",
+            _ => unimplemented!(),
+        };
+
+        if hide_renamed && *renamed {
+            continue;
+        }
+
+        info!(
+            "\ttype: {}, dependency: {}, {}package: {}",
+            d_type,
+            dependency_type,
+            if *renamed { "renamed " } else { "" },
+            fmri
+        );
+    }
+
+    if !dependents.incorporations.is_empty() {
+        debug!(
+            "{} incorporate dependencies aggregated into {} counters",
+            dependents.aggregated_edges(),
+            dependents.incorporations.len()
+        );
+        info!(
+            "\tincorporated by {} incorporations (use --full-incorporate to list them)",
+            dependents.incorporations.len()
+        );
+    }
+}